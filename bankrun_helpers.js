@@ -0,0 +1,219 @@
+// Helpers for exercising programs built on pinocchio-groth16 from
+// bankrun / anchor-bankrun test suites.
+//
+// Encodes snarkjs proofs and verification keys into the byte layouts the
+// crate expects, builds account state for verifying key and proof accounts
+// and decodes Groth16Error custom program errors.
+//
+// The helpers only depend on Node's Buffer. Instructions are returned as
+// plain `{ programId, keys, data }` objects accepted by
+// `new TransactionInstruction(...)`, accounts are set with
+// `context.setAccount(address, account)`.
+
+const FIELD_MODULUS = BigInt(
+  "21888242871839275222246405745257275088696311157297823662689037894645226208583",
+);
+
+// Groth16Error variants indexed by their u32 code
+const GROTH16_ERRORS = [
+  "IncompatibleVerifyingKeyWithNrPublicInputs",
+  "ProofVerificationFailed",
+  "PreparingInputsG1AdditionFailed",
+  "PreparingInputsG1MulFailed",
+  "InvalidG1Length",
+  "InvalidG2Length",
+  "InvalidPublicInputsLength",
+  "DecompressingG1Failed",
+  "DecompressingG2Failed",
+  "PublicInputGreaterThanFieldSize",
+  "ProofConversionError",
+  "ArkworksSerializationError",
+  "NullifierAlreadySpent",
+  "NullifierSetFull",
+  "InvalidAccountOwner",
+  "InvalidAccountData",
+  "AccountNotWritable",
+  "ProofAccountStale",
+  "ProofAccountNotFinalized",
+  "MissingRequiredSignature",
+  "InvalidAuthority",
+  "InvalidActivationSlot",
+  "NoPendingVerifyingKey",
+  "CpiFailed",
+  "TransactionTooLarge",
+  "SyscallNotActive",
+  "PublicInputMismatch",
+  "InvalidZkey",
+  "ProvingFailed",
+  "InvalidMerkleProof",
+  "VerifierDisagreement",
+  "VerifyingKeyMismatch",
+  "NonCanonicalCoordinate",
+  "PointNotOnCurve",
+  "PointNotInSubgroup",
+  "UnknownCircuit",
+  "G2OperationFailed",
+  "PairingInputTooLarge",
+  "CommitmentVerificationFailed",
+  "InvalidPublicInput",
+  "PointAtInfinity",
+  "InvalidInstructionData",
+];
+
+const PROOF_ACCOUNT_DISCRIMINATOR = Buffer.from("g16proof");
+const VK_UPGRADE_DISCRIMINATOR = Buffer.from("g16vkupg");
+
+function toBytes32(value) {
+  const hex = BigInt(value).toString(16).padStart(64, "0");
+  if (hex.length > 64) {
+    throw new Error(`value does not fit into 32 bytes: ${value}`);
+  }
+  return Buffer.from(hex, "hex");
+}
+
+// [x, y, "1"] -> x | y, big-endian
+function g1ToBytes(point) {
+  return Buffer.concat([toBytes32(point[0]), toBytes32(point[1])]);
+}
+
+// [[x_c0, x_c1], [y_c0, y_c1], ["1", "0"]] -> x_c1 | x_c0 | y_c1 | y_c0, big-endian
+function g2ToBytes(point) {
+  return Buffer.concat([
+    toBytes32(point[0][1]),
+    toBytes32(point[0][0]),
+    toBytes32(point[1][1]),
+    toBytes32(point[1][0]),
+  ]);
+}
+
+// Negates an uncompressed G1 point, the verifier expects proof a negated
+function negateG1(bytes) {
+  const x = bytes.subarray(0, 32);
+  const y = BigInt("0x" + bytes.subarray(32, 64).toString("hex"));
+  const negY = y === 0n ? 0n : FIELD_MODULUS - y;
+  return Buffer.concat([Buffer.from(x), toBytes32(negY)]);
+}
+
+// snarkjs proof.json -> { proofA, proofB, proofC } with proof a negated
+function proofToBytes(proof) {
+  return {
+    proofA: negateG1(g1ToBytes(proof.pi_a)),
+    proofB: g2ToBytes(proof.pi_b),
+    proofC: g1ToBytes(proof.pi_c),
+  };
+}
+
+// snarkjs public.json -> array of 32 byte big-endian buffers
+function publicSignalsToBytes(publicSignals) {
+  return publicSignals.map((signal) => {
+    if (BigInt(signal) >= FIELD_MODULUS) {
+      throw new Error(`public signal is not a field element: ${signal}`);
+    }
+    return toBytes32(signal);
+  });
+}
+
+// Groth16Verifyingkey::packed_size
+function packedVerifyingKeySize(nrPubinputs) {
+  return 4 + 64 + 3 * 128 + (nrPubinputs + 1) * 64;
+}
+
+// snarkjs verification_key.json -> Groth16Verifyingkey packed encoding
+function packVerifyingKey(vk) {
+  const nrPubinputs = Buffer.alloc(4);
+  nrPubinputs.writeUInt32LE(vk.IC.length - 1);
+  return Buffer.concat([
+    nrPubinputs,
+    g1ToBytes(vk.vk_alpha_1),
+    g2ToBytes(vk.vk_beta_2),
+    g2ToBytes(vk.vk_gamma_2),
+    g2ToBytes(vk.vk_delta_2),
+    ...vk.IC.map(g1ToBytes),
+  ]);
+}
+
+// Data of a vk_upgrade account holding `vk` as active key without pending key
+function vkUpgradeAccountData({ authority, vk, maxNrPubinputs }) {
+  const max = maxNrPubinputs ?? vk.IC.length - 1;
+  const slotSize = packedVerifyingKeySize(max);
+  const packed = packVerifyingKey(vk);
+  if (packed.length > slotSize) {
+    throw new Error("verifying key has more public inputs than maxNrPubinputs");
+  }
+  const data = Buffer.alloc(64 + 2 * slotSize);
+  VK_UPGRADE_DISCRIMINATOR.copy(data, 0);
+  Buffer.from(authority).copy(data, 8);
+  data.writeUInt32LE(max, 48);
+  data.writeUInt32LE(packed.length, 52);
+  packed.copy(data, 64);
+  return data;
+}
+
+// Data of a finalized proof account, see src/proof_account.rs
+function proofAccountData({ authority, proof, publicInputs, slot }) {
+  const data = Buffer.alloc(56 + 256 + 32 * publicInputs.length);
+  PROOF_ACCOUNT_DISCRIMINATOR.copy(data, 0);
+  Buffer.from(authority).copy(data, 8);
+  data.writeBigUInt64LE(BigInt(slot), 40);
+  data.writeUInt32LE(publicInputs.length, 48);
+  data[52] = 1;
+  Buffer.concat([proof.proofA, proof.proofB, proof.proofC, ...publicInputs]).copy(data, 56);
+  return data;
+}
+
+// Stores `data` at `address`, rent exempt and owned by `owner`
+async function setAccountData(context, address, owner, data) {
+  const rent = await context.banksClient.getRent();
+  context.setAccount(address, {
+    lamports: Number(rent.minimumBalance(BigInt(data.length))),
+    data,
+    owner,
+    executable: false,
+  });
+}
+
+// Verify instruction with `prefix | proof_a | proof_b | proof_c | inputs` as data
+function verifyInstruction({ programId, prefix, proof, publicInputs, keys }) {
+  return {
+    programId,
+    keys,
+    data: Buffer.concat([
+      Buffer.from(prefix ?? []),
+      proof.proofA,
+      proof.proofB,
+      proof.proofC,
+      ...publicInputs,
+    ]),
+  };
+}
+
+// Maps a custom program error code, an error or transaction logs to the Groth16Error name
+function decodeGroth16Error(error) {
+  let code = error;
+  if (typeof error !== "number") {
+    const text = Array.isArray(error) ? error.join("\n") : String(error);
+    const match = text.match(/custom program error: 0x([0-9a-fA-F]+)/);
+    if (!match) {
+      return undefined;
+    }
+    code = parseInt(match[1], 16);
+  }
+  return GROTH16_ERRORS[code];
+}
+
+module.exports = {
+  FIELD_MODULUS,
+  GROTH16_ERRORS,
+  g1ToBytes,
+  g2ToBytes,
+  negateG1,
+  proofToBytes,
+  publicSignalsToBytes,
+  packedVerifyingKeySize,
+  packVerifyingKey,
+  vkUpgradeAccountData,
+  proofAccountData,
+  setAccountData,
+  verifyInstruction,
+  decodeGroth16Error,
+};
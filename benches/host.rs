@@ -0,0 +1,103 @@
+//! Benchmarks of the host-side paths relayers run at high throughput
+//!
+//! ```bash
+//! cargo bench --features "test-vectors vk"
+//! ```
+//!
+//! On the host the alt_bn128 syscalls are served by the arkworks mock, so
+//! the verification numbers measure that backend, not on-chain compute units.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use pinocchio_groth16::bn254::convert_endianness;
+use pinocchio_groth16::decompression::{decompress_g1, decompress_g2};
+use pinocchio_groth16::groth16::{Groth16Verifier, Groth16Verifyingkey};
+use pinocchio_groth16::test_vectors::generate;
+use pinocchio_groth16::vk_parser::{parse_vk_json_to_rust_string_with_mode, CodegenMode};
+
+fn verification(c: &mut Criterion) {
+    let vector = generate(0);
+    let vk = vector.verifying_key();
+    let inputs: [[u8; 32]; 2] = vector.public_inputs.clone().try_into().unwrap();
+
+    c.bench_function("verify", |b| {
+        b.iter(|| {
+            Groth16Verifier::new(
+                black_box(&vector.proof_a),
+                black_box(&vector.proof_b),
+                black_box(&vector.proof_c),
+                black_box(&inputs),
+                &vk,
+            )
+            .unwrap()
+            .verify()
+            .unwrap()
+        })
+    });
+    c.bench_function("prepare_inputs", |b| {
+        b.iter(|| {
+            Groth16Verifier::new(
+                &vector.proof_a,
+                &vector.proof_b,
+                &vector.proof_c,
+                black_box(&inputs),
+                &vk,
+            )
+            .unwrap()
+            .prepare_inputs::<true>()
+            .unwrap()
+        })
+    });
+    let compressed = vector.proof_compressed();
+    c.bench_function("decompress proof", |b| {
+        b.iter(|| {
+            let compressed = black_box(&compressed);
+            (
+                decompress_g1(compressed[..32].try_into().unwrap()).unwrap(),
+                decompress_g2(compressed[32..96].try_into().unwrap()).unwrap(),
+                decompress_g1(compressed[96..].try_into().unwrap()).unwrap(),
+            )
+        })
+    });
+}
+
+fn parsers(c: &mut Criterion) {
+    let vector = generate(0);
+    let packed = vector.packed_verifying_key();
+    c.bench_function("packed verifying key", |b| {
+        b.iter(|| Groth16Verifyingkey::from_packed_bytes(black_box(&packed)).unwrap())
+    });
+
+    let json = vector.snarkjs_verification_key_json();
+    for (name, mode) in [
+        ("verifying key json legacy", CodegenMode::Legacy),
+        (
+            "verifying key json deterministic",
+            CodegenMode::Deterministic,
+        ),
+    ] {
+        c.bench_function(name, |b| {
+            b.iter(|| parse_vk_json_to_rust_string_with_mode(black_box(&json), mode).unwrap())
+        });
+    }
+}
+
+fn endianness(c: &mut Criterion) {
+    let vector = generate(0);
+    c.bench_function("convert_endianness g1", |b| {
+        b.iter(|| convert_endianness::<32, 64>(black_box(&vector.proof_a)))
+    });
+    c.bench_function("convert_endianness g2", |b| {
+        b.iter(|| convert_endianness::<64, 128>(black_box(&vector.proof_b)))
+    });
+    c.bench_function("convert_endianness public inputs", |b| {
+        b.iter(|| {
+            black_box(&vector.public_inputs)
+                .iter()
+                .map(convert_endianness::<32, 32>)
+                .collect::<Vec<_>>()
+        })
+    });
+}
+
+criterion_group!(benches, verification, parsers, endianness);
+criterion_main!(benches);
@@ -0,0 +1,28 @@
+use pinocchio_groth16::vk_parser::generate_vk_file;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build/verification_key.json");
+    println!("cargo:rerun-if-changed=build/withdraw_js");
+
+    // The verifying key is generated from the circuit artifacts into OUT_DIR
+    // and included by src/lib.rs, run `npm run build-all` to create them.
+    // Without them the host build uses a key rejecting every proof, so the
+    // workspace builds on a fresh checkout; the program itself does not build.
+    let vk_json_path = "./build/verification_key.json";
+    if std::path::Path::new(vk_json_path).exists() {
+        let out_dir = std::env::var("OUT_DIR").unwrap();
+        generate_vk_file(vk_json_path, &out_dir, "verifying_key.rs")
+            .expect("Failed to generate verifying key Rust file");
+        println!("cargo:rustc-cfg=has_verifying_key");
+    } else {
+        println!("cargo:warning=Verification key JSON not found. Run 'npm run build-all' first.");
+    }
+
+    // The witness generator is only needed by the host-side tests.
+    let witness_wasm_dir = "./build/withdraw_js";
+    let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    if target_os != "solana" && std::path::Path::new(witness_wasm_dir).exists() {
+        rust_witness::transpile::transpile_wasm(witness_wasm_dir.to_string());
+        println!("cargo:warning=Transpiled witness generator");
+    }
+}
@@ -0,0 +1,77 @@
+pragma circom 2.0.0;
+
+include "../node_modules/circomlib/circuits/poseidon.circom";
+include "../node_modules/circomlib/circuits/bitify.circom";
+include "../node_modules/circomlib/circuits/switcher.circom";
+
+// Merkle Proof Verification Template
+// Computes the root of the tree that contains leaf at leafIndex
+template MerkleProof(levels) {
+    signal input leaf;
+    signal input pathElements[levels];
+    signal input leafIndex;
+    signal output root;
+
+    component switcher[levels];
+    component hasher[levels];
+
+    component indexBits = Num2Bits(levels);
+    indexBits.in <== leafIndex;
+
+    for (var i = 0; i < levels; i++) {
+        switcher[i] = Switcher();
+        switcher[i].L <== i == 0 ? leaf : hasher[i - 1].out;
+        switcher[i].R <== pathElements[i];
+        switcher[i].sel <== indexBits.out[i];
+
+        hasher[i] = Poseidon(2);
+        hasher[i].inputs[0] <== switcher[i].outL;
+        hasher[i].inputs[1] <== switcher[i].outR;
+    }
+
+    root <== hasher[levels - 1].out;
+}
+
+// Withdraw Circuit
+// Proves knowledge of (nullifier, secret) for a commitment Poseidon(nullifier, secret)
+// in the pool tree and reveals nullifierHash = Poseidon(nullifier) to prevent double spends.
+template Withdraw(levels) {
+    // Public inputs, in this order on chain
+    signal input root;
+    signal input nullifierHash;
+    signal input recipient;
+
+    // Private inputs
+    signal input nullifier;
+    signal input secret;
+    signal input pathElements[levels];
+    signal input leafIndex;
+
+    component commitmentHasher = Poseidon(2);
+    commitmentHasher.inputs[0] <== nullifier;
+    commitmentHasher.inputs[1] <== secret;
+
+    component nullifierHasher = Poseidon(1);
+    nullifierHasher.inputs[0] <== nullifier;
+    nullifierHash === nullifierHasher.out;
+
+    component merkleProof = MerkleProof(levels);
+    merkleProof.leaf <== commitmentHasher.out;
+    merkleProof.pathElements <== pathElements;
+    merkleProof.leafIndex <== leafIndex;
+    merkleProof.root === root;
+
+    // Bind the proof to the recipient so it cannot be front-run with another
+    // recipient. The program passes keccak256(recipient pubkey) mod r as this
+    // input, which binds all 32 bytes of the key.
+    signal recipientSquare;
+    recipientSquare <== recipient * recipient;
+}
+
+component main {
+    public [
+        root,
+        nullifierHash,
+        recipient
+    ]
+} = Withdraw(20);
@@ -0,0 +1,250 @@
+//! # shielded-pool
+//!
+//! Example program wiring `pinocchio-groth16` into a fixed-denomination
+//! shielded pool:
+//!
+//! * `Deposit` transfers `DENOMINATION` lamports into the pool and appends a
+//!   commitment `Poseidon(nullifier, secret)` to the pool merkle tree.
+//! * `Withdraw` verifies a proof of `circuits/withdraw.circom` for a known
+//!   root, records the revealed nullifier hash in a nullifier page and pays
+//!   out `DENOMINATION` lamports to the recipient bound by the proof.
+//!
+//! The verifying key is generated from the circuit artifacts by `build.rs`.
+
+#![cfg_attr(target_os = "solana", no_std)]
+
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::{Seed, Signer};
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey::{find_program_address, Pubkey};
+use pinocchio::sysvars::{rent::Rent, Sysvar};
+use pinocchio::ProgramResult;
+use pinocchio_groth16::errors::Groth16Error;
+use pinocchio_groth16::groth16::{
+    is_less_than_bn254_field_size_be, Groth16Verifier, Groth16Verifyingkey,
+};
+use pinocchio_groth16::hash::hashv_to_bn254_scalar_be;
+use pinocchio_groth16::nullifier::{
+    insert_nullifier_into_page_account_checked, nullifier_page_size, NullifierPage,
+    NULLIFIER_PAGE_SEED,
+};
+use pinocchio_system::instructions::{CreateAccount, Transfer};
+
+pub mod state;
+
+#[cfg(has_verifying_key)]
+mod verifying_key {
+    include!(concat!(env!("OUT_DIR"), "/verifying_key.rs"));
+}
+
+#[cfg(has_verifying_key)]
+pub use verifying_key::VERIFYINGKEY;
+
+#[cfg(all(not(has_verifying_key), target_os = "solana"))]
+compile_error!("build/verification_key.json not found, run `npm run build-all` first");
+
+/// Placeholder without the circuit artifacts, rejects every proof
+#[cfg(not(has_verifying_key))]
+pub const VERIFYINGKEY: Groth16Verifyingkey = Groth16Verifyingkey {
+    nr_pubinputs: 3,
+    vk_alpha_g1: [0u8; 64],
+    vk_beta_g2: [0u8; 128],
+    vk_gamma_g2: [0u8; 128],
+    vk_delta_g2: [0u8; 128],
+    vk_ic: &[],
+};
+
+use state::{PoolState, POOL_SEED};
+
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::entrypoint!(process_instruction);
+
+/// Lamports moved by every deposit and withdrawal
+pub const DENOMINATION: u64 = 100_000_000;
+
+/// Number of nullifiers per nullifier page
+pub const NULLIFIER_PAGE_CAPACITY: usize = 256;
+
+pub const INITIALIZE: u8 = 0;
+pub const INIT_NULLIFIER_PAGE: u8 = 1;
+pub const DEPOSIT: u8 = 2;
+pub const WITHDRAW: u8 = 3;
+
+/// Size of the withdraw instruction data after the tag
+pub const WITHDRAW_DATA_SIZE: usize = 64 + 128 + 64 + 32 + 32;
+
+/// Maps a recipient pubkey to the field element the proof is bound to
+///
+/// keccak256 of the whole key reduced modulo r, so every byte of the key is
+/// bound and recipients cannot be swapped for another key with the same
+/// public input.
+pub fn recipient_field_element(recipient: &Pubkey) -> [u8; 32] {
+    hashv_to_bn254_scalar_be(&[recipient])
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (tag, data) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    match *tag {
+        INITIALIZE => initialize(program_id, accounts, data),
+        INIT_NULLIFIER_PAGE => init_nullifier_page(program_id, accounts, data),
+        DEPOSIT => deposit(program_id, accounts, data),
+        WITHDRAW => withdraw(program_id, accounts, data),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Accounts: `[payer (signer, writable), pool (writable), system program]`
+/// Data: `nr_nullifier_pages u16 LE`
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [payer, pool, _system_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let nr_pages = u16::from_le_bytes(
+        data.try_into()
+            .map_err(|_| ProgramError::InvalidInstructionData)?,
+    );
+    if nr_pages == 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let (address, bump) = find_program_address(&[POOL_SEED], program_id);
+    if pool.key() != &address {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let bump_seed = [bump];
+    let seeds = [Seed::from(POOL_SEED), Seed::from(&bump_seed)];
+    CreateAccount {
+        from: payer,
+        to: pool,
+        lamports: Rent::get()?.minimum_balance(PoolState::LEN),
+        space: PoolState::LEN as u64,
+        owner: program_id,
+    }
+    .invoke_signed(&[Signer::from(&seeds)])?;
+
+    let mut pool_data = pool.try_borrow_mut_data()?;
+    PoolState::from_bytes_mut(&mut pool_data)?.initialize(nr_pages, bump)
+}
+
+/// Accounts: `[payer (signer, writable), page (writable), pool, system program]`
+/// Data: `page_index u16 LE`
+fn init_nullifier_page(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let [payer, page, pool, _system_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let page_index = u16::from_le_bytes(
+        data.try_into()
+            .map_err(|_| ProgramError::InvalidInstructionData)?,
+    );
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if page_index >= PoolState::from_bytes(&pool.try_borrow_data()?)?.nr_nullifier_pages() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let index_seed = page_index.to_le_bytes();
+    let (address, bump) = find_program_address(&[NULLIFIER_PAGE_SEED, &index_seed], program_id);
+    if page.key() != &address {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let bump_seed = [bump];
+    let seeds = [
+        Seed::from(NULLIFIER_PAGE_SEED),
+        Seed::from(&index_seed),
+        Seed::from(&bump_seed),
+    ];
+    let space = nullifier_page_size(NULLIFIER_PAGE_CAPACITY);
+    CreateAccount {
+        from: payer,
+        to: page,
+        lamports: Rent::get()?.minimum_balance(space),
+        space: space as u64,
+        owner: program_id,
+    }
+    .invoke_signed(&[Signer::from(&seeds)])?;
+
+    let mut page_data = page.try_borrow_mut_data()?;
+    NullifierPage::initialize(&mut page_data, page_index)?;
+    Ok(())
+}
+
+/// Accounts: `[depositor (signer, writable), pool (writable), system program]`
+/// Data: `commitment [u8; 32]`
+fn deposit(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [depositor, pool, _system_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let commitment: &[u8; 32] = data
+        .try_into()
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !is_less_than_bn254_field_size_be(commitment) {
+        return Err(Groth16Error::PublicInputGreaterThanFieldSize.into());
+    }
+
+    Transfer {
+        from: depositor,
+        to: pool,
+        lamports: DENOMINATION,
+    }
+    .invoke()?;
+
+    let mut pool_data = pool.try_borrow_mut_data()?;
+    PoolState::from_bytes_mut(&mut pool_data)?.insert(commitment)?;
+    Ok(())
+}
+
+/// Accounts: `[pool (writable), nullifier page (writable), recipient (writable)]`
+/// Data: `proof_a (negated) | proof_b | proof_c | root | nullifier_hash`
+fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [pool, page, recipient] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if data.len() != WITHDRAW_DATA_SIZE {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if !pool.is_owned_by(program_id) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let proof_a: &[u8; 64] = data[0..64].try_into().unwrap();
+    let proof_b: &[u8; 128] = data[64..192].try_into().unwrap();
+    let proof_c: &[u8; 64] = data[192..256].try_into().unwrap();
+    let root: [u8; 32] = data[256..288].try_into().unwrap();
+    let nullifier_hash: [u8; 32] = data[288..320].try_into().unwrap();
+
+    let nr_pages = {
+        let pool_data = pool.try_borrow_data()?;
+        let state = PoolState::from_bytes(&pool_data)?;
+        if !state.is_known_root(&root) {
+            return Err(ProgramError::InvalidArgument);
+        }
+        state.nr_nullifier_pages()
+    };
+
+    let public_inputs = [
+        root,
+        nullifier_hash,
+        recipient_field_element(recipient.key()),
+    ];
+    Groth16Verifier::new(proof_a, proof_b, proof_c, &public_inputs, &VERIFYINGKEY)
+        .and_then(|mut verifier| verifier.verify())?;
+
+    insert_nullifier_into_page_account_checked(page, program_id, nr_pages, &public_inputs, 1)?;
+
+    *pool.try_borrow_mut_lamports()? -= DENOMINATION;
+    *recipient.try_borrow_mut_lamports()? += DENOMINATION;
+    Ok(())
+}
@@ -0,0 +1,130 @@
+//! Pool state: an incremental Poseidon merkle tree of deposit commitments
+//! with a ring buffer of recent roots.
+
+use pinocchio::program_error::ProgramError;
+
+pub const TREE_DEPTH: usize = 20;
+pub const ROOT_HISTORY_SIZE: usize = 30;
+
+pub const POOL_SEED: &[u8] = b"pool";
+pub const POOL_DISCRIMINATOR: [u8; 8] = *b"shdpool\0";
+
+/// Pool account data, all fields have alignment 1 so the struct can be cast
+/// from account data directly
+#[repr(C)]
+pub struct PoolState {
+    pub discriminator: [u8; 8],
+    pub nr_nullifier_pages: [u8; 2],
+    pub bump: u8,
+    _padding: u8,
+    pub next_index: [u8; 4],
+    pub current_root_index: [u8; 4],
+    _padding2: [u8; 4],
+    pub filled_subtrees: [[u8; 32]; TREE_DEPTH],
+    pub zeros: [[u8; 32]; TREE_DEPTH],
+    pub roots: [[u8; 32]; ROOT_HISTORY_SIZE],
+}
+
+impl PoolState {
+    pub const LEN: usize = core::mem::size_of::<PoolState>();
+
+    pub fn from_bytes_mut(data: &mut [u8]) -> Result<&mut PoolState, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        // SAFETY: length checked above and PoolState has alignment 1.
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut PoolState) })
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<&PoolState, ProgramError> {
+        if data.len() != Self::LEN || data[..8] != POOL_DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        // SAFETY: length checked above and PoolState has alignment 1.
+        Ok(unsafe { &*(data.as_ptr() as *const PoolState) })
+    }
+
+    pub fn nr_nullifier_pages(&self) -> u16 {
+        u16::from_le_bytes(self.nr_nullifier_pages)
+    }
+
+    /// Initializes an empty tree whose leaves are all zero
+    pub fn initialize(&mut self, nr_nullifier_pages: u16, bump: u8) -> Result<(), ProgramError> {
+        self.discriminator = POOL_DISCRIMINATOR;
+        self.nr_nullifier_pages = nr_nullifier_pages.to_le_bytes();
+        self.bump = bump;
+        self.next_index = 0u32.to_le_bytes();
+        self.current_root_index = 0u32.to_le_bytes();
+
+        let mut zero = [0u8; 32];
+        for i in 0..TREE_DEPTH {
+            self.zeros[i] = zero;
+            self.filled_subtrees[i] = zero;
+            zero = poseidon(&[&zero, &zero])?;
+        }
+        self.roots[0] = zero;
+        Ok(())
+    }
+
+    /// Appends a leaf and records the new root
+    pub fn insert(&mut self, leaf: &[u8; 32]) -> Result<u32, ProgramError> {
+        let index = u32::from_le_bytes(self.next_index);
+        if index as u64 >= 1u64 << TREE_DEPTH {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        let mut current_index = index;
+        let mut current = *leaf;
+        for i in 0..TREE_DEPTH {
+            current = if current_index.is_multiple_of(2) {
+                self.filled_subtrees[i] = current;
+                poseidon(&[&current, &self.zeros[i]])?
+            } else {
+                poseidon(&[&self.filled_subtrees[i], &current])?
+            };
+            current_index /= 2;
+        }
+
+        let root_index =
+            (u32::from_le_bytes(self.current_root_index) + 1) % ROOT_HISTORY_SIZE as u32;
+        self.current_root_index = root_index.to_le_bytes();
+        self.roots[root_index as usize] = current;
+        self.next_index = (index + 1).to_le_bytes();
+        Ok(index)
+    }
+
+    pub fn is_known_root(&self, root: &[u8; 32]) -> bool {
+        *root != [0u8; 32] && self.roots.iter().any(|known| known == root)
+    }
+}
+
+/// Poseidon hash with the BN254 x5 parameters used by circomlib, big-endian
+pub fn poseidon(inputs: &[&[u8]]) -> Result<[u8; 32], ProgramError> {
+    let mut result = [0u8; 32];
+
+    #[cfg(target_os = "solana")]
+    {
+        // parameters: Bn254X5 = 0, endianness: BigEndian = 0
+        let return_code = unsafe {
+            pinocchio::syscalls::sol_poseidon(
+                0,
+                0,
+                inputs as *const _ as *const u8,
+                inputs.len() as u64,
+                result.as_mut_ptr(),
+            )
+        };
+        if return_code != 0 {
+            return Err(ProgramError::InvalidArgument);
+        }
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    {
+        core::hint::black_box((inputs, &mut result));
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    #[allow(unreachable_code)]
+    Ok(result)
+}
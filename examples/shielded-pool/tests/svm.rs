@@ -0,0 +1,197 @@
+//! Deposit and withdraw against the compiled program in LiteSVM
+//!
+//! Requires the circuit artifacts (`npm run build-all`) and the program
+//! binary (`cargo build-sbf`), and is skipped without the artifacts.
+
+#![cfg(has_verifying_key)]
+
+use light_hasher::{Hasher, Poseidon};
+use light_merkle_tree_reference::MerkleTree;
+use num_bigint::BigUint;
+use pinocchio_groth16::nullifier::{page_index_for, NULLIFIER_PAGE_SEED};
+use pinocchio_groth16::prover::prove_with_rust_witness;
+use pinocchio_groth16::svm::VerifierSvm;
+use shielded_pool::state::{POOL_SEED, TREE_DEPTH};
+use shielded_pool::{
+    recipient_field_element, DENOMINATION, DEPOSIT, INITIALIZE, INIT_NULLIFIER_PAGE, WITHDRAW,
+};
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use std::collections::HashMap;
+
+// Link the generated witness library
+#[link(name = "circuit", kind = "static")]
+extern "C" {}
+
+rust_witness::witness!(withdraw);
+
+const PROGRAM_PATH: &str = "../../target/deploy/shielded_pool.so";
+const ZKEY_PATH: &str = "./build/withdraw_final.zkey";
+const NR_NULLIFIER_PAGES: u16 = 2;
+
+fn send(svm: &mut VerifierSvm, instruction: Instruction) -> Result<(), String> {
+    svm.send(&[instruction])
+        .map(|_| ())
+        .map_err(|execution| execution.error.unwrap())
+}
+
+fn field_string(bytes: &[u8]) -> String {
+    BigUint::from_bytes_be(bytes).to_string()
+}
+
+fn prove_withdraw(
+    nullifier: &[u8; 32],
+    secret: &[u8; 32],
+    tree: &MerkleTree<Poseidon>,
+    leaf_index: usize,
+    recipient: &Pubkey,
+) -> ([u8; 64], [u8; 128], [u8; 64]) {
+    let path_elements = tree.get_proof_of_leaf(leaf_index, true).unwrap();
+    let nullifier_hash = Poseidon::hash(nullifier).unwrap();
+
+    let mut inputs: HashMap<String, Vec<String>> = HashMap::new();
+    inputs.insert("root".to_string(), vec![field_string(&tree.root())]);
+    inputs.insert(
+        "nullifierHash".to_string(),
+        vec![field_string(&nullifier_hash)],
+    );
+    inputs.insert(
+        "recipient".to_string(),
+        vec![field_string(&recipient_field_element(
+            &recipient.to_bytes(),
+        ))],
+    );
+    inputs.insert("nullifier".to_string(), vec![field_string(nullifier)]);
+    inputs.insert("secret".to_string(), vec![field_string(secret)]);
+    inputs.insert(
+        "pathElements".to_string(),
+        path_elements.iter().map(|e| field_string(e)).collect(),
+    );
+    inputs.insert("leafIndex".to_string(), vec![leaf_index.to_string()]);
+
+    let zkey = std::fs::read(ZKEY_PATH).unwrap();
+    let proof =
+        prove_with_rust_witness(&zkey, withdraw_witness, &inputs).expect("Proof generation failed");
+    (proof.proof_a, proof.proof_b, proof.proof_c)
+}
+
+#[test]
+fn deposit_and_withdraw() {
+    let mut svm = VerifierSvm::new();
+    let program_id = svm.load_program(PROGRAM_PATH).unwrap();
+    let payer = svm.payer.pubkey();
+    let system_program = solana_sdk_ids::system_program::id();
+    let (pool, _) = Pubkey::find_program_address(&[POOL_SEED], &program_id);
+
+    let mut data = vec![INITIALIZE];
+    data.extend_from_slice(&NR_NULLIFIER_PAGES.to_le_bytes());
+    send(
+        &mut svm,
+        Instruction::new_with_bytes(
+            program_id,
+            &data,
+            vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new(pool, false),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        ),
+    )
+    .unwrap();
+
+    for index in 0..NR_NULLIFIER_PAGES {
+        let (page, _) =
+            Pubkey::find_program_address(&[NULLIFIER_PAGE_SEED, &index.to_le_bytes()], &program_id);
+        let mut data = vec![INIT_NULLIFIER_PAGE];
+        data.extend_from_slice(&index.to_le_bytes());
+        send(
+            &mut svm,
+            Instruction::new_with_bytes(
+                program_id,
+                &data,
+                vec![
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new(page, false),
+                    AccountMeta::new_readonly(pool, false),
+                    AccountMeta::new_readonly(system_program, false),
+                ],
+            ),
+        )
+        .unwrap();
+    }
+
+    // Deposit
+    let nullifier = Poseidon::hash(&[1u8; 31]).unwrap();
+    let secret = Poseidon::hash(&[2u8; 31]).unwrap();
+    let commitment = Poseidon::hashv(&[&nullifier, &secret]).unwrap();
+    let mut data = vec![DEPOSIT];
+    data.extend_from_slice(&commitment);
+    send(
+        &mut svm,
+        Instruction::new_with_bytes(
+            program_id,
+            &data,
+            vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new(pool, false),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        ),
+    )
+    .unwrap();
+
+    let mut tree = MerkleTree::<Poseidon>::new(TREE_DEPTH, 0);
+    tree.append(&commitment).unwrap();
+
+    // Withdraw
+    let recipient = Pubkey::new_unique();
+    let (proof_a, proof_b, proof_c) = prove_withdraw(&nullifier, &secret, &tree, 0, &recipient);
+    let nullifier_hash = Poseidon::hash(&nullifier).unwrap();
+    let page_index = page_index_for(&nullifier_hash, NR_NULLIFIER_PAGES).unwrap();
+    let (page, _) = Pubkey::find_program_address(
+        &[NULLIFIER_PAGE_SEED, &page_index.to_le_bytes()],
+        &program_id,
+    );
+
+    let mut data = vec![WITHDRAW];
+    data.extend_from_slice(&proof_a);
+    data.extend_from_slice(&proof_b);
+    data.extend_from_slice(&proof_c);
+    data.extend_from_slice(&tree.root());
+    data.extend_from_slice(&nullifier_hash);
+    let withdraw = Instruction::new_with_bytes(
+        program_id,
+        &data,
+        vec![
+            AccountMeta::new(pool, false),
+            AccountMeta::new(page, false),
+            AccountMeta::new(recipient, false),
+        ],
+    );
+
+    // The proof does not verify for a recipient differing only in byte 0
+    let mut front_runner = recipient.to_bytes();
+    front_runner[0] ^= 1;
+    let front_runner = Pubkey::new_from_array(front_runner);
+    let mut front_run = withdraw.clone();
+    front_run.accounts[2] = AccountMeta::new(front_runner, false);
+    assert!(send(&mut svm, front_run).is_err());
+    assert_eq!(svm.svm.get_balance(&front_runner).unwrap_or(0), 0);
+
+    // Another page than the nullifier's PDA is rejected
+    let (other_page, _) = Pubkey::find_program_address(
+        &[NULLIFIER_PAGE_SEED, &(1 - page_index).to_le_bytes()],
+        &program_id,
+    );
+    let mut wrong_page = withdraw.clone();
+    wrong_page.accounts[1] = AccountMeta::new(other_page, false);
+    assert!(send(&mut svm, wrong_page).is_err());
+
+    send(&mut svm, withdraw.clone()).unwrap();
+    assert_eq!(svm.svm.get_balance(&recipient), Some(DENOMINATION));
+
+    // Spending the same nullifier again fails
+    assert!(send(&mut svm, withdraw).is_err());
+    assert_eq!(svm.svm.get_balance(&recipient), Some(DENOMINATION));
+}
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pinocchio_groth16::decompression::{decompress_g1, decompress_g2};
+
+fuzz_target!(|points: ([u8; 32], [u8; 64])| {
+    let _ = decompress_g1(&points.0);
+    let _ = decompress_g2(&points.1);
+});
@@ -0,0 +1,23 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pinocchio_groth16::nullifier::{NullifierBloomFilter, NullifierPage};
+
+fuzz_target!(|input: (Vec<u8>, [u8; 32])| {
+    let (data, nullifier) = input;
+
+    let mut page_data = data.clone();
+    if let Ok(mut page) = NullifierPage::from_bytes(&mut page_data) {
+        assert!(page.len() <= page.capacity());
+        let contained = page.contains(&nullifier);
+        let inserted = page.insert_if_absent(&nullifier);
+        assert!(!(contained && inserted.is_ok()));
+    }
+
+    let mut filter_data = data;
+    if let Ok(mut filter) = NullifierBloomFilter::from_bytes(&mut filter_data) {
+        if filter.insert_if_absent(&nullifier).is_ok() {
+            assert!(filter.may_contain(&nullifier));
+        }
+    }
+});
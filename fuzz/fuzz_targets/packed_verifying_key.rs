@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pinocchio_groth16::groth16::Groth16Verifyingkey;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(vk) = Groth16Verifyingkey::from_packed_bytes(data) {
+        assert_eq!(
+            data.len(),
+            Groth16Verifyingkey::packed_size(vk.nr_pubinputs)
+        );
+        let mut packed = vec![0u8; data.len()];
+        assert_eq!(vk.write_packed_bytes(&mut packed), Ok(data.len()));
+        assert_eq!(packed, data);
+    }
+});
@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pinocchio_groth16::proof_account::ProofAccount;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(account) = ProofAccount::from_bytes(data) {
+        let _ = account.authority();
+        let _ = (account.slot(), account.is_finalized());
+        let _ = (account.proof_a(), account.proof_b(), account.proof_c());
+        let _ = account.public_inputs::<1>();
+        let _ = account.public_inputs::<2>();
+    }
+    let _ = ProofAccount::from_bytes_checked(data, u64::MAX, 150);
+});
@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pinocchio_groth16::groth16::{Groth16Verifier, Groth16Verifyingkey};
+
+type Input<'a> = (
+    Groth16Verifyingkey<'a>,
+    [u8; 64],
+    [u8; 128],
+    [u8; 64],
+    [[u8; 32]; 2],
+);
+
+fuzz_target!(|input: Input| {
+    let (vk, proof_a, proof_b, proof_c, public_inputs) = input;
+    if let Ok(mut verifier) =
+        Groth16Verifier::new(&proof_a, &proof_b, &proof_c, &public_inputs, &vk)
+    {
+        let _ = verifier.verify();
+    }
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pinocchio_groth16::vk_parser::parse_vk_json_to_rust_string;
+
+fuzz_target!(|json: &str| {
+    let _ = parse_vk_json_to_rust_string(json);
+});
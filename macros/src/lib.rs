@@ -0,0 +1,105 @@
+//! Compile-time embedding of verifying keys
+//!
+//! [`include_vk!`] reads a snarkjs `verification_key.json` while the calling
+//! crate is compiled and expands to the verifying key, the same constant
+//! `vk_parser::generate_vk_file_with_mode(..., CodegenMode::Deterministic)`
+//! writes from a build script:
+//!
+//! ```rust,ignore
+//! use pinocchio_groth16::groth16::Groth16Verifyingkey;
+//! use pinocchio_groth16_macros::include_vk;
+//!
+//! const VERIFYINGKEY: Groth16Verifyingkey = include_vk!("build/verification_key.json", nr_pubinputs = 2);
+//! ```
+//!
+//! The path is relative to the calling crate's `Cargo.toml`. The crate is
+//! rebuilt when the file changes. A malformed key, or a key taking another
+//! number of public inputs than `nr_pubinputs`, fails the build.
+//!
+//! BLS12-381 and PLONK keys expand to a `Bls12Verifyingkey` and a
+//! `PlonkVerifyingkey`, which need the `bls12-381` and `plonk` features of
+//! `pinocchio-groth16`.
+
+use pinocchio_groth16::vk_parser::{parse_vk_json_to_rust_string_with_mode, CodegenMode};
+use proc_macro::TokenStream;
+use std::path::PathBuf;
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, Ident, LitInt, LitStr, Token};
+
+struct IncludeVk {
+    path: LitStr,
+    nr_pubinputs: Option<LitInt>,
+}
+
+impl Parse for IncludeVk {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let mut nr_pubinputs = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let name: Ident = input.parse()?;
+            if name != "nr_pubinputs" {
+                return Err(syn::Error::new(name.span(), "expected `nr_pubinputs`"));
+            }
+            input.parse::<Token![=]>()?;
+            nr_pubinputs = Some(input.parse()?);
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(IncludeVk { path, nr_pubinputs })
+    }
+}
+
+/// Expands to the verifying key of a snarkjs `verification_key.json`
+///
+/// # Arguments
+/// * A string literal, the path of the key relative to the crate's `Cargo.toml`
+/// * `nr_pubinputs = N` - Optional, fails the build if the key takes another number of inputs
+#[proc_macro]
+pub fn include_vk(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as IncludeVk);
+    match expand(&input) {
+        Ok(tokens) => tokens,
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &IncludeVk) -> syn::Result<TokenStream> {
+    let error = |message: String| syn::Error::new(input.path.span(), message);
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| error("CARGO_MANIFEST_DIR is not set".to_string()))?;
+    let path = PathBuf::from(manifest_dir).join(input.path.value());
+    let json_content = std::fs::read_to_string(&path)
+        .map_err(|e| error(format!("failed to read {}: {}", path.display(), e)))?;
+    let rust_code =
+        parse_vk_json_to_rust_string_with_mode(&json_content, CodegenMode::Deterministic)
+            .map_err(|e| error(format!("invalid verifying key {}: {}", path.display(), e)))?;
+
+    if let Some(expected) = &input.nr_pubinputs {
+        let expected: usize = expected.base10_parse()?;
+        let actual = rust_code
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("nr_pubinputs: "))
+            .and_then(|value| value.trim_end_matches(',').parse::<usize>().ok())
+            .ok_or_else(|| error("generated key has no nr_pubinputs".to_string()))?;
+        if actual != expected {
+            return Err(syn::Error::new(
+                input.nr_pubinputs.as_ref().unwrap().span(),
+                format!(
+                    "{} takes {} public inputs, expected {}",
+                    path.display(),
+                    actual,
+                    expected
+                ),
+            ));
+        }
+    }
+
+    // The `include_bytes!` makes cargo rebuild the caller when the key changes
+    let expanded = format!(
+        "{{ const _: &[u8] = include_bytes!({:?}); {} VERIFYINGKEY }}",
+        path.display().to_string(),
+        rust_code
+    );
+    expanded
+        .parse()
+        .map_err(|e| error(format!("failed to expand verifying key: {}", e)))
+}
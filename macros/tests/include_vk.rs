@@ -0,0 +1,29 @@
+use pinocchio_groth16::groth16::Groth16Verifyingkey;
+use pinocchio_groth16_macros::include_vk;
+
+const VERIFYINGKEY: Groth16Verifyingkey =
+    include_vk!("tests/fixtures/verification_key.json", nr_pubinputs = 1);
+
+#[test]
+fn include_vk_embeds_the_key() {
+    let field = |value: u8| {
+        let mut bytes = [0u8; 32];
+        bytes[31] = value;
+        bytes
+    };
+    assert_eq!(VERIFYINGKEY.nr_pubinputs, 1);
+    assert_eq!(
+        VERIFYINGKEY.vk_alpha_g1,
+        *[field(1), field(2)].as_flattened()
+    );
+    // G2 coordinates are ordered c1 | c0
+    assert_eq!(
+        VERIFYINGKEY.vk_beta_g2,
+        *[field(4), field(3), field(6), field(5)].as_flattened()
+    );
+    assert_eq!(VERIFYINGKEY.vk_ic.len(), 2);
+    assert_eq!(
+        VERIFYINGKEY,
+        include_vk!("tests/fixtures/verification_key.json")
+    );
+}
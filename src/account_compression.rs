@@ -0,0 +1,200 @@
+//! spl-account-compression integration
+//!
+//! Privacy pools usually verify a proof and then append the commitment it
+//! proves into a concurrent merkle tree. This module provides the account
+//! checks and the `append` CPI into spl-account-compression so the two steps
+//! can be done with one call to [`verify_and_append`].
+
+use crate::errors::Groth16Error;
+use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+use crate::Pubkey;
+use pinocchio::account_info::AccountInfo;
+use pinocchio::cpi::invoke_signed;
+use pinocchio::instruction::{AccountMeta, Instruction, Signer};
+
+/// spl-account-compression program id, `cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK`
+pub const SPL_ACCOUNT_COMPRESSION_ID: Pubkey = [
+    9, 42, 19, 238, 149, 196, 28, 186, 8, 166, 127, 90, 198, 126, 141, 247, 225, 218, 17, 98, 94,
+    29, 100, 19, 127, 143, 79, 35, 131, 3, 127, 20,
+];
+
+/// spl-noop program id, `noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV`
+pub const SPL_NOOP_ID: Pubkey = [
+    11, 188, 15, 192, 187, 71, 202, 47, 116, 196, 17, 46, 148, 171, 19, 207, 163, 198, 52, 229,
+    220, 23, 234, 203, 3, 205, 26, 35, 205, 126, 120, 124,
+];
+
+/// Anchor discriminator of the `append` instruction, `sha256("global:append")[..8]`
+pub const APPEND_DISCRIMINATOR: [u8; 8] = [149, 120, 18, 222, 236, 225, 88, 203];
+
+// CompressionAccountType::ConcurrentMerkleTree
+const CONCURRENT_MERKLE_TREE_ACCOUNT_TYPE: u8 = 1;
+// ConcurrentMerkleTreeHeaderData::V1
+const HEADER_VERSION_V1: u8 = 0;
+// account type (1) + version (1) + max buffer size (4) + max depth (4) + authority (32) + ...
+const HEADER_AUTHORITY_OFFSET: usize = 10;
+const HEADER_MIN_SIZE: usize = HEADER_AUTHORITY_OFFSET + 32;
+
+/// Returns the data of an `append` instruction for `leaf`
+pub fn append_instruction_data(leaf: &[u8; 32]) -> [u8; 40] {
+    let mut data = [0u8; 40];
+    data[..8].copy_from_slice(&APPEND_DISCRIMINATOR);
+    data[8..].copy_from_slice(leaf);
+    data
+}
+
+/// Checks the header of a concurrent merkle tree account
+///
+/// # Arguments
+/// * `data` - Account data of the tree
+/// * `authority` - Expected tree authority, usually a PDA of the calling program
+pub fn check_merkle_tree_data(data: &[u8], authority: &Pubkey) -> Result<(), Groth16Error> {
+    if data.len() < HEADER_MIN_SIZE
+        || data[0] != CONCURRENT_MERKLE_TREE_ACCOUNT_TYPE
+        || data[1] != HEADER_VERSION_V1
+    {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    if data[HEADER_AUTHORITY_OFFSET..HEADER_MIN_SIZE] != authority[..] {
+        return Err(Groth16Error::InvalidAuthority);
+    }
+    Ok(())
+}
+
+/// Checks the merkle tree account, its authority and the noop program account
+pub fn check_merkle_tree_account(
+    merkle_tree: &AccountInfo,
+    authority: &AccountInfo,
+    noop_program: &AccountInfo,
+) -> Result<(), Groth16Error> {
+    if !merkle_tree.is_owned_by(&SPL_ACCOUNT_COMPRESSION_ID) {
+        return Err(Groth16Error::InvalidAccountOwner);
+    }
+    if !merkle_tree.is_writable() {
+        return Err(Groth16Error::AccountNotWritable);
+    }
+    if noop_program.key() != &SPL_NOOP_ID {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    let data = merkle_tree
+        .try_borrow_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    check_merkle_tree_data(&data, authority.key())
+}
+
+/// Appends `leaf` to the tree via CPI
+///
+/// # Arguments
+/// * `merkle_tree` - The concurrent merkle tree account
+/// * `authority` - Tree authority, signs with `signers` if it is a PDA
+/// * `noop_program` - The spl-noop program, receives the changelog event
+/// * `compression_program` - The spl-account-compression program account
+/// * `leaf` - The leaf to append
+/// * `signers` - Seeds of the authority PDA, empty if the authority signed the transaction
+pub fn append_leaf(
+    merkle_tree: &AccountInfo,
+    authority: &AccountInfo,
+    noop_program: &AccountInfo,
+    compression_program: &AccountInfo,
+    leaf: &[u8; 32],
+    signers: &[Signer],
+) -> Result<(), Groth16Error> {
+    check_merkle_tree_account(merkle_tree, authority, noop_program)?;
+    if compression_program.key() != &SPL_ACCOUNT_COMPRESSION_ID {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+
+    let data = append_instruction_data(leaf);
+    let accounts = [
+        AccountMeta::writable(merkle_tree.key()),
+        AccountMeta::readonly_signer(authority.key()),
+        AccountMeta::readonly(noop_program.key()),
+    ];
+    let instruction = Instruction {
+        program_id: &SPL_ACCOUNT_COMPRESSION_ID,
+        data: &data,
+        accounts: &accounts,
+    };
+    invoke_signed(
+        &instruction,
+        &[merkle_tree, authority, noop_program],
+        signers,
+    )
+    .map_err(|_| Groth16Error::CpiFailed)
+}
+
+/// Verifies a proof and appends the public input at `leaf_index` to the tree
+///
+/// The leaf is only appended if verification succeeds, so the tree only ever
+/// contains proven commitments.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_and_append<const NR_INPUTS: usize>(
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+    verifyingkey: &Groth16Verifyingkey,
+    leaf_index: usize,
+    merkle_tree: &AccountInfo,
+    authority: &AccountInfo,
+    noop_program: &AccountInfo,
+    compression_program: &AccountInfo,
+    signers: &[Signer],
+) -> Result<(), Groth16Error> {
+    let leaf = public_inputs
+        .get(leaf_index)
+        .ok_or(Groth16Error::InvalidPublicInputsLength)?;
+    Groth16Verifier::new(proof_a, proof_b, proof_c, public_inputs, verifyingkey)?.verify()?;
+    append_leaf(
+        merkle_tree,
+        authority,
+        noop_program,
+        compression_program,
+        leaf,
+        signers,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree_header(authority: &Pubkey) -> Vec<u8> {
+        let mut data = vec![0u8; 128];
+        data[0] = CONCURRENT_MERKLE_TREE_ACCOUNT_TYPE;
+        data[1] = HEADER_VERSION_V1;
+        data[2..6].copy_from_slice(&64u32.to_le_bytes());
+        data[6..10].copy_from_slice(&26u32.to_le_bytes());
+        data[10..42].copy_from_slice(authority);
+        data
+    }
+
+    #[test]
+    fn merkle_tree_header_checks() {
+        let authority = [5u8; 32];
+        let data = tree_header(&authority);
+        check_merkle_tree_data(&data, &authority).unwrap();
+        assert_eq!(
+            check_merkle_tree_data(&data, &[6u8; 32]),
+            Err(Groth16Error::InvalidAuthority)
+        );
+
+        let mut uninitialized = data.clone();
+        uninitialized[0] = 0;
+        assert_eq!(
+            check_merkle_tree_data(&uninitialized, &authority),
+            Err(Groth16Error::InvalidAccountData)
+        );
+        assert_eq!(
+            check_merkle_tree_data(&data[..41], &authority),
+            Err(Groth16Error::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn append_data_layout() {
+        let data = append_instruction_data(&[7u8; 32]);
+        assert_eq!(data[..8], APPEND_DISCRIMINATOR);
+        assert_eq!(data[8..], [7u8; 32]);
+    }
+}
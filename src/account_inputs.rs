@@ -0,0 +1,166 @@
+//! Public inputs read from account data
+//!
+//! A proof over a merkle root or commitment only means something if the root
+//! is the one stored on chain. When the root is passed as instruction data a
+//! caller can substitute any root it has a proof for. These helpers read the
+//! value from a designated account instead, after checking the account owner
+//! and discriminator, and place it into the public inputs before verifying.
+
+use crate::errors::Groth16Error;
+use crate::groth16::is_less_than_bn254_field_size_be;
+#[cfg(feature = "pinocchio")]
+use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+use crate::Pubkey;
+#[cfg(feature = "pinocchio")]
+use pinocchio::account_info::AccountInfo;
+
+/// Location of a 32 byte public input in an account
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountInput<'a> {
+    /// Program that must own the account
+    pub owner: &'a Pubkey,
+    /// Bytes the account data must start with, may be empty
+    pub discriminator: &'a [u8],
+    /// Offset of the big-endian input in the account data
+    pub offset: usize,
+}
+
+impl AccountInput<'_> {
+    /// Reads the input from account data, the owner is not checked
+    pub fn read_from_data(&self, data: &[u8]) -> Result<[u8; 32], Groth16Error> {
+        if !data.starts_with(self.discriminator) {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        let end = self
+            .offset
+            .checked_add(32)
+            .ok_or(Groth16Error::InvalidAccountData)?;
+        let input: [u8; 32] = data
+            .get(self.offset..end)
+            .ok_or(Groth16Error::InvalidAccountData)?
+            .try_into()
+            .unwrap();
+        if !is_less_than_bn254_field_size_be(&input) {
+            return Err(Groth16Error::PublicInputGreaterThanFieldSize);
+        }
+        Ok(input)
+    }
+
+    /// Reads the input from an account after checking its owner
+    #[cfg(feature = "pinocchio")]
+    pub fn read(&self, account: &AccountInfo) -> Result<[u8; 32], Groth16Error> {
+        if !account.is_owned_by(self.owner) {
+            return Err(Groth16Error::InvalidAccountOwner);
+        }
+        let data = account
+            .try_borrow_data()
+            .map_err(|_| Groth16Error::InvalidAccountData)?;
+        self.read_from_data(&data)
+    }
+}
+
+/// Overwrites `public_inputs[index]` with the value stored in `account`
+///
+/// # Arguments
+/// * `public_inputs` - Public inputs, the input at `index` is replaced
+/// * `index` - Position of the account bound input in the circuit
+/// * `account` - The account storing the input
+/// * `source` - Owner, discriminator and offset of the input
+#[cfg(feature = "pinocchio")]
+pub fn bind_account_input<const NR_INPUTS: usize>(
+    public_inputs: &mut [[u8; 32]; NR_INPUTS],
+    index: usize,
+    account: &AccountInfo,
+    source: &AccountInput,
+) -> Result<(), Groth16Error> {
+    let slot = public_inputs
+        .get_mut(index)
+        .ok_or(Groth16Error::InvalidPublicInputsLength)?;
+    *slot = source.read(account)?;
+    Ok(())
+}
+
+/// Checks that `public_inputs[index]` equals the value stored in account data
+///
+/// # Returns
+/// * `Err(Groth16Error::PublicInputMismatch)` - If the caller supplied input
+///   differs from the account
+pub fn check_account_input_data<const NR_INPUTS: usize>(
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+    index: usize,
+    data: &[u8],
+    source: &AccountInput,
+) -> Result<(), Groth16Error> {
+    let input = public_inputs
+        .get(index)
+        .ok_or(Groth16Error::InvalidPublicInputsLength)?;
+    if *input != source.read_from_data(data)? {
+        return Err(Groth16Error::PublicInputMismatch);
+    }
+    Ok(())
+}
+
+/// Verifies a proof whose public input at `index` is read from `account`
+///
+/// The value at `index` of `public_inputs` is ignored and replaced.
+#[allow(clippy::too_many_arguments)]
+#[cfg(feature = "pinocchio")]
+pub fn verify_with_account_input<const NR_INPUTS: usize>(
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    mut public_inputs: [[u8; 32]; NR_INPUTS],
+    index: usize,
+    account: &AccountInfo,
+    source: &AccountInput,
+    verifyingkey: &Groth16Verifyingkey,
+) -> Result<(), Groth16Error> {
+    bind_account_input(&mut public_inputs, index, account, source)?;
+    Groth16Verifier::new(proof_a, proof_b, proof_c, &public_inputs, verifyingkey)?.verify()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_and_check_account_input() {
+        let owner = [9u8; 32];
+        let source = AccountInput {
+            owner: &owner,
+            discriminator: b"pool",
+            offset: 8,
+        };
+        let mut data = vec![0u8; 40];
+        data[..4].copy_from_slice(b"pool");
+        data[8..40].copy_from_slice(&[3u8; 32]);
+        assert_eq!(source.read_from_data(&data), Ok([3u8; 32]));
+
+        let inputs = [[1u8; 32], [3u8; 32]];
+        check_account_input_data(&inputs, 1, &data, &source).unwrap();
+        assert_eq!(
+            check_account_input_data(&inputs, 0, &data, &source),
+            Err(Groth16Error::PublicInputMismatch)
+        );
+        assert_eq!(
+            check_account_input_data(&inputs, 2, &data, &source),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+
+        assert_eq!(
+            source.read_from_data(&data[..39]),
+            Err(Groth16Error::InvalidAccountData)
+        );
+        data[0] = b'x';
+        assert_eq!(
+            source.read_from_data(&data),
+            Err(Groth16Error::InvalidAccountData)
+        );
+        data[..4].copy_from_slice(b"pool");
+        data[8..40].copy_from_slice(&[0xffu8; 32]);
+        assert_eq!(
+            source.read_from_data(&data),
+            Err(Groth16Error::PublicInputGreaterThanFieldSize)
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Conversions between arkworks' Groth16 types and the verifier's encoding
+//!
+//! The verifier takes points big-endian with G2 coordinates ordered
+//! `c1 | c0` and proof a negated, arkworks serializes them little-endian,
+//! `c0 | c1`, and keeps a as proven. A service proving with ark-groth16
+//! converts its proof with [`Proof::from`], the verifying key with
+//! [`verifying_key_to_bytes`]:
+//!
+//! ```rust,ignore
+//! let proof = Proof::from(&Groth16::<Bn254>::prove(&pk, circuit, &mut rng)?);
+//! let vk_bytes = verifying_key_to_bytes(&pk.vk)?;
+//! proof.verify(&public_inputs, &Groth16Verifyingkey::from_bytes(&vk_bytes)?)?;
+//! ```
+//!
+//! The conversions back, `TryFrom<&Proof>` and `TryFrom<&Groth16Verifyingkey>`,
+//! check that every point is on the curve and in the subgroup.
+
+use crate::bn254::convert_endianness;
+use crate::errors::Groth16Error;
+use crate::groth16::Groth16Verifyingkey;
+use crate::proof::{Proof, ProofA, ProofB, ProofC};
+use crate::validation::{validate_g1, validate_g2};
+use alloc::vec::Vec;
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{CanonicalSerialize, Compress};
+
+/// `x | y` big-endian, zero for the point at infinity
+pub fn g1_to_be(point: &G1Affine) -> [u8; 64] {
+    let mut le = [0u8; 64];
+    point
+        .x
+        .serialize_with_mode(&mut le[..32], Compress::No)
+        .expect("32 bytes");
+    point
+        .y
+        .serialize_with_mode(&mut le[32..], Compress::No)
+        .expect("32 bytes");
+    convert_endianness::<32, 64>(&le)
+}
+
+/// `x.c1 | x.c0 | y.c1 | y.c0` big-endian, zero for the point at infinity
+pub fn g2_to_be(point: &G2Affine) -> [u8; 128] {
+    let mut le = [0u8; 128];
+    point
+        .x
+        .serialize_with_mode(&mut le[..64], Compress::No)
+        .expect("64 bytes");
+    point
+        .y
+        .serialize_with_mode(&mut le[64..], Compress::No)
+        .expect("64 bytes");
+    convert_endianness::<64, 128>(&le)
+}
+
+/// The G1 point of [`g1_to_be`], see [`validate_g1`] for the errors
+pub fn g1_from_be(bytes: &[u8; 64]) -> Result<G1Affine, Groth16Error> {
+    validate_g1(bytes)?;
+    if *bytes == [0u8; 64] {
+        return Ok(G1Affine::zero());
+    }
+    Ok(G1Affine::new_unchecked(
+        Fq::from_be_bytes_mod_order(&bytes[..32]),
+        Fq::from_be_bytes_mod_order(&bytes[32..]),
+    ))
+}
+
+/// The G2 point of [`g2_to_be`], see [`validate_g2`] for the errors
+pub fn g2_from_be(bytes: &[u8; 128]) -> Result<G2Affine, Groth16Error> {
+    validate_g2(bytes)?;
+    if *bytes == [0u8; 128] {
+        return Ok(G2Affine::zero());
+    }
+    let fq2 = |bytes: &[u8]| {
+        Fq2::new(
+            Fq::from_be_bytes_mod_order(&bytes[32..]),
+            Fq::from_be_bytes_mod_order(&bytes[..32]),
+        )
+    };
+    Ok(G2Affine::new_unchecked(
+        fq2(&bytes[..64]),
+        fq2(&bytes[64..]),
+    ))
+}
+
+/// A public input big-endian
+pub fn fr_to_be(value: &Fr) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&value.into_bigint().to_bytes_be());
+    bytes
+}
+
+/// Negates proof a
+impl From<&ark_groth16::Proof<Bn254>> for Proof {
+    fn from(proof: &ark_groth16::Proof<Bn254>) -> Self {
+        Proof {
+            a: ProofA(g1_to_be(&(-proof.a))),
+            b: ProofB(g2_to_be(&proof.b)),
+            c: ProofC(g1_to_be(&proof.c)),
+        }
+    }
+}
+
+/// Negates proof a back
+impl TryFrom<&Proof> for ark_groth16::Proof<Bn254> {
+    type Error = Groth16Error;
+
+    fn try_from(proof: &Proof) -> Result<Self, Groth16Error> {
+        Ok(ark_groth16::Proof {
+            a: -g1_from_be(&proof.a.0)?,
+            b: g2_from_be(&proof.b.0)?,
+            c: g1_from_be(&proof.c.0)?,
+        })
+    }
+}
+
+impl TryFrom<&Groth16Verifyingkey<'_>> for ark_groth16::VerifyingKey<Bn254> {
+    type Error = Groth16Error;
+
+    fn try_from(vk: &Groth16Verifyingkey) -> Result<Self, Groth16Error> {
+        Ok(ark_groth16::VerifyingKey {
+            alpha_g1: g1_from_be(&vk.vk_alpha_g1)?,
+            beta_g2: g2_from_be(&vk.vk_beta_g2)?,
+            gamma_g2: g2_from_be(&vk.vk_gamma_g2)?,
+            delta_g2: g2_from_be(&vk.vk_delta_g2)?,
+            gamma_abc_g1: vk.vk_ic.iter().map(g1_from_be).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// The binary encoding of `vk`, borrowed by [`Groth16Verifyingkey::from_bytes`]
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidPublicInputsLength)` - If `gamma_abc_g1` is empty
+pub fn verifying_key_to_bytes(
+    vk: &ark_groth16::VerifyingKey<Bn254>,
+) -> Result<Vec<u8>, Groth16Error> {
+    let ic: Vec<[u8; 64]> = vk.gamma_abc_g1.iter().map(g1_to_be).collect();
+    Groth16Verifyingkey {
+        nr_pubinputs: ic.len().saturating_sub(1),
+        vk_alpha_g1: g1_to_be(&vk.alpha_g1),
+        vk_beta_g2: g2_to_be(&vk.beta_g2),
+        vk_gamma_g2: g2_to_be(&vk.gamma_g2),
+        vk_delta_g2: g2_to_be(&vk.delta_g2),
+        vk_ic: &ic,
+    }
+    .to_bytes()
+}
+
+#[cfg(all(test, feature = "test-vectors"))]
+mod tests {
+    use super::*;
+    use crate::proof::PublicInputs;
+    use crate::test_vectors::generate;
+    use ark_groth16::Groth16;
+
+    #[test]
+    fn proofs_and_keys_roundtrip() {
+        let vector = generate(0);
+        let proof = Proof {
+            a: ProofA(vector.proof_a),
+            b: ProofB(vector.proof_b),
+            c: ProofC(vector.proof_c),
+        };
+        let ark_proof = ark_groth16::Proof::<Bn254>::try_from(&proof).unwrap();
+        assert_eq!(Proof::from(&ark_proof), proof);
+        let ark_vk = ark_groth16::VerifyingKey::<Bn254>::try_from(&vector.verifying_key()).unwrap();
+        let vk_bytes = verifying_key_to_bytes(&ark_vk).unwrap();
+        let vk = Groth16Verifyingkey::from_bytes(&vk_bytes).unwrap();
+        assert_eq!(vk, vector.verifying_key());
+
+        // Both verifiers accept the converted proof
+        let inputs: Vec<Fr> = vector
+            .public_inputs
+            .iter()
+            .map(|input| Fr::from_be_bytes_mod_order(input))
+            .collect();
+        assert_eq!(
+            inputs.iter().map(fr_to_be).collect::<Vec<_>>(),
+            vector.public_inputs
+        );
+        let pvk = ark_groth16::prepare_verifying_key(&ark_vk);
+        assert!(Groth16::<Bn254>::verify_proof(&pvk, &ark_proof, &inputs).unwrap());
+        let public_inputs =
+            PublicInputs::<2>::new(vector.public_inputs.clone().try_into().unwrap()).unwrap();
+        proof.verify(&public_inputs, &vk).unwrap();
+
+        let mut off_curve = proof;
+        off_curve.c.0[63] ^= 1;
+        assert_eq!(
+            ark_groth16::Proof::<Bn254>::try_from(&off_curve),
+            Err(Groth16Error::PointNotOnCurve)
+        );
+    }
+}
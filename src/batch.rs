@@ -0,0 +1,414 @@
+//! Challenges for randomized batch verification
+//!
+//! A batch of Groth16 proofs is checked with a single pairing product by
+//! scaling the check of the i-th proof with a challenge `r_i`. If any proof in
+//! the batch is invalid, the combined check passes only if the challenges
+//! happen to cancel the failing terms, which for challenges drawn uniformly
+//! from `2^128` values has a probability of at most `2^-128`, by the
+//! Schwartz-Zippel lemma over the exponents of the target group. That bound
+//! only holds if the prover cannot choose the proofs after learning the
+//! challenges.
+//!
+//! [`batch_challenges`] derives them Fiat-Shamir style: the seed is the
+//! keccak256 hash of a domain separator, the verifying key hash, the number of
+//! proofs and every proof and public input of the batch. Changing any bit of
+//! the batch changes all challenges, so a prover trying to sneak in a forged
+//! proof has to grind the hash and succeeds with probability `2^-128` per
+//! attempt, as if the challenges were random.
+//!
+//! The seed also includes the most recent entry of the `SlotHashes` sysvar,
+//! read with [`recent_slot_hash`]. It does not carry the soundness argument,
+//! the slot leader has some influence on it, but it makes the challenges of
+//! a batch unknown until the transaction executes, so grinding cannot be done
+//! offline ahead of time against a fixed set of challenges. Callers must not
+//! derive challenges from the slot hash alone: without the proofs in the
+//! hash, a submitter who knows the slot hash can adapt the proofs to it.
+//!
+//! [`verify_batch`] performs the combined check. Proofs sharing the same b,
+//! as produced by some batching provers, are detected and their a points
+//! summed before the pairing, so they cost a single pair together:
+//! `e(r_1 a_1, b) * e(r_2 a_2, b) = e(r_1 a_1 + r_2 a_2, b)`.
+//!
+//! ```rust,ignore
+//! let slot_hash = recent_slot_hash(slot_hashes_account)?;
+//! let challenges = batch_challenges(&slot_hash, &vk_hash(&VERIFYINGKEY), &proofs);
+//! verify_batch(&proofs, &challenges, &VERIFYINGKEY)?;
+//! ```
+//!
+//! [`Groth16BatchVerifier`] does both for proofs collected one by one:
+//!
+//! ```rust,ignore
+//! let mut batch = Groth16BatchVerifier::new(&VERIFYINGKEY);
+//! for (proof_a, proof_b, proof_c, public_inputs) in &proofs {
+//!     batch.push(proof_a, proof_b, proof_c, public_inputs);
+//! }
+//! batch.verify(&recent_slot_hash(slot_hashes_account)?)?;
+//! ```
+
+use crate::curve::{Bn254, PairingCurve};
+use crate::errors::Groth16Error;
+use crate::groth16::Groth16Verifyingkey;
+use crate::hash::keccak256;
+use crate::recursion::vk_hash;
+use alloc::vec::Vec;
+use ark_ff::{BigInteger, PrimeField};
+#[cfg(feature = "pinocchio")]
+use pinocchio::account_info::AccountInfo;
+#[cfg(feature = "pinocchio")]
+use pinocchio::sysvars::slot_hashes::SlotHashes;
+
+/// Domain separator of the challenge seed
+pub const BATCH_CHALLENGE_DOMAIN: &[u8] = b"pinocchio-groth16/batch-challenges/v1";
+
+/// A proof and its public inputs, big-endian with proof a negated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchProof<'a, const NR_INPUTS: usize> {
+    pub proof_a: &'a [u8; 64],
+    pub proof_b: &'a [u8; 128],
+    pub proof_c: &'a [u8; 64],
+    pub public_inputs: &'a [[u8; 32]; NR_INPUTS],
+}
+
+/// Hash of the most recent slot from the `SlotHashes` sysvar account
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidAccountData)` - If `account` is not the
+///   `SlotHashes` sysvar or has no entries
+#[cfg(feature = "pinocchio")]
+pub fn recent_slot_hash(account: &AccountInfo) -> Result<[u8; 32], Groth16Error> {
+    let slot_hashes =
+        SlotHashes::from_account_info(account).map_err(|_| Groth16Error::InvalidAccountData)?;
+    slot_hashes
+        .get_entry(0)
+        .map(|entry| entry.hash)
+        .ok_or(Groth16Error::InvalidAccountData)
+}
+
+/// Seed the challenges of a batch are expanded from
+///
+/// # Arguments
+/// * `slot_hash` - Recent slot hash, see [`recent_slot_hash`]
+/// * `vk_hash` - Hash of the verifying key, e.g. [`crate::recursion::vk_hash`]
+/// * `proofs` - All proofs of the batch, in verification order
+pub fn batch_seed<const NR_INPUTS: usize>(
+    slot_hash: &[u8; 32],
+    vk_hash: &[u8; 32],
+    proofs: &[BatchProof<NR_INPUTS>],
+) -> [u8; 32] {
+    let nr_proofs = (proofs.len() as u64).to_le_bytes();
+    let nr_inputs = (NR_INPUTS as u64).to_le_bytes();
+    let mut vals: Vec<&[u8]> = Vec::with_capacity(5 + proofs.len() * (3 + NR_INPUTS));
+    vals.extend_from_slice(&[
+        BATCH_CHALLENGE_DOMAIN,
+        slot_hash,
+        vk_hash,
+        &nr_proofs,
+        &nr_inputs,
+    ]);
+    for proof in proofs {
+        vals.extend_from_slice(&[proof.proof_a, proof.proof_b, proof.proof_c]);
+        vals.extend(proof.public_inputs.iter().map(|input| input.as_slice()));
+    }
+    keccak256(&vals)
+}
+
+/// The `index`-th 128-bit challenge of `seed`, as a big-endian scalar
+pub fn batch_challenge(seed: &[u8; 32], index: u32) -> [u8; 32] {
+    let hash = keccak256(&[seed, &index.to_le_bytes()[..]]);
+    let mut challenge = [0u8; 32];
+    challenge[16..].copy_from_slice(&hash[..16]);
+    challenge
+}
+
+/// One challenge per proof, see the module documentation for the security argument
+///
+/// # Returns
+/// Big-endian scalars smaller than `2^128`, in the order of `proofs`
+pub fn batch_challenges<const NR_INPUTS: usize>(
+    slot_hash: &[u8; 32],
+    vk_hash: &[u8; 32],
+    proofs: &[BatchProof<NR_INPUTS>],
+) -> Vec<[u8; 32]> {
+    let seed = batch_seed(slot_hash, vk_hash, proofs);
+    (0..proofs.len() as u32)
+        .map(|index| batch_challenge(&seed, index))
+        .collect()
+}
+
+/// Number of pairs of the pairing [`verify_batch`] computes for `proofs`
+///
+/// One pair per distinct proof b, plus three for the inputs, c and alpha.
+pub fn batch_pairing_count<const NR_INPUTS: usize>(proofs: &[BatchProof<NR_INPUTS>]) -> usize {
+    let distinct_b = proofs
+        .iter()
+        .enumerate()
+        .filter(|(i, proof)| !proofs[..*i].iter().any(|p| p.proof_b == proof.proof_b))
+        .count();
+    distinct_b + 3
+}
+
+fn scalar_to_be(scalar: ark_bn254::Fr) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&scalar.into_bigint().to_bytes_be());
+    bytes
+}
+
+/// Verifies all `proofs` with one pairing, scaling the i-th proof by `challenges[i]`
+///
+/// Accepts invalid proofs with probability at most `2^-128` if the challenges
+/// are derived with [`batch_challenges`].
+///
+/// # Arguments
+/// * `proofs` - Proofs under `verifyingkey`, proof a negated
+/// * `challenges` - One nonzero challenge per proof, e.g. from [`batch_challenges`]
+/// * `verifyingkey` - The verifying key shared by all proofs
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidPublicInputsLength)` - If the batch is empty,
+///   the number of challenges differs or the key takes another number of inputs
+/// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If a public input
+///   is not smaller than the field size
+/// * `Err(Groth16Error::ProofVerificationFailed)` - If the combined check fails
+pub fn verify_batch<const NR_INPUTS: usize>(
+    proofs: &[BatchProof<NR_INPUTS>],
+    challenges: &[[u8; 32]],
+    verifyingkey: &Groth16Verifyingkey,
+) -> Result<(), Groth16Error> {
+    if proofs.is_empty()
+        || proofs.len() != challenges.len()
+        || verifyingkey.vk_ic.len() != NR_INPUTS + 1
+    {
+        return Err(Groth16Error::InvalidPublicInputsLength);
+    }
+
+    // sum_i r_i * (ic_0 + sum_j x_ij ic_j+1) = (sum_i r_i) ic_0 + sum_j (sum_i r_i x_ij) ic_j+1,
+    // so the inputs cost NR_INPUTS + 1 multiplications regardless of the batch size
+    let mut challenge_sum = ark_bn254::Fr::from(0u8);
+    let mut input_sums = [ark_bn254::Fr::from(0u8); NR_INPUTS];
+    for (proof, challenge) in proofs.iter().zip(challenges) {
+        let challenge = ark_bn254::Fr::from_be_bytes_mod_order(challenge);
+        challenge_sum += challenge;
+        for (sum, input) in input_sums.iter_mut().zip(proof.public_inputs) {
+            if !Bn254::is_less_than_scalar_field_size_be(input) {
+                return Err(Groth16Error::PublicInputGreaterThanFieldSize);
+            }
+            *sum += challenge * ark_bn254::Fr::from_be_bytes_mod_order(input);
+        }
+    }
+    let challenge_sum = scalar_to_be(challenge_sum);
+    let mut prepared_inputs = Bn254::g1_mul(&verifyingkey.vk_ic[0], &challenge_sum)?;
+    for (sum, ic) in input_sums.iter().zip(&verifyingkey.vk_ic[1..]) {
+        let product = Bn254::g1_mul(ic, &scalar_to_be(*sum))?;
+        prepared_inputs = Bn254::g1_add(&prepared_inputs, &product)?;
+    }
+    let alpha = Bn254::g1_mul(&verifyingkey.vk_alpha_g1, &challenge_sum)?;
+
+    // Proofs with the same b share a pair
+    let mut combined_c = Bn254::G1_IDENTITY;
+    let mut pairs: Vec<([u8; 64], &[u8; 128])> = Vec::with_capacity(proofs.len());
+    for (proof, challenge) in proofs.iter().zip(challenges) {
+        let scaled_c = Bn254::g1_mul(proof.proof_c, challenge)?;
+        combined_c = Bn254::g1_add(&combined_c, &scaled_c)?;
+        let scaled_a = Bn254::g1_mul(proof.proof_a, challenge)?;
+        match pairs.iter_mut().find(|(_, b)| *b == proof.proof_b) {
+            Some((a, _)) => *a = Bn254::g1_add(a, &scaled_a)?,
+            None => pairs.push((scaled_a, proof.proof_b)),
+        }
+    }
+
+    let mut g1_points: Vec<&[u8; 64]> = pairs.iter().map(|(a, _)| a).collect();
+    let mut g2_points: Vec<&[u8; 128]> = pairs.iter().map(|(_, b)| *b).collect();
+    g1_points.extend([&prepared_inputs, &combined_c, &alpha]);
+    g2_points.extend([
+        &verifyingkey.vk_gamma_g2,
+        &verifyingkey.vk_delta_g2,
+        &verifyingkey.vk_beta_g2,
+    ]);
+    if !Bn254::pairing_is_one(&g1_points, &g2_points)? {
+        return Err(Groth16Error::ProofVerificationFailed);
+    }
+    Ok(())
+}
+
+/// Collects proofs under one verifying key and verifies them with one pairing
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Groth16BatchVerifier<'a, const NR_INPUTS: usize> {
+    proofs: Vec<BatchProof<'a, NR_INPUTS>>,
+    verifyingkey: &'a Groth16Verifyingkey<'a>,
+}
+
+impl<'a, const NR_INPUTS: usize> Groth16BatchVerifier<'a, NR_INPUTS> {
+    /// Starts an empty batch of proofs under `verifyingkey`
+    pub fn new(verifyingkey: &'a Groth16Verifyingkey<'a>) -> Self {
+        Groth16BatchVerifier {
+            proofs: Vec::new(),
+            verifyingkey,
+        }
+    }
+
+    /// Adds a proof, proof a negated
+    pub fn push(
+        &mut self,
+        proof_a: &'a [u8; 64],
+        proof_b: &'a [u8; 128],
+        proof_c: &'a [u8; 64],
+        public_inputs: &'a [[u8; 32]; NR_INPUTS],
+    ) {
+        self.proofs.push(BatchProof {
+            proof_a,
+            proof_b,
+            proof_c,
+            public_inputs,
+        });
+    }
+
+    /// The proofs added so far
+    pub fn proofs(&self) -> &[BatchProof<'a, NR_INPUTS>] {
+        &self.proofs
+    }
+
+    pub fn len(&self) -> usize {
+        self.proofs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proofs.is_empty()
+    }
+
+    /// Number of pairs of the pairing, see [`batch_pairing_count`]
+    pub fn pairing_count(&self) -> usize {
+        batch_pairing_count(&self.proofs)
+    }
+
+    /// Verifies the batch with challenges from [`batch_challenges`]
+    ///
+    /// # Arguments
+    /// * `slot_hash` - Recent slot hash, see [`recent_slot_hash`]
+    pub fn verify(&self, slot_hash: &[u8; 32]) -> Result<(), Groth16Error> {
+        let challenges = batch_challenges(slot_hash, &vk_hash(self.verifyingkey), &self.proofs);
+        self.verify_with_challenges(&challenges)
+    }
+
+    /// Verifies the batch with caller-provided challenges, see [`verify_batch`]
+    pub fn verify_with_challenges(&self, challenges: &[[u8; 32]]) -> Result<(), Groth16Error> {
+        verify_batch(&self.proofs, challenges, self.verifyingkey)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn challenges_bind_every_proof_and_the_slot_hash() {
+        let inputs = [[1u8; 32], [2u8; 32]];
+        let other_inputs = [[1u8; 32], [3u8; 32]];
+        let proof = BatchProof {
+            proof_a: &[4u8; 64],
+            proof_b: &[5u8; 128],
+            proof_c: &[6u8; 64],
+            public_inputs: &inputs,
+        };
+        let proofs = [proof, proof];
+        let challenges = batch_challenges(&[7u8; 32], &[8u8; 32], &proofs);
+        assert_eq!(challenges.len(), 2);
+        assert_ne!(challenges[0], challenges[1]);
+        assert!(challenges.iter().all(|c| c[..16] == [0u8; 16]));
+
+        let tampered = [
+            proof,
+            BatchProof {
+                public_inputs: &other_inputs,
+                ..proof
+            },
+        ];
+        for other in [
+            batch_challenges(&[7u8; 32], &[8u8; 32], &tampered),
+            batch_challenges(&[9u8; 32], &[8u8; 32], &proofs),
+            batch_challenges(&[7u8; 32], &[9u8; 32], &proofs),
+            batch_challenges(&[7u8; 32], &[8u8; 32], &proofs[..1]),
+        ] {
+            assert_ne!(other[0], challenges[0]);
+        }
+    }
+
+    #[cfg(feature = "test-vectors")]
+    #[test]
+    fn batches_merge_proofs_with_the_same_b() {
+        let vector = crate::test_vectors::generate(0);
+        let other = vector.rerandomize(1);
+        let vk = vector.verifying_key();
+        let inputs: [[u8; 32]; 2] = vector.public_inputs.clone().try_into().unwrap();
+        let first = BatchProof {
+            proof_a: &vector.proof_a,
+            proof_b: &vector.proof_b,
+            proof_c: &vector.proof_c,
+            public_inputs: &inputs,
+        };
+        let second = BatchProof {
+            proof_a: &other.proof_a,
+            proof_b: &other.proof_b,
+            proof_c: &other.proof_c,
+            public_inputs: &inputs,
+        };
+        let proofs = [first, second, first];
+        assert_eq!(batch_pairing_count(&proofs), 5);
+        let challenges = batch_challenges(&[1u8; 32], &[2u8; 32], &proofs);
+        verify_batch(&proofs, &challenges, &vk).unwrap();
+
+        let wrong_inputs = [inputs[1], inputs[0]];
+        let wrong = BatchProof {
+            public_inputs: &wrong_inputs,
+            ..first
+        };
+        let proofs = [first, wrong];
+        assert_eq!(batch_pairing_count(&proofs), 4);
+        assert_eq!(
+            verify_batch(&proofs, &challenges[..2], &vk),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+        assert_eq!(
+            verify_batch(&proofs, &challenges, &vk),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+    }
+
+    #[cfg(feature = "test-vectors")]
+    #[test]
+    fn batch_verifier_collects_proofs() {
+        let vectors: Vec<_> = (0..2).map(crate::test_vectors::generate).collect();
+        let inputs: Vec<[[u8; 32]; 2]> = vectors
+            .iter()
+            .map(|vector| vector.public_inputs.clone().try_into().unwrap())
+            .collect();
+        let vk = vectors[0].verifying_key();
+        let mut batch = Groth16BatchVerifier::new(&vk);
+        assert_eq!(
+            batch.verify(&[1u8; 32]),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+        // Proofs of the same key only
+        let same_key = vectors[0].rerandomize(1);
+        for vector in [&vectors[0], &same_key] {
+            batch.push(
+                &vector.proof_a,
+                &vector.proof_b,
+                &vector.proof_c,
+                &inputs[0],
+            );
+        }
+        assert_eq!((batch.len(), batch.pairing_count()), (2, 5));
+        batch.verify(&[1u8; 32]).unwrap();
+
+        batch.push(
+            &vectors[1].proof_a,
+            &vectors[1].proof_b,
+            &vectors[1].proof_c,
+            &inputs[1],
+        );
+        assert_eq!(
+            batch.verify(&[1u8; 32]),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! bellman and bellperson proofs over BLS12-381
+//!
+//! bellman and its Filecoin fork bellperson serialize a proof as the
+//! compressed points `a | b | c`, 48 + 96 + 48 = 192 bytes, in the Zcash
+//! encoding: the first byte of each point carries the compression flag
+//! `0x80`, the infinity flag `0x40` and the sort flag `0x20` of the y
+//! coordinate, and G2 coordinates are ordered `c1 | c0`. Verifying keys are
+//! written uncompressed as `alpha_g1 | beta_g1 | beta_g2 | gamma_g2 |
+//! delta_g1 | delta_g2 | nr_ic u32 BE | ic`.
+//!
+//! [`convert_bellman_proof`] decompresses and checks the points and negates
+//! a, so the result goes straight into
+//! [`Bls12Verifier`](crate::bls12_381::Bls12Verifier). bellman encodes
+//! scalars little-endian, [`convert_bellman_public_inputs`] reverses them
+//! to the big-endian inputs of the verifier.
+
+use crate::bls12_381::{Bls12Verifier, Bls12Verifyingkey, G1_SIZE, G2_SIZE};
+use crate::errors::Groth16Error;
+use alloc::vec::Vec;
+use ark_bls12_381::{G1Affine, G2Affine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+
+/// Size of a serialized bellman proof
+pub const BELLMAN_PROOF_SIZE: usize = 48 + 96 + 48;
+
+fn decompress_g1(bytes: &[u8]) -> Result<G1Affine, Groth16Error> {
+    G1Affine::deserialize_with_mode(bytes, Compress::Yes, Validate::Yes)
+        .map_err(|_| Groth16Error::DecompressingG1Failed(0))
+}
+
+fn decompress_g2(bytes: &[u8]) -> Result<G2Affine, Groth16Error> {
+    G2Affine::deserialize_with_mode(bytes, Compress::Yes, Validate::Yes)
+        .map_err(|_| Groth16Error::DecompressingG2Failed(0))
+}
+
+fn uncompressed_g1(bytes: &[u8]) -> Result<[u8; G1_SIZE], Groth16Error> {
+    let point = G1Affine::deserialize_with_mode(bytes, Compress::No, Validate::Yes)
+        .map_err(|_| Groth16Error::ProofConversionError)?;
+    Ok(g1_bytes(&point))
+}
+
+fn uncompressed_g2(bytes: &[u8]) -> Result<[u8; G2_SIZE], Groth16Error> {
+    let point = G2Affine::deserialize_with_mode(bytes, Compress::No, Validate::Yes)
+        .map_err(|_| Groth16Error::ProofConversionError)?;
+    Ok(g2_bytes(&point))
+}
+
+fn g1_bytes(point: &G1Affine) -> [u8; G1_SIZE] {
+    let mut bytes = [0u8; G1_SIZE];
+    point.serialize_uncompressed(&mut bytes[..]).unwrap();
+    bytes
+}
+
+fn g2_bytes(point: &G2Affine) -> [u8; G2_SIZE] {
+    let mut bytes = [0u8; G2_SIZE];
+    point.serialize_uncompressed(&mut bytes[..]).unwrap();
+    bytes
+}
+
+/// Uncompressed `(proof_a, proof_b, proof_c)` as taken by [`Bls12Verifier`]
+pub type Bls12Proof = ([u8; G1_SIZE], [u8; G2_SIZE], [u8; G1_SIZE]);
+
+/// Converts a bellman proof into the encoding of [`Bls12Verifier`]
+///
+/// # Returns
+/// `(proof_a, proof_b, proof_c)`, uncompressed, with `proof_a` negated
+/// * `Err(Groth16Error::DecompressingG1Failed)` - If a or c is not a valid
+///   compressed point of the G1 subgroup
+/// * `Err(Groth16Error::DecompressingG2Failed)` - If b is not a valid
+///   compressed point of the G2 subgroup
+pub fn convert_bellman_proof(proof: &[u8; BELLMAN_PROOF_SIZE]) -> Result<Bls12Proof, Groth16Error> {
+    let a = decompress_g1(&proof[..48])?;
+    let b = decompress_g2(&proof[48..144])?;
+    let c = decompress_g1(&proof[144..])?;
+    Ok((g1_bytes(&-a), g2_bytes(&b), g1_bytes(&c)))
+}
+
+/// Reverses bellman's little-endian scalars to big-endian public inputs
+pub fn convert_bellman_public_inputs<const NR_INPUTS: usize>(
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+) -> [[u8; 32]; NR_INPUTS] {
+    let mut converted = *public_inputs;
+    for input in converted.iter_mut() {
+        input.reverse();
+    }
+    converted
+}
+
+/// A verifying key read from bellman's serialization
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BellmanVerifyingKey {
+    pub vk_alpha_g1: [u8; G1_SIZE],
+    pub vk_beta_g2: [u8; G2_SIZE],
+    pub vk_gamma_g2: [u8; G2_SIZE],
+    pub vk_delta_g2: [u8; G2_SIZE],
+    pub vk_ic: Vec<[u8; G1_SIZE]>,
+}
+
+impl BellmanVerifyingKey {
+    /// Parses and checks a key written by bellman's `VerifyingKey::write`
+    ///
+    /// `beta_g1` and `delta_g1` are only used by bellman's prover and are
+    /// skipped after their checks.
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::ProofConversionError)` - If the length does not
+    ///   match the number of IC points or a point is invalid
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Groth16Error> {
+        const IC_OFFSET: usize = 3 * G1_SIZE + 3 * G2_SIZE + 4;
+        if bytes.len() < IC_OFFSET {
+            return Err(Groth16Error::ProofConversionError);
+        }
+        let nr_ic = u32::from_be_bytes(bytes[IC_OFFSET - 4..IC_OFFSET].try_into().unwrap());
+        let ic_len = (nr_ic as usize)
+            .checked_mul(G1_SIZE)
+            .ok_or(Groth16Error::ProofConversionError)?;
+        if nr_ic == 0 || bytes.len() != IC_OFFSET + ic_len {
+            return Err(Groth16Error::ProofConversionError);
+        }
+        let vk_alpha_g1 = uncompressed_g1(&bytes[..96])?;
+        uncompressed_g1(&bytes[96..192])?;
+        let vk_beta_g2 = uncompressed_g2(&bytes[192..384])?;
+        let vk_gamma_g2 = uncompressed_g2(&bytes[384..576])?;
+        uncompressed_g1(&bytes[576..672])?;
+        let vk_delta_g2 = uncompressed_g2(&bytes[672..864])?;
+        let vk_ic = bytes[IC_OFFSET..]
+            .chunks(G1_SIZE)
+            .map(uncompressed_g1)
+            .collect::<Result<_, _>>()?;
+        Ok(BellmanVerifyingKey {
+            vk_alpha_g1,
+            vk_beta_g2,
+            vk_gamma_g2,
+            vk_delta_g2,
+            vk_ic,
+        })
+    }
+
+    /// Borrows the key as taken by [`Bls12Verifier`]
+    pub fn as_verifyingkey(&self) -> Bls12Verifyingkey<'_> {
+        Bls12Verifyingkey {
+            nr_pubinputs: self.vk_ic.len() - 1,
+            vk_alpha_g1: self.vk_alpha_g1,
+            vk_beta_g2: self.vk_beta_g2,
+            vk_gamma_g2: self.vk_gamma_g2,
+            vk_delta_g2: self.vk_delta_g2,
+            vk_ic: &self.vk_ic,
+        }
+    }
+}
+
+/// Verifies a serialized bellman proof with bellman's little-endian public inputs
+pub fn verify_bellman_proof<const NR_INPUTS: usize>(
+    proof: &[u8; BELLMAN_PROOF_SIZE],
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+    verifyingkey: &Bls12Verifyingkey,
+) -> Result<(), Groth16Error> {
+    let (proof_a, proof_b, proof_c) = convert_bellman_proof(proof)?;
+    let public_inputs = convert_bellman_public_inputs(public_inputs);
+    Bls12Verifier::new(&proof_a, &proof_b, &proof_c, &public_inputs, verifyingkey)?.verify()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_proofs_are_rejected() {
+        // Without the compression flag
+        assert_eq!(
+            convert_bellman_proof(&[0u8; BELLMAN_PROOF_SIZE]),
+            Err(Groth16Error::DecompressingG1Failed(0))
+        );
+        let mut infinity = [0u8; BELLMAN_PROOF_SIZE];
+        for offset in [0, 48, 144] {
+            infinity[offset] = 0xc0;
+        }
+        let (a, b, c) = convert_bellman_proof(&infinity).unwrap();
+        assert_eq!(a, crate::bls12_381::G1_INFINITY);
+        assert_eq!(c, a);
+        assert_eq!(b[0], 0x40);
+        infinity[48] = 0x80;
+        assert_eq!(
+            convert_bellman_proof(&infinity),
+            Err(Groth16Error::DecompressingG2Failed(0))
+        );
+        assert_eq!(
+            BellmanVerifyingKey::from_bytes(&[0u8; 100]),
+            Err(Groth16Error::ProofConversionError)
+        );
+    }
+
+    #[cfg(feature = "test-vectors")]
+    #[test]
+    fn bellman_serialized_proof_verifies() {
+        use crate::test_vectors::TinyCircuit;
+        use ark_bls12_381::{Bls12_381, Fr};
+        use ark_ff::{BigInteger, PrimeField};
+        use ark_groth16::Groth16;
+        use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+        let rng = &mut StdRng::seed_from_u64(1);
+        let (a, b) = (Fr::from(3u8), Fr::from(5u8));
+        let pk = Groth16::<Bls12_381>::generate_random_parameters_with_reduction(
+            TinyCircuit::<Fr>::default(),
+            rng,
+        )
+        .unwrap();
+        let proof = Groth16::<Bls12_381>::create_random_proof_with_reduction(
+            TinyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &pk,
+            rng,
+        )
+        .unwrap();
+
+        // bellman's serialization, arkworks writes the same Zcash encoding
+        let mut proof_bytes = Vec::new();
+        proof.a.serialize_compressed(&mut proof_bytes).unwrap();
+        proof.b.serialize_compressed(&mut proof_bytes).unwrap();
+        proof.c.serialize_compressed(&mut proof_bytes).unwrap();
+        let proof_bytes: [u8; BELLMAN_PROOF_SIZE] = proof_bytes.try_into().unwrap();
+
+        let mut vk_bytes = Vec::new();
+        pk.vk
+            .alpha_g1
+            .serialize_uncompressed(&mut vk_bytes)
+            .unwrap();
+        pk.beta_g1.serialize_uncompressed(&mut vk_bytes).unwrap();
+        pk.vk.beta_g2.serialize_uncompressed(&mut vk_bytes).unwrap();
+        pk.vk
+            .gamma_g2
+            .serialize_uncompressed(&mut vk_bytes)
+            .unwrap();
+        pk.delta_g1.serialize_uncompressed(&mut vk_bytes).unwrap();
+        pk.vk
+            .delta_g2
+            .serialize_uncompressed(&mut vk_bytes)
+            .unwrap();
+        vk_bytes.extend_from_slice(&(pk.vk.gamma_abc_g1.len() as u32).to_be_bytes());
+        for ic in &pk.vk.gamma_abc_g1 {
+            ic.serialize_uncompressed(&mut vk_bytes).unwrap();
+        }
+        let vk = BellmanVerifyingKey::from_bytes(&vk_bytes).unwrap();
+
+        let scalar =
+            |value: Fr| -> [u8; 32] { value.into_bigint().to_bytes_le().try_into().unwrap() };
+        let mut inputs = [scalar(a * b), scalar(a + b)];
+        verify_bellman_proof(&proof_bytes, &inputs, &vk.as_verifyingkey()).unwrap();
+        inputs.swap(0, 1);
+        assert_eq!(
+            verify_bellman_proof(&proof_bytes, &inputs, &vk.as_verifyingkey()),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+    }
+}
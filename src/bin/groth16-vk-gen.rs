@@ -0,0 +1,175 @@
+//! Converts Groth16 verifying keys for the verifier
+//!
+//! Reads a BN254 verifying key exported by snarkjs, gnark or arkworks, or in
+//! the crate's binary format, and writes it as Rust source or in the binary
+//! format, see [`vk_parser`](pinocchio_groth16::vk_parser). The conversion a
+//! `build.rs` would do with `generate_vk_file`, as an explicit step:
+//!
+//! ```bash
+//! cargo run --features cli --bin groth16-vk-gen -- build/verification_key.json -o src/verifying_key.rs
+//! cargo run --features cli --bin groth16-vk-gen -- vk.bin --from gnark --to binary -o vk.g16k
+//! ```
+
+use pinocchio_groth16::vk_parser::{
+    binary_vk_to_rust_string, parse_arkworks_vk_to_bytes, parse_gnark_vk_to_bytes,
+    parse_gnark_vk_to_rust_string, parse_vk_json_to_bytes, parse_vk_json_to_rust_string_with_mode,
+    CodegenMode,
+};
+use std::io::Write;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+usage: groth16-vk-gen <INPUT> [options]
+
+options:
+  --from <FORMAT>          snarkjs, gnark, arkworks or binary [default: snarkjs]
+  --to <FORMAT>            rust or binary [default: rust]
+  --endian <ORDER>         byte order of the coordinates in rust output, big or
+                           little [default: big, as taken by the syscalls]
+  -o, --output <PATH>      output file, left untouched if unchanged [default: stdout]";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Input {
+    Snarkjs,
+    Gnark,
+    Arkworks,
+    Binary,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Output {
+    Rust,
+    Binary,
+}
+
+struct Args {
+    input: String,
+    from: Input,
+    to: Output,
+    little_endian: bool,
+    output: Option<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut input = None;
+    let mut from = Input::Snarkjs;
+    let mut to = Output::Rust;
+    let mut little_endian = false;
+    let mut output = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--from" => {
+                from = match value()?.as_str() {
+                    "snarkjs" => Input::Snarkjs,
+                    "gnark" => Input::Gnark,
+                    "arkworks" => Input::Arkworks,
+                    "binary" => Input::Binary,
+                    format => return Err(format!("unknown input format {format}")),
+                }
+            }
+            "--to" => {
+                to = match value()?.as_str() {
+                    "rust" => Output::Rust,
+                    "binary" => Output::Binary,
+                    format => return Err(format!("unknown output format {format}")),
+                }
+            }
+            "--endian" => {
+                little_endian = match value()?.as_str() {
+                    "big" => false,
+                    "little" => true,
+                    order => return Err(format!("unknown byte order {order}")),
+                }
+            }
+            "-o" | "--output" => output = Some(value()?),
+            _ if arg.starts_with('-') => return Err(format!("unknown argument {arg}")),
+            _ if input.is_none() => input = Some(arg),
+            _ => return Err(format!("unexpected argument {arg}")),
+        }
+    }
+    if little_endian && to == Output::Binary {
+        return Err("the binary format is big-endian, --endian little needs --to rust".into());
+    }
+    Ok(Args {
+        input: input.ok_or("<INPUT> is required")?,
+        from,
+        to,
+        little_endian,
+        output,
+    })
+}
+
+/// The converted key
+fn convert(args: &Args, content: &[u8]) -> Result<Vec<u8>, String> {
+    let json = || std::str::from_utf8(content).map_err(|e| format!("{}: {e}", args.input));
+    let deterministic_rust = args.to == Output::Rust && !args.little_endian;
+    let converted = match args.from {
+        // Rust output without a binary round trip keeps BLS12-381 and PLONK keys
+        Input::Snarkjs if deterministic_rust => {
+            return parse_vk_json_to_rust_string_with_mode(json()?, CodegenMode::Deterministic)
+                .map(String::into_bytes)
+                .map_err(|e| e.to_string());
+        }
+        Input::Gnark if deterministic_rust => {
+            return parse_gnark_vk_to_rust_string(content)
+                .map(String::into_bytes)
+                .map_err(|e| e.to_string());
+        }
+        Input::Snarkjs => parse_vk_json_to_bytes(json()?),
+        Input::Gnark => parse_gnark_vk_to_bytes(content),
+        Input::Arkworks => parse_arkworks_vk_to_bytes(content),
+        Input::Binary => Ok(content.to_vec()),
+    };
+    let mut bytes = converted.map_err(|e| e.to_string())?;
+    if args.to == Output::Binary {
+        return Ok(bytes);
+    }
+    if args.little_endian {
+        // Every coordinate after the header and the number of inputs
+        if bytes.len() < 12 {
+            return Err(format!("{}: truncated binary key", args.input));
+        }
+        for coordinate in bytes[12..].chunks_mut(32) {
+            coordinate.reverse();
+        }
+    }
+    binary_vk_to_rust_string(&bytes)
+        .map(String::into_bytes)
+        .map_err(|e| e.to_string())
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    let content = std::fs::read(&args.input).map_err(|e| format!("{}: {e}", args.input))?;
+    let converted = convert(args, &content)?;
+    match &args.output {
+        Some(path) => {
+            if std::fs::read(path).ok().as_deref() == Some(converted.as_slice()) {
+                return Ok(());
+            }
+            std::fs::write(path, converted).map_err(|e| format!("{path}: {e}"))
+        }
+        None => std::io::stdout()
+            .write_all(&converted)
+            .map_err(|e| format!("stdout: {e}")),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,366 @@
+//! Groth16 verification over BLS12-381
+//!
+//! The counterpart of [`Groth16Verifier`](crate::groth16::Groth16Verifier)
+//! on the BLS12-381 curve syscalls of SIMD-0388, `sol_curve_group_op` and
+//! `sol_curve_pairing_map`, which are active once the
+//! [`ENABLE_BLS12_381_SYSCALL`](crate::syscall_features::ENABLE_BLS12_381_SYSCALL)
+//! feature is activated on the cluster.
+//!
+//! Points use the big-endian Zcash encoding the syscalls take: G1 points are
+//! `x | y` with 48-byte coordinates, G2 points `x.c1 | x.c0 | y.c1 | y.c0`
+//! and the point at infinity has the infinity flag `0x40` set in the first
+//! byte. Like for BN254, proof a is negated by the client and public inputs
+//! are 32-byte big-endian scalars. The verifying key is generated from a
+//! snarkjs `verification_key.json` with `"curve": "bls12381"` by the
+//! [`vk_parser`](crate::vk_parser) in [`CodegenMode::Deterministic`](crate::vk_parser::CodegenMode).
+//!
+//! ```rust,ignore
+//! use pinocchio_groth16::bls12_381::Bls12Verifier;
+//!
+//! Bls12Verifier::new(&proof_a, &proof_b, &proof_c, &public_inputs, &VERIFYINGKEY)?.verify()?;
+//! ```
+
+use crate::curve::{negate_coordinate_be, PairingCurve};
+use crate::errors::Groth16Error;
+use crate::groth16::{CurveVerifier, CurveVerifyingkey, SizedCurveVerifyingkey};
+use alloc::vec::Vec;
+
+/// `sol_curve_group_op` and `sol_curve_pairing_map` curve id of BLS12-381 pairings, big-endian
+pub const BLS12_381_BE: u64 = 4 | 0x80;
+/// `sol_curve_group_op` curve id of BLS12-381 G1, big-endian
+pub const BLS12_381_G1_BE: u64 = 5 | 0x80;
+
+/// `sol_curve_group_op` operations
+pub const GROUP_OP_ADD: u64 = 0;
+pub const GROUP_OP_MUL: u64 = 2;
+
+pub const G1_SIZE: usize = 96;
+pub const G2_SIZE: usize = 192;
+pub const SCALAR_SIZE: usize = 32;
+/// Size of a target group element, 12 base field elements
+pub const GT_SIZE: usize = 576;
+
+/// Big-endian encoding of the identity of the target group
+pub const GT_ONE: [u8; GT_SIZE] = {
+    let mut one = [0u8; GT_SIZE];
+    one[GT_SIZE - 1] = 1;
+    one
+};
+
+/// Encoding of the G1 point at infinity
+pub const G1_INFINITY: [u8; G1_SIZE] = {
+    let mut infinity = [0u8; G1_SIZE];
+    infinity[0] = 0x40;
+    infinity
+};
+
+/// BLS12-381 scalar field modulus, big-endian
+pub const SCALAR_FIELD_MODULUS: [u8; 32] = [
+    0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8, 0x05,
+    0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01,
+];
+
+#[cfg(target_os = "solana")]
+extern "C" {
+    // pinocchio declares the pre SIMD-0388 signature of this syscall
+    fn sol_curve_pairing_map(
+        curve_id: u64,
+        num_pairs: u64,
+        g1_points: *const u8,
+        g2_points: *const u8,
+        result: *mut u8,
+    ) -> u64;
+}
+
+/// Calls `sol_curve_group_op`, or its mock on the host
+fn group_op(curve_id: u64, op: u64, left: &[u8], right: &[u8], result: &mut [u8]) -> u64 {
+    #[cfg(target_os = "solana")]
+    {
+        unsafe {
+            crate::syscalls::sol_curve_group_op(
+                curve_id,
+                op,
+                left.as_ptr(),
+                right.as_ptr(),
+                result.as_mut_ptr(),
+            )
+        }
+    }
+
+    #[cfg(all(not(target_os = "solana"), any(test, feature = "mock-syscalls")))]
+    {
+        crate::mock::sol_curve_group_op(curve_id, op, left, right, result)
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(any(test, feature = "mock-syscalls"))))]
+    {
+        let _ = (curve_id, op, left, right, result);
+        1
+    }
+}
+
+/// Calls `sol_curve_pairing_map`, or its mock on the host
+fn pairing_map(g1_points: &[u8], g2_points: &[u8], result: &mut [u8; GT_SIZE]) -> u64 {
+    let num_pairs = (g1_points.len() / G1_SIZE) as u64;
+
+    #[cfg(target_os = "solana")]
+    {
+        unsafe {
+            sol_curve_pairing_map(
+                BLS12_381_BE,
+                num_pairs,
+                g1_points.as_ptr(),
+                g2_points.as_ptr(),
+                result.as_mut_ptr(),
+            )
+        }
+    }
+
+    #[cfg(all(not(target_os = "solana"), any(test, feature = "mock-syscalls")))]
+    {
+        crate::mock::sol_curve_pairing_map(BLS12_381_BE, num_pairs, g1_points, g2_points, result)
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(any(test, feature = "mock-syscalls"))))]
+    {
+        let _ = (num_pairs, g2_points, result);
+        1
+    }
+}
+
+/// Adds two G1 points
+///
+/// The points are checked to be on the curve, not to be in the subgroup.
+pub fn bls12_381_g1_addition(
+    left: &[u8; G1_SIZE],
+    right: &[u8; G1_SIZE],
+) -> Result<[u8; G1_SIZE], Groth16Error> {
+    let mut result = [0u8; G1_SIZE];
+    match group_op(BLS12_381_G1_BE, GROUP_OP_ADD, left, right, &mut result) {
+        0 => {}
+        code => return Err(Groth16Error::PreparingInputsG1AdditionFailed(code)),
+    }
+    Ok(result)
+}
+
+/// Multiplies a G1 point by a scalar
+///
+/// # Returns
+/// * `Err(Groth16Error::PreparingInputsG1MulFailed)` - If the point is not in
+///   the subgroup or the scalar is not smaller than the scalar field modulus
+pub fn bls12_381_g1_multiplication(
+    point: &[u8; G1_SIZE],
+    scalar: &[u8; SCALAR_SIZE],
+) -> Result<[u8; G1_SIZE], Groth16Error> {
+    let mut result = [0u8; G1_SIZE];
+    match group_op(BLS12_381_G1_BE, GROUP_OP_MUL, scalar, point, &mut result) {
+        0 => {}
+        code => return Err(Groth16Error::PreparingInputsG1MulFailed(code)),
+    }
+    Ok(result)
+}
+
+/// Computes the product of the pairings of `g1_points[i]` and `g2_points[i]`
+///
+/// # Arguments
+/// * `g1_points` - Concatenated G1 points
+/// * `g2_points` - Concatenated G2 points, as many as G1 points
+pub fn bls12_381_pairing(
+    g1_points: &[u8],
+    g2_points: &[u8],
+) -> Result<[u8; GT_SIZE], Groth16Error> {
+    if !g1_points.len().is_multiple_of(G1_SIZE)
+        || g2_points.len() != g1_points.len() / G1_SIZE * G2_SIZE
+    {
+        return Err(Groth16Error::ProofVerificationFailed);
+    }
+    let mut result = [0u8; GT_SIZE];
+    if pairing_map(g1_points, g2_points, &mut result) != 0 {
+        return Err(Groth16Error::ProofVerificationFailed);
+    }
+    Ok(result)
+}
+
+/// Returns true if the big-endian scalar is smaller than the scalar field modulus
+pub fn is_less_than_bls12_381_scalar_field_be(bytes: &[u8; 32]) -> bool {
+    *bytes < SCALAR_FIELD_MODULUS
+}
+
+/// Base field modulus, big-endian
+pub const BASE_FIELD_MODULUS: [u8; 48] = [
+    0x1a, 0x01, 0x11, 0xea, 0x39, 0x7f, 0xe6, 0x9a, 0x4b, 0x1b, 0xa7, 0xb6, 0x43, 0x4b, 0xac, 0xd7,
+    0x64, 0x77, 0x4b, 0x84, 0xf3, 0x85, 0x12, 0xbf, 0x67, 0x30, 0xd2, 0xa0, 0xf6, 0xb0, 0xf6, 0x24,
+    0x1e, 0xab, 0xff, 0xfe, 0xb1, 0x53, 0xff, 0xff, 0xb9, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xaa, 0xab,
+];
+
+/// BLS12-381 with the `sol_curve_group_op` and `sol_curve_pairing_map` syscalls
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bls12_381;
+
+impl PairingCurve for Bls12_381 {
+    type G1 = [u8; G1_SIZE];
+    type G2 = [u8; G2_SIZE];
+
+    const G1_SIZE: usize = G1_SIZE;
+    const G2_SIZE: usize = G2_SIZE;
+    const G1_IDENTITY: [u8; G1_SIZE] = G1_INFINITY;
+    const BASE_FIELD_MODULUS: &'static [u8] = &BASE_FIELD_MODULUS;
+    const SCALAR_FIELD_MODULUS: [u8; 32] = SCALAR_FIELD_MODULUS;
+    const G1_ADD_OP: u64 = GROUP_OP_ADD;
+    const G1_MUL_OP: u64 = GROUP_OP_MUL;
+
+    fn g1_add(left: &[u8; G1_SIZE], right: &[u8; G1_SIZE]) -> Result<[u8; G1_SIZE], Groth16Error> {
+        bls12_381_g1_addition(left, right)
+    }
+
+    fn g1_mul(point: &[u8; G1_SIZE], scalar: &[u8; 32]) -> Result<[u8; G1_SIZE], Groth16Error> {
+        bls12_381_g1_multiplication(point, scalar)
+    }
+
+    fn g1_negate(point: &[u8; G1_SIZE]) -> Result<[u8; G1_SIZE], Groth16Error> {
+        let mut negated = *point;
+        if point[0] & 0x40 == 0 {
+            negate_coordinate_be(&mut negated[48..], &BASE_FIELD_MODULUS)?;
+        }
+        Ok(negated)
+    }
+
+    fn pairing_is_one(
+        g1_points: &[&[u8; G1_SIZE]],
+        g2_points: &[&[u8; G2_SIZE]],
+    ) -> Result<bool, Groth16Error> {
+        let g1_points: Vec<u8> = g1_points.iter().flat_map(|p| p.iter().copied()).collect();
+        let g2_points: Vec<u8> = g2_points.iter().flat_map(|p| p.iter().copied()).collect();
+        Ok(bls12_381_pairing(&g1_points, &g2_points)? == GT_ONE)
+    }
+}
+
+/// A Groth16 verifying key over BLS12-381
+pub type Bls12Verifyingkey<'a> = CurveVerifyingkey<'a, Bls12_381>;
+
+/// A Groth16 verifying key over BLS12-381 of a circuit with `NR_INPUTS` public inputs
+pub type SizedBls12Verifyingkey<'a, const NR_INPUTS: usize> =
+    SizedCurveVerifyingkey<'a, NR_INPUTS, Bls12_381>;
+
+/// Groth16 verifier over BLS12-381
+pub type Bls12Verifier<'a, const NR_INPUTS: usize> = CurveVerifier<'a, NR_INPUTS, Bls12_381>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const G2_INFINITY: [u8; G2_SIZE] = {
+        let mut infinity = [0u8; G2_SIZE];
+        infinity[0] = 0x40;
+        infinity
+    };
+
+    #[test]
+    fn identity_key_accepts_identity_proof() {
+        let vk_ic = [G1_INFINITY; 3];
+        let vk = Bls12Verifyingkey {
+            nr_pubinputs: 2,
+            vk_alpha_g1: G1_INFINITY,
+            vk_beta_g2: G2_INFINITY,
+            vk_gamma_g2: G2_INFINITY,
+            vk_delta_g2: G2_INFINITY,
+            vk_ic: &vk_ic,
+        };
+        let inputs = [[1u8; 32], [2u8; 32]];
+        let mut verifier =
+            Bls12Verifier::new(&G1_INFINITY, &G2_INFINITY, &G1_INFINITY, &inputs, &vk).unwrap();
+        assert_eq!(verifier.verify(), Ok(()));
+
+        let too_large = [SCALAR_FIELD_MODULUS, [0u8; 32]];
+        assert_eq!(
+            Bls12Verifier::new(&G1_INFINITY, &G2_INFINITY, &G1_INFINITY, &too_large, &vk)
+                .unwrap()
+                .verify(),
+            Err(Groth16Error::PublicInputGreaterThanFieldSize)
+        );
+        assert_eq!(Bls12_381::g1_negate(&G1_INFINITY), Ok(G1_INFINITY));
+        assert_eq!(
+            BASE_FIELD_MODULUS.as_slice(),
+            ark_ff::BigInteger::to_bytes_be(&<ark_bls12_381::Fq as ark_ff::PrimeField>::MODULUS)
+        );
+        // All-zero bytes are not the point at infinity in the Zcash encoding
+        assert_eq!(
+            Bls12Verifier::new(&[0u8; G1_SIZE], &G2_INFINITY, &G1_INFINITY, &inputs, &vk)
+                .unwrap()
+                .verify(),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+    }
+
+    #[cfg(feature = "test-vectors")]
+    #[test]
+    fn arkworks_proof_verifies() {
+        use crate::test_vectors::TinyCircuit;
+        use ark_bls12_381::{Bls12_381, Fr, G1Affine, G2Affine};
+        use ark_ff::{BigInteger, PrimeField};
+        use ark_groth16::Groth16;
+        use ark_serialize::CanonicalSerialize;
+        use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+        fn g1(point: &G1Affine) -> [u8; G1_SIZE] {
+            let mut bytes = [0u8; G1_SIZE];
+            point.serialize_uncompressed(&mut bytes[..]).unwrap();
+            bytes
+        }
+        fn g2(point: &G2Affine) -> [u8; G2_SIZE] {
+            let mut bytes = [0u8; G2_SIZE];
+            point.serialize_uncompressed(&mut bytes[..]).unwrap();
+            bytes
+        }
+
+        let rng = &mut StdRng::seed_from_u64(0);
+        let (a, b) = (Fr::from(3u8), Fr::from(5u8));
+        let pk = Groth16::<Bls12_381>::generate_random_parameters_with_reduction(
+            TinyCircuit::<Fr>::default(),
+            rng,
+        )
+        .unwrap();
+        let proof = Groth16::<Bls12_381>::create_random_proof_with_reduction(
+            TinyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &pk,
+            rng,
+        )
+        .unwrap();
+
+        let vk_ic: Vec<[u8; G1_SIZE]> = pk.vk.gamma_abc_g1.iter().map(g1).collect();
+        let vk = Bls12Verifyingkey {
+            nr_pubinputs: 2,
+            vk_alpha_g1: g1(&pk.vk.alpha_g1),
+            vk_beta_g2: g2(&pk.vk.beta_g2),
+            vk_gamma_g2: g2(&pk.vk.gamma_g2),
+            vk_delta_g2: g2(&pk.vk.delta_g2),
+            vk_ic: &vk_ic,
+        };
+        let scalar =
+            |value: Fr| -> [u8; 32] { value.into_bigint().to_bytes_be().try_into().unwrap() };
+        let mut inputs = [scalar(a * b), scalar(a + b)];
+        let (proof_a, proof_b, proof_c) = (g1(&-proof.a), g2(&proof.b), g1(&proof.c));
+
+        Bls12Verifier::new(&proof_a, &proof_b, &proof_c, &inputs, &vk)
+            .unwrap()
+            .verify()
+            .unwrap();
+        let unnegated_a = g1(&proof.a);
+        assert_eq!(super::Bls12_381::g1_negate(&unnegated_a).unwrap(), proof_a);
+        Bls12Verifier::new(&unnegated_a, &proof_b, &proof_c, &inputs, &vk)
+            .unwrap()
+            .with_convention(crate::groth16::ProverConvention::RawA)
+            .verify()
+            .unwrap();
+        inputs.swap(0, 1);
+        assert_eq!(
+            Bls12Verifier::new(&proof_a, &proof_b, &proof_c, &inputs, &vk)
+                .unwrap()
+                .verify(),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+    }
+}
@@ -2,289 +2,542 @@
 //!
 //! This module provides wrapper functions around Pinocchio's raw syscalls
 //! for BN254 elliptic curve operations.
+//!
+//! Off chain the syscalls do not exist and every wrapper returns an error,
+//! unless the `mock-syscalls` feature is enabled: then they run on the
+//! arkworks implementations in [`crate::mock`], with the same encodings and
+//! error behaviour, and the complete verifier works in a plain `cargo test`.
+//!
+//! The same holds on `wasm32-unknown-unknown`: with the `wasm` feature, which
+//! enables `mock-syscalls`, wallets and dApps verify proofs in the browser
+//! with the code and byte layouts the program uses.
 
 use crate::errors::Groth16Error;
 use alloc::vec::Vec;
 
 // Operation codes for sol_alt_bn128_group_op
-const ALT_BN128_G1_ADD: u64 = 0;
-const ALT_BN128_G1_MUL: u64 = 2;
-const ALT_BN128_PAIRING: u64 = 3;
+pub(crate) const ALT_BN128_G1_ADD: u64 = 0;
+pub(crate) const ALT_BN128_G1_SUB: u64 = 1;
+pub(crate) const ALT_BN128_G1_MUL: u64 = 2;
+pub(crate) const ALT_BN128_PAIRING: u64 = 3;
+pub(crate) const ALT_BN128_G2_ADD: u64 = 4;
+pub(crate) const ALT_BN128_G2_SUB: u64 = 5;
+pub(crate) const ALT_BN128_G2_MUL: u64 = 6;
 
 // Operation codes for sol_alt_bn128_compression
-const ALT_BN128_G1_COMPRESS: u64 = 0;
-const ALT_BN128_G1_DECOMPRESS: u64 = 1;
-const ALT_BN128_G2_COMPRESS: u64 = 2;
-const ALT_BN128_G2_DECOMPRESS: u64 = 3;
+pub(crate) const ALT_BN128_G1_COMPRESS: u64 = 0;
+pub(crate) const ALT_BN128_G1_DECOMPRESS: u64 = 1;
+pub(crate) const ALT_BN128_G2_COMPRESS: u64 = 2;
+pub(crate) const ALT_BN128_G2_DECOMPRESS: u64 = 3;
 
 // Size constants
 const ALT_BN128_ADDITION_INPUT_SIZE: usize = 128;
 const ALT_BN128_ADDITION_OUTPUT_SIZE: usize = 64;
 const ALT_BN128_MULTIPLICATION_INPUT_SIZE: usize = 96;
 const ALT_BN128_MULTIPLICATION_OUTPUT_SIZE: usize = 64;
-const ALT_BN128_PAIRING_ELEMENT_SIZE: usize = 192;
-const ALT_BN128_PAIRING_OUTPUT_SIZE: usize = 32;
+const ALT_BN128_G2_ADDITION_INPUT_SIZE: usize = 256;
+const ALT_BN128_G2_MULTIPLICATION_INPUT_SIZE: usize = 160;
+pub(crate) const ALT_BN128_PAIRING_ELEMENT_SIZE: usize = 192;
+pub(crate) const ALT_BN128_PAIRING_OUTPUT_SIZE: usize = 32;
+
+/// Most G1/G2 pairs one pairing syscall can take within the compute unit
+/// limit of a transaction
+pub const ALT_BN128_PAIRING_MAX_PAIRS: usize =
+    crate::compute_units::max_pairing_pairs(crate::compute_units::MAX_COMPUTE_UNIT_LIMIT as u64);
 const ALT_BN128_G1_POINT_SIZE: usize = 64;
 const ALT_BN128_G1_COMPRESSED_SIZE: usize = 32;
 const ALT_BN128_G2_POINT_SIZE: usize = 128;
 const ALT_BN128_G2_COMPRESSED_SIZE: usize = 64;
 
-/// Performs BN254 G1 point addition
-///
-/// # Arguments
-/// * `input` - Concatenated G1 points (128 bytes: two 64-byte points)
+/// Provider of the alt_bn128 group operations, pairing and compression
 ///
-/// # Returns
-/// * `Ok(Vec<u8>)` - The resulting G1 point (64 bytes)
-/// * `Err(Groth16Error)` - If the operation fails
-pub fn alt_bn128_addition(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
-    if input.len() > ALT_BN128_ADDITION_INPUT_SIZE {
-        return Err(Groth16Error::PreparingInputsG1AdditionFailed);
-    }
-
-    let mut result = vec![0u8; ALT_BN128_ADDITION_OUTPUT_SIZE];
+/// Implementors supply the two raw operations with the semantics of the
+/// `sol_alt_bn128_group_op` and `sol_alt_bn128_compression` syscalls:
+/// big-endian encodings, G2 coordinates ordered `c1 | c0`, the all-zero
+/// encoding as the identity and a return code of 0 on success. The checked
+/// wrappers are provided on top, so programs and off-chain services run the
+/// same verifier, see [`crate::curve::Bn254With`].
+pub trait Bn254Backend {
+    /// Runs the group operation `op` on `input`, writing to `result`
+    fn group_op(op: u64, input: &[u8], result: &mut [u8]) -> u64;
+
+    /// Runs the compression operation `op` on `input`, writing to `result`
+    fn compression(op: u64, input: &[u8], result: &mut [u8]) -> u64;
+
+    /// Performs BN254 G1 point addition
+    ///
+    /// # Arguments
+    /// * `input` - Concatenated G1 points (128 bytes: two 64-byte points)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - The resulting G1 point (64 bytes)
+    /// * `Err(Groth16Error)` - If the operation fails
+    fn alt_bn128_addition(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
+        if input.len() > ALT_BN128_ADDITION_INPUT_SIZE {
+            return Err(Groth16Error::PreparingInputsG1AdditionFailed(0));
+        }
 
-    #[cfg(target_os = "solana")]
-    {
-        let return_code = unsafe {
-            pinocchio::syscalls::sol_alt_bn128_group_op(
-                ALT_BN128_G1_ADD,
-                input.as_ptr(),
-                input.len() as u64,
-                result.as_mut_ptr(),
-            )
-        };
+        let mut result = vec![0u8; ALT_BN128_ADDITION_OUTPUT_SIZE];
 
-        if return_code != 0 {
-            return Err(Groth16Error::PreparingInputsG1AdditionFailed);
+        match Self::group_op(ALT_BN128_G1_ADD, input, &mut result) {
+            0 => {}
+            code => return Err(Groth16Error::PreparingInputsG1AdditionFailed(code)),
         }
-    }
 
-    #[cfg(not(target_os = "solana"))]
-    {
-        // For non-Solana targets, return error as we can't perform the operation
-        return Err(Groth16Error::PreparingInputsG1AdditionFailed);
+        Ok(result)
     }
 
-    Ok(result)
-}
+    /// Performs BN254 G1 scalar multiplication
+    ///
+    /// # Arguments
+    /// * `input` - G1 point and scalar (96 bytes: 64-byte point + 32-byte scalar)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - The resulting G1 point (64 bytes)
+    /// * `Err(Groth16Error)` - If the operation fails
+    fn alt_bn128_multiplication(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
+        if input.len() > ALT_BN128_MULTIPLICATION_INPUT_SIZE {
+            return Err(Groth16Error::PreparingInputsG1MulFailed(0));
+        }
 
-/// Performs BN254 G1 scalar multiplication
-///
-/// # Arguments
-/// * `input` - G1 point and scalar (96 bytes: 64-byte point + 32-byte scalar)
-///
-/// # Returns
-/// * `Ok(Vec<u8>)` - The resulting G1 point (64 bytes)
-/// * `Err(Groth16Error)` - If the operation fails
-pub fn alt_bn128_multiplication(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
-    if input.len() > ALT_BN128_MULTIPLICATION_INPUT_SIZE {
-        return Err(Groth16Error::PreparingInputsG1MulFailed);
+        let mut result = vec![0u8; ALT_BN128_MULTIPLICATION_OUTPUT_SIZE];
+
+        match Self::group_op(ALT_BN128_G1_MUL, input, &mut result) {
+            0 => {}
+            code => return Err(Groth16Error::PreparingInputsG1MulFailed(code)),
+        }
+
+        Ok(result)
     }
 
-    let mut result = vec![0u8; ALT_BN128_MULTIPLICATION_OUTPUT_SIZE];
+    /// Performs BN254 G1 point subtraction, the first point minus the second
+    ///
+    /// Needs the G1 subtraction operation of `sol_alt_bn128_group_op` to be
+    /// active on the cluster.
+    ///
+    /// # Arguments
+    /// * `input` - Concatenated G1 points (128 bytes: two 64-byte points)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - The resulting G1 point (64 bytes)
+    /// * `Err(Groth16Error)` - If the operation fails
+    fn alt_bn128_g1_subtraction(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
+        if input.len() > ALT_BN128_ADDITION_INPUT_SIZE {
+            return Err(Groth16Error::PreparingInputsG1AdditionFailed(0));
+        }
 
-    #[cfg(target_os = "solana")]
-    {
-        let return_code = unsafe {
-            pinocchio::syscalls::sol_alt_bn128_group_op(
-                ALT_BN128_G1_MUL,
-                input.as_ptr(),
-                input.len() as u64,
-                result.as_mut_ptr(),
-            )
-        };
+        let mut result = vec![0u8; ALT_BN128_ADDITION_OUTPUT_SIZE];
 
-        if return_code != 0 {
-            return Err(Groth16Error::PreparingInputsG1MulFailed);
+        match Self::group_op(ALT_BN128_G1_SUB, input, &mut result) {
+            0 => {}
+            code => return Err(Groth16Error::PreparingInputsG1AdditionFailed(code)),
         }
+
+        Ok(result)
     }
 
-    #[cfg(not(target_os = "solana"))]
-    {
-        return Err(Groth16Error::PreparingInputsG1MulFailed);
+    /// Performs BN254 G2 point addition
+    ///
+    /// Needs the G2 operations of `sol_alt_bn128_group_op` to be active on
+    /// the cluster.
+    ///
+    /// # Arguments
+    /// * `input` - Concatenated G2 points (256 bytes: two 128-byte points)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - The resulting G2 point (128 bytes)
+    /// * `Err(Groth16Error)` - If the operation fails
+    fn alt_bn128_g2_addition(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
+        g2_op::<Self>(ALT_BN128_G2_ADD, ALT_BN128_G2_ADDITION_INPUT_SIZE, input)
     }
 
-    Ok(result)
-}
+    /// Performs BN254 G2 point subtraction, the first point minus the second
+    ///
+    /// Needs the G2 operations of `sol_alt_bn128_group_op` to be active on
+    /// the cluster.
+    ///
+    /// # Arguments
+    /// * `input` - Concatenated G2 points (256 bytes: two 128-byte points)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - The resulting G2 point (128 bytes)
+    /// * `Err(Groth16Error)` - If the operation fails
+    fn alt_bn128_g2_subtraction(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
+        g2_op::<Self>(ALT_BN128_G2_SUB, ALT_BN128_G2_ADDITION_INPUT_SIZE, input)
+    }
 
-/// Performs BN254 pairing operation
-///
-/// # Arguments
-/// * `input` - Pairs of G1 and G2 points (multiple of 192 bytes)
-///
-/// # Returns
-/// * `Ok(Vec<u8>)` - Result (32 bytes, last byte is 1 if pairing succeeds)
-/// * `Err(Groth16Error)` - If the operation fails
-pub fn alt_bn128_pairing(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
-    if input.len() % ALT_BN128_PAIRING_ELEMENT_SIZE != 0 {
-        return Err(Groth16Error::ProofVerificationFailed);
+    /// Performs BN254 G2 scalar multiplication
+    ///
+    /// Needs the G2 operations of `sol_alt_bn128_group_op` to be active on
+    /// the cluster.
+    ///
+    /// # Arguments
+    /// * `input` - G2 point and scalar (160 bytes: 128-byte point + 32-byte scalar)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - The resulting G2 point (128 bytes)
+    /// * `Err(Groth16Error)` - If the operation fails
+    fn alt_bn128_g2_multiplication(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
+        g2_op::<Self>(ALT_BN128_G2_MUL, ALT_BN128_G2_MULTIPLICATION_INPUT_SIZE, input)
     }
 
-    let mut result = vec![0u8; ALT_BN128_PAIRING_OUTPUT_SIZE];
+    /// Performs BN254 pairing operation
+    ///
+    /// # Arguments
+    /// * `input` - Pairs of G1 and G2 points (multiple of 192 bytes)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - Result (32 bytes, last byte is 1 if pairing succeeds)
+    /// * `Err(Groth16Error::PairingInputTooLarge)` - If the input has more than
+    ///   [`ALT_BN128_PAIRING_MAX_PAIRS`] pairs, the syscall would exceed the
+    ///   compute unit limit and abort the transaction
+    /// * `Err(Groth16Error)` - If the operation fails
+    fn alt_bn128_pairing(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
+        if !input.len().is_multiple_of(ALT_BN128_PAIRING_ELEMENT_SIZE) {
+            return Err(Groth16Error::ProofVerificationFailed);
+        }
+        if input.len() > ALT_BN128_PAIRING_MAX_PAIRS * ALT_BN128_PAIRING_ELEMENT_SIZE {
+            return Err(Groth16Error::PairingInputTooLarge);
+        }
 
-    #[cfg(target_os = "solana")]
-    {
-        let return_code = unsafe {
-            pinocchio::syscalls::sol_alt_bn128_group_op(
-                ALT_BN128_PAIRING,
-                input.as_ptr(),
-                input.len() as u64,
-                result.as_mut_ptr(),
-            )
-        };
+        let mut result = vec![0u8; ALT_BN128_PAIRING_OUTPUT_SIZE];
 
-        if return_code != 0 {
+        if Self::group_op(ALT_BN128_PAIRING, input, &mut result) != 0 {
             return Err(Groth16Error::ProofVerificationFailed);
         }
-    }
 
-    #[cfg(not(target_os = "solana"))]
-    {
-        return Err(Groth16Error::ProofVerificationFailed);
+        Ok(result)
     }
 
-    Ok(result)
-}
+    /// Checks that each of several pairing products is one, a syscall per product
+    ///
+    /// The syscall only reveals whether the product of its input is one, so
+    /// one product cannot be split across syscalls: `e(a, b) * e(c, d) = 1`
+    /// does not follow from two separate checks of `e(a, b)` and `e(c, d)`.
+    /// Independent checks, e.g. proofs verified one by one, are passed as
+    /// separate products instead. Combining them into one product first, as
+    /// [`crate::batch`] does, is cheaper.
+    ///
+    /// # Arguments
+    /// * `products` - Pairing inputs, each pairs of G1 and G2 points
+    ///
+    /// # Returns
+    /// * `Ok(true)` - If every product is one
+    /// * `Ok(false)` - If a product is not one, later ones are not checked
+    /// * `Err(Groth16Error::PairingInputTooLarge)` - If a product has more than
+    ///   [`ALT_BN128_PAIRING_MAX_PAIRS`] pairs
+    /// * `Err(Groth16Error)` - If the operation fails
+    fn alt_bn128_pairing_batch(products: &[&[u8]]) -> Result<bool, Groth16Error> {
+        if products
+            .iter()
+            .any(|input| input.len() > ALT_BN128_PAIRING_MAX_PAIRS * ALT_BN128_PAIRING_ELEMENT_SIZE)
+        {
+            return Err(Groth16Error::PairingInputTooLarge);
+        }
+        for input in products {
+            if Self::alt_bn128_pairing(input)?[31] != 1 {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
 
-/// Compresses a G1 point from 64 bytes to 32 bytes
-///
-/// # Arguments
-/// * `point` - Uncompressed G1 point (64 bytes)
-///
-/// # Returns
-/// * `Ok([u8; 32])` - Compressed G1 point
-/// * `Err(Groth16Error)` - If compression fails
-pub fn alt_bn128_g1_compress(point: &[u8; 64]) -> Result<[u8; 32], Groth16Error> {
-    let mut result = [0u8; ALT_BN128_G1_COMPRESSED_SIZE];
-
-    #[cfg(target_os = "solana")]
-    {
-        let return_code = unsafe {
-            pinocchio::syscalls::sol_alt_bn128_compression(
-                ALT_BN128_G1_COMPRESS,
-                point.as_ptr(),
-                point.len() as u64,
-                result.as_mut_ptr(),
-            )
-        };
+    /// Compresses a G1 point from 64 bytes to 32 bytes
+    fn alt_bn128_g1_compress(point: &[u8; 64]) -> Result<[u8; 32], Groth16Error> {
+        let mut result = [0u8; ALT_BN128_G1_COMPRESSED_SIZE];
 
-        if return_code != 0 {
+        if Self::compression(ALT_BN128_G1_COMPRESS, point, &mut result) != 0 {
             return Err(Groth16Error::ProofConversionError);
         }
+
+        Ok(result)
     }
 
-    #[cfg(not(target_os = "solana"))]
-    {
-        return Err(Groth16Error::ProofConversionError);
+    /// Decompresses a G1 point from 32 bytes to 64 bytes
+    fn alt_bn128_g1_decompress(compressed: &[u8; 32]) -> Result<[u8; 64], Groth16Error> {
+        let mut result = [0u8; ALT_BN128_G1_POINT_SIZE];
+
+        match Self::compression(ALT_BN128_G1_DECOMPRESS, compressed, &mut result) {
+            0 => {}
+            code => return Err(Groth16Error::DecompressingG1Failed(code)),
+        }
+
+        Ok(result)
     }
 
-    Ok(result)
-}
+    /// Compresses a G2 point from 128 bytes to 64 bytes
+    fn alt_bn128_g2_compress(point: &[u8; 128]) -> Result<[u8; 64], Groth16Error> {
+        let mut result = [0u8; ALT_BN128_G2_COMPRESSED_SIZE];
 
-/// Decompresses a G1 point from 32 bytes to 64 bytes
-///
-/// # Arguments
-/// * `compressed` - Compressed G1 point (32 bytes)
-///
-/// # Returns
-/// * `Ok([u8; 64])` - Decompressed G1 point
-/// * `Err(Groth16Error)` - If decompression fails
-pub fn alt_bn128_g1_decompress(compressed: &[u8; 32]) -> Result<[u8; 64], Groth16Error> {
-    let mut result = [0u8; ALT_BN128_G1_POINT_SIZE];
-
-    #[cfg(target_os = "solana")]
-    {
-        let return_code = unsafe {
-            pinocchio::syscalls::sol_alt_bn128_compression(
-                ALT_BN128_G1_DECOMPRESS,
-                compressed.as_ptr(),
-                compressed.len() as u64,
-                result.as_mut_ptr(),
-            )
-        };
+        if Self::compression(ALT_BN128_G2_COMPRESS, point, &mut result) != 0 {
+            return Err(Groth16Error::ProofConversionError);
+        }
+
+        Ok(result)
+    }
 
-        if return_code != 0 {
-            return Err(Groth16Error::DecompressingG1Failed);
+    /// Decompresses a G2 point from 64 bytes to 128 bytes
+    fn alt_bn128_g2_decompress(compressed: &[u8; 64]) -> Result<[u8; 128], Groth16Error> {
+        let mut result = [0u8; ALT_BN128_G2_POINT_SIZE];
+
+        match Self::compression(ALT_BN128_G2_DECOMPRESS, compressed, &mut result) {
+            0 => {}
+            code => return Err(Groth16Error::DecompressingG2Failed(code)),
         }
+
+        Ok(result)
     }
+}
 
-    #[cfg(not(target_os = "solana"))]
-    {
-        return Err(Groth16Error::DecompressingG1Failed);
+/// Runs the G2 group operation `op`, the checks shared by add, sub and mul
+fn g2_op<B: Bn254Backend + ?Sized>(
+    op: u64,
+    max_input_size: usize,
+    input: &[u8],
+) -> Result<Vec<u8>, Groth16Error> {
+    if input.len() > max_input_size {
+        return Err(Groth16Error::G2OperationFailed(0));
+    }
+
+    let mut result = vec![0u8; ALT_BN128_G2_POINT_SIZE];
+
+    match B::group_op(op, input, &mut result) {
+        0 => {}
+        code => return Err(Groth16Error::G2OperationFailed(code)),
     }
 
     Ok(result)
 }
 
-/// Compresses a G2 point from 128 bytes to 64 bytes
+/// The syscalls, the default backend
 ///
-/// # Arguments
-/// * `point` - Uncompressed G2 point (128 bytes)
+/// On chain they are declared by Pinocchio, or by `solana-define-syscall`
+/// with the `solana-program` feature instead of the `pinocchio` feature.
 ///
-/// # Returns
-/// * `Ok([u8; 64])` - Compressed G2 point
-/// * `Err(Groth16Error)` - If compression fails
-pub fn alt_bn128_g2_compress(point: &[u8; 128]) -> Result<[u8; 64], Groth16Error> {
-    let mut result = [0u8; ALT_BN128_G2_COMPRESSED_SIZE];
-
-    #[cfg(target_os = "solana")]
-    {
-        let return_code = unsafe {
-            pinocchio::syscalls::sol_alt_bn128_compression(
-                ALT_BN128_G2_COMPRESS,
-                point.as_ptr(),
-                point.len() as u64,
-                result.as_mut_ptr(),
-            )
-        };
+/// On the host it runs on [`crate::mock`] with the `mock-syscalls` feature
+/// and fails every operation without it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PinocchioSyscalls;
+
+impl Bn254Backend for PinocchioSyscalls {
+    fn group_op(op: u64, input: &[u8], result: &mut [u8]) -> u64 {
+        #[cfg(target_os = "solana")]
+        {
+            unsafe {
+                crate::syscalls::sol_alt_bn128_group_op(
+                    op,
+                    input.as_ptr(),
+                    input.len() as u64,
+                    result.as_mut_ptr(),
+                )
+            }
+        }
 
-        if return_code != 0 {
-            return Err(Groth16Error::ProofConversionError);
+        #[cfg(all(not(target_os = "solana"), any(test, feature = "mock-syscalls")))]
+        {
+            crate::mock::sol_alt_bn128_group_op(op, input, result)
         }
-    }
 
-    #[cfg(not(target_os = "solana"))]
-    {
-        return Err(Groth16Error::ProofConversionError);
+        #[cfg(all(not(target_os = "solana"), not(any(test, feature = "mock-syscalls"))))]
+        {
+            let _ = (op, input, result);
+            1
+        }
     }
 
-    Ok(result)
+    fn compression(op: u64, input: &[u8], result: &mut [u8]) -> u64 {
+        #[cfg(target_os = "solana")]
+        {
+            unsafe {
+                crate::syscalls::sol_alt_bn128_compression(
+                    op,
+                    input.as_ptr(),
+                    input.len() as u64,
+                    result.as_mut_ptr(),
+                )
+            }
+        }
+
+        #[cfg(all(not(target_os = "solana"), any(test, feature = "mock-syscalls")))]
+        {
+            crate::mock::sol_alt_bn128_compression(op, input, result)
+        }
+
+        #[cfg(all(not(target_os = "solana"), not(any(test, feature = "mock-syscalls"))))]
+        {
+            let _ = (op, input, result);
+            1
+        }
+    }
 }
 
-/// Decompresses a G2 point from 64 bytes to 128 bytes
-///
-/// # Arguments
-/// * `compressed` - Compressed G2 point (64 bytes)
+/// Native arkworks implementation of the syscalls, [`crate::mock`]
 ///
-/// # Returns
-/// * `Ok([u8; 128])` - Decompressed G2 point
-/// * `Err(Groth16Error)` - If decompression fails
-pub fn alt_bn128_g2_decompress(compressed: &[u8; 64]) -> Result<[u8; 128], Groth16Error> {
-    let mut result = [0u8; ALT_BN128_G2_POINT_SIZE];
-
-    #[cfg(target_os = "solana")]
-    {
-        let return_code = unsafe {
-            pinocchio::syscalls::sol_alt_bn128_compression(
-                ALT_BN128_G2_DECOMPRESS,
-                compressed.as_ptr(),
-                compressed.len() as u64,
-                result.as_mut_ptr(),
-            )
-        };
+/// Unlike [`PinocchioSyscalls`] it never reaches the runtime, so the same
+/// binary can use both side by side.
+#[cfg(all(not(target_os = "solana"), any(test, feature = "mock-syscalls")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NativeBackend;
+
+#[cfg(all(not(target_os = "solana"), any(test, feature = "mock-syscalls")))]
+impl Bn254Backend for NativeBackend {
+    fn group_op(op: u64, input: &[u8], result: &mut [u8]) -> u64 {
+        crate::mock::sol_alt_bn128_group_op(op, input, result)
+    }
 
-        if return_code != 0 {
-            return Err(Groth16Error::DecompressingG2Failed);
+    fn compression(op: u64, input: &[u8], result: &mut [u8]) -> u64 {
+        crate::mock::sol_alt_bn128_compression(op, input, result)
+    }
+}
+
+/// The `solana-bn254` crate, syscalls on chain and arkworks off chain
+#[cfg(feature = "solana-bn254")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SolanaBn254Backend;
+
+#[cfg(feature = "solana-bn254")]
+impl Bn254Backend for SolanaBn254Backend {
+    fn group_op(op: u64, input: &[u8], result: &mut [u8]) -> u64 {
+        use solana_bn254::prelude::{
+            alt_bn128_g1_addition_be, alt_bn128_g1_multiplication_be, alt_bn128_g2_addition_be,
+            alt_bn128_g2_multiplication_be, alt_bn128_pairing_be,
+        };
+        // The crate has no subtraction, G1_SUB and G2_SUB are unsupported
+        let output = match op {
+            ALT_BN128_G1_ADD => alt_bn128_g1_addition_be(input),
+            ALT_BN128_G1_MUL => alt_bn128_g1_multiplication_be(input),
+            ALT_BN128_PAIRING => alt_bn128_pairing_be(input),
+            ALT_BN128_G2_ADD => match <&[u8; ALT_BN128_G2_ADDITION_INPUT_SIZE]>::try_from(input) {
+                Ok(input) => alt_bn128_g2_addition_be(input),
+                Err(_) => return 1,
+            },
+            ALT_BN128_G2_MUL => {
+                match <&[u8; ALT_BN128_G2_MULTIPLICATION_INPUT_SIZE]>::try_from(input) {
+                    Ok(input) => alt_bn128_g2_multiplication_be(input),
+                    Err(_) => return 1,
+                }
+            }
+            _ => return 1,
+        };
+        match output {
+            Ok(output) if output.len() == result.len() => {
+                result.copy_from_slice(&output);
+                0
+            }
+            _ => 1,
         }
     }
 
-    #[cfg(not(target_os = "solana"))]
-    {
-        return Err(Groth16Error::DecompressingG2Failed);
+    fn compression(op: u64, input: &[u8], result: &mut [u8]) -> u64 {
+        use solana_bn254::compression::prelude::{
+            alt_bn128_g1_compress_be, alt_bn128_g1_decompress_be, alt_bn128_g2_compress_be,
+            alt_bn128_g2_decompress_be,
+        };
+        fn write<const N: usize, E>(output: Result<[u8; N], E>, result: &mut [u8]) -> u64 {
+            match output {
+                Ok(output) if output.len() == result.len() => {
+                    result.copy_from_slice(&output);
+                    0
+                }
+                _ => 1,
+            }
+        }
+        match op {
+            ALT_BN128_G1_COMPRESS => match <&[u8; 64]>::try_from(input) {
+                Ok(point) => write(alt_bn128_g1_compress_be(point), result),
+                Err(_) => 1,
+            },
+            ALT_BN128_G1_DECOMPRESS => match <&[u8; 32]>::try_from(input) {
+                Ok(point) => write(alt_bn128_g1_decompress_be(point), result),
+                Err(_) => 1,
+            },
+            ALT_BN128_G2_COMPRESS => match <&[u8; 128]>::try_from(input) {
+                Ok(point) => write(alt_bn128_g2_compress_be(point), result),
+                Err(_) => 1,
+            },
+            ALT_BN128_G2_DECOMPRESS => match <&[u8; 64]>::try_from(input) {
+                Ok(point) => write(alt_bn128_g2_decompress_be(point), result),
+                Err(_) => 1,
+            },
+            _ => 1,
+        }
     }
+}
 
-    Ok(result)
+/// [`Bn254Backend::alt_bn128_addition`] on [`PinocchioSyscalls`]
+pub fn alt_bn128_addition(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
+    PinocchioSyscalls::alt_bn128_addition(input)
+}
+
+/// [`Bn254Backend::alt_bn128_multiplication`] on [`PinocchioSyscalls`]
+pub fn alt_bn128_multiplication(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
+    PinocchioSyscalls::alt_bn128_multiplication(input)
+}
+
+/// [`Bn254Backend::alt_bn128_g1_subtraction`] on [`PinocchioSyscalls`]
+pub fn alt_bn128_g1_subtraction(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
+    PinocchioSyscalls::alt_bn128_g1_subtraction(input)
+}
+
+/// [`Bn254Backend::alt_bn128_g2_addition`] on [`PinocchioSyscalls`]
+pub fn alt_bn128_g2_addition(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
+    PinocchioSyscalls::alt_bn128_g2_addition(input)
+}
+
+/// [`Bn254Backend::alt_bn128_g2_subtraction`] on [`PinocchioSyscalls`]
+pub fn alt_bn128_g2_subtraction(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
+    PinocchioSyscalls::alt_bn128_g2_subtraction(input)
+}
+
+/// [`Bn254Backend::alt_bn128_g2_multiplication`] on [`PinocchioSyscalls`]
+pub fn alt_bn128_g2_multiplication(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
+    PinocchioSyscalls::alt_bn128_g2_multiplication(input)
+}
+
+/// [`Bn254Backend::alt_bn128_pairing`] on [`PinocchioSyscalls`]
+pub fn alt_bn128_pairing(input: &[u8]) -> Result<Vec<u8>, Groth16Error> {
+    PinocchioSyscalls::alt_bn128_pairing(input)
+}
+
+/// [`Bn254Backend::alt_bn128_pairing_batch`] on [`PinocchioSyscalls`]
+pub fn alt_bn128_pairing_batch(products: &[&[u8]]) -> Result<bool, Groth16Error> {
+    PinocchioSyscalls::alt_bn128_pairing_batch(products)
+}
+
+/// [`Bn254Backend::alt_bn128_g1_compress`] on [`PinocchioSyscalls`]
+pub fn alt_bn128_g1_compress(point: &[u8; 64]) -> Result<[u8; 32], Groth16Error> {
+    PinocchioSyscalls::alt_bn128_g1_compress(point)
+}
+
+/// [`Bn254Backend::alt_bn128_g1_decompress`] on [`PinocchioSyscalls`]
+pub fn alt_bn128_g1_decompress(compressed: &[u8; 32]) -> Result<[u8; 64], Groth16Error> {
+    PinocchioSyscalls::alt_bn128_g1_decompress(compressed)
+}
+
+/// [`Bn254Backend::alt_bn128_g2_compress`] on [`PinocchioSyscalls`]
+pub fn alt_bn128_g2_compress(point: &[u8; 128]) -> Result<[u8; 64], Groth16Error> {
+    PinocchioSyscalls::alt_bn128_g2_compress(point)
+}
+
+/// [`Bn254Backend::alt_bn128_g2_decompress`] on [`PinocchioSyscalls`]
+pub fn alt_bn128_g2_decompress(compressed: &[u8; 64]) -> Result<[u8; 128], Groth16Error> {
+    PinocchioSyscalls::alt_bn128_g2_decompress(compressed)
+}
+
+/// Negates a G1 point, `(x, q - y)`, without a syscall
+///
+/// snarkjs' `proof.json` holds proof a as proven, the verifier takes it
+/// negated, see also
+/// [`CurveVerifier::with_proof_a_negation`](crate::groth16::CurveVerifier::with_proof_a_negation).
+/// The point at infinity is its own negation.
+///
+/// # Returns
+/// * `Err(Groth16Error::NonCanonicalCoordinate)` - If y is not smaller than
+///   the base field modulus
+pub fn negate_g1(point: &[u8; 64]) -> Result<[u8; 64], Groth16Error> {
+    use crate::curve::{Bn254, PairingCurve};
+
+    Bn254::g1_negate(point).map_err(|_| Groth16Error::NonCanonicalCoordinate)
 }
 
 /// Converts endianness by reversing byte chunks
@@ -321,6 +574,31 @@ pub fn convert_endianness<const CHUNK_SIZE: usize, const ARRAY_SIZE: usize>(
 mod tests {
     use super::*;
 
+    #[test]
+    fn negate_g1_flips_y() {
+        use crate::curve::{Bn254, PairingCurve};
+
+        // The generator (1, 2) negates to (1, q - 2)
+        let mut generator = [0u8; 64];
+        generator[31] = 1;
+        generator[63] = 2;
+        let negated = negate_g1(&generator).unwrap();
+        assert_eq!(negated[..32], generator[..32]);
+        let mut expected = [0u8; 32];
+        expected.copy_from_slice(Bn254::BASE_FIELD_MODULUS);
+        expected[31] -= 2;
+        assert_eq!(negated[32..], expected);
+        assert_eq!(negate_g1(&negated).unwrap(), generator);
+        assert_eq!(negate_g1(&[0u8; 64]).unwrap(), [0u8; 64]);
+
+        let mut non_canonical = generator;
+        non_canonical[32..].copy_from_slice(Bn254::BASE_FIELD_MODULUS);
+        assert_eq!(
+            negate_g1(&non_canonical),
+            Err(Groth16Error::NonCanonicalCoordinate)
+        );
+    }
+
     #[test]
     fn test_convert_endianness_32_64() {
         let input: [u8; 64] = [
@@ -361,4 +639,70 @@ mod tests {
         assert_eq!(result[64], 128);
         assert_eq!(result[127], 65);
     }
+
+    #[cfg(feature = "test-vectors")]
+    #[test]
+    fn backends_verify_the_same_proofs() {
+        use crate::curve::Bn254With;
+        use crate::groth16::CurveVerifier;
+
+        fn verify<B: Bn254Backend>(
+            vector: &crate::test_vectors::TestVector,
+            inputs: &[[u8; 32]; 2],
+        ) {
+            let vk = vector.verifying_key();
+            let vk = vk.with_backend::<B>();
+            CurveVerifier::<2, Bn254With<B>>::new(
+                &vector.proof_a,
+                &vector.proof_b,
+                &vector.proof_c,
+                inputs,
+                &vk,
+            )
+            .unwrap()
+            .verify()
+            .unwrap();
+            let compressed = B::alt_bn128_g2_compress(&vector.proof_b).unwrap();
+            assert_eq!(
+                B::alt_bn128_g2_decompress(&compressed).unwrap(),
+                vector.proof_b
+            );
+        }
+
+        let vector = crate::test_vectors::generate(0);
+        let inputs: [[u8; 32]; 2] = vector.public_inputs.clone().try_into().unwrap();
+        verify::<PinocchioSyscalls>(&vector, &inputs);
+        verify::<NativeBackend>(&vector, &inputs);
+        #[cfg(feature = "solana-bn254")]
+        verify::<SolanaBn254Backend>(&vector, &inputs);
+    }
+
+    #[cfg(all(feature = "test-vectors", feature = "solana-bn254"))]
+    #[test]
+    fn native_compression_matches_the_runtime() {
+        use crate::decompression::DecompressedProof;
+
+        for seed in 0..4 {
+            let vector = crate::test_vectors::generate(seed);
+            let proof = DecompressedProof {
+                proof_a: vector.proof_a,
+                proof_b: vector.proof_b,
+                proof_c: vector.proof_c,
+            };
+            let compressed = proof.compress::<NativeBackend>().unwrap();
+            assert_eq!(compressed, proof.compress::<SolanaBn254Backend>().unwrap());
+            assert_eq!(
+                DecompressedProof::from_compressed_bytes(&compressed).unwrap(),
+                proof
+            );
+        }
+        assert_eq!(
+            NativeBackend::alt_bn128_g2_compress(&[0u8; 128]),
+            SolanaBn254Backend::alt_bn128_g2_compress(&[0u8; 128])
+        );
+        assert_eq!(
+            NativeBackend::alt_bn128_g1_compress(&[1u8; 64]),
+            Err(Groth16Error::ProofConversionError)
+        );
+    }
 }
@@ -0,0 +1,420 @@
+//! Client-side transaction building
+//!
+//! [`TransactionBuilder`] turns a proof and its public inputs into the
+//! instructions of one or more transactions that each fit into a packet:
+//!
+//! * If the verify instruction with the proof and inputs inline fits, a single
+//!   transaction `[compute budget, verify]` is returned.
+//! * Otherwise the proof and inputs are streamed into a proof account (see
+//!   [`crate::proof_account`]) with as many write transactions as needed,
+//!   followed by `[compute budget, finalize, verify]`.
+//!
+//! Instruction data emitted by the builder:
+//!
+//! | instruction | data                                             | accounts                                  |
+//! |-------------|--------------------------------------------------|-------------------------------------------|
+//! | verify      | `verify_prefix \| proof_a \| proof_b \| proof_c \| inputs` | verify accounts                 |
+//! | write       | `write_prefix \| offset u32 LE \| chunk`         | proof account (w), authority (s)          |
+//! | finalize    | `finalize_prefix`                                | proof account (w), authority (s)          |
+//! | verify from account | `verify_prefix`                          | verify accounts, proof account            |
+//!
+//! The prefixes are the program's instruction discriminators, `offset` is
+//! relative to the start of the proof bytes as expected by
+//! [`crate::proof_account::write_proof_account_chunk`].
+
+use crate::compute_units::compute_unit_limit;
+use crate::errors::Groth16Error;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+use std::vec::Vec;
+
+/// Maximum size of a serialized transaction
+pub const PACKET_DATA_SIZE: usize = 1232;
+
+/// Compute budget program id, `ComputeBudget111111111111111111111111111111`
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    3, 6, 70, 111, 229, 33, 23, 50, 255, 236, 173, 186, 114, 195, 155, 231, 188, 140, 229, 187,
+    197, 247, 18, 107, 44, 67, 155, 58, 64, 0, 0, 0,
+]);
+
+/// Instructions that write a proof into a proof account and verify it from there
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofAccountInstructions {
+    /// The proof account, initialized with enough space for the public inputs
+    pub proof_account: Pubkey,
+    /// Authority of the proof account, signs writes and finalization
+    pub authority: Pubkey,
+    pub write_prefix: Vec<u8>,
+    pub finalize_prefix: Vec<u8>,
+    pub verify_prefix: Vec<u8>,
+}
+
+/// Builds the transactions needed to verify a proof with a program
+#[derive(Clone, Debug)]
+pub struct TransactionBuilder {
+    program_id: Pubkey,
+    payer: Pubkey,
+    verify_prefix: Vec<u8>,
+    verify_accounts: Vec<AccountMeta>,
+    proof_account: Option<ProofAccountInstructions>,
+    compute_unit_limit: ComputeUnitLimit,
+    compute_unit_price: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ComputeUnitLimit {
+    Default,
+    Fixed(u32),
+    Estimated { extra_units: u32 },
+}
+
+impl TransactionBuilder {
+    /// # Arguments
+    /// * `program_id` - The program verifying the proof
+    /// * `payer` - Fee payer, always the first signer of every transaction
+    /// * `verify_prefix` - Instruction data preceding the proof in the verify instruction
+    /// * `verify_accounts` - Accounts of the verify instruction
+    pub fn new(
+        program_id: Pubkey,
+        payer: Pubkey,
+        verify_prefix: Vec<u8>,
+        verify_accounts: Vec<AccountMeta>,
+    ) -> Self {
+        TransactionBuilder {
+            program_id,
+            payer,
+            verify_prefix,
+            verify_accounts,
+            proof_account: None,
+            compute_unit_limit: ComputeUnitLimit::Default,
+            compute_unit_price: None,
+        }
+    }
+
+    /// Falls back to a proof account if the proof does not fit inline
+    pub fn with_proof_account(mut self, proof_account: ProofAccountInstructions) -> Self {
+        self.proof_account = Some(proof_account);
+        self
+    }
+
+    /// Prepends `SetComputeUnitLimit` to the verifying transaction
+    pub fn with_compute_unit_limit(mut self, units: u32) -> Self {
+        self.compute_unit_limit = ComputeUnitLimit::Fixed(units);
+        self
+    }
+
+    /// Prepends `SetComputeUnitLimit` sized from the number of public inputs,
+    /// see [`verify_compute_unit_limit`]
+    pub fn with_estimated_compute_unit_limit(mut self, extra_units: u32) -> Self {
+        self.compute_unit_limit = ComputeUnitLimit::Estimated { extra_units };
+        self
+    }
+
+    /// Prepends `SetComputeUnitPrice` to the verifying transaction
+    pub fn with_compute_unit_price(mut self, micro_lamports: u64) -> Self {
+        self.compute_unit_price = Some(micro_lamports);
+        self
+    }
+
+    /// Builds the instructions of every transaction, in the order they must land
+    ///
+    /// Write transactions are independent of each other and may be sent
+    /// concurrently, the last transaction must land after all of them.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Vec<Instruction>>)` - One instruction list per transaction
+    /// * `Err(Groth16Error::TransactionTooLarge)` - If the proof does not fit
+    ///   inline and no proof account is configured, or even a single write
+    ///   does not fit
+    pub fn build(
+        &self,
+        proof_a: &[u8; 64],
+        proof_b: &[u8; 128],
+        proof_c: &[u8; 64],
+        public_inputs: &[[u8; 32]],
+    ) -> Result<Vec<Vec<Instruction>>, Groth16Error> {
+        let mut proof = Vec::with_capacity(256 + public_inputs.len() * 32);
+        proof.extend_from_slice(proof_a);
+        proof.extend_from_slice(proof_b);
+        proof.extend_from_slice(proof_c);
+        for input in public_inputs {
+            proof.extend_from_slice(input);
+        }
+
+        let mut inline = self.compute_budget_instructions(public_inputs.len());
+        inline.push(Instruction::new_with_bytes(
+            self.program_id,
+            &[&self.verify_prefix[..], &proof[..]].concat(),
+            self.verify_accounts.clone(),
+        ));
+        if transaction_size(&self.payer, &inline) <= PACKET_DATA_SIZE {
+            return Ok(vec![inline]);
+        }
+
+        let config = self
+            .proof_account
+            .as_ref()
+            .ok_or(Groth16Error::TransactionTooLarge)?;
+        let buffer_accounts = vec![
+            AccountMeta::new(config.proof_account, false),
+            AccountMeta::new_readonly(config.authority, true),
+        ];
+
+        let mut last = self.compute_budget_instructions(public_inputs.len());
+        last.push(Instruction::new_with_bytes(
+            self.program_id,
+            &config.finalize_prefix,
+            buffer_accounts.clone(),
+        ));
+        let mut verify_accounts = self.verify_accounts.clone();
+        verify_accounts.push(AccountMeta::new_readonly(config.proof_account, false));
+        last.push(Instruction::new_with_bytes(
+            self.program_id,
+            &config.verify_prefix,
+            verify_accounts,
+        ));
+        if transaction_size(&self.payer, &last) > PACKET_DATA_SIZE {
+            return Err(Groth16Error::TransactionTooLarge);
+        }
+
+        let write = |offset: usize, chunk: &[u8]| {
+            let mut data = config.write_prefix.clone();
+            data.extend_from_slice(&(offset as u32).to_le_bytes());
+            data.extend_from_slice(chunk);
+            Instruction::new_with_bytes(self.program_id, &data, buffer_accounts.clone())
+        };
+        // Chunks of 128 bytes or more need a 2 byte compact length prefix.
+        let overhead = transaction_size(&self.payer, &[write(0, &[0u8; 0x80])]) - 0x80;
+        let max_chunk = PACKET_DATA_SIZE
+            .checked_sub(overhead)
+            .filter(|max_chunk| *max_chunk > 0)
+            .ok_or(Groth16Error::TransactionTooLarge)?;
+
+        let mut transactions: Vec<Vec<Instruction>> = proof
+            .chunks(max_chunk)
+            .enumerate()
+            .map(|(i, chunk)| vec![write(i * max_chunk, chunk)])
+            .collect();
+        transactions.push(last);
+        Ok(transactions)
+    }
+
+    fn compute_budget_instructions(&self, nr_inputs: usize) -> Vec<Instruction> {
+        let mut instructions = Vec::new();
+        let units = match self.compute_unit_limit {
+            ComputeUnitLimit::Default => None,
+            ComputeUnitLimit::Fixed(units) => Some(units),
+            ComputeUnitLimit::Estimated { extra_units } => {
+                Some(verify_compute_unit_limit(nr_inputs, extra_units))
+            }
+        };
+        if let Some(units) = units {
+            instructions.push(set_compute_unit_limit_instruction(units));
+        }
+        if let Some(micro_lamports) = self.compute_unit_price {
+            instructions.push(set_compute_unit_price_instruction(micro_lamports));
+        }
+        instructions
+    }
+}
+
+/// Returns a `SetComputeUnitLimit` instruction
+pub fn set_compute_unit_limit_instruction(units: u32) -> Instruction {
+    let mut data = vec![2u8];
+    data.extend_from_slice(&units.to_le_bytes());
+    Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM_ID, &data, Vec::new())
+}
+
+/// Returns a `SetComputeUnitPrice` instruction
+pub fn set_compute_unit_price_instruction(micro_lamports: u64) -> Instruction {
+    let mut data = vec![3u8];
+    data.extend_from_slice(&micro_lamports.to_le_bytes());
+    Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM_ID, &data, Vec::new())
+}
+
+/// Compute unit limit for verifying a proof with `nr_inputs` public inputs
+///
+/// # Arguments
+/// * `nr_inputs` - Number of public inputs of the circuit
+/// * `extra_units` - Units the program spends besides verification
+pub fn verify_compute_unit_limit(nr_inputs: usize, extra_units: u32) -> u32 {
+    compute_unit_limit(nr_inputs, false, extra_units as u64)
+}
+
+/// Compute budget instructions to prepend before a verify instruction
+///
+/// # Arguments
+/// * `nr_inputs` - Number of public inputs of the circuit
+/// * `extra_units` - Units the program spends besides verification
+/// * `micro_lamports` - Optional priority fee per compute unit
+pub fn verify_compute_budget_instructions(
+    nr_inputs: usize,
+    extra_units: u32,
+    micro_lamports: Option<u64>,
+) -> Vec<Instruction> {
+    let mut instructions = vec![set_compute_unit_limit_instruction(
+        verify_compute_unit_limit(nr_inputs, extra_units),
+    )];
+    if let Some(micro_lamports) = micro_lamports {
+        instructions.push(set_compute_unit_price_instruction(micro_lamports));
+    }
+    instructions
+}
+
+/// Returns the serialized size of a legacy transaction with `payer` as fee payer
+///
+/// Account keys are deduplicated the same way the runtime compiles messages,
+/// signers are the payer and every account marked as signer.
+pub fn transaction_size(payer: &Pubkey, instructions: &[Instruction]) -> usize {
+    let mut keys = vec![*payer];
+    let mut signers = vec![*payer];
+    for instruction in instructions {
+        for meta in &instruction.accounts {
+            if !keys.contains(&meta.pubkey) {
+                keys.push(meta.pubkey);
+            }
+            if meta.is_signer && !signers.contains(&meta.pubkey) {
+                signers.push(meta.pubkey);
+            }
+        }
+        if !keys.contains(&instruction.program_id) {
+            keys.push(instruction.program_id);
+        }
+    }
+
+    let instructions_size: usize = instructions
+        .iter()
+        .map(|instruction| {
+            1 + compact_u16_len(instruction.accounts.len())
+                + instruction.accounts.len()
+                + compact_u16_len(instruction.data.len())
+                + instruction.data.len()
+        })
+        .sum();
+
+    compact_u16_len(signers.len())
+        + signers.len() * 64
+        + 3
+        + compact_u16_len(keys.len())
+        + keys.len() * 32
+        + 32
+        + compact_u16_len(instructions.len())
+        + instructions_size
+}
+
+fn compact_u16_len(value: usize) -> usize {
+    match value {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_units::{estimate_verify_compute_units, MAX_COMPUTE_UNIT_LIMIT};
+
+    fn builder() -> TransactionBuilder {
+        let program_id = Pubkey::new_from_array([1u8; 32]);
+        let payer = Pubkey::new_from_array([2u8; 32]);
+        TransactionBuilder::new(
+            program_id,
+            payer,
+            vec![0],
+            vec![AccountMeta::new(Pubkey::new_from_array([3u8; 32]), false)],
+        )
+        .with_compute_unit_limit(200_000)
+    }
+
+    fn proof_account() -> ProofAccountInstructions {
+        ProofAccountInstructions {
+            proof_account: Pubkey::new_from_array([4u8; 32]),
+            authority: Pubkey::new_from_array([2u8; 32]),
+            write_prefix: vec![1],
+            finalize_prefix: vec![2],
+            verify_prefix: vec![3],
+        }
+    }
+
+    #[test]
+    fn transaction_size_of_single_instruction() {
+        let payer = Pubkey::new_from_array([2u8; 32]);
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_from_array([1u8; 32]),
+            &[0u8; 10],
+            vec![AccountMeta::new(payer, true)],
+        );
+        // 1 + 64 signature, 3 header, 1 + 2 * 32 keys, 32 blockhash,
+        // 1 + (1 + 1 + 1 + 1 + 10) instruction
+        assert_eq!(
+            transaction_size(&payer, &[instruction]),
+            65 + 3 + 65 + 32 + 15
+        );
+    }
+
+    #[test]
+    fn compute_budget_instructions() {
+        let instructions = verify_compute_budget_instructions(3, 10_000, Some(5));
+        assert_eq!(instructions.len(), 2);
+        let units = (estimate_verify_compute_units(3) + 10_000) as u32;
+        assert_eq!(instructions[0].program_id, COMPUTE_BUDGET_PROGRAM_ID);
+        assert_eq!(
+            instructions[0].data,
+            [&[2u8][..], &units.to_le_bytes()].concat()
+        );
+        assert_eq!(
+            instructions[1].data,
+            [&[3u8][..], &5u64.to_le_bytes()].concat()
+        );
+        assert_eq!(
+            verify_compute_unit_limit(3, u32::MAX),
+            MAX_COMPUTE_UNIT_LIMIT
+        );
+    }
+
+    #[test]
+    fn small_proof_is_verified_inline() {
+        let transactions = builder()
+            .build(&[0u8; 64], &[0u8; 128], &[0u8; 64], &[[0u8; 32]; 4])
+            .unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].len(), 2);
+        assert_eq!(transactions[0][0].program_id, COMPUTE_BUDGET_PROGRAM_ID);
+        assert_eq!(transactions[0][1].data.len(), 1 + 256 + 4 * 32);
+    }
+
+    #[test]
+    fn large_proof_is_chunked_into_proof_account() {
+        let public_inputs = [[7u8; 32]; 64];
+        assert_eq!(
+            builder().build(&[0u8; 64], &[0u8; 128], &[0u8; 64], &public_inputs),
+            Err(Groth16Error::TransactionTooLarge)
+        );
+
+        let builder = builder().with_proof_account(proof_account());
+        let transactions = builder
+            .build(&[0u8; 64], &[0u8; 128], &[0u8; 64], &public_inputs)
+            .unwrap();
+        assert!(transactions.len() > 2);
+
+        let mut written = Vec::new();
+        for transaction in &transactions[..transactions.len() - 1] {
+            assert!(transaction_size(&builder.payer, transaction) <= PACKET_DATA_SIZE);
+            let data = &transaction[0].data;
+            let offset = u32::from_le_bytes(data[1..5].try_into().unwrap()) as usize;
+            assert_eq!(offset, written.len());
+            written.extend_from_slice(&data[5..]);
+        }
+        assert_eq!(written.len(), 256 + 64 * 32);
+        assert_eq!(written[256..], [7u8; 64 * 32]);
+
+        let last = transactions.last().unwrap();
+        assert_eq!(last[1].data, vec![2]);
+        assert_eq!(last[2].data, vec![3]);
+        assert_eq!(
+            last[2].accounts.last().unwrap().pubkey,
+            proof_account().proof_account
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! Groth16 with Pedersen commitments, gnark's commitment extension
+//!
+//! gnark circuits using the `Commit` API, and LegoGroth16-style proofs in
+//! general, carry a commitment point `D` per commitment and a proof of
+//! knowledge of its opening. Each commitment becomes an extra public input,
+//! the hash of `D` and the public inputs it commits to, and `D` itself is
+//! added to the prepared inputs:
+//!
+//! ```text
+//! value_i  = hash_to_field("bsb22-commitment", D_i | committed public inputs)
+//! prepared = K_0 + sum x_j K_j+1 + sum value_i K_n+i+1 + sum D_i
+//! e(-a, b) * e(prepared, gamma) * e(c, delta) * e(alpha, beta) == 1
+//! ```
+//!
+//! The proof of knowledge is checked against the Pedersen keys of the
+//! commitments, folded with powers of a challenge `r` hashed from the values:
+//! `e(D_0, -sigma_0 G) * e(r D_1, -sigma_1 G) * ... * e(pok, G) == 1`.
+//! `hash_to_field` is `expand_message_xmd` with SHA-256 of RFC 9380, as
+//! gnark-crypto's `fr.Hash`.
+//!
+//! [`verify_with_commitments`] runs both checks and returns the commitment
+//! values, the public outputs of the committed wires. Proofs are read from
+//! gnark's `WriteRawTo` output with [`CommitmentProof::from_gnark_bytes`];
+//! the verifying key is filled in from gnark's `VerifyingKey`, whose `K`
+//! holds the IC points of the commitment values after those of the public
+//! inputs.
+
+use crate::curve::{Bn254, PairingCurve};
+use crate::errors::Groth16Error;
+use crate::groth16::{check_pairing, Groth16Verifyingkey, PreparedInputs};
+use crate::hash::sha256;
+use crate::proof::{Proof, GNARK_FLAG_MASK, PROOF_SIZE};
+use alloc::vec::Vec;
+use ark_ff::{BigInteger, Field, PrimeField};
+
+/// Domain separation tag of the commitment values, gnark's `constraint.CommitmentDst`
+pub const COMMITMENT_DST: &[u8] = b"bsb22-commitment";
+
+/// Domain separation tag of the challenge folding the proofs of knowledge
+pub const COMMITMENT_CHALLENGE_DST: &[u8] = b"G16-BSB22";
+
+/// Pedersen verifying key of a commitment, `G` and `-sigma G` in G2
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PedersenVerifyingkey {
+    pub g: [u8; 128],
+    pub g_sigma_neg: [u8; 128],
+}
+
+/// Verifying key of a circuit with commitments
+#[derive(Debug, PartialEq, Eq)]
+pub struct CommitmentVerifyingkey<'a> {
+    /// The Groth16 key, with an IC point per public input and then one per commitment
+    pub verifyingkey: Groth16Verifyingkey<'a>,
+    /// Pedersen key of each commitment
+    pub commitment_keys: &'a [PedersenVerifyingkey],
+    /// Per commitment the 1-based indices of the public inputs it commits to,
+    /// indices past the public inputs refer to earlier commitment values
+    pub public_and_commitment_committed: &'a [&'a [usize]],
+}
+
+/// A proof with commitments, proof a negated
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitmentProof {
+    pub proof: Proof,
+    pub commitments: Vec<[u8; 64]>,
+    pub commitment_pok: [u8; 64],
+}
+
+impl CommitmentProof {
+    /// Reads a proof serialized by gnark's `WriteRawTo`
+    ///
+    /// Layout: `Ar | Bs | Krs | nr_commitments u32 BE | commitments | pok`,
+    /// uncompressed and big-endian. `Ar` is negated as by
+    /// [`Proof::from_gnark_bytes`].
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::ProofConversionError)` - If the length does not
+    ///   match the number of commitments or a point is flagged compressed or
+    ///   at infinity
+    pub fn from_gnark_bytes(bytes: &[u8]) -> Result<Self, Groth16Error> {
+        if bytes.len() < PROOF_SIZE + 4 {
+            return Err(Groth16Error::ProofConversionError);
+        }
+        let proof = Proof::from_gnark_bytes(&bytes[..PROOF_SIZE])?;
+        let nr_commitments =
+            u32::from_be_bytes(bytes[PROOF_SIZE..PROOF_SIZE + 4].try_into().unwrap()) as usize;
+        let points = &bytes[PROOF_SIZE + 4..];
+        let points_size = nr_commitments
+            .checked_add(1)
+            .and_then(|nr_points| nr_points.checked_mul(64))
+            .ok_or(Groth16Error::ProofConversionError)?;
+        if points.len() != points_size
+            || points
+                .chunks_exact(64)
+                .any(|point| point[0] & GNARK_FLAG_MASK != 0)
+        {
+            return Err(Groth16Error::ProofConversionError);
+        }
+        let mut points = points
+            .chunks_exact(64)
+            .map(|point| <[u8; 64]>::try_from(point).unwrap());
+        Ok(CommitmentProof {
+            proof,
+            commitments: points.by_ref().take(nr_commitments).collect(),
+            commitment_pok: points.next().unwrap(),
+        })
+    }
+}
+
+/// `expand_message_xmd` of RFC 9380 with SHA-256, `LEN` at most 255 * 32
+fn expand_message_xmd<const LEN: usize>(message: &[&[u8]], dst: &[u8]) -> [u8; LEN] {
+    let dst_len = [dst.len() as u8];
+    let b_0 = sha256(
+        &[
+            &[&[0u8; 64][..]][..],
+            message,
+            &[&(LEN as u16).to_be_bytes(), &[0], dst, &dst_len],
+        ]
+        .concat(),
+    );
+    let mut uniform = [0u8; LEN];
+    let mut b_i = [0u8; 32];
+    for (i, chunk) in uniform.chunks_mut(32).enumerate() {
+        for (byte, other) in b_i.iter_mut().zip(b_0) {
+            *byte ^= other;
+        }
+        b_i = sha256(&[&b_i, &[i as u8 + 1], dst, &dst_len]);
+        chunk.copy_from_slice(&b_i[..chunk.len()]);
+    }
+    uniform
+}
+
+/// gnark-crypto's `fr.Hash` to one big-endian scalar, 48 uniform bytes reduced
+fn hash_to_field(message: &[&[u8]], dst: &[u8]) -> [u8; 32] {
+    let uniform = expand_message_xmd::<48>(message, dst);
+    scalar_to_be(ark_bn254::Fr::from_be_bytes_mod_order(&uniform))
+}
+
+fn scalar_to_be(scalar: ark_bn254::Fr) -> [u8; 32] {
+    scalar.into_bigint().to_bytes_be().try_into().unwrap()
+}
+
+/// Verifies a proof with commitments and returns the commitment values
+///
+/// # Arguments
+/// * `proof` - The proof, proof a negated
+/// * `commitments` - The commitment points `D_i`
+/// * `commitment_pok` - The folded proof of knowledge of the commitments
+/// * `public_inputs` - The public inputs, without the commitment values
+/// * `verifyingkey` - The key of the circuit
+///
+/// # Returns
+/// * `Ok(values)` - The commitment value of each commitment, big-endian
+/// * `Err(Groth16Error::InvalidPublicInputsLength)` - If the numbers of
+///   inputs, commitments and keys do not match or a committed index is out of range
+/// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If an input is
+///   not smaller than the scalar field modulus
+/// * `Err(Groth16Error::CommitmentVerificationFailed)` - If the proof of
+///   knowledge does not verify
+/// * `Err(Groth16Error::ProofVerificationFailed)` - If the proof does not verify
+pub fn verify_with_commitments(
+    proof: &Proof,
+    commitments: &[[u8; 64]],
+    commitment_pok: &[u8; 64],
+    public_inputs: &[[u8; 32]],
+    verifyingkey: &CommitmentVerifyingkey,
+) -> Result<Vec<[u8; 32]>, Groth16Error> {
+    let nr_commitments = commitments.len();
+    if verifyingkey.commitment_keys.len() != nr_commitments
+        || verifyingkey.public_and_commitment_committed.len() != nr_commitments
+        || public_inputs.len() + nr_commitments + 1 != verifyingkey.verifyingkey.vk_ic.len()
+    {
+        return Err(Groth16Error::InvalidPublicInputsLength);
+    }
+
+    // The commitment values extend the public inputs
+    let mut witness = public_inputs.to_vec();
+    for (commitment, committed) in commitments
+        .iter()
+        .zip(verifyingkey.public_and_commitment_committed)
+    {
+        let mut prehash: Vec<&[u8]> = Vec::with_capacity(committed.len() + 1);
+        prehash.push(commitment);
+        for index in committed.iter() {
+            let value = index
+                .checked_sub(1)
+                .and_then(|index| witness.get(index))
+                .ok_or(Groth16Error::InvalidPublicInputsLength)?;
+            prehash.push(value);
+        }
+        let value = hash_to_field(&prehash, COMMITMENT_DST);
+        witness.push(value);
+    }
+    let values = witness.split_off(public_inputs.len());
+
+    if nr_commitments > 0 {
+        let challenge = ark_bn254::Fr::from_be_bytes_mod_order(&hash_to_field(
+            &[&values.concat()],
+            COMMITMENT_CHALLENGE_DST,
+        ));
+        let mut g1_points = Vec::with_capacity(nr_commitments + 1);
+        for (i, commitment) in commitments.iter().enumerate() {
+            g1_points.push(match i {
+                0 => *commitment,
+                i => Bn254::g1_mul(commitment, &scalar_to_be(challenge.pow([i as u64])))?,
+            });
+        }
+        g1_points.push(*commitment_pok);
+        let mut g2_points: Vec<&[u8; 128]> = verifyingkey
+            .commitment_keys
+            .iter()
+            .map(|key| &key.g_sigma_neg)
+            .collect();
+        g2_points.push(&verifyingkey.commitment_keys[0].g);
+        let g1_points: Vec<&[u8; 64]> = g1_points.iter().collect();
+        if !Bn254::pairing_is_one(&g1_points, &g2_points)
+            .map_err(|_| Groth16Error::CommitmentVerificationFailed)?
+        {
+            return Err(Groth16Error::CommitmentVerificationFailed);
+        }
+    }
+
+    witness.extend_from_slice(&values);
+    let mut prepared_inputs = PreparedInputs::new(&witness, &verifyingkey.verifyingkey)?.0;
+    for commitment in commitments {
+        prepared_inputs = Bn254::g1_add(commitment, &prepared_inputs)?;
+    }
+    check_pairing::<Bn254>(
+        &proof.a.0,
+        &proof.b.0,
+        &proof.c.0,
+        &prepared_inputs,
+        &verifyingkey.verifyingkey,
+    )?;
+    Ok(values)
+}
+
+#[cfg(all(test, feature = "test-vectors"))]
+mod tests {
+    use super::*;
+    use crate::proof::{ProofA, ProofB, ProofC};
+    use crate::test_vectors::{g1_to_be, g2_to_be};
+    use ark_bn254::{Fr, G1Affine, G2Affine};
+    use ark_ec::{AffineRepr, CurveGroup};
+
+    #[test]
+    fn expand_message_matches_rfc_9380() {
+        // RFC 9380 K.1, expand_message_xmd(SHA-256)
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        let hex = |bytes: [u8; 32]| bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+        assert_eq!(
+            hex(expand_message_xmd::<32>(&[], dst)),
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+        );
+        assert_eq!(
+            hex(expand_message_xmd::<32>(&[b"ab", b"c"], dst)),
+            "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+        );
+    }
+
+    /// A key and proof built from a known trapdoor, as a simulator would
+    #[test]
+    fn proofs_with_a_commitment_verify() {
+        let g1 = |scalar: Fr| g1_to_be(&(G1Affine::generator() * scalar).into_affine());
+        let g2 = |scalar: Fr| g2_to_be(&(G2Affine::generator() * scalar).into_affine());
+        let fr = |bytes: &[u8; 32]| Fr::from_be_bytes_mod_order(bytes);
+        let (alpha, beta, gamma, delta) = (Fr::from(3), Fr::from(5), Fr::from(7), Fr::from(11));
+        let k = [Fr::from(13), Fr::from(17), Fr::from(19), Fr::from(23)];
+        let (sigma, g, d) = (Fr::from(29), Fr::from(31), Fr::from(37));
+
+        let ic: Vec<[u8; 64]> = k.iter().map(|k| g1(*k)).collect();
+        let commitment_keys = [PedersenVerifyingkey {
+            g: g2(g),
+            g_sigma_neg: g2(-sigma * g),
+        }];
+        let vk = CommitmentVerifyingkey {
+            verifyingkey: Groth16Verifyingkey {
+                nr_pubinputs: 3,
+                vk_alpha_g1: g1(alpha),
+                vk_beta_g2: g2(beta),
+                vk_gamma_g2: g2(gamma),
+                vk_delta_g2: g2(delta),
+                vk_ic: &ic,
+            },
+            commitment_keys: &commitment_keys,
+            public_and_commitment_committed: &[&[1]],
+        };
+
+        let public_inputs = [scalar_to_be(Fr::from(41)), scalar_to_be(Fr::from(43))];
+        let commitment = g1(d);
+        let value = hash_to_field(&[&commitment, &public_inputs[0]], COMMITMENT_DST);
+        let (a, b) = (Fr::from(47), Fr::from(53));
+        let inputs = k[0]
+            + fr(&public_inputs[0]) * k[1]
+            + fr(&public_inputs[1]) * k[2]
+            + fr(&value) * k[3]
+            + d;
+        let c = (a * b - alpha * beta - gamma * inputs) / delta;
+        let proof = Proof {
+            a: ProofA(g1(-a)),
+            b: ProofB(g2(b)),
+            c: ProofC(g1(c)),
+        };
+        let pok = g1(sigma * d);
+
+        assert_eq!(
+            verify_with_commitments(&proof, &[commitment], &pok, &public_inputs, &vk),
+            Ok(vec![value])
+        );
+        assert_eq!(
+            verify_with_commitments(&proof, &[commitment], &g1(d), &public_inputs, &vk),
+            Err(Groth16Error::CommitmentVerificationFailed)
+        );
+        // Another committed input changes the commitment value
+        let mut other_inputs = public_inputs;
+        other_inputs[0][31] ^= 1;
+        assert_eq!(
+            verify_with_commitments(&proof, &[commitment], &pok, &other_inputs, &vk),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+        assert_eq!(
+            verify_with_commitments(&proof, &[], &pok, &public_inputs, &vk),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+
+        // gnark's WriteRawTo layout, with Ar as proven
+        let mut bytes = proof.to_gnark_bytes().unwrap().to_vec();
+        bytes.extend(1u32.to_be_bytes());
+        bytes.extend(commitment);
+        bytes.extend(pok);
+        assert_eq!(
+            CommitmentProof::from_gnark_bytes(&bytes),
+            Ok(CommitmentProof {
+                proof,
+                commitments: vec![commitment],
+                commitment_pok: pok,
+            })
+        );
+        assert_eq!(
+            CommitmentProof::from_gnark_bytes(&bytes[..bytes.len() - 1]),
+            Err(Groth16Error::ProofConversionError)
+        );
+        bytes[PROOF_SIZE..PROOF_SIZE + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert_eq!(
+            CommitmentProof::from_gnark_bytes(&bytes),
+            Err(Groth16Error::ProofConversionError)
+        );
+    }
+}
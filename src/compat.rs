@@ -0,0 +1,176 @@
+//! The API of Light Protocol's `groth16-solana`
+//!
+//! Enabled with the `compat` feature. The modules mirror the paths of
+//! `groth16-solana`, so a program migrates by depending on this crate and
+//! replacing `groth16_solana::` with `pinocchio_groth16::compat::`:
+//!
+//! ```rust,ignore
+//! use pinocchio_groth16::compat::groth16::{Groth16Verifier, Groth16Verifyingkey};
+//!
+//! let mut verifier =
+//!     Groth16Verifier::new(&proof_a, &proof_b, &proof_c, &public_inputs, &VERIFYINGKEY)?;
+//! let valid = verifier.verify()?;
+//! ```
+//!
+//! [`groth16::Groth16Verifyingkey`] keeps the `vk_gamme_g2` field name of
+//! `groth16-solana`, so keys generated for it compile unchanged, and `verify`
+//! returns `Ok(true)` instead of `Ok(())`. Errors are this crate's
+//! [`Groth16Error`](crate::errors::Groth16Error), whose failed syscall
+//! variants carry the syscall's return code.
+
+pub mod errors {
+    pub use crate::errors::Groth16Error;
+}
+
+pub mod decompression {
+    pub use crate::decompression::{decompress_g1, decompress_g2};
+}
+
+pub mod groth16 {
+    use crate::errors::Groth16Error;
+    pub use crate::groth16::is_less_than_bn254_field_size_be;
+
+    /// Verifying key with the field names of `groth16-solana`
+    #[derive(PartialEq, Eq, Debug)]
+    pub struct Groth16Verifyingkey<'a> {
+        pub nr_pubinputs: usize,
+        pub vk_alpha_g1: [u8; 64],
+        pub vk_beta_g2: [u8; 128],
+        pub vk_gamme_g2: [u8; 128],
+        pub vk_delta_g2: [u8; 128],
+        pub vk_ic: &'a [[u8; 64]],
+    }
+
+    impl<'a> From<&Groth16Verifyingkey<'a>> for crate::groth16::Groth16Verifyingkey<'a> {
+        fn from(verifyingkey: &Groth16Verifyingkey<'a>) -> Self {
+            crate::groth16::Groth16Verifyingkey {
+                nr_pubinputs: verifyingkey.nr_pubinputs,
+                vk_alpha_g1: verifyingkey.vk_alpha_g1,
+                vk_beta_g2: verifyingkey.vk_beta_g2,
+                vk_gamma_g2: verifyingkey.vk_gamme_g2,
+                vk_delta_g2: verifyingkey.vk_delta_g2,
+                vk_ic: verifyingkey.vk_ic,
+            }
+        }
+    }
+
+    /// Verifier with the signatures of `groth16-solana`
+    #[derive(PartialEq, Eq, Debug)]
+    pub struct Groth16Verifier<'a, const NR_INPUTS: usize> {
+        proof_a: &'a [u8; 64],
+        proof_b: &'a [u8; 128],
+        proof_c: &'a [u8; 64],
+        public_inputs: &'a [[u8; 32]; NR_INPUTS],
+        verifyingkey: crate::groth16::Groth16Verifyingkey<'a>,
+    }
+
+    impl<'a, const NR_INPUTS: usize> Groth16Verifier<'a, NR_INPUTS> {
+        pub fn new(
+            proof_a: &'a [u8; 64],
+            proof_b: &'a [u8; 128],
+            proof_c: &'a [u8; 64],
+            public_inputs: &'a [[u8; 32]; NR_INPUTS],
+            verifyingkey: &'a Groth16Verifyingkey<'a>,
+        ) -> Result<Groth16Verifier<'a, NR_INPUTS>, Groth16Error> {
+            if public_inputs.len() + 1 != verifyingkey.vk_ic.len() {
+                return Err(Groth16Error::InvalidPublicInputsLength);
+            }
+            Ok(Groth16Verifier {
+                proof_a,
+                proof_b,
+                proof_c,
+                public_inputs,
+                verifyingkey: verifyingkey.into(),
+            })
+        }
+
+        fn verifier(&self) -> Result<crate::groth16::Groth16Verifier<'_, NR_INPUTS>, Groth16Error> {
+            crate::groth16::Groth16Verifier::new(
+                self.proof_a,
+                self.proof_b,
+                self.proof_c,
+                self.public_inputs,
+                &self.verifyingkey,
+            )
+        }
+
+        /// Prepares the public inputs, `verify` prepares them again
+        pub fn prepare_inputs<const CHECK: bool>(&mut self) -> Result<(), Groth16Error> {
+            self.verifier()?.prepare_inputs::<CHECK>()
+        }
+
+        /// Verifies the proof, and checks that public inputs are smaller than
+        /// field size.
+        ///
+        /// # Returns
+        /// * `Ok(true)` - If the proof is valid, an invalid proof is an error
+        pub fn verify(&mut self) -> Result<bool, Groth16Error> {
+            self.verifier()?.verify()?;
+            Ok(true)
+        }
+
+        /// Verifies the proof, and does not check that public inputs are
+        /// smaller than field size.
+        pub fn verify_unchecked(&mut self) -> Result<bool, Groth16Error> {
+            self.verifier()?.verify_unchecked()?;
+            Ok(true)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test-vectors"))]
+mod tests {
+    use super::errors::Groth16Error;
+    use super::groth16::{Groth16Verifier, Groth16Verifyingkey};
+    use crate::test_vectors::generate;
+
+    #[test]
+    fn verifies_with_the_groth16_solana_api() {
+        let vector = generate(0);
+        let vk = vector.verifying_key();
+        let compat_vk = Groth16Verifyingkey {
+            nr_pubinputs: vk.nr_pubinputs,
+            vk_alpha_g1: vk.vk_alpha_g1,
+            vk_beta_g2: vk.vk_beta_g2,
+            vk_gamme_g2: vk.vk_gamma_g2,
+            vk_delta_g2: vk.vk_delta_g2,
+            vk_ic: vk.vk_ic,
+        };
+        let public_inputs: [[u8; 32]; 2] = vector.public_inputs.clone().try_into().unwrap();
+        let mut verifier = Groth16Verifier::new(
+            &vector.proof_a,
+            &vector.proof_b,
+            &vector.proof_c,
+            &public_inputs,
+            &compat_vk,
+        )
+        .unwrap();
+        verifier.prepare_inputs::<true>().unwrap();
+        assert_eq!(verifier.verify(), Ok(true));
+        assert_eq!(verifier.verify_unchecked(), Ok(true));
+
+        let wrong_inputs = [public_inputs[1], public_inputs[0]];
+        let mut verifier = Groth16Verifier::new(
+            &vector.proof_a,
+            &vector.proof_b,
+            &vector.proof_c,
+            &wrong_inputs,
+            &compat_vk,
+        )
+        .unwrap();
+        assert_eq!(
+            verifier.verify(),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+        assert_eq!(
+            Groth16Verifier::new(
+                &vector.proof_a,
+                &vector.proof_b,
+                &vector.proof_c,
+                &[public_inputs[0]],
+                &compat_vk,
+            ),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+    }
+}
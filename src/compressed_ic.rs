@@ -0,0 +1,132 @@
+//! Verifying keys with compressed IC points
+//!
+//! A verifying key holds a 64 byte IC point for each public input and one
+//! more, most of the key for circuits with many inputs. [`CompressedIcVerifyingkey`]
+//! stores them compressed to 32 bytes, roughly halving the key in the program
+//! binary. Each point is decompressed with `alt_bn128_g1_decompress` when its
+//! input is prepared, which adds
+//! [`ALT_BN128_G1_DECOMPRESS_COST`](crate::compute_units::ALT_BN128_G1_DECOMPRESS_COST)
+//! compute units per point.
+//!
+//! `vk_parser::parse_vk_json_to_rust_string_with_compressed_ic` generates such keys.
+
+use crate::curve::{Bn254, PairingCurve};
+use crate::decompression::decompress_g1;
+use crate::errors::Groth16Error;
+use crate::groth16::{check_pairing, Groth16Verifyingkey, PreparedInputs};
+
+/// BN254 verifying key with IC points compressed as taken by `alt_bn128_g1_decompress`
+#[derive(PartialEq, Eq, Debug)]
+pub struct CompressedIcVerifyingkey<'a> {
+    pub nr_pubinputs: usize,
+    pub vk_alpha_g1: [u8; 64],
+    pub vk_beta_g2: [u8; 128],
+    pub vk_gamma_g2: [u8; 128],
+    pub vk_delta_g2: [u8; 128],
+    pub vk_ic: &'a [[u8; 32]],
+}
+
+impl CompressedIcVerifyingkey<'_> {
+    /// Prepares `public_inputs`, decompressing each IC point as it is used
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidPublicInputsLength)` - If the key takes
+    ///   another number of public inputs
+    /// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If an input is
+    ///   not smaller than the scalar field modulus
+    /// * `Err(Groth16Error::DecompressingG1Failed)` - If an IC point is not a valid point
+    pub fn prepare_inputs(
+        &self,
+        public_inputs: &[[u8; 32]],
+    ) -> Result<PreparedInputs, Groth16Error> {
+        if public_inputs.len() + 1 != self.vk_ic.len() {
+            return Err(Groth16Error::InvalidPublicInputsLength);
+        }
+        if !public_inputs
+            .iter()
+            .all(Bn254::is_less_than_scalar_field_size_be)
+        {
+            return Err(Groth16Error::PublicInputGreaterThanFieldSize);
+        }
+
+        cu_log!("compressed_ic: prepare_inputs");
+        let mut prepared_public_inputs = decompress_g1(&self.vk_ic[0])?;
+        for (input, ic) in public_inputs.iter().zip(&self.vk_ic[1..]) {
+            let mul_res = Bn254::g1_mul(&decompress_g1(ic)?, input)?;
+            prepared_public_inputs = Bn254::g1_add(&mul_res, &prepared_public_inputs)?;
+        }
+        Ok(PreparedInputs(prepared_public_inputs))
+    }
+}
+
+/// Verifies a proof with negated proof a against a key with compressed IC points
+///
+/// # Returns
+/// * `Err(Groth16Error::ProofVerificationFailed)` - If the proof does not verify
+/// * The errors of [`CompressedIcVerifyingkey::prepare_inputs`]
+pub fn verify_with_compressed_ic(
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    public_inputs: &[[u8; 32]],
+    verifyingkey: &CompressedIcVerifyingkey,
+) -> Result<(), Groth16Error> {
+    let prepared_inputs = verifyingkey.prepare_inputs(public_inputs)?;
+    // The pairing only takes the key's alpha, beta, gamma and delta
+    let pairing_key = Groth16Verifyingkey {
+        nr_pubinputs: verifyingkey.nr_pubinputs,
+        vk_alpha_g1: verifyingkey.vk_alpha_g1,
+        vk_beta_g2: verifyingkey.vk_beta_g2,
+        vk_gamma_g2: verifyingkey.vk_gamma_g2,
+        vk_delta_g2: verifyingkey.vk_delta_g2,
+        vk_ic: &[],
+    };
+    check_pairing::<Bn254>(proof_a, proof_b, proof_c, &prepared_inputs.0, &pairing_key)
+}
+
+#[cfg(all(test, feature = "test-vectors"))]
+mod tests {
+    use super::*;
+    use crate::bn254::alt_bn128_g1_compress;
+    use crate::test_vectors::generate;
+
+    #[test]
+    fn compressed_ic_key_verifies() {
+        let vector = generate(0);
+        let ic: Vec<[u8; 32]> = vector
+            .vk_ic
+            .iter()
+            .map(|point| alt_bn128_g1_compress(point).unwrap())
+            .collect();
+        let vk = CompressedIcVerifyingkey {
+            nr_pubinputs: ic.len() - 1,
+            vk_alpha_g1: vector.vk_alpha_g1,
+            vk_beta_g2: vector.vk_beta_g2,
+            vk_gamma_g2: vector.vk_gamma_g2,
+            vk_delta_g2: vector.vk_delta_g2,
+            vk_ic: &ic,
+        };
+        assert_eq!(
+            vk.prepare_inputs(&vector.public_inputs).unwrap(),
+            PreparedInputs::new(&vector.public_inputs, &vector.verifying_key()).unwrap()
+        );
+        let verify = |inputs: &[[u8; 32]]| {
+            verify_with_compressed_ic(
+                &vector.proof_a,
+                &vector.proof_b,
+                &vector.proof_c,
+                inputs,
+                &vk,
+            )
+        };
+        verify(&vector.public_inputs).unwrap();
+
+        let mut inputs = vector.public_inputs.clone();
+        inputs[0][31] ^= 1;
+        assert_eq!(verify(&inputs), Err(Groth16Error::ProofVerificationFailed));
+        assert_eq!(
+            verify(&inputs[..1]),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! Compute unit costs of the alt_bn128 and BLS12-381 syscalls
+//!
+//! Costs are the values charged by the Agave runtime. The estimates add a
+//! margin for the work the verifier does around the syscalls (field size
+//! checks, buffer concatenation), they are meant to size a
+//! `SetComputeUnitLimit` instruction, not to predict exact consumption.
+
+pub const ALT_BN128_ADDITION_COST: u64 = 334;
+pub const ALT_BN128_MULTIPLICATION_COST: u64 = 3_840;
+pub const ALT_BN128_PAIRING_ONE_PAIR_COST_FIRST: u64 = 36_364;
+pub const ALT_BN128_PAIRING_ONE_PAIR_COST_OTHER: u64 = 12_121;
+pub const ALT_BN128_G1_COMPRESS_COST: u64 = 30;
+pub const ALT_BN128_G1_DECOMPRESS_COST: u64 = 398;
+pub const ALT_BN128_G2_COMPRESS_COST: u64 = 86;
+pub const ALT_BN128_G2_DECOMPRESS_COST: u64 = 13_610;
+pub const SHA256_BASE_COST: u64 = 85;
+pub const BLS12_381_G1_ADD_COST: u64 = 128;
+pub const BLS12_381_G1_MUL_COST: u64 = 4_627;
+pub const BLS12_381_PAIRING_ONE_PAIR_COST: u64 = 25_445;
+pub const BLS12_381_PAIRING_ADDITIONAL_PAIR_COST: u64 = 13_023;
+
+/// Maximum compute unit limit of a transaction
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Margin per public input for the field size check and input preparation
+pub const VERIFY_OVERHEAD_PER_INPUT: u64 = 3_000;
+/// Margin for building the pairing input and checking its result
+pub const VERIFY_OVERHEAD: u64 = 5_000;
+
+/// Cost of a pairing syscall over `nr_pairs` G1/G2 pairs
+///
+/// The runtime charges the sha256 base cost and one unit per input and
+/// output byte on top of the per pair cost.
+pub const fn pairing_cost(nr_pairs: usize) -> u64 {
+    if nr_pairs == 0 {
+        return SHA256_BASE_COST + 32;
+    }
+    ALT_BN128_PAIRING_ONE_PAIR_COST_FIRST
+        + ALT_BN128_PAIRING_ONE_PAIR_COST_OTHER * (nr_pairs as u64 - 1)
+        + SHA256_BASE_COST
+        + 192 * nr_pairs as u64
+        + 32
+}
+
+/// Most pairs a pairing syscall over `compute_units` can take
+pub const fn max_pairing_pairs(compute_units: u64) -> usize {
+    let fixed = ALT_BN128_PAIRING_ONE_PAIR_COST_FIRST - ALT_BN128_PAIRING_ONE_PAIR_COST_OTHER
+        + SHA256_BASE_COST
+        + 32;
+    if compute_units < fixed {
+        return 0;
+    }
+    ((compute_units - fixed) / (ALT_BN128_PAIRING_ONE_PAIR_COST_OTHER + 192)) as usize
+}
+
+/// Estimated compute units of preparing one public input
+pub const PREPARE_INPUT_COST: u64 =
+    ALT_BN128_MULTIPLICATION_COST + ALT_BN128_ADDITION_COST + VERIFY_OVERHEAD_PER_INPUT;
+
+/// Estimated compute units of preparing `nr_inputs` public inputs
+pub const fn estimate_prepare_inputs_compute_units(nr_inputs: usize) -> u64 {
+    PREPARE_INPUT_COST * nr_inputs as u64
+}
+
+/// Most public inputs that can be prepared within `compute_units`
+pub const fn max_prepared_inputs(compute_units: u64) -> usize {
+    (compute_units / PREPARE_INPUT_COST) as usize
+}
+
+/// Estimated compute units of `Groth16Verifier::verify` with `nr_inputs` public inputs
+pub const fn estimate_verify_compute_units(nr_inputs: usize) -> u64 {
+    estimate_prepare_inputs_compute_units(nr_inputs) + pairing_cost(4) + VERIFY_OVERHEAD
+}
+
+/// Estimated compute units of decompressing a proof, two G1 and one G2 point
+pub const fn estimate_decompress_proof_compute_units() -> u64 {
+    2 * ALT_BN128_G1_DECOMPRESS_COST + ALT_BN128_G2_DECOMPRESS_COST
+}
+
+/// Estimated compute units of verifying a proof with `nr_inputs` public inputs
+///
+/// # Arguments
+/// * `nr_inputs` - Number of public inputs of the circuit
+/// * `compressed` - Whether the proof is decompressed first, as by
+///   `Groth16Verifier::verify_compressed`
+pub const fn estimate_compute_units(nr_inputs: usize, compressed: bool) -> u64 {
+    let verify = estimate_verify_compute_units(nr_inputs);
+    if compressed {
+        verify + estimate_decompress_proof_compute_units()
+    } else {
+        verify
+    }
+}
+
+/// Compute unit limit for a `SetComputeUnitLimit` instruction of a
+/// transaction verifying one proof, [`estimate_compute_units`] plus
+/// `extra_units`, capped at [`MAX_COMPUTE_UNIT_LIMIT`]
+///
+/// # Arguments
+/// * `extra_units` - Units the program spends besides verification
+pub const fn compute_unit_limit(nr_inputs: usize, compressed: bool, extra_units: u64) -> u32 {
+    let units = estimate_compute_units(nr_inputs, compressed).saturating_add(extra_units);
+    if units > MAX_COMPUTE_UNIT_LIMIT as u64 {
+        MAX_COMPUTE_UNIT_LIMIT
+    } else {
+        units as u32
+    }
+}
+
+/// Cost of a BLS12-381 pairing syscall over `nr_pairs` G1/G2 pairs
+pub const fn bls12_381_pairing_cost(nr_pairs: usize) -> u64 {
+    if nr_pairs == 0 {
+        return 0;
+    }
+    BLS12_381_PAIRING_ONE_PAIR_COST + BLS12_381_PAIRING_ADDITIONAL_PAIR_COST * (nr_pairs as u64 - 1)
+}
+
+/// Estimated compute units of `Bls12Verifier::verify` with `nr_inputs` public inputs
+pub const fn estimate_bls12_381_verify_compute_units(nr_inputs: usize) -> u64 {
+    (BLS12_381_G1_MUL_COST + BLS12_381_G1_ADD_COST + VERIFY_OVERHEAD_PER_INPUT) * nr_inputs as u64
+        + bls12_381_pairing_cost(4)
+        + VERIFY_OVERHEAD
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_estimate() {
+        assert_eq!(pairing_cost(1), 36_364 + 85 + 192 + 32);
+        assert_eq!(pairing_cost(4), 36_364 + 3 * 12_121 + 85 + 4 * 192 + 32);
+        assert_eq!(
+            estimate_verify_compute_units(0),
+            pairing_cost(4) + VERIFY_OVERHEAD
+        );
+        // Verification of circuits with a handful of inputs stays below the
+        // default limit of 200,000 compute units.
+        assert!(estimate_verify_compute_units(10) < 200_000);
+        assert!(estimate_verify_compute_units(11) > estimate_verify_compute_units(10));
+    }
+
+    #[test]
+    fn compressed_estimate_and_limit() {
+        assert_eq!(
+            estimate_compute_units(2, true) - estimate_compute_units(2, false),
+            2 * 398 + 13_610
+        );
+        assert_eq!(
+            compute_unit_limit(2, false, 10_000) as u64,
+            estimate_compute_units(2, false) + 10_000
+        );
+        assert_eq!(compute_unit_limit(1_000, true, 0), MAX_COMPUTE_UNIT_LIMIT);
+
+        let max_pairs = max_pairing_pairs(MAX_COMPUTE_UNIT_LIMIT as u64);
+        assert!(pairing_cost(max_pairs) <= MAX_COMPUTE_UNIT_LIMIT as u64);
+        assert!(pairing_cost(max_pairs + 1) > MAX_COMPUTE_UNIT_LIMIT as u64);
+        assert_eq!(max_pairing_pairs(pairing_cost(1) - 1), 0);
+    }
+
+    #[cfg(feature = "test-vectors")]
+    #[test]
+    fn estimate_covers_the_syscall_costs() {
+        use crate::mock::{compute_units_consumed, set_compute_unit_limit};
+        use crate::test_vectors::generate;
+
+        let vector = generate(0);
+        set_compute_unit_limit(None);
+        vector.verify().unwrap();
+        let consumed = compute_units_consumed();
+        assert!(consumed <= estimate_compute_units(vector.public_inputs.len(), false));
+    }
+}
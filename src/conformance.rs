@@ -0,0 +1,269 @@
+//! Conformance suite for Groth16 verifier implementations
+//!
+//! A fixed, seeded set of accept/reject vectors over BN254. The verdicts are
+//! those of ark-groth16 and of the snarkjs Solidity verifier, which rejects
+//! public inputs that are not smaller than the scalar field modulus and,
+//! through the EIP-196/197 precompiles, points with non-canonical coordinates,
+//! points off the curve and G2 points outside the prime order subgroup. Any
+//! implementation of [`Groth16Backend`] can be checked against it:
+//!
+//! ```rust,ignore
+//! use pinocchio_groth16::conformance::{run, suite};
+//!
+//! let report = run(&suite(), &mut |vector: &TestVector| my_backend::verify(vector));
+//! assert!(report.failures.is_empty(), "{report:#?}");
+//! ```
+//!
+//! Backends outside of Rust, e.g. a Solidity verifier under Foundry, read
+//! the same suite from [`suite_json`].
+
+use crate::differential::{arkworks_verify, corpus, syscall_verify, Case};
+use crate::test_vectors::{
+    g2_to_be, generate_for_circuit, generate_hash_preimage, generate_merkle_proof, Invalidation,
+    TestVector, TinyCircuit,
+};
+use alloc::string::String;
+use alloc::vec::Vec;
+use ark_bn254::{Fq, Fq2, Fr, G2Affine};
+use ark_ff::PrimeField;
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use num_bigint::BigUint;
+use serde_json::{json, Value};
+
+/// Seeds of the vectors the suite is built from
+pub const SUITE_SEEDS: [u64; 2] = [0, 1];
+
+/// A Groth16 verifier under test
+///
+/// The verifier takes the big-endian encodings of [`TestVector`], with
+/// proof a negated.
+pub trait Groth16Backend {
+    /// Returns whether the backend accepts the proof of `vector`
+    fn verify(&mut self, vector: &TestVector) -> bool;
+}
+
+impl<F: FnMut(&TestVector) -> bool> Groth16Backend for F {
+    fn verify(&mut self, vector: &TestVector) -> bool {
+        self(vector)
+    }
+}
+
+/// ark-groth16, the reference the suite's verdicts are checked against
+pub struct ArkworksBackend;
+
+impl Groth16Backend for ArkworksBackend {
+    fn verify(&mut self, vector: &TestVector) -> bool {
+        arkworks_verify(vector)
+    }
+}
+
+/// This crate's [`Groth16Verifier`](crate::groth16::Groth16Verifier)
+pub struct SyscallBackend;
+
+impl Groth16Backend for SyscallBackend {
+    fn verify(&mut self, vector: &TestVector) -> bool {
+        syscall_verify(vector)
+    }
+}
+
+/// A vector on which a backend returned the wrong verdict
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure {
+    pub name: String,
+    pub expected: bool,
+}
+
+/// Result of running the suite
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConformanceReport {
+    pub passed: usize,
+    pub failures: Vec<Failure>,
+}
+
+/// Builds the conformance suite
+pub fn suite() -> Vec<Case> {
+    let mut cases = Vec::new();
+    for seed in SUITE_SEEDS {
+        for case in corpus(seed, 2) {
+            cases.push(Case {
+                name: format!("tiny {seed}: {}", case.name),
+                ..case
+            });
+        }
+    }
+
+    let zero = Fr::from(0u8);
+    let zero_inputs = generate_for_circuit(
+        TinyCircuit::default(),
+        TinyCircuit {
+            a: Some(zero),
+            b: Some(zero),
+        },
+        &[zero, zero],
+        &mut StdRng::seed_from_u64(0),
+    );
+    cases.push(case("zero public inputs", zero_inputs, true));
+
+    for (name, valid) in [
+        ("hash preimage", generate_hash_preimage(0)),
+        ("merkle proof", generate_merkle_proof(0)),
+    ] {
+        cases.push(case(name, valid.clone(), true));
+        for invalidation in Invalidation::ALL {
+            cases.push(case(
+                &format!("{name}: {invalidation:?}"),
+                valid.invalidate(invalidation, 0),
+                false,
+            ));
+        }
+    }
+
+    let valid = cases[0].vector.clone();
+    cases.push(case(
+        "proof a identity",
+        TestVector {
+            proof_a: [0u8; 64],
+            ..valid.clone()
+        },
+        false,
+    ));
+    cases.push(case(
+        "identity proof",
+        TestVector {
+            proof_a: [0u8; 64],
+            proof_b: [0u8; 128],
+            proof_c: [0u8; 64],
+            ..valid.clone()
+        },
+        false,
+    ));
+    let mut missing_input = valid.clone();
+    missing_input.public_inputs.pop();
+    cases.push(case("missing public input", missing_input, false));
+    let mut extra_input = valid.clone();
+    extra_input.public_inputs.push([0u8; 32]);
+    cases.push(case("extra public input", extra_input, false));
+
+    let mut non_canonical = valid.clone();
+    let x = BigUint::from_bytes_be(&valid.proof_c[..32]) + BigUint::from(Fq::MODULUS);
+    if let Some(offset) = 32usize.checked_sub(x.to_bytes_be().len()) {
+        non_canonical.proof_c[..32].fill(0);
+        non_canonical.proof_c[offset..32].copy_from_slice(&x.to_bytes_be());
+        cases.push(case("proof c non-canonical x", non_canonical, false));
+    }
+    cases.push(case(
+        "proof b outside the subgroup",
+        TestVector {
+            proof_b: g2_to_be(&g2_outside_subgroup()),
+            ..valid.clone()
+        },
+        false,
+    ));
+    cases
+}
+
+/// Runs every case of `cases` through `backend`
+pub fn run<B: Groth16Backend + ?Sized>(cases: &[Case], backend: &mut B) -> ConformanceReport {
+    let mut report = ConformanceReport::default();
+    for case in cases {
+        if backend.verify(&case.vector) == case.expected {
+            report.passed += 1;
+        } else {
+            report.failures.push(Failure {
+                name: case.name.clone(),
+                expected: case.expected,
+            });
+        }
+    }
+    report
+}
+
+/// The suite as JSON for backends in other languages
+///
+/// An array of objects with the `name`, the `expected` verdict and the
+/// big-endian hex encodings of the verifying key, the proof with proof a
+/// negated and the public inputs.
+pub fn suite_json() -> String {
+    let hex = |bytes: &[u8]| format!("0x{}", hex_string(bytes));
+    let cases: Vec<Value> = suite()
+        .iter()
+        .map(|case| {
+            let v = &case.vector;
+            json!({
+                "name": case.name,
+                "expected": case.expected,
+                "vk_alpha_g1": hex(&v.vk_alpha_g1),
+                "vk_beta_g2": hex(&v.vk_beta_g2),
+                "vk_gamma_g2": hex(&v.vk_gamma_g2),
+                "vk_delta_g2": hex(&v.vk_delta_g2),
+                "vk_ic": v.vk_ic.iter().map(|ic| hex(ic)).collect::<Vec<_>>(),
+                "proof_a": hex(&v.proof_a),
+                "proof_b": hex(&v.proof_b),
+                "proof_c": hex(&v.proof_c),
+                "public_inputs": v.public_inputs.iter().map(|input| hex(input)).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&cases).unwrap()
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn case(name: &str, vector: TestVector, expected: bool) -> Case {
+    Case {
+        name: name.into(),
+        vector,
+        expected,
+    }
+}
+
+/// The point of the twist with the smallest x = (i, 0) that is not in the prime order subgroup
+fn g2_outside_subgroup() -> G2Affine {
+    (1u64..)
+        .filter_map(|i| {
+            G2Affine::get_point_from_x_unchecked(Fq2::new(Fq::from(i), Fq::from(0u8)), false)
+        })
+        .find(|point| !point.is_in_correct_subgroup_assuming_on_curve())
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_backends_conform() {
+        let cases = suite();
+        assert!(cases.iter().filter(|case| case.expected).count() >= 8);
+        assert!(cases
+            .iter()
+            .any(|case| case.name == "proof b outside the subgroup"));
+
+        for backend in [
+            &mut ArkworksBackend as &mut dyn Groth16Backend,
+            &mut SyscallBackend,
+        ] {
+            let report = run(&cases, backend);
+            assert_eq!(report.failures, vec![]);
+            assert_eq!(report.passed, cases.len());
+        }
+
+        // A backend accepting proof b outside the subgroup is caught
+        let outside = g2_to_be(&g2_outside_subgroup());
+        let report = run(&cases, &mut |vector: &TestVector| {
+            vector.proof_b == outside || syscall_verify(vector)
+        });
+        assert_eq!(
+            report.failures,
+            vec![Failure {
+                name: "proof b outside the subgroup".into(),
+                expected: false,
+            }]
+        );
+
+        let json: Value = serde_json::from_str(&suite_json()).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), cases.len());
+    }
+}
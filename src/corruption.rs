@@ -0,0 +1,192 @@
+//! Targeted tampering of proofs and public inputs
+//!
+//! Each helper applies one specific manipulation an attacker or an encoding
+//! bug produces. [`Tampering`] pairs them with the error the verifier has to
+//! return, so test suites can assert that every item of the threat model is
+//! rejected for the right reason:
+//!
+//! ```rust,ignore
+//! use pinocchio_groth16::corruption::Tampering;
+//!
+//! for tampering in Tampering::ALL {
+//!     let (a, b, c, inputs) = tampering.apply(&proof_a, &proof_b, &proof_c, &public_inputs);
+//!     assert_eq!(my_program::verify(&a, &b, &c, &inputs), Err(tampering.expected_error()));
+//! }
+//! ```
+
+use crate::bn254::convert_endianness;
+use crate::errors::Groth16Error;
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+
+fn to_be(value: &BigUint) -> [u8; 32] {
+    let bytes = value.to_bytes_be();
+    let mut be = [0u8; 32];
+    be[32 - bytes.len()..].copy_from_slice(&bytes);
+    be
+}
+
+/// Flips the lowest bit of the y coordinate, so the point is not on the curve
+pub fn corrupt_a(proof_a: &[u8; 64]) -> [u8; 64] {
+    let mut corrupted = *proof_a;
+    corrupted[63] ^= 1;
+    corrupted
+}
+
+/// Negates proof a, i.e. submits it as the prover returned it
+pub fn negate_a(proof_a: &[u8; 64]) -> [u8; 64] {
+    let y = BigUint::from_bytes_be(&proof_a[32..]);
+    if y == BigUint::ZERO {
+        return *proof_a;
+    }
+    let mut negated = *proof_a;
+    negated[32..].copy_from_slice(&to_be(&(BigUint::from(ark_bn254::Fq::MODULUS) - y)));
+    negated
+}
+
+/// Writes proof a in little-endian
+pub fn little_endian_a(proof_a: &[u8; 64]) -> [u8; 64] {
+    convert_endianness::<32, 64>(proof_a)
+}
+
+/// Swaps `c0` and `c1` of both coordinates of proof b
+pub fn swap_b_limb_order(proof_b: &[u8; 128]) -> [u8; 128] {
+    let mut swapped = [0u8; 128];
+    for coordinate in 0..2 {
+        let offset = coordinate * 64;
+        swapped[offset..offset + 32].copy_from_slice(&proof_b[offset + 32..offset + 64]);
+        swapped[offset + 32..offset + 64].copy_from_slice(&proof_b[offset..offset + 32]);
+    }
+    swapped
+}
+
+/// Replaces proof c with the point at infinity
+pub fn zero_c(_proof_c: &[u8; 64]) -> [u8; 64] {
+    [0u8; 64]
+}
+
+/// Adds one to a public input modulo the scalar field
+pub fn increment_input(input: &[u8; 32]) -> [u8; 32] {
+    let modulus = BigUint::from(ark_bn254::Fr::MODULUS);
+    to_be(&((BigUint::from_bytes_be(input) + 1u8) % modulus))
+}
+
+/// Adds the scalar field modulus to a public input, the non-canonical encoding of the same value
+pub fn input_plus_modulus(input: &[u8; 32]) -> [u8; 32] {
+    to_be(&(BigUint::from_bytes_be(input) + BigUint::from(ark_bn254::Fr::MODULUS)))
+}
+
+/// A manipulation of a proof or its public inputs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tampering {
+    /// [`corrupt_a`]
+    CorruptA,
+    /// [`negate_a`]
+    NegateA,
+    /// [`little_endian_a`]
+    LittleEndianA,
+    /// [`swap_b_limb_order`]
+    SwapBLimbOrder,
+    /// [`zero_c`]
+    ZeroC,
+    /// Proof a and proof c exchanged
+    SwapAC,
+    /// [`increment_input`] on the first public input
+    IncrementInput,
+    /// [`input_plus_modulus`] on the first public input
+    InputPlusModulus,
+}
+
+impl Tampering {
+    pub const ALL: [Tampering; 8] = [
+        Tampering::CorruptA,
+        Tampering::NegateA,
+        Tampering::LittleEndianA,
+        Tampering::SwapBLimbOrder,
+        Tampering::ZeroC,
+        Tampering::SwapAC,
+        Tampering::IncrementInput,
+        Tampering::InputPlusModulus,
+    ];
+
+    /// Returns the tampered proof and public inputs
+    ///
+    /// The public input tamperings leave an empty input list unchanged.
+    pub fn apply<const N: usize>(
+        &self,
+        proof_a: &[u8; 64],
+        proof_b: &[u8; 128],
+        proof_c: &[u8; 64],
+        public_inputs: &[[u8; 32]; N],
+    ) -> ([u8; 64], [u8; 128], [u8; 64], [[u8; 32]; N]) {
+        let (mut a, mut b, mut c, mut inputs) = (*proof_a, *proof_b, *proof_c, *public_inputs);
+        match self {
+            Tampering::CorruptA => a = corrupt_a(&a),
+            Tampering::NegateA => a = negate_a(&a),
+            Tampering::LittleEndianA => a = little_endian_a(&a),
+            Tampering::SwapBLimbOrder => b = swap_b_limb_order(&b),
+            Tampering::ZeroC => c = zero_c(&c),
+            Tampering::SwapAC => core::mem::swap(&mut a, &mut c),
+            Tampering::IncrementInput => {
+                if let Some(input) = inputs.first_mut() {
+                    *input = increment_input(input);
+                }
+            }
+            Tampering::InputPlusModulus => {
+                if let Some(input) = inputs.first_mut() {
+                    *input = input_plus_modulus(input);
+                }
+            }
+        }
+        (a, b, c, inputs)
+    }
+
+    /// Error of [`Groth16Verifier::verify`](crate::groth16::Groth16Verifier::verify) for the tampered proof
+    pub fn expected_error(&self) -> Groth16Error {
+        match self {
+            Tampering::InputPlusModulus => Groth16Error::PublicInputGreaterThanFieldSize,
+            _ => Groth16Error::ProofVerificationFailed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groth16::Groth16Verifier;
+    use crate::test_fixtures::{Fixture, HASH_PREIMAGE, MERKLE_PROOF};
+
+    fn assert_rejected<const N: usize>(fixture: &Fixture<N>) {
+        for tampering in Tampering::ALL {
+            let (a, b, c, inputs) = tampering.apply(
+                &fixture.proof_a,
+                &fixture.proof_b,
+                &fixture.proof_c,
+                &fixture.public_inputs,
+            );
+            assert_eq!(
+                Groth16Verifier::new(&a, &b, &c, &inputs, &fixture.verifying_key)
+                    .unwrap()
+                    .verify(),
+                Err(tampering.expected_error()),
+                "{tampering:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn tampered_fixtures_are_rejected_with_expected_error() {
+        assert_rejected(&HASH_PREIMAGE);
+        assert_rejected(&MERKLE_PROOF);
+
+        let a = HASH_PREIMAGE.proof_a;
+        assert_eq!(negate_a(&negate_a(&a)), a);
+        assert_eq!(
+            swap_b_limb_order(&swap_b_limb_order(&HASH_PREIMAGE.proof_b)),
+            HASH_PREIMAGE.proof_b
+        );
+        let mut max = to_be(&(BigUint::from(ark_bn254::Fr::MODULUS) - 1u8));
+        max = increment_input(&max);
+        assert_eq!(max, [0u8; 32]);
+    }
+}
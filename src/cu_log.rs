@@ -0,0 +1,28 @@
+//! Compute unit logging of the verification phases
+//!
+//! With the `cu-log` feature the verifier logs a label followed by the
+//! remaining compute units before input validation, input preparation, every
+//! group operation and the pairing, and once more when it is done. The
+//! difference between consecutive `Program consumption` lines is the cost of
+//! the phase:
+//!
+//! ```text
+//! Program log: groth16: prepare_inputs
+//! Program consumption: 1398512 units remaining
+//! Program log: alt_bn128: g1_mul
+//! Program consumption: 1398390 units remaining
+//! ```
+//!
+//! Without the feature the logging calls compile to nothing. Logging costs
+//! compute units itself and is meant for devnet builds only.
+
+/// Logs `label` and the remaining compute units with `sol_log_`
+pub fn log(label: &str) {
+    #[cfg(target_os = "solana")]
+    {
+        crate::syscalls::log(label);
+        crate::syscalls::log_compute_units();
+    }
+    #[cfg(not(target_os = "solana"))]
+    let _ = label;
+}
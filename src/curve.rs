@@ -0,0 +1,333 @@
+//! Pairing curves the verifier is generic over
+//!
+//! [`PairingCurve`] describes a curve by its point encodings, field moduli
+//! and the syscalls implementing its group operations and pairing. The
+//! Groth16 verifier, [`crate::groth16::CurveVerifier`], is written once
+//! against it. [`Bn254`] is always available, `Bls12_381` with the
+//! `bls12-381` feature; supporting another curve the runtime adds means
+//! implementing this trait on top of its syscalls.
+//!
+//! BN254 is also generic over the implementation of its operations,
+//! [`Bn254With`] runs the verifier on any [`Bn254Backend`] and [`Bn254`] is
+//! the one on the syscalls.
+
+use crate::bn254::{
+    Bn254Backend, PinocchioSyscalls, ALT_BN128_G1_ADD, ALT_BN128_G1_MUL, ALT_BN128_PAIRING,
+    ALT_BN128_PAIRING_ELEMENT_SIZE, ALT_BN128_PAIRING_MAX_PAIRS, ALT_BN128_PAIRING_OUTPUT_SIZE,
+};
+use crate::errors::Groth16Error;
+use crate::fr;
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+/// A pairing-friendly curve with syscall support
+pub trait PairingCurve {
+    /// Encoded G1 point, uncompressed and big-endian
+    type G1: Copy + Debug + Eq + AsRef<[u8]>;
+    /// Encoded G2 point, uncompressed and big-endian
+    type G2: Copy + Debug + Eq + AsRef<[u8]>;
+
+    /// Size of an encoded G1 point
+    const G1_SIZE: usize;
+    /// Size of an encoded G2 point
+    const G2_SIZE: usize;
+    /// Size of an encoded scalar
+    const SCALAR_SIZE: usize = 32;
+    /// Encoding of the G1 identity
+    const G1_IDENTITY: Self::G1;
+    /// Big-endian modulus of the base field
+    const BASE_FIELD_MODULUS: &'static [u8];
+    /// Big-endian modulus of the scalar field
+    const SCALAR_FIELD_MODULUS: [u8; 32];
+    /// Operation code of G1 addition in the curve's group op syscall
+    const G1_ADD_OP: u64;
+    /// Operation code of G1 scalar multiplication in the curve's group op syscall
+    const G1_MUL_OP: u64;
+
+    /// `left + right`, fails with [`Groth16Error::PreparingInputsG1AdditionFailed`]
+    fn g1_add(left: &Self::G1, right: &Self::G1) -> Result<Self::G1, Groth16Error>;
+
+    /// `scalar * point`, fails with [`Groth16Error::PreparingInputsG1MulFailed`]
+    fn g1_mul(point: &Self::G1, scalar: &[u8; 32]) -> Result<Self::G1, Groth16Error>;
+
+    /// `sum of scalars[i] * points[i]`, the identity for no points
+    ///
+    /// No syscall computes a multi-scalar multiplication yet, so this runs a
+    /// G1 multiplication and addition per point, skipping points with a zero
+    /// scalar and the multiplication for a scalar of one. A curve whose
+    /// syscalls gain one overrides this, and everything preparing inputs
+    /// through it gets cheaper. Points with a zero scalar are not checked.
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidPublicInputsLength)` - If the slices differ in length
+    fn msm_g1(points: &[Self::G1], scalars: &[[u8; 32]]) -> Result<Self::G1, Groth16Error> {
+        if points.len() != scalars.len() {
+            return Err(Groth16Error::InvalidPublicInputsLength);
+        }
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let mut sum: Option<Self::G1> = None;
+        for (i, (point, scalar)) in points.iter().zip(scalars).enumerate() {
+            if *scalar != [0u8; 32] {
+                let product = match *scalar == one {
+                    true => *point,
+                    false => Self::g1_mul(point, scalar)?,
+                };
+                sum = Some(match sum {
+                    Some(sum) => Self::g1_add(&product, &sum)?,
+                    None => product,
+                });
+            }
+            #[cfg(feature = "trace")]
+            crate::trace::record(
+                &format!("msm after point {i}"),
+                sum.unwrap_or(Self::G1_IDENTITY).as_ref(),
+            );
+            #[cfg(not(feature = "trace"))]
+            let _ = i;
+        }
+        Ok(sum.unwrap_or(Self::G1_IDENTITY))
+    }
+
+    /// `-point`, the identity is its own negation
+    ///
+    /// A y coordinate that is not a canonical field element is rejected
+    /// with [`Groth16Error::ProofVerificationFailed`] instead of being
+    /// reduced, as the pairing syscall would reject it.
+    fn g1_negate(point: &Self::G1) -> Result<Self::G1, Groth16Error>;
+
+    /// Whether the product of the pairings `e(g1_points[i], g2_points[i])` is one
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::ProofVerificationFailed)` - If the syscall rejects
+    ///   the points or the slices differ in length
+    /// * `Err(Groth16Error::PairingInputTooLarge)` - If one syscall cannot take
+    ///   that many pairs
+    fn pairing_is_one(
+        g1_points: &[&Self::G1],
+        g2_points: &[&Self::G2],
+    ) -> Result<bool, Groth16Error>;
+
+    /// Whether `scalar` is smaller than the scalar field modulus
+    fn is_less_than_scalar_field_size_be(scalar: &[u8; 32]) -> bool {
+        *scalar < Self::SCALAR_FIELD_MODULUS
+    }
+}
+
+/// `modulus - y` of the big-endian coordinate `y`, zero stays zero
+///
+/// `y` and `modulus` have the same length. Computed in place, without allocating.
+pub(crate) fn negate_coordinate_be(y: &mut [u8], modulus: &[u8]) -> Result<(), Groth16Error> {
+    if y.len() != modulus.len() || *y >= *modulus {
+        return Err(Groth16Error::ProofVerificationFailed);
+    }
+    if y.iter().all(|byte| *byte == 0) {
+        return Ok(());
+    }
+    let mut borrow = 0u8;
+    for (y, modulus) in y.iter_mut().zip(modulus).rev() {
+        let (difference, underflow) = modulus.overflowing_sub(*y);
+        let (difference, borrow_underflow) = difference.overflowing_sub(borrow);
+        *y = difference;
+        borrow = (underflow || borrow_underflow) as u8;
+    }
+    Ok(())
+}
+
+/// Pairs the pairing input of [`Bn254With`] holds on the stack, the verifier
+/// needs four
+const PAIRING_STACK_PAIRS: usize = 4;
+
+/// BN254, also called alt_bn128, with its operations provided by `B`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bn254With<B: Bn254Backend>(PhantomData<B>);
+
+/// BN254 with the `sol_alt_bn128_group_op` syscalls
+pub type Bn254 = Bn254With<PinocchioSyscalls>;
+
+impl<B: Bn254Backend> PairingCurve for Bn254With<B> {
+    type G1 = [u8; 64];
+    type G2 = [u8; 128];
+
+    const G1_SIZE: usize = 64;
+    const G2_SIZE: usize = 128;
+    const G1_IDENTITY: [u8; 64] = [0u8; 64];
+    const BASE_FIELD_MODULUS: &'static [u8] = &fr::BASE_FIELD_MODULUS;
+    const SCALAR_FIELD_MODULUS: [u8; 32] = fr::SCALAR_FIELD_MODULUS;
+    const G1_ADD_OP: u64 = ALT_BN128_G1_ADD;
+    const G1_MUL_OP: u64 = ALT_BN128_G1_MUL;
+
+    fn g1_add(left: &[u8; 64], right: &[u8; 64]) -> Result<[u8; 64], Groth16Error> {
+        cu_log!("alt_bn128: g1_add");
+        let mut input = [0u8; 128];
+        input[..64].copy_from_slice(left);
+        input[64..].copy_from_slice(right);
+        let mut result = [0u8; 64];
+        match B::group_op(ALT_BN128_G1_ADD, &input, &mut result) {
+            0 => {}
+            code => return Err(Groth16Error::PreparingInputsG1AdditionFailed(code)),
+        }
+        Ok(result)
+    }
+
+    fn g1_mul(point: &[u8; 64], scalar: &[u8; 32]) -> Result<[u8; 64], Groth16Error> {
+        cu_log!("alt_bn128: g1_mul");
+        let mut input = [0u8; 96];
+        input[..64].copy_from_slice(point);
+        input[64..].copy_from_slice(scalar);
+        let mut result = [0u8; 64];
+        match B::group_op(ALT_BN128_G1_MUL, &input, &mut result) {
+            0 => {}
+            code => return Err(Groth16Error::PreparingInputsG1MulFailed(code)),
+        }
+        Ok(result)
+    }
+
+    fn g1_negate(point: &[u8; 64]) -> Result<[u8; 64], Groth16Error> {
+        let mut negated = *point;
+        negate_coordinate_be(&mut negated[32..], Self::BASE_FIELD_MODULUS)?;
+        Ok(negated)
+    }
+
+    fn pairing_is_one(
+        g1_points: &[&[u8; 64]],
+        g2_points: &[&[u8; 128]],
+    ) -> Result<bool, Groth16Error> {
+        if g1_points.len() != g2_points.len() {
+            return Err(Groth16Error::ProofVerificationFailed);
+        }
+        if g1_points.len() > ALT_BN128_PAIRING_MAX_PAIRS {
+            return Err(Groth16Error::PairingInputTooLarge);
+        }
+        // Up to PAIRING_STACK_PAIRS pairs the input lives on the stack, so
+        // verifying a proof does not touch the heap
+        let mut stack_input = [0u8; PAIRING_STACK_PAIRS * ALT_BN128_PAIRING_ELEMENT_SIZE];
+        let mut heap_input = alloc::vec::Vec::new();
+        let pairing_input = match g1_points.len() {
+            len if len <= PAIRING_STACK_PAIRS => {
+                &mut stack_input[..len * ALT_BN128_PAIRING_ELEMENT_SIZE]
+            }
+            len => {
+                heap_input.resize(len * ALT_BN128_PAIRING_ELEMENT_SIZE, 0);
+                &mut heap_input[..]
+            }
+        };
+        for ((g1, g2), element) in g1_points
+            .iter()
+            .zip(g2_points)
+            .zip(pairing_input.chunks_exact_mut(ALT_BN128_PAIRING_ELEMENT_SIZE))
+        {
+            element[..64].copy_from_slice(*g1);
+            element[64..].copy_from_slice(*g2);
+        }
+        #[cfg(feature = "trace")]
+        crate::trace::record("pairing_input", pairing_input);
+        let mut pairing_res = [0u8; ALT_BN128_PAIRING_OUTPUT_SIZE];
+        if B::group_op(ALT_BN128_PAIRING, pairing_input, &mut pairing_res) != 0 {
+            return Err(Groth16Error::ProofVerificationFailed);
+        }
+        #[cfg(feature = "trace")]
+        crate::trace::record("pairing_result", &pairing_res);
+        Ok(pairing_res[31] == 1)
+    }
+
+    fn is_less_than_scalar_field_size_be(scalar: &[u8; 32]) -> bool {
+        fr::is_canonical(scalar)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::{BigInteger, PrimeField};
+
+    #[test]
+    fn bn254_constants_match_arkworks() {
+        assert_eq!(
+            Bn254::BASE_FIELD_MODULUS,
+            ark_bn254::Fq::MODULUS.to_bytes_be().as_slice()
+        );
+        assert_eq!(
+            Bn254::SCALAR_FIELD_MODULUS.as_slice(),
+            ark_bn254::Fr::MODULUS.to_bytes_be().as_slice()
+        );
+        assert_eq!(
+            Bn254::G1_SIZE,
+            core::mem::size_of::<<Bn254 as PairingCurve>::G1>()
+        );
+        assert_eq!(
+            Bn254::G2_SIZE,
+            core::mem::size_of::<<Bn254 as PairingCurve>::G2>()
+        );
+
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let mut generator = [0u8; 64];
+        generator[31] = 1;
+        generator[63] = 2;
+        let negated = Bn254::g1_negate(&generator).unwrap();
+        assert_eq!(
+            Bn254::g1_add(&generator, &negated).unwrap(),
+            Bn254::G1_IDENTITY
+        );
+        assert_eq!(Bn254::g1_mul(&generator, &one).unwrap(), generator);
+        assert_eq!(
+            Bn254::g1_negate(&Bn254::G1_IDENTITY).unwrap(),
+            Bn254::G1_IDENTITY
+        );
+        assert!(!Bn254::is_less_than_scalar_field_size_be(
+            &Bn254::SCALAR_FIELD_MODULUS
+        ));
+    }
+
+    #[test]
+    fn negate_coordinate_matches_field_negation() {
+        use ark_ff::Field;
+        for value in [
+            ark_bn254::Fq::ONE,
+            ark_bn254::Fq::from(u64::MAX),
+            -ark_bn254::Fq::ONE,
+        ] {
+            let mut y: [u8; 32] = value.into_bigint().to_bytes_be().try_into().unwrap();
+            negate_coordinate_be(&mut y, Bn254::BASE_FIELD_MODULUS).unwrap();
+            assert_eq!(y.to_vec(), (-value).into_bigint().to_bytes_be());
+        }
+        let mut zero = [0u8; 32];
+        negate_coordinate_be(&mut zero, Bn254::BASE_FIELD_MODULUS).unwrap();
+        assert_eq!(zero, [0u8; 32]);
+        let mut modulus: [u8; 32] = Bn254::BASE_FIELD_MODULUS.try_into().unwrap();
+        assert!(negate_coordinate_be(&mut modulus, Bn254::BASE_FIELD_MODULUS).is_err());
+    }
+
+    #[test]
+    fn msm_matches_mul_and_add() {
+        let mut generator = [0u8; 64];
+        generator[31] = 1;
+        generator[63] = 2;
+        let scalar = |value: u8| {
+            let mut scalar = [0u8; 32];
+            scalar[31] = value;
+            scalar
+        };
+        let doubled = Bn254::g1_add(&generator, &generator).unwrap();
+
+        // 0 * g + 1 * g + 3 * 2g == 7g
+        assert_eq!(
+            Bn254::msm_g1(
+                &[generator, generator, doubled],
+                &[scalar(0), scalar(1), scalar(3)]
+            )
+            .unwrap(),
+            Bn254::g1_mul(&generator, &scalar(7)).unwrap()
+        );
+        assert_eq!(
+            Bn254::msm_g1(&[generator], &[scalar(0)]).unwrap(),
+            Bn254::G1_IDENTITY
+        );
+        assert_eq!(Bn254::msm_g1(&[], &[]).unwrap(), Bn254::G1_IDENTITY);
+        assert_eq!(
+            Bn254::msm_g1(&[generator], &[]),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+    }
+}
@@ -1,16 +1,82 @@
 use crate::errors::Groth16Error;
-use crate::bn254::{alt_bn128_g1_decompress, alt_bn128_g2_decompress};
+use crate::bn254::{
+    alt_bn128_g1_decompress, alt_bn128_g2_decompress, Bn254Backend, PinocchioSyscalls,
+};
 
 pub fn decompress_g1(g1_bytes: &[u8; 32]) -> Result<[u8; 64], Groth16Error> {
-    let decompressed_g1 = alt_bn128_g1_decompress(g1_bytes)
-        .map_err(|_| crate::errors::Groth16Error::DecompressingG1Failed {})?;
-    Ok(decompressed_g1)
+    alt_bn128_g1_decompress(g1_bytes)
 }
 
 pub fn decompress_g2(g2_bytes: &[u8; 64]) -> Result<[u8; 128], Groth16Error> {
-    let decompressed_g2 = alt_bn128_g2_decompress(g2_bytes)
-        .map_err(|_| crate::errors::Groth16Error::DecompressingG2Failed {})?;
-    Ok(decompressed_g2)
+    alt_bn128_g2_decompress(g2_bytes)
+}
+
+/// Size of a compressed proof, `proof_a (32) | proof_b (64) | proof_c (32)`
+pub const COMPRESSED_PROOF_SIZE: usize = 128;
+
+/// Uncompressed proof points, storage for
+/// [`Groth16Verifier::new_compressed`](crate::groth16::CurveVerifier::new_compressed)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecompressedProof {
+    pub proof_a: [u8; 64],
+    pub proof_b: [u8; 128],
+    pub proof_c: [u8; 64],
+}
+
+impl Default for DecompressedProof {
+    fn default() -> Self {
+        DecompressedProof {
+            proof_a: [0u8; 64],
+            proof_b: [0u8; 128],
+            proof_c: [0u8; 64],
+        }
+    }
+}
+
+impl DecompressedProof {
+    /// Decompresses the proof points on the backend `B`
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::DecompressingG1Failed)` - If proof a or c is not a valid point
+    /// * `Err(Groth16Error::DecompressingG2Failed)` - If proof b is not a valid point
+    pub fn decompress<B: Bn254Backend>(
+        proof_a: &[u8; 32],
+        proof_b: &[u8; 64],
+        proof_c: &[u8; 32],
+    ) -> Result<Self, Groth16Error> {
+        Ok(DecompressedProof {
+            proof_a: B::alt_bn128_g1_decompress(proof_a)?,
+            proof_b: B::alt_bn128_g2_decompress(proof_b)?,
+            proof_c: B::alt_bn128_g1_decompress(proof_c)?,
+        })
+    }
+
+    /// Compresses the proof points on the backend `B` into the layout of
+    /// [`COMPRESSED_PROOF_SIZE`]
+    ///
+    /// Off chain, `B` is `NativeBackend` or
+    /// `SolanaBn254Backend`, both write the bytes the syscall writes.
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::ProofConversionError)` - If a point is not on the curve
+    pub fn compress<B: Bn254Backend>(&self) -> Result<[u8; COMPRESSED_PROOF_SIZE], Groth16Error> {
+        let mut bytes = [0u8; COMPRESSED_PROOF_SIZE];
+        bytes[..32].copy_from_slice(&B::alt_bn128_g1_compress(&self.proof_a)?);
+        bytes[32..96].copy_from_slice(&B::alt_bn128_g2_compress(&self.proof_b)?);
+        bytes[96..].copy_from_slice(&B::alt_bn128_g1_compress(&self.proof_c)?);
+        Ok(bytes)
+    }
+
+    /// Decompresses a proof in the layout of [`COMPRESSED_PROOF_SIZE`] with the syscalls
+    pub fn from_compressed_bytes(
+        bytes: &[u8; COMPRESSED_PROOF_SIZE],
+    ) -> Result<Self, Groth16Error> {
+        Self::decompress::<PinocchioSyscalls>(
+            bytes[..32].try_into().unwrap(),
+            bytes[32..96].try_into().unwrap(),
+            bytes[96..].try_into().unwrap(),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,267 @@
+//! Verification that reports the stage a proof failed at
+//!
+//! [`Groth16Verifier::verify`](crate::groth16::Groth16Verifier::verify) keeps
+//! the terse path: a malformed point, a failed syscall and a wrong proof all
+//! end up as an error code that says little about the cause. With the
+//! `diagnostics` feature, [`Groth16Verifier::verify_diagnostic`](crate::groth16::Groth16Verifier::verify_diagnostic)
+//! runs the stages one by one and returns a [`Diagnostic`] naming the stage
+//! and the offending input or point:
+//!
+//! ```rust,ignore
+//! match verifier.verify_diagnostic() {
+//!     Err(Diagnostic::NonCanonicalInput { index }) => log!("input {index} not reduced"),
+//!     Err(Diagnostic::PairingNotOne) => log!("proof does not verify"),
+//!     ...
+//! }
+//! ```
+//!
+//! The proof points are validated in software, which for G2 includes the
+//! subgroup check and costs far more compute units than verification
+//! itself. The mode is meant for tests, clients and dev builds of programs.
+
+use crate::curve::{Bn254, PairingCurve};
+use crate::decompression::{decompress_g1, decompress_g2};
+use crate::errors::Groth16Error;
+use crate::groth16::{Groth16Verifyingkey, ProverConvention};
+use crate::validation::{validate_g1, validate_g2};
+
+/// A point of a Groth16 proof
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofPoint {
+    A,
+    B,
+    C,
+}
+
+/// The stage verification failed at
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+pub enum Diagnostic {
+    /// The key takes `expected` public inputs, `actual` were passed
+    #[cfg_attr(
+        feature = "std",
+        error("expected {expected} public inputs, got {actual}")
+    )]
+    InputCount { expected: usize, actual: usize },
+    /// Public input `index` is not smaller than the scalar field modulus
+    #[cfg_attr(feature = "std", error("public input {index} is not a field element"))]
+    NonCanonicalInput { index: usize },
+    /// A compressed proof point does not decompress
+    #[cfg_attr(
+        feature = "std",
+        error("proof point {point:?} does not decompress: {error:?}")
+    )]
+    Decompression {
+        point: ProofPoint,
+        error: Groth16Error,
+    },
+    /// A proof point is not a valid point, see [`crate::validation`]
+    #[cfg_attr(feature = "std", error("proof point {point:?} is invalid: {error:?}"))]
+    MalformedProofPoint {
+        point: ProofPoint,
+        error: Groth16Error,
+    },
+    /// Multiplying IC point `index + 1` with public input `index` failed
+    #[cfg_attr(feature = "std", error("IC multiplication {index} failed: {error:?}"))]
+    IcMultiplication { index: usize, error: Groth16Error },
+    /// Adding the product of public input `index` failed
+    #[cfg_attr(feature = "std", error("IC addition {index} failed: {error:?}"))]
+    IcAddition { index: usize, error: Groth16Error },
+    /// The pairing syscall rejected its input
+    #[cfg_attr(feature = "std", error("pairing failed: {0:?}"))]
+    PairingRejected(Groth16Error),
+    /// The pairing product is not one, the proof is wrong for these inputs
+    #[cfg_attr(feature = "std", error("pairing product is not one"))]
+    PairingNotOne,
+}
+
+/// The error the terse path returns for the same failure
+impl From<Diagnostic> for Groth16Error {
+    fn from(diagnostic: Diagnostic) -> Self {
+        match diagnostic {
+            Diagnostic::InputCount { .. } => Groth16Error::InvalidPublicInputsLength,
+            Diagnostic::NonCanonicalInput { .. } => Groth16Error::PublicInputGreaterThanFieldSize,
+            Diagnostic::Decompression { error, .. }
+            | Diagnostic::MalformedProofPoint { error, .. }
+            | Diagnostic::IcMultiplication { error, .. }
+            | Diagnostic::IcAddition { error, .. }
+            | Diagnostic::PairingRejected(error) => error,
+            Diagnostic::PairingNotOne => Groth16Error::ProofVerificationFailed,
+        }
+    }
+}
+
+/// Verifies a proof stage by stage, see the module documentation
+///
+/// # Arguments
+/// * `proof_a` - Proof a, negated unless `convention` is [`ProverConvention::RawA`]
+/// * `proof_b` - Proof b
+/// * `proof_c` - Proof c
+/// * `public_inputs` - As many inputs as the key takes
+/// * `verifyingkey` - The verifying key
+/// * `convention` - Whether proof a is negated
+pub fn diagnose(
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    public_inputs: &[[u8; 32]],
+    verifyingkey: &Groth16Verifyingkey,
+    convention: ProverConvention,
+) -> Result<(), Diagnostic> {
+    if public_inputs.len() + 1 != verifyingkey.vk_ic.len() {
+        return Err(Diagnostic::InputCount {
+            expected: verifyingkey.vk_ic.len().saturating_sub(1),
+            actual: public_inputs.len(),
+        });
+    }
+    if let Some(index) = public_inputs
+        .iter()
+        .position(|input| !Bn254::is_less_than_scalar_field_size_be(input))
+    {
+        return Err(Diagnostic::NonCanonicalInput { index });
+    }
+
+    let malformed = |point| move |error| Diagnostic::MalformedProofPoint { point, error };
+    validate_g1(proof_a).map_err(malformed(ProofPoint::A))?;
+    validate_g2(proof_b).map_err(malformed(ProofPoint::B))?;
+    validate_g1(proof_c).map_err(malformed(ProofPoint::C))?;
+
+    let mut prepared_public_inputs = verifyingkey.vk_ic[0];
+    for (index, (input, ic)) in public_inputs
+        .iter()
+        .zip(&verifyingkey.vk_ic[1..])
+        .enumerate()
+    {
+        let product = Bn254::g1_mul(ic, input)
+            .map_err(|error| Diagnostic::IcMultiplication { index, error })?;
+        prepared_public_inputs = Bn254::g1_add(&product, &prepared_public_inputs)
+            .map_err(|error| Diagnostic::IcAddition { index, error })?;
+    }
+
+    let proof_a = match convention {
+        ProverConvention::NegatedA => *proof_a,
+        ProverConvention::RawA => Bn254::g1_negate(proof_a).map_err(malformed(ProofPoint::A))?,
+    };
+    let pairing_is_one = Bn254::pairing_is_one(
+        &[
+            &proof_a,
+            &prepared_public_inputs,
+            proof_c,
+            &verifyingkey.vk_alpha_g1,
+        ],
+        &[
+            proof_b,
+            &verifyingkey.vk_gamma_g2,
+            &verifyingkey.vk_delta_g2,
+            &verifyingkey.vk_beta_g2,
+        ],
+    )
+    .map_err(Diagnostic::PairingRejected)?;
+    if !pairing_is_one {
+        return Err(Diagnostic::PairingNotOne);
+    }
+    Ok(())
+}
+
+/// Decompresses a proof with negated proof a and verifies it stage by stage
+///
+/// # Returns
+/// * `Err(Diagnostic::Decompression)` - If a point does not decompress
+/// * The diagnostics of [`diagnose`]
+pub fn diagnose_compressed(
+    proof_a: &[u8; 32],
+    proof_b: &[u8; 64],
+    proof_c: &[u8; 32],
+    public_inputs: &[[u8; 32]],
+    verifyingkey: &Groth16Verifyingkey,
+) -> Result<(), Diagnostic> {
+    let decompression = |point| move |error| Diagnostic::Decompression { point, error };
+    let proof_a = decompress_g1(proof_a).map_err(decompression(ProofPoint::A))?;
+    let proof_b = decompress_g2(proof_b).map_err(decompression(ProofPoint::B))?;
+    let proof_c = decompress_g1(proof_c).map_err(decompression(ProofPoint::C))?;
+    diagnose(
+        &proof_a,
+        &proof_b,
+        &proof_c,
+        public_inputs,
+        verifyingkey,
+        ProverConvention::NegatedA,
+    )
+}
+
+#[cfg(all(test, feature = "test-vectors"))]
+mod tests {
+    use super::*;
+    use crate::bn254::{alt_bn128_g1_compress, alt_bn128_g2_compress};
+    use crate::test_vectors::generate;
+
+    #[test]
+    fn reports_the_failing_stage() {
+        let vector = generate(0);
+        let vk = vector.verifying_key();
+        let diagnose = |proof_c: &[u8; 64], inputs: &[[u8; 32]]| {
+            diagnose(
+                &vector.proof_a,
+                &vector.proof_b,
+                proof_c,
+                inputs,
+                &vk,
+                ProverConvention::NegatedA,
+            )
+        };
+        diagnose(&vector.proof_c, &vector.public_inputs).unwrap();
+
+        let mut inputs = vector.public_inputs.clone();
+        inputs[1][31] ^= 1;
+        assert_eq!(
+            diagnose(&vector.proof_c, &inputs),
+            Err(Diagnostic::PairingNotOne)
+        );
+        inputs[1] = Bn254::SCALAR_FIELD_MODULUS;
+        assert_eq!(
+            diagnose(&vector.proof_c, &inputs),
+            Err(Diagnostic::NonCanonicalInput { index: 1 })
+        );
+        assert_eq!(
+            diagnose(&vector.proof_c, &inputs[..1]),
+            Err(Diagnostic::InputCount {
+                expected: 2,
+                actual: 1
+            })
+        );
+        let mut proof_c = vector.proof_c;
+        proof_c[63] ^= 1;
+        let diagnostic = diagnose(&proof_c, &vector.public_inputs).unwrap_err();
+        assert_eq!(
+            diagnostic,
+            Diagnostic::MalformedProofPoint {
+                point: ProofPoint::C,
+                error: Groth16Error::PointNotOnCurve
+            }
+        );
+        assert_eq!(
+            Groth16Error::from(diagnostic),
+            Groth16Error::PointNotOnCurve
+        );
+    }
+
+    #[test]
+    fn reports_points_that_do_not_decompress() {
+        let vector = generate(0);
+        let vk = vector.verifying_key();
+        let proof_a = alt_bn128_g1_compress(&vector.proof_a).unwrap();
+        let proof_b = alt_bn128_g2_compress(&vector.proof_b).unwrap();
+        let proof_c = alt_bn128_g1_compress(&vector.proof_c).unwrap();
+        diagnose_compressed(&proof_a, &proof_b, &proof_c, &vector.public_inputs, &vk).unwrap();
+
+        // x is not smaller than the base field modulus
+        let invalid = [0xff; 32];
+        assert!(matches!(
+            diagnose_compressed(&proof_a, &proof_b, &invalid, &vector.public_inputs, &vk),
+            Err(Diagnostic::Decompression {
+                point: ProofPoint::C,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,228 @@
+//! Differential testing of verifier backends
+//!
+//! Runs a corpus of accepted and rejected [`TestVector`]s through two
+//! verification backends and reports every case on which they disagree.
+//! [`arkworks_verify`] is the reference: it decodes the big-endian encodings
+//! into arkworks types and calls `ark_groth16`. [`syscall_verify`] runs the
+//! crate's [`Groth16Verifier`](crate::groth16::Groth16Verifier) on the
+//! alt_bn128 syscalls, or on their mock on the host. A program under an SVM
+//! test runtime can be plugged in as another backend, see
+//! `tests/differential`.
+//!
+//! The corpus mutates valid proofs the way encoding bugs do: swapped G2
+//! coordinate halves, little-endian inputs, a non-negated proof a and so on,
+//! and contains well-formed proofs that only fail the pairing check.
+
+use crate::mock::{g1_from_be, g2_from_be};
+use crate::test_vectors::{g1_to_be, generate, Invalidation, TestVector};
+use alloc::string::String;
+use alloc::vec::Vec;
+use ark_bn254::{Bn254, Fr};
+use ark_ff::PrimeField;
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+use num_bigint::BigUint;
+
+/// A named verification case
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Case {
+    pub name: String,
+    pub vector: TestVector,
+    /// Whether a correct verifier accepts the case
+    pub expected: bool,
+}
+
+/// A case on which two backends disagree
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    pub name: String,
+    pub expected: bool,
+    pub reference: bool,
+    pub backend: bool,
+}
+
+/// Builds the corpus of the test vector of `seed`
+///
+/// # Arguments
+/// * `seed` - Seed of the test vector the cases are derived from
+/// * `nr_rerandomized` - Number of additional valid proofs of the same statement
+pub fn corpus(seed: u64, nr_rerandomized: u64) -> Vec<Case> {
+    let valid = generate(seed);
+    let mut cases = vec![case("valid", valid.clone(), true)];
+    for i in 0..nr_rerandomized {
+        cases.push(case(
+            &format!("rerandomized {i}"),
+            valid.rerandomize(i),
+            true,
+        ));
+    }
+
+    let mut other = generate(seed.wrapping_add(1));
+    other.public_inputs = valid.public_inputs.clone();
+    cases.push(case(
+        "proof of another key",
+        TestVector {
+            proof_a: other.proof_a,
+            proof_b: other.proof_b,
+            proof_c: other.proof_c,
+            ..valid.clone()
+        },
+        false,
+    ));
+
+    cases.push(mutate(&valid, "proof a not negated", |v| {
+        v.proof_a = g1_to_be(&-g1_from_be(&v.proof_a).unwrap());
+    }));
+    cases.push(mutate(&valid, "proof a and c swapped", |v| {
+        core::mem::swap(&mut v.proof_a, &mut v.proof_c);
+    }));
+    cases.push(mutate(&valid, "proof b c0 and c1 swapped", |v| {
+        let b = v.proof_b;
+        v.proof_b[..32].copy_from_slice(&b[32..64]);
+        v.proof_b[32..64].copy_from_slice(&b[..32]);
+        v.proof_b[64..96].copy_from_slice(&b[96..]);
+        v.proof_b[96..].copy_from_slice(&b[64..96]);
+    }));
+    cases.push(mutate(&valid, "proof a off curve", |v| v.proof_a[63] ^= 1));
+    cases.push(mutate(&valid, "proof c identity", |v| {
+        v.proof_c = [0u8; 64]
+    }));
+    cases.push(mutate(&valid, "public inputs swapped", |v| {
+        v.public_inputs.swap(0, 1)
+    }));
+    cases.push(mutate(&valid, "public inputs little-endian", |v| {
+        v.public_inputs = v.public_inputs_le()
+    }));
+    cases.push(mutate(&valid, "public input plus one", |v| {
+        v.public_inputs[0] = add_be(&v.public_inputs[0], &BigUint::from(1u8))
+    }));
+    cases.push(mutate(&valid, "public input plus modulus", |v| {
+        v.public_inputs[0] = add_be(&v.public_inputs[0], &BigUint::from(Fr::MODULUS))
+    }));
+    for invalidation in Invalidation::ALL {
+        cases.push(case(
+            &format!("{invalidation:?}"),
+            valid.invalidate(invalidation, seed),
+            false,
+        ));
+    }
+    cases
+}
+
+/// Reference verification with arkworks
+///
+/// Rejects points that are not canonical, not on the curve or not in the
+/// prime order subgroup and public inputs that are not smaller than the
+/// scalar field modulus.
+pub fn arkworks_verify(vector: &TestVector) -> bool {
+    fn verify(vector: &TestVector) -> Option<bool> {
+        let vk = arkworks_verifying_key(vector)?;
+        let proof = Proof::<Bn254> {
+            a: -g1_from_be(&vector.proof_a)?,
+            b: g2_from_be(&vector.proof_b)?,
+            c: g1_from_be(&vector.proof_c)?,
+        };
+        let modulus = BigUint::from(Fr::MODULUS);
+        let inputs = vector
+            .public_inputs
+            .iter()
+            .map(|input| {
+                (BigUint::from_bytes_be(input) < modulus)
+                    .then(|| Fr::from_be_bytes_mod_order(input))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if inputs.len() + 1 != vk.gamma_abc_g1.len() {
+            return None;
+        }
+        let pvk = ark_groth16::prepare_verifying_key(&vk);
+        Groth16::<Bn254>::verify_proof(&pvk, &proof, &inputs).ok()
+    }
+    verify(vector).unwrap_or(false)
+}
+
+/// Decodes the verifying key of `vector`
+///
+/// # Returns
+/// * `None` - If a point is not canonical, not on the curve or not in the subgroup
+pub fn arkworks_verifying_key(vector: &TestVector) -> Option<VerifyingKey<Bn254>> {
+    Some(VerifyingKey::<Bn254> {
+        alpha_g1: g1_from_be(&vector.vk_alpha_g1)?,
+        beta_g2: g2_from_be(&vector.vk_beta_g2)?,
+        gamma_g2: g2_from_be(&vector.vk_gamma_g2)?,
+        delta_g2: g2_from_be(&vector.vk_delta_g2)?,
+        gamma_abc_g1: vector
+            .vk_ic
+            .iter()
+            .map(g1_from_be)
+            .collect::<Option<Vec<_>>>()?,
+    })
+}
+
+/// Verification with [`Groth16Verifier`](crate::groth16::Groth16Verifier)
+pub fn syscall_verify(vector: &TestVector) -> bool {
+    vector.verify().is_ok()
+}
+
+/// Runs every case through [`arkworks_verify`] and `backend`
+///
+/// # Returns
+/// * `Vec<Mismatch>` - Cases on which the backends disagree or the reference
+///   result differs from the expected one, empty if all agree
+pub fn compare<F: FnMut(&Case) -> bool>(cases: &[Case], mut backend: F) -> Vec<Mismatch> {
+    cases
+        .iter()
+        .filter_map(|case| {
+            let reference = arkworks_verify(&case.vector);
+            let backend = backend(case);
+            (reference != backend || reference != case.expected).then(|| Mismatch {
+                name: case.name.clone(),
+                expected: case.expected,
+                reference,
+                backend,
+            })
+        })
+        .collect()
+}
+
+fn case(name: &str, vector: TestVector, expected: bool) -> Case {
+    Case {
+        name: name.into(),
+        vector,
+        expected,
+    }
+}
+
+fn mutate(valid: &TestVector, name: &str, f: impl FnOnce(&mut TestVector)) -> Case {
+    let mut vector = valid.clone();
+    f(&mut vector);
+    case(name, vector, false)
+}
+
+fn add_be(value: &[u8; 32], summand: &BigUint) -> [u8; 32] {
+    let sum = (BigUint::from_bytes_be(value) + summand).to_bytes_be();
+    let mut be = [0u8; 32];
+    be[32 - sum.len()..].copy_from_slice(&sum);
+    be
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn syscall_backend_agrees_with_arkworks() {
+        let cases = corpus(3, 2);
+        assert!(cases.iter().any(|case| case.expected));
+        assert!(cases.iter().any(|case| !case.expected));
+        assert_eq!(compare(&cases, |case| syscall_verify(&case.vector)), vec![]);
+
+        // A backend ignoring the public inputs is caught
+        let mismatches = compare(&cases, |case| {
+            let mut vector = case.vector.clone();
+            vector.public_inputs = cases[0].vector.public_inputs.clone();
+            syscall_verify(&vector)
+        });
+        assert!(mismatches
+            .iter()
+            .any(|mismatch| mismatch.name == "public inputs swapped"));
+    }
+}
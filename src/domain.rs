@@ -0,0 +1,99 @@
+//! Domain separation of proofs by program and cluster
+//!
+//! A proof is valid for every program that uses the same verifying key. If
+//! a circuit takes a domain separator as public input and the program checks
+//! it against [`domain_separator`] of its own id, proofs generated for a
+//! fork of the program or for a devnet deployment cannot be replayed.
+//!
+//! Programs cannot read the genesis hash at runtime, so the cluster is bound
+//! by compiling in one of the genesis hash constants. Clients compute the
+//! same separator with the same function off chain.
+
+use crate::errors::Groth16Error;
+use crate::hash::hashv_to_bn254_field_size_be;
+use crate::Pubkey;
+
+/// Prefix hashed into every domain separator
+pub const DOMAIN_SEPARATOR_PREFIX: &[u8] = b"groth16-solana-domain";
+
+/// Genesis hash of mainnet-beta, `5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d`
+pub const MAINNET_BETA_GENESIS_HASH: [u8; 32] = [
+    69, 41, 105, 152, 166, 248, 226, 167, 132, 219, 93, 159, 149, 225, 143, 194, 63, 112, 68, 26,
+    16, 57, 68, 104, 1, 8, 152, 121, 176, 140, 126, 240,
+];
+
+/// Genesis hash of devnet, `EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG`
+pub const DEVNET_GENESIS_HASH: [u8; 32] = [
+    206, 89, 219, 80, 128, 252, 44, 109, 59, 207, 124, 169, 7, 18, 211, 194, 229, 230, 194, 143,
+    39, 240, 223, 187, 153, 83, 189, 176, 137, 76, 3, 171,
+];
+
+/// Genesis hash of testnet, `4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY`
+pub const TESTNET_GENESIS_HASH: [u8; 32] = [
+    58, 19, 46, 206, 16, 48, 94, 193, 131, 7, 37, 80, 47, 162, 183, 231, 235, 129, 87, 233, 18, 61,
+    76, 31, 101, 74, 113, 120, 113, 97, 220, 33,
+];
+
+/// Derives the domain separator field element of a program
+///
+/// # Arguments
+/// * `program_id` - The verifying program
+/// * `genesis_hash` - Genesis hash of the cluster, `None` binds the program only
+///
+/// # Returns
+/// * `[u8; 32]` - Big-endian field element `keccak256(prefix | program_id | genesis_hash)`
+///   with the first byte zeroed
+pub fn domain_separator(program_id: &Pubkey, genesis_hash: Option<&[u8; 32]>) -> [u8; 32] {
+    match genesis_hash {
+        Some(genesis_hash) => {
+            hashv_to_bn254_field_size_be(&[DOMAIN_SEPARATOR_PREFIX, program_id, genesis_hash])
+        }
+        None => hashv_to_bn254_field_size_be(&[DOMAIN_SEPARATOR_PREFIX, program_id]),
+    }
+}
+
+/// Checks that the public input at `index` is the domain separator of `program_id`
+///
+/// # Returns
+/// * `Err(Groth16Error::PublicInputMismatch)` - If the proof was generated for
+///   another program or cluster
+pub fn check_domain_separator<const NR_INPUTS: usize>(
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+    index: usize,
+    program_id: &Pubkey,
+    genesis_hash: Option<&[u8; 32]>,
+) -> Result<(), Groth16Error> {
+    let input = public_inputs
+        .get(index)
+        .ok_or(Groth16Error::InvalidPublicInputsLength)?;
+    if *input != domain_separator(program_id, genesis_hash) {
+        return Err(Groth16Error::PublicInputMismatch);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn domain_separator_binds_program_and_cluster() {
+        let program_id = [1u8; 32];
+        let mainnet = domain_separator(&program_id, Some(&MAINNET_BETA_GENESIS_HASH));
+        let devnet = domain_separator(&program_id, Some(&DEVNET_GENESIS_HASH));
+        let any_cluster = domain_separator(&program_id, None);
+        assert_ne!(mainnet, devnet);
+        assert_ne!(mainnet, any_cluster);
+        assert_ne!(
+            mainnet,
+            domain_separator(&[2u8; 32], Some(&MAINNET_BETA_GENESIS_HASH))
+        );
+
+        let inputs = [[0u8; 32], mainnet];
+        check_domain_separator(&inputs, 1, &program_id, Some(&MAINNET_BETA_GENESIS_HASH)).unwrap();
+        assert_eq!(
+            check_domain_separator(&inputs, 1, &program_id, Some(&DEVNET_GENESIS_HASH)),
+            Err(Groth16Error::PublicInputMismatch)
+        );
+    }
+}
@@ -8,27 +8,99 @@ pub enum Groth16Error {
     IncompatibleVerifyingKeyWithNrPublicInputs,
     #[cfg_attr(feature = "std", error("ProofVerificationFailed"))]
     ProofVerificationFailed,
-    #[cfg_attr(feature = "std", error("PreparingInputsG1AdditionFailed"))]
-    PreparingInputsG1AdditionFailed,
-    #[cfg_attr(feature = "std", error("PreparingInputsG1MulFailed"))]
-    PreparingInputsG1MulFailed,
+    /// Carries the return code of the failed syscall, 0 if the input was
+    /// rejected before calling it
+    #[cfg_attr(feature = "std", error("PreparingInputsG1AdditionFailed, return code {0}"))]
+    PreparingInputsG1AdditionFailed(u64),
+    /// Carries the return code of the failed syscall, 0 if the input was
+    /// rejected before calling it
+    #[cfg_attr(feature = "std", error("PreparingInputsG1MulFailed, return code {0}"))]
+    PreparingInputsG1MulFailed(u64),
     #[cfg_attr(feature = "std", error("InvalidG1Length"))]
     InvalidG1Length,
     #[cfg_attr(feature = "std", error("InvalidG2Length"))]
     InvalidG2Length,
     #[cfg_attr(feature = "std", error("InvalidPublicInputsLength"))]
     InvalidPublicInputsLength,
-    #[cfg_attr(feature = "std", error("DecompressingG1Failed"))]
-    DecompressingG1Failed,
-    #[cfg_attr(feature = "std", error("DecompressingG2Failed"))]
-    DecompressingG2Failed,
+    /// Carries the return code of the failed syscall, 0 if the input was
+    /// rejected before calling it
+    #[cfg_attr(feature = "std", error("DecompressingG1Failed, return code {0}"))]
+    DecompressingG1Failed(u64),
+    /// Carries the return code of the failed syscall, 0 if the input was
+    /// rejected before calling it
+    #[cfg_attr(feature = "std", error("DecompressingG2Failed, return code {0}"))]
+    DecompressingG2Failed(u64),
     #[cfg_attr(feature = "std", error("PublicInputGreaterThanFieldSize"))]
     PublicInputGreaterThanFieldSize,
     #[cfg_attr(feature = "std", error("Failed to convert proof component to byte array"))]
     ProofConversionError,
+    #[cfg_attr(feature = "std", error("NullifierAlreadySpent"))]
+    NullifierAlreadySpent,
+    #[cfg_attr(feature = "std", error("NullifierSetFull"))]
+    NullifierSetFull,
+    #[cfg_attr(feature = "std", error("InvalidAccountOwner"))]
+    InvalidAccountOwner,
+    #[cfg_attr(feature = "std", error("InvalidAccountData"))]
+    InvalidAccountData,
+    #[cfg_attr(feature = "std", error("AccountNotWritable"))]
+    AccountNotWritable,
+    #[cfg_attr(feature = "std", error("ProofAccountStale"))]
+    ProofAccountStale,
+    #[cfg_attr(feature = "std", error("ProofAccountNotFinalized"))]
+    ProofAccountNotFinalized,
+    #[cfg_attr(feature = "std", error("MissingRequiredSignature"))]
+    MissingRequiredSignature,
+    #[cfg_attr(feature = "std", error("InvalidAuthority"))]
+    InvalidAuthority,
+    #[cfg_attr(feature = "std", error("InvalidActivationSlot"))]
+    InvalidActivationSlot,
+    #[cfg_attr(feature = "std", error("NoPendingVerifyingKey"))]
+    NoPendingVerifyingKey,
+    #[cfg_attr(feature = "std", error("CpiFailed"))]
+    CpiFailed,
+    #[cfg_attr(feature = "std", error("TransactionTooLarge"))]
+    TransactionTooLarge,
+    #[cfg_attr(feature = "std", error("SyscallNotActive"))]
+    SyscallNotActive,
+    #[cfg_attr(feature = "std", error("PublicInputMismatch"))]
+    PublicInputMismatch,
+    #[cfg_attr(feature = "std", error("InvalidMerkleProof"))]
+    InvalidMerkleProof,
+    #[cfg_attr(feature = "std", error("VerifierDisagreement"))]
+    VerifierDisagreement,
+    #[cfg_attr(feature = "std", error("VerifyingKeyMismatch"))]
+    VerifyingKeyMismatch,
+    #[cfg_attr(feature = "std", error("NonCanonicalCoordinate"))]
+    NonCanonicalCoordinate,
+    #[cfg_attr(feature = "std", error("PointNotOnCurve"))]
+    PointNotOnCurve,
+    #[cfg_attr(feature = "std", error("PointNotInSubgroup"))]
+    PointNotInSubgroup,
+    #[cfg_attr(feature = "std", error("UnknownCircuit"))]
+    UnknownCircuit,
+    /// Carries the return code of the failed syscall, 0 if the input was
+    /// rejected before calling it
+    #[cfg_attr(feature = "std", error("G2OperationFailed, return code {0}"))]
+    G2OperationFailed(u64),
+    #[cfg_attr(feature = "std", error("PairingInputTooLarge"))]
+    PairingInputTooLarge,
+    #[cfg_attr(feature = "std", error("CommitmentVerificationFailed"))]
+    CommitmentVerificationFailed,
+    #[cfg_attr(feature = "std", error("InvalidPublicInput"))]
+    InvalidPublicInput,
+    #[cfg_attr(feature = "std", error("PointAtInfinity"))]
+    PointAtInfinity,
+    #[cfg_attr(feature = "std", error("InvalidInstructionData"))]
+    InvalidInstructionData,
     #[cfg(feature = "circom")]
     #[cfg_attr(feature = "std", error("Arkworks serialization error"))]
     ArkworksSerializationError,
+    #[cfg(feature = "prover")]
+    #[cfg_attr(feature = "std", error("InvalidZkey"))]
+    InvalidZkey,
+    #[cfg(feature = "prover")]
+    #[cfg_attr(feature = "std", error("ProvingFailed"))]
+    ProvingFailed,
 }
 
 #[cfg(feature = "circom")]
@@ -43,17 +115,132 @@ impl From<Groth16Error> for u32 {
         match error {
             Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs => 0,
             Groth16Error::ProofVerificationFailed => 1,
-            Groth16Error::PreparingInputsG1AdditionFailed => 2,
-            Groth16Error::PreparingInputsG1MulFailed => 3,
+            Groth16Error::PreparingInputsG1AdditionFailed(_) => 2,
+            Groth16Error::PreparingInputsG1MulFailed(_) => 3,
             Groth16Error::InvalidG1Length => 4,
             Groth16Error::InvalidG2Length => 5,
             Groth16Error::InvalidPublicInputsLength => 6,
-            Groth16Error::DecompressingG1Failed => 7,
-            Groth16Error::DecompressingG2Failed => 8,
+            Groth16Error::DecompressingG1Failed(_) => 7,
+            Groth16Error::DecompressingG2Failed(_) => 8,
             Groth16Error::PublicInputGreaterThanFieldSize => 9,
             Groth16Error::ProofConversionError => 10,
+            Groth16Error::NullifierAlreadySpent => 12,
+            Groth16Error::NullifierSetFull => 13,
+            Groth16Error::InvalidAccountOwner => 14,
+            Groth16Error::InvalidAccountData => 15,
+            Groth16Error::AccountNotWritable => 16,
+            Groth16Error::ProofAccountStale => 17,
+            Groth16Error::ProofAccountNotFinalized => 18,
+            Groth16Error::MissingRequiredSignature => 19,
+            Groth16Error::InvalidAuthority => 20,
+            Groth16Error::InvalidActivationSlot => 21,
+            Groth16Error::NoPendingVerifyingKey => 22,
+            Groth16Error::CpiFailed => 23,
+            Groth16Error::TransactionTooLarge => 24,
+            Groth16Error::SyscallNotActive => 25,
+            Groth16Error::PublicInputMismatch => 26,
+            Groth16Error::InvalidMerkleProof => 29,
+            Groth16Error::VerifierDisagreement => 30,
+            Groth16Error::VerifyingKeyMismatch => 31,
+            Groth16Error::NonCanonicalCoordinate => 32,
+            Groth16Error::PointNotOnCurve => 33,
+            Groth16Error::PointNotInSubgroup => 34,
+            Groth16Error::UnknownCircuit => 35,
+            Groth16Error::G2OperationFailed(_) => 36,
+            Groth16Error::PairingInputTooLarge => 37,
+            Groth16Error::CommitmentVerificationFailed => 38,
+            Groth16Error::InvalidPublicInput => 39,
+            Groth16Error::PointAtInfinity => 40,
+            Groth16Error::InvalidInstructionData => 41,
             #[cfg(feature = "circom")]
             Groth16Error::ArkworksSerializationError => 11,
+            #[cfg(feature = "prover")]
+            Groth16Error::InvalidZkey => 27,
+            #[cfg(feature = "prover")]
+            Groth16Error::ProvingFailed => 28,
         }
     }
 }
+
+#[cfg(feature = "pinocchio")]
+impl From<Groth16Error> for pinocchio::program_error::ProgramError {
+    /// `ProgramError::Custom` with the code of the error, so handlers can use `?`
+    fn from(error: Groth16Error) -> Self {
+        pinocchio::program_error::ProgramError::Custom(error.into())
+    }
+}
+
+impl TryFrom<u32> for Groth16Error {
+    type Error = u32;
+
+    /// Decodes the custom error code of a failed instruction
+    fn try_from(code: u32) -> Result<Self, u32> {
+        Ok(match code {
+            0 => Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs,
+            1 => Groth16Error::ProofVerificationFailed,
+            2 => Groth16Error::PreparingInputsG1AdditionFailed(0),
+            3 => Groth16Error::PreparingInputsG1MulFailed(0),
+            4 => Groth16Error::InvalidG1Length,
+            5 => Groth16Error::InvalidG2Length,
+            6 => Groth16Error::InvalidPublicInputsLength,
+            7 => Groth16Error::DecompressingG1Failed(0),
+            8 => Groth16Error::DecompressingG2Failed(0),
+            9 => Groth16Error::PublicInputGreaterThanFieldSize,
+            10 => Groth16Error::ProofConversionError,
+            12 => Groth16Error::NullifierAlreadySpent,
+            13 => Groth16Error::NullifierSetFull,
+            14 => Groth16Error::InvalidAccountOwner,
+            15 => Groth16Error::InvalidAccountData,
+            16 => Groth16Error::AccountNotWritable,
+            17 => Groth16Error::ProofAccountStale,
+            18 => Groth16Error::ProofAccountNotFinalized,
+            19 => Groth16Error::MissingRequiredSignature,
+            20 => Groth16Error::InvalidAuthority,
+            21 => Groth16Error::InvalidActivationSlot,
+            22 => Groth16Error::NoPendingVerifyingKey,
+            23 => Groth16Error::CpiFailed,
+            24 => Groth16Error::TransactionTooLarge,
+            25 => Groth16Error::SyscallNotActive,
+            26 => Groth16Error::PublicInputMismatch,
+            29 => Groth16Error::InvalidMerkleProof,
+            30 => Groth16Error::VerifierDisagreement,
+            31 => Groth16Error::VerifyingKeyMismatch,
+            32 => Groth16Error::NonCanonicalCoordinate,
+            33 => Groth16Error::PointNotOnCurve,
+            34 => Groth16Error::PointNotInSubgroup,
+            35 => Groth16Error::UnknownCircuit,
+            36 => Groth16Error::G2OperationFailed(0),
+            37 => Groth16Error::PairingInputTooLarge,
+            38 => Groth16Error::CommitmentVerificationFailed,
+            39 => Groth16Error::InvalidPublicInput,
+            40 => Groth16Error::PointAtInfinity,
+            41 => Groth16Error::InvalidInstructionData,
+            #[cfg(feature = "circom")]
+            11 => Groth16Error::ArkworksSerializationError,
+            #[cfg(feature = "prover")]
+            27 => Groth16Error::InvalidZkey,
+            #[cfg(feature = "prover")]
+            28 => Groth16Error::ProvingFailed,
+            _ => return Err(code),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_roundtrip() {
+        for code in 0..64 {
+            if let Ok(error) = Groth16Error::try_from(code) {
+                assert_eq!(u32::from(error), code);
+            }
+        }
+        assert_eq!(
+            Groth16Error::try_from(1),
+            Ok(Groth16Error::ProofVerificationFailed)
+        );
+        assert_eq!(Groth16Error::try_from(1000), Err(1000));
+    }
+}
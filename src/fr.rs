@@ -0,0 +1,137 @@
+//! BN254 scalar field elements as big-endian bytes
+//!
+//! Public inputs are elements of the scalar field, integers smaller than the
+//! modulus r, encoded as 32 big-endian bytes. The verifier rejects inputs
+//! that are not with [`Groth16Error::PublicInputGreaterThanFieldSize`];
+//! programs validate or derive inputs with the same checks before calling it:
+//!
+//! ```rust,ignore
+//! if !fr::is_canonical(&input) {
+//!     return Err(MyError::InvalidAmount);
+//! }
+//! let nullifier_hash = fr::reduce_be_bytes(&hash);
+//! ```
+//!
+//! Encodings of field elements that are not big-endian, such as arkworks'
+//! little-endian `Fr::to_bytes`, are converted by
+//! [`PublicInputsBuilder`](crate::inputs_builder::PublicInputsBuilder).
+
+use crate::errors::Groth16Error;
+use num_bigint::BigUint;
+
+/// The scalar field modulus r, big-endian
+pub const SCALAR_FIELD_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/// The base field modulus q of the curve's coordinates, big-endian
+pub const BASE_FIELD_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d, 0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
+
+/// Whether `scalar` is a field element, smaller than r
+pub fn is_canonical(scalar: &[u8; 32]) -> bool {
+    *scalar < SCALAR_FIELD_MODULUS
+}
+
+/// `value` modulo r
+///
+/// Matches `uint256(value) % r` of Solidity verifiers. Use it for values
+/// the circuit reduces the same way, such as hashes; rejecting with
+/// [`is_canonical`] is the right check for values a user chose.
+pub fn reduce_be_bytes(value: &[u8; 32]) -> [u8; 32] {
+    let mut value = *value;
+    // 2^256 / r < 6, so at most five subtractions
+    while !is_canonical(&value) {
+        let mut borrow = 0u8;
+        for (byte, modulus) in value.iter_mut().zip(SCALAR_FIELD_MODULUS).rev() {
+            let (difference, underflow) = byte.overflowing_sub(modulus);
+            let (difference, borrow_underflow) = difference.overflowing_sub(borrow);
+            *byte = difference;
+            borrow = (underflow || borrow_underflow) as u8;
+        }
+    }
+    value
+}
+
+/// `value` as a field element, always smaller than r
+pub fn from_u64(value: u64) -> [u8; 32] {
+    let mut scalar = [0u8; 32];
+    scalar[24..].copy_from_slice(&value.to_be_bytes());
+    scalar
+}
+
+/// Parses a non-negative decimal integer, as in snarkjs' `public.json`
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidPublicInput)` - If `value` is not a decimal integer
+/// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If it is not smaller than r
+pub fn from_decimal_str(value: &str) -> Result<[u8; 32], Groth16Error> {
+    let bytes = BigUint::parse_bytes(value.as_bytes(), 10)
+        .filter(|_| value.bytes().all(|byte| byte.is_ascii_digit()))
+        .ok_or(Groth16Error::InvalidPublicInput)?
+        .to_bytes_be();
+    if bytes.len() > 32 {
+        return Err(Groth16Error::PublicInputGreaterThanFieldSize);
+    }
+    let mut scalar = [0u8; 32];
+    scalar[32 - bytes.len()..].copy_from_slice(&bytes);
+    if !is_canonical(&scalar) {
+        return Err(Groth16Error::PublicInputGreaterThanFieldSize);
+    }
+    Ok(scalar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::{BigInteger, PrimeField};
+
+    #[test]
+    fn moduli_match_arkworks() {
+        assert_eq!(
+            SCALAR_FIELD_MODULUS.as_slice(),
+            ark_bn254::Fr::MODULUS.to_bytes_be().as_slice()
+        );
+        assert_eq!(
+            BASE_FIELD_MODULUS.as_slice(),
+            ark_bn254::Fq::MODULUS.to_bytes_be().as_slice()
+        );
+    }
+
+    #[test]
+    fn converts_and_reduces_scalars() {
+        let mut largest = SCALAR_FIELD_MODULUS;
+        largest[31] -= 1;
+        assert!(is_canonical(&largest));
+        assert!(!is_canonical(&SCALAR_FIELD_MODULUS));
+        assert_eq!(reduce_be_bytes(&largest), largest);
+        assert_eq!(reduce_be_bytes(&SCALAR_FIELD_MODULUS), [0u8; 32]);
+        assert_eq!(
+            reduce_be_bytes(&[0xff; 32]).as_slice(),
+            ark_bn254::Fr::from_be_bytes_mod_order(&[0xff; 32])
+                .into_bigint()
+                .to_bytes_be()
+        );
+
+        assert_eq!(from_u64(7), from_decimal_str("7").unwrap());
+        assert_eq!(
+            from_decimal_str(
+                "21888242871839275222246405745257275088548364400416034343698204186575808495616"
+            ),
+            Ok(largest)
+        );
+        assert_eq!(
+            from_decimal_str(
+                "21888242871839275222246405745257275088548364400416034343698204186575808495617"
+            ),
+            Err(Groth16Error::PublicInputGreaterThanFieldSize)
+        );
+        assert_eq!(
+            from_decimal_str("0x07"),
+            Err(Groth16Error::InvalidPublicInput)
+        );
+    }
+}
@@ -25,48 +25,402 @@
 //!
 //! See functional test for a running example how to use this library.
 //!
+pub use crate::bn254::Bn254Backend;
+use crate::curve::PairingCurve;
+pub use crate::curve::{Bn254, Bn254With};
+use crate::decompression::DecompressedProof;
 use crate::errors::Groth16Error;
-use ark_ff::PrimeField;
-use num_bigint::BigUint;
-use crate::bn254::{alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing};
+use crate::fr;
 
+/// Records an intermediate value with the `trace` feature, see [`crate::trace`]
+macro_rules! trace {
+    ($label:expr, $bytes:expr) => {
+        #[cfg(feature = "trace")]
+        crate::trace::record($label, $bytes);
+    };
+}
+
+/// First bytes of the binary verifying key encoding
+pub const VK_BINARY_MAGIC: [u8; 4] = *b"g16k";
+
+/// Version of the binary verifying key encoding
+pub const VK_BINARY_VERSION: u32 = 1;
+
+/// Groth16 verifying key over the curve `C`, points encoded as taken by its syscalls
 #[derive(PartialEq, Eq, Debug)]
-pub struct Groth16Verifyingkey<'a> {
+pub struct CurveVerifyingkey<'a, C: PairingCurve> {
     pub nr_pubinputs: usize,
-    pub vk_alpha_g1: [u8; 64],
-    pub vk_beta_g2: [u8; 128],
-    pub vk_gamma_g2: [u8; 128],
-    pub vk_delta_g2: [u8; 128],
-    pub vk_ic: &'a [[u8; 64]],
+    pub vk_alpha_g1: C::G1,
+    pub vk_beta_g2: C::G2,
+    pub vk_gamma_g2: C::G2,
+    pub vk_delta_g2: C::G2,
+    pub vk_ic: &'a [C::G1],
+}
+
+/// Verifying key over BN254
+pub type Groth16Verifyingkey<'a> = CurveVerifyingkey<'a, Bn254>;
+
+impl<C: PairingCurve> CurveVerifyingkey<'_, C> {
+    /// Size of the packed encoding of a key with `nr_pubinputs` public inputs
+    ///
+    /// Packed layout: `nr_pubinputs u32 LE | alpha_g1 | beta_g2 | gamma_g2 | delta_g2 | ic`
+    pub const fn packed_size(nr_pubinputs: usize) -> usize {
+        4 + C::G1_SIZE + 3 * C::G2_SIZE + (nr_pubinputs + 1) * C::G1_SIZE
+    }
+
+    /// [`packed_size`](Self::packed_size), `None` if it overflows `usize`
+    ///
+    /// For counts read from untrusted data, which overflow on 32-bit targets.
+    pub const fn checked_packed_size(nr_pubinputs: usize) -> Option<usize> {
+        let Some(nr_ic) = nr_pubinputs.checked_add(1) else {
+            return None;
+        };
+        let Some(ic_size) = nr_ic.checked_mul(C::G1_SIZE) else {
+            return None;
+        };
+        ic_size.checked_add(4 + C::G1_SIZE + 3 * C::G2_SIZE)
+    }
+
+    /// Writes the packed encoding into `out` and returns the number of bytes written
+    ///
+    /// `nr_pubinputs` is taken from the number of IC points.
+    pub fn write_packed_bytes(&self, out: &mut [u8]) -> Result<usize, Groth16Error> {
+        if self.vk_ic.is_empty() {
+            return Err(Groth16Error::InvalidPublicInputsLength);
+        }
+        let nr_pubinputs = self.vk_ic.len() - 1;
+        let size = Self::packed_size(nr_pubinputs);
+        if out.len() < size {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        out[..4].copy_from_slice(&(nr_pubinputs as u32).to_le_bytes());
+        let mut offset = 4;
+        for point in [
+            self.vk_alpha_g1.as_ref(),
+            self.vk_beta_g2.as_ref(),
+            self.vk_gamma_g2.as_ref(),
+            self.vk_delta_g2.as_ref(),
+        ]
+        .into_iter()
+        .chain(self.vk_ic.iter().map(|ic| ic.as_ref()))
+        {
+            out[offset..offset + point.len()].copy_from_slice(point);
+            offset += point.len();
+        }
+        Ok(size)
+    }
+
+    /// Size of the binary encoding, the packed encoding behind an 8 byte header
+    ///
+    /// Binary layout: `VK_BINARY_MAGIC | version u32 LE | packed encoding`
+    pub const fn binary_size(nr_pubinputs: usize) -> usize {
+        8 + Self::packed_size(nr_pubinputs)
+    }
+
+    /// Writes the binary encoding into `out` and returns the number of bytes written
+    pub fn write_bytes(&self, out: &mut [u8]) -> Result<usize, Groth16Error> {
+        if out.len() < 8 {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        out[..4].copy_from_slice(&VK_BINARY_MAGIC);
+        out[4..8].copy_from_slice(&VK_BINARY_VERSION.to_le_bytes());
+        Ok(8 + self.write_packed_bytes(&mut out[8..])?)
+    }
+
+    /// The binary encoding, see [`CurveVerifyingkey::binary_size`]
+    pub fn to_bytes(&self) -> Result<alloc::vec::Vec<u8>, Groth16Error> {
+        let nr_pubinputs = self
+            .vk_ic
+            .len()
+            .checked_sub(1)
+            .ok_or(Groth16Error::InvalidPublicInputsLength)?;
+        let mut bytes = vec![0u8; Self::binary_size(nr_pubinputs)];
+        self.write_bytes(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+impl<'a, B: Bn254Backend> CurveVerifyingkey<'a, Bn254With<B>> {
+    /// The same key, verified on the backend `B2`, see [`Bn254Backend`]
+    pub fn with_backend<B2: Bn254Backend>(&self) -> CurveVerifyingkey<'a, Bn254With<B2>> {
+        CurveVerifyingkey {
+            nr_pubinputs: self.nr_pubinputs,
+            vk_alpha_g1: self.vk_alpha_g1,
+            vk_beta_g2: self.vk_beta_g2,
+            vk_gamma_g2: self.vk_gamma_g2,
+            vk_delta_g2: self.vk_delta_g2,
+            vk_ic: self.vk_ic,
+        }
+    }
+}
+
+impl<'a> Groth16Verifyingkey<'a> {
+    /// Borrows a verifying key from its binary encoding
+    ///
+    /// The encoding is written by [`CurveVerifyingkey::write_bytes`] or
+    /// `vk_parser::parse_vk_json_to_bytes`, e.g. into an account or a file
+    /// embedded with `include_bytes!`.
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidAccountData)` - If the magic, version or size is wrong
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Groth16Error> {
+        if bytes.len() < 8
+            || bytes[..4] != VK_BINARY_MAGIC
+            || bytes[4..8] != VK_BINARY_VERSION.to_le_bytes()
+        {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        Self::from_packed_bytes(&bytes[8..])
+    }
+
+    /// Borrows a verifying key from its binary encoding and checks its fingerprint
+    ///
+    /// Programs loading their key from an account pass the fingerprint of the
+    /// circuit they expect, `vk_parser` emits it as `VERIFYINGKEY_FINGERPRINT`,
+    /// so a stale or swapped key is rejected instead of verifying proofs of
+    /// another circuit.
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidAccountData)` - If the magic, version or size is wrong
+    /// * `Err(Groth16Error::VerifyingKeyMismatch)` - If the fingerprint differs
+    pub fn from_bytes_checked(
+        bytes: &'a [u8],
+        expected_fingerprint: &[u8; 32],
+    ) -> Result<Self, Groth16Error> {
+        let verifyingkey = Self::from_bytes(bytes)?;
+        verifyingkey.check_fingerprint(expected_fingerprint)?;
+        Ok(verifyingkey)
+    }
+
+    /// Keccak256 of the key's points, [`crate::recursion::vk_hash`]
+    pub fn fingerprint(&self) -> [u8; 32] {
+        crate::recursion::vk_hash(self)
+    }
+
+    /// # Returns
+    /// * `Err(Groth16Error::VerifyingKeyMismatch)` - If the key's fingerprint
+    ///   is not `expected_fingerprint`
+    pub fn check_fingerprint(&self, expected_fingerprint: &[u8; 32]) -> Result<(), Groth16Error> {
+        if self.fingerprint() != *expected_fingerprint {
+            return Err(Groth16Error::VerifyingKeyMismatch);
+        }
+        Ok(())
+    }
+
+    /// Borrows a verifying key from its packed encoding, e.g. account data
+    pub fn from_packed_bytes(bytes: &'a [u8]) -> Result<Self, Groth16Error> {
+        if bytes.len() < 4 {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        let nr_pubinputs = u32::from_le_bytes(bytes[..4].try_into().unwrap()) as usize;
+        if Some(bytes.len()) != Self::checked_packed_size(nr_pubinputs) {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        let (vk_ic, _) = bytes[452..].as_chunks::<64>();
+        Ok(Groth16Verifyingkey {
+            nr_pubinputs,
+            vk_alpha_g1: bytes[4..68].try_into().unwrap(),
+            vk_beta_g2: bytes[68..196].try_into().unwrap(),
+            vk_gamma_g2: bytes[196..324].try_into().unwrap(),
+            vk_delta_g2: bytes[324..452].try_into().unwrap(),
+            vk_ic,
+        })
+    }
 }
 
+/// Verifying key over the curve `C` of a circuit with `NR_INPUTS` public inputs
+///
+/// [`CurveVerifier::from_sized_key`] only takes public inputs of this count,
+/// so passing another number is a compile error. Keys built in a `const`
+/// are checked at compile time as well, `vk_parser` emits one next to
+/// `VERIFYINGKEY`:
+///
+/// ```rust,ignore
+/// pub const NR_PUBLIC_INPUTS: usize = 2;
+/// pub const SIZED_VERIFYINGKEY: SizedVerifyingkey<NR_PUBLIC_INPUTS> =
+///     SizedVerifyingkey::new(VERIFYINGKEY);
+/// ```
 #[derive(PartialEq, Eq, Debug)]
-pub struct Groth16Verifier<'a, const NR_INPUTS: usize> {
-    proof_a: &'a [u8; 64],
-    proof_b: &'a [u8; 128],
-    proof_c: &'a [u8; 64],
+pub struct SizedCurveVerifyingkey<'a, const NR_INPUTS: usize, C: PairingCurve>(
+    CurveVerifyingkey<'a, C>,
+);
+
+/// Verifying key over BN254 of a circuit with `NR_INPUTS` public inputs
+pub type SizedVerifyingkey<'a, const NR_INPUTS: usize> =
+    SizedCurveVerifyingkey<'a, NR_INPUTS, Bn254>;
+
+impl<'a, const NR_INPUTS: usize, C: PairingCurve> SizedCurveVerifyingkey<'a, NR_INPUTS, C> {
+    /// Checks that `verifyingkey` takes `NR_INPUTS` public inputs
+    ///
+    /// # Panics
+    /// If the key has another number of IC points than `NR_INPUTS + 1`, which
+    /// fails the build when evaluated in a `const`.
+    pub const fn new(verifyingkey: CurveVerifyingkey<'a, C>) -> Self {
+        if verifyingkey.vk_ic.len() != NR_INPUTS + 1 {
+            panic!("verifying key takes another number of public inputs");
+        }
+        SizedCurveVerifyingkey(verifyingkey)
+    }
+
+    /// Checks that `verifyingkey` takes `NR_INPUTS` public inputs, for keys
+    /// loaded at runtime
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs)` - If
+    ///   the key has another number of IC points than `NR_INPUTS + 1`
+    pub fn try_new(verifyingkey: CurveVerifyingkey<'a, C>) -> Result<Self, Groth16Error> {
+        if verifyingkey.vk_ic.len() != NR_INPUTS + 1 {
+            return Err(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs);
+        }
+        Ok(SizedCurveVerifyingkey(verifyingkey))
+    }
+
+    pub fn verifyingkey(&self) -> &CurveVerifyingkey<'a, C> {
+        &self.0
+    }
+}
+
+/// Whether proof a reaches the verifier negated
+///
+/// The pairing check `e(-a, b) * e(inputs, gamma) * e(c, delta) * e(alpha, beta) == 1`
+/// takes proof a negated. Provers output a as proven, snarkjs' `proof.json`
+/// and gnark's proofs alike; the parsers of this crate, such as
+/// [`crate::proof_parser`], negate it for the client. Verifying keys generated
+/// by [`crate::vk_parser`] are always in the form the check takes, the
+/// convention only decides whether the verifier negates proof a.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProverConvention {
+    /// Proof a was negated by the client
+    #[default]
+    NegatedA,
+    /// Proof a is passed as output by the prover and negated by the verifier
+    RawA,
+}
+
+/// Pairing check of a proof with negated proof a and prepared public inputs
+pub(crate) fn check_pairing<C: PairingCurve>(
+    proof_a: &C::G1,
+    proof_b: &C::G2,
+    proof_c: &C::G1,
+    prepared_public_inputs: &C::G1,
+    vk: &CurveVerifyingkey<C>,
+) -> Result<(), Groth16Error> {
+    cu_log!("groth16: pairing");
+    let pairing_is_one = C::pairing_is_one(
+        &[proof_a, prepared_public_inputs, proof_c, &vk.vk_alpha_g1],
+        &[proof_b, &vk.vk_gamma_g2, &vk.vk_delta_g2, &vk.vk_beta_g2],
+    )?;
+    cu_log!("groth16: done");
+    if !pairing_is_one {
+        return Err(Groth16Error::ProofVerificationFailed);
+    }
+    Ok(())
+}
+
+/// `vk_ic[0] + sum of vk_ic[i + 1] * public_inputs[i]`
+fn prepare_curve_inputs<C: PairingCurve, const CHECK: bool>(
+    public_inputs: &[[u8; 32]],
+    verifyingkey: &CurveVerifyingkey<C>,
+) -> Result<C::G1, Groth16Error> {
+    if public_inputs.len() + 1 != verifyingkey.vk_ic.len() {
+        return Err(Groth16Error::InvalidPublicInputsLength);
+    }
+    cu_log!("groth16: validate_inputs");
+    if CHECK
+        && !public_inputs
+            .iter()
+            .all(C::is_less_than_scalar_field_size_be)
+    {
+        return Err(Groth16Error::PublicInputGreaterThanFieldSize);
+    }
+
+    cu_log!("groth16: prepare_inputs");
+    #[cfg(feature = "trace")]
+    for (i, input) in public_inputs.iter().enumerate() {
+        crate::trace::record(&format!("public_inputs[{i}]"), input);
+    }
+    let sum = C::msm_g1(&verifyingkey.vk_ic[1..], public_inputs)?;
+    let prepared_public_inputs = C::g1_add(&sum, &verifyingkey.vk_ic[0])?;
+
+    trace!("prepared_inputs", prepared_public_inputs.as_ref());
+    Ok(prepared_public_inputs)
+}
+
+/// Public inputs prepared for a verifying key, `vk_ic[0] + sum of vk_ic[i + 1] * input[i]`
+///
+/// Preparing costs a G1 multiplication and addition per input. A program
+/// verifying several proofs of the same inputs, or one that prepared the
+/// inputs in an earlier instruction, passes the stored point to
+/// [`verify_with_prepared_inputs`] instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PreparedInputs(pub [u8; 64]);
+
+impl PreparedInputs {
+    /// Prepares `public_inputs` for `verifyingkey`
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidPublicInputsLength)` - If the key takes
+    ///   another number of public inputs
+    /// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If an input is
+    ///   not smaller than the scalar field modulus
+    pub fn new(
+        public_inputs: &[[u8; 32]],
+        verifyingkey: &Groth16Verifyingkey,
+    ) -> Result<Self, Groth16Error> {
+        prepare_curve_inputs::<Bn254, true>(public_inputs, verifyingkey).map(PreparedInputs)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 64] {
+        &self.0
+    }
+}
+
+/// Verifies a proof against public inputs prepared with [`PreparedInputs::new`]
+///
+/// `prepared_inputs` must have been prepared for `verifyingkey`, a point of
+/// other inputs or another key fails the pairing check like a wrong proof.
+pub fn verify_with_prepared_inputs(
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    prepared_inputs: &PreparedInputs,
+    verifyingkey: &Groth16Verifyingkey,
+) -> Result<(), Groth16Error> {
+    check_pairing::<Bn254>(proof_a, proof_b, proof_c, &prepared_inputs.0, verifyingkey)
+}
+
+/// Groth16 verifier over the curve `C`
+#[derive(PartialEq, Eq, Debug)]
+pub struct CurveVerifier<'a, const NR_INPUTS: usize, C: PairingCurve> {
+    proof_a: &'a C::G1,
+    proof_b: &'a C::G2,
+    proof_c: &'a C::G1,
     public_inputs: &'a [[u8; 32]; NR_INPUTS],
-    prepared_public_inputs: [u8; 64],
-    verifyingkey: &'a Groth16Verifyingkey<'a>,
+    prepared_public_inputs: C::G1,
+    verifyingkey: &'a CurveVerifyingkey<'a, C>,
+    convention: ProverConvention,
 }
 
-impl<const NR_INPUTS: usize> Groth16Verifier<'_, NR_INPUTS> {
+/// Verifier over BN254
+pub type Groth16Verifier<'a, const NR_INPUTS: usize> = CurveVerifier<'a, NR_INPUTS, Bn254>;
+
+impl<const NR_INPUTS: usize, C: PairingCurve> CurveVerifier<'_, NR_INPUTS, C> {
     pub fn new<'a>(
-        proof_a: &'a [u8; 64],
-        proof_b: &'a [u8; 128],
-        proof_c: &'a [u8; 64],
+        proof_a: &'a C::G1,
+        proof_b: &'a C::G2,
+        proof_c: &'a C::G1,
         public_inputs: &'a [[u8; 32]; NR_INPUTS],
-        verifyingkey: &'a Groth16Verifyingkey<'a>,
-    ) -> Result<Groth16Verifier<'a, NR_INPUTS>, Groth16Error> {
-        if proof_a.len() != 64 {
+        verifyingkey: &'a CurveVerifyingkey<'a, C>,
+    ) -> Result<CurveVerifier<'a, NR_INPUTS, C>, Groth16Error> {
+        if proof_a.as_ref().len() != C::G1_SIZE {
             return Err(Groth16Error::InvalidG1Length);
         }
 
-        if proof_b.len() != 128 {
+        if proof_b.as_ref().len() != C::G2_SIZE {
             return Err(Groth16Error::InvalidG2Length);
         }
 
-        if proof_c.len() != 64 {
+        if proof_c.as_ref().len() != C::G1_SIZE {
             return Err(Groth16Error::InvalidG1Length);
         }
 
@@ -74,36 +428,54 @@ impl<const NR_INPUTS: usize> Groth16Verifier<'_, NR_INPUTS> {
             return Err(Groth16Error::InvalidPublicInputsLength);
         }
 
-        Ok(Groth16Verifier {
+        Ok(CurveVerifier {
             proof_a,
             proof_b,
             proof_c,
             public_inputs,
-            prepared_public_inputs: [0u8; 64],
+            prepared_public_inputs: C::G1_IDENTITY,
             verifyingkey,
+            convention: ProverConvention::NegatedA,
         })
     }
 
-    pub fn prepare_inputs<const CHECK: bool>(&mut self) -> Result<(), Groth16Error> {
-        let mut prepared_public_inputs = self.verifyingkey.vk_ic[0];
-
-        for (i, input) in self.public_inputs.iter().enumerate() {
-            if CHECK && !is_less_than_bn254_field_size_be(input) {
-                return Err(Groth16Error::PublicInputGreaterThanFieldSize);
-            }
-            let mul_res = alt_bn128_multiplication(
-                &[&self.verifyingkey.vk_ic[i + 1][..], &input[..]].concat(),
-            )
-            .map_err(|_| Groth16Error::PreparingInputsG1MulFailed)?;
-            prepared_public_inputs =
-                alt_bn128_addition(&[&mul_res[..], &prepared_public_inputs[..]].concat())
-                    .map_err(|_| Groth16Error::PreparingInputsG1AdditionFailed)?[..]
-                    .try_into()
-                    .map_err(|_| Groth16Error::PreparingInputsG1AdditionFailed)?;
+    /// A verifier of a key whose number of public inputs is checked at compile time
+    pub fn from_sized_key<'a>(
+        proof_a: &'a C::G1,
+        proof_b: &'a C::G2,
+        proof_c: &'a C::G1,
+        public_inputs: &'a [[u8; 32]; NR_INPUTS],
+        verifyingkey: &'a SizedCurveVerifyingkey<'a, NR_INPUTS, C>,
+    ) -> CurveVerifier<'a, NR_INPUTS, C> {
+        CurveVerifier {
+            proof_a,
+            proof_b,
+            proof_c,
+            public_inputs,
+            prepared_public_inputs: C::G1_IDENTITY,
+            verifyingkey: &verifyingkey.0,
+            convention: ProverConvention::NegatedA,
         }
+    }
 
-        self.prepared_public_inputs = prepared_public_inputs;
+    /// Sets the convention proof a is encoded in, [`ProverConvention::NegatedA`] by default
+    pub fn with_convention(mut self, convention: ProverConvention) -> Self {
+        self.convention = convention;
+        self
+    }
 
+    /// Negates proof a in the verifier, for proofs with a as output by the prover
+    ///
+    /// snarkjs' `proof.json` and gnark's proofs hold a as proven, this saves
+    /// clients negating it with [`crate::bn254::negate_g1`]. The same as
+    /// `with_convention(ProverConvention::RawA)`.
+    pub fn with_proof_a_negation(self) -> Self {
+        self.with_convention(ProverConvention::RawA)
+    }
+
+    pub fn prepare_inputs<const CHECK: bool>(&mut self) -> Result<(), Groth16Error> {
+        self.prepared_public_inputs =
+            prepare_curve_inputs::<C, CHECK>(self.public_inputs, self.verifyingkey)?;
         Ok(())
     }
 
@@ -120,33 +492,167 @@ impl<const NR_INPUTS: usize> Groth16Verifier<'_, NR_INPUTS> {
     }
 
     fn verify_common<const CHECK: bool>(&mut self) -> Result<(), Groth16Error> {
+        #[cfg(feature = "trace")]
+        self.trace_inputs();
         self.prepare_inputs::<CHECK>()?;
 
-        let pairing_input = [
-            self.proof_a.as_slice(),
-            self.proof_b.as_slice(),
-            self.prepared_public_inputs.as_slice(),
-            self.verifyingkey.vk_gamma_g2.as_slice(),
-            self.proof_c.as_slice(),
-            self.verifyingkey.vk_delta_g2.as_slice(),
-            self.verifyingkey.vk_alpha_g1.as_slice(),
-            self.verifyingkey.vk_beta_g2.as_slice(),
-        ]
-        .concat();
-
-        let pairing_res = alt_bn128_pairing(pairing_input.as_slice())
-            .map_err(|_| Groth16Error::ProofVerificationFailed)?;
+        let proof_a = match self.convention {
+            ProverConvention::NegatedA => *self.proof_a,
+            ProverConvention::RawA => C::g1_negate(self.proof_a)?,
+        };
+        check_pairing::<C>(
+            &proof_a,
+            self.proof_b,
+            self.proof_c,
+            &self.prepared_public_inputs,
+            self.verifyingkey,
+        )
+    }
 
-        if pairing_res[31] != 1 {
-            return Err(Groth16Error::ProofVerificationFailed);
+    #[cfg(feature = "trace")]
+    fn trace_inputs(&self) {
+        let vk = self.verifyingkey;
+        trace!("vk.alpha_g1", vk.vk_alpha_g1.as_ref());
+        trace!("vk.beta_g2", vk.vk_beta_g2.as_ref());
+        trace!("vk.gamma_g2", vk.vk_gamma_g2.as_ref());
+        trace!("vk.delta_g2", vk.vk_delta_g2.as_ref());
+        for (i, ic) in vk.vk_ic.iter().enumerate() {
+            trace!(&format!("vk.ic[{i}]"), ic.as_ref());
         }
-        Ok(())
+        trace!("proof.a", self.proof_a.as_ref());
+        trace!("proof.b", self.proof_b.as_ref());
+        trace!("proof.c", self.proof_c.as_ref());
     }
 }
 
+impl<'a, const NR_INPUTS: usize, B: Bn254Backend> CurveVerifier<'a, NR_INPUTS, Bn254With<B>> {
+    /// Creates a verifier of a compressed proof
+    ///
+    /// Proof a and c take 32 bytes and proof b 64 bytes instead of 64 and
+    /// 128, see [`crate::decompression::COMPRESSED_PROOF_SIZE`]. The points
+    /// are decompressed into `storage`, which the verifier borrows.
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::DecompressingG1Failed)` - If proof a or c is not a valid point
+    /// * `Err(Groth16Error::DecompressingG2Failed)` - If proof b is not a valid point
+    pub fn new_compressed(
+        storage: &'a mut DecompressedProof,
+        proof_a: &[u8; 32],
+        proof_b: &[u8; 64],
+        proof_c: &[u8; 32],
+        public_inputs: &'a [[u8; 32]; NR_INPUTS],
+        verifyingkey: &'a CurveVerifyingkey<'a, Bn254With<B>>,
+    ) -> Result<Self, Groth16Error> {
+        *storage = DecompressedProof::decompress::<B>(proof_a, proof_b, proof_c)?;
+        let storage = &*storage;
+        Self::new(
+            &storage.proof_a,
+            &storage.proof_b,
+            &storage.proof_c,
+            public_inputs,
+            verifyingkey,
+        )
+    }
+
+    /// Decompresses and verifies a proof, see [`Self::new_compressed`]
+    pub fn verify_compressed(
+        proof_a: &[u8; 32],
+        proof_b: &[u8; 64],
+        proof_c: &[u8; 32],
+        public_inputs: &'a [[u8; 32]; NR_INPUTS],
+        verifyingkey: &'a CurveVerifyingkey<'a, Bn254With<B>>,
+    ) -> Result<(), Groth16Error> {
+        let mut storage = DecompressedProof::default();
+        CurveVerifier::<NR_INPUTS, Bn254With<B>>::new_compressed(
+            &mut storage,
+            proof_a,
+            proof_b,
+            proof_c,
+            public_inputs,
+            verifyingkey,
+        )?
+        .verify()
+    }
+}
+
+#[cfg(feature = "diagnostics")]
+impl<const NR_INPUTS: usize> Groth16Verifier<'_, NR_INPUTS> {
+    /// Verifies the proof stage by stage, see [`crate::diagnostics`]
+    ///
+    /// # Returns
+    /// * `Err(Diagnostic)` - The stage that failed and the offending input or point
+    pub fn verify_diagnostic(&mut self) -> Result<(), crate::diagnostics::Diagnostic> {
+        crate::diagnostics::diagnose(
+            self.proof_a,
+            self.proof_b,
+            self.proof_c,
+            self.public_inputs,
+            self.verifyingkey,
+            self.convention,
+        )
+    }
+}
+
+#[cfg(feature = "strict")]
+impl<const NR_INPUTS: usize> Groth16Verifier<'_, NR_INPUTS> {
+    /// Verifies the proof after checking its encoding, see [`crate::strict`]
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::NonCanonicalCoordinate)` - If a coordinate of a
+    ///   proof point is not smaller than the base field modulus
+    /// * `Err(Groth16Error::PointAtInfinity)` - If a proof point is all zero
+    pub fn verify_strict(&mut self) -> Result<(), Groth16Error> {
+        crate::strict::check_canonical_proof(self.proof_a, self.proof_b, self.proof_c)?;
+        self.verify()
+    }
+}
+
+#[cfg(feature = "paranoid")]
+impl<const NR_INPUTS: usize, B: Bn254Backend> CurveVerifier<'_, NR_INPUTS, Bn254With<B>> {
+    /// Verifies the proof on the backend `B` and with arkworks, see [`crate::paranoid`]
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::VerifierDisagreement)` - If only one of the two accepts
+    pub fn verify_paranoid(&mut self) -> Result<(), Groth16Error> {
+        let arkworks_result = crate::paranoid::verify_arkworks(
+            self.proof_a,
+            self.proof_b,
+            self.proof_c,
+            self.public_inputs,
+            &self.verifyingkey.with_backend(),
+            self.convention,
+        );
+        crate::paranoid::require_agreement(self.verify(), arkworks_result)
+    }
+}
+
+/// Whether `bytes` is a BN254 scalar, see [`fr::is_canonical`]
 pub fn is_less_than_bn254_field_size_be(bytes: &[u8; 32]) -> bool {
-    let bigint = BigUint::from_bytes_be(bytes);
-    bigint < ark_bn254::Fr::MODULUS.into()
+    fr::is_canonical(bytes)
+}
+
+/// Keys of arbitrary points with a consistent `nr_pubinputs`
+///
+/// The IC points are borrowed from the fuzzer input, as packed keys borrow
+/// them from account data.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Groth16Verifyingkey<'a> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let vk_alpha_g1 = u.arbitrary()?;
+        let vk_beta_g2 = u.arbitrary()?;
+        let vk_gamma_g2 = u.arbitrary()?;
+        let vk_delta_g2 = u.arbitrary()?;
+        let nr_ic = u.arbitrary_len::<[u8; 64]>()?.max(1);
+        let (vk_ic, _) = u.bytes(nr_ic * 64)?.as_chunks::<64>();
+        Ok(Groth16Verifyingkey {
+            nr_pubinputs: nr_ic - 1,
+            vk_alpha_g1,
+            vk_beta_g2,
+            vk_gamma_g2,
+            vk_delta_g2,
+            vk_ic,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -155,9 +661,10 @@ mod tests {
 
     use super::*;
     use ark_bn254;
-    use ark_ff::BigInteger;
+    use ark_ff::{BigInteger, PrimeField};
     use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
     use core::ops::Neg;
+    use num_bigint::BigUint;
     type G1 = ark_bn254::g1::G1Affine;
     type G2 = ark_bn254::g2::G2Affine;
     use crate::bn254::convert_endianness;
@@ -343,6 +850,59 @@ mod tests {
         assert!(!is_less_than_bn254_field_size_be(&bytes));
     }
 
+    #[test]
+    fn packed_verifying_key_roundtrip() {
+        let mut bytes = vec![0u8; Groth16Verifyingkey::packed_size(9)];
+        assert_eq!(
+            VERIFYING_KEY.write_packed_bytes(&mut bytes).unwrap(),
+            bytes.len()
+        );
+        let vk = Groth16Verifyingkey::from_packed_bytes(&bytes).unwrap();
+        assert_eq!(vk.nr_pubinputs, 9);
+        assert_eq!(vk.vk_alpha_g1, VERIFYING_KEY.vk_alpha_g1);
+        assert_eq!(vk.vk_beta_g2, VERIFYING_KEY.vk_beta_g2);
+        assert_eq!(vk.vk_gamma_g2, VERIFYING_KEY.vk_gamma_g2);
+        assert_eq!(vk.vk_delta_g2, VERIFYING_KEY.vk_delta_g2);
+        assert_eq!(vk.vk_ic, VERIFYING_KEY.vk_ic);
+
+        assert_eq!(
+            Groth16Verifyingkey::from_packed_bytes(&bytes[..bytes.len() - 1]),
+            Err(Groth16Error::InvalidAccountData)
+        );
+
+        // A count whose size overflows on 32-bit targets
+        bytes[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            Groth16Verifyingkey::from_packed_bytes(&bytes),
+            Err(Groth16Error::InvalidAccountData)
+        );
+        assert_eq!(Groth16Verifyingkey::checked_packed_size(usize::MAX), None);
+        assert_eq!(
+            Groth16Verifyingkey::checked_packed_size(9),
+            Some(Groth16Verifyingkey::packed_size(9))
+        );
+    }
+
+    #[test]
+    fn fingerprint_detects_swapped_keys() {
+        let bytes = VERIFYING_KEY.to_bytes().unwrap();
+        let fingerprint = VERIFYING_KEY.fingerprint();
+        let vk = Groth16Verifyingkey::from_bytes_checked(&bytes, &fingerprint).unwrap();
+        assert_eq!(vk.vk_ic, VERIFYING_KEY.vk_ic);
+
+        // The same key with another IC point
+        let mut swapped = bytes.clone();
+        *swapped.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            Groth16Verifyingkey::from_bytes_checked(&swapped, &fingerprint),
+            Err(Groth16Error::VerifyingKeyMismatch)
+        );
+        assert_eq!(
+            Groth16Verifyingkey::from_bytes_checked(&bytes[8..], &fingerprint),
+            Err(Groth16Error::InvalidAccountData)
+        );
+    }
+
     #[test]
     fn proof_verification_should_succeed() {
         let proof_a: G1 = G1::deserialize_with_mode(
@@ -372,6 +932,46 @@ mod tests {
                 .unwrap();
         verifier.verify().unwrap();
         verifier.verify_unchecked().unwrap();
+
+        let prepared_inputs = PreparedInputs::new(&PUBLIC_INPUTS, &VERIFYING_KEY).unwrap();
+        assert_eq!(prepared_inputs.0, verifier.prepared_public_inputs);
+        verify_with_prepared_inputs(
+            &proof_a,
+            &proof_b,
+            &proof_c,
+            &prepared_inputs,
+            &VERIFYING_KEY,
+        )
+        .unwrap();
+        let mut other_inputs = PUBLIC_INPUTS;
+        other_inputs[0][31] ^= 1;
+        let other_inputs = PreparedInputs::new(&other_inputs, &VERIFYING_KEY).unwrap();
+        assert_eq!(
+            verify_with_prepared_inputs(
+                &proof_a,
+                &proof_b,
+                &proof_c,
+                &other_inputs,
+                &VERIFYING_KEY
+            ),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+
+        // The input count is part of the key's type
+        const SIZED_VERIFYING_KEY: SizedVerifyingkey<9> = SizedVerifyingkey::new(VERIFYING_KEY);
+        Groth16Verifier::from_sized_key(
+            &proof_a,
+            &proof_b,
+            &proof_c,
+            &PUBLIC_INPUTS,
+            &SIZED_VERIFYING_KEY,
+        )
+        .verify()
+        .unwrap();
+        assert_eq!(
+            SizedVerifyingkey::<8>::try_new(VERIFYING_KEY),
+            Err(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs)
+        );
     }
 
     fn compress_g1_be(g1: &[u8; 64]) -> [u8; 32] {
@@ -431,6 +1031,37 @@ mod tests {
         verifier.verify_unchecked().unwrap();
     }
 
+    #[test]
+    fn compressed_proof_verification_should_succeed() {
+        let proof_a = Bn254::g1_negate(&PROOF[0..64].try_into().unwrap()).unwrap();
+        let proof_a = compress_g1_be(&proof_a);
+        let proof_b = compress_g2_be(&PROOF[64..192].try_into().unwrap());
+        let proof_c = compress_g1_be(&PROOF[192..].try_into().unwrap());
+        Groth16Verifier::verify_compressed(
+            &proof_a,
+            &proof_b,
+            &proof_c,
+            &PUBLIC_INPUTS,
+            &VERIFYING_KEY,
+        )
+        .unwrap();
+
+        let mut invalid_c = proof_c;
+        invalid_c[31] ^= 1;
+        let mut storage = DecompressedProof::default();
+        assert_eq!(
+            Groth16Verifier::new_compressed(
+                &mut storage,
+                &proof_a,
+                &proof_b,
+                &invalid_c,
+                &PUBLIC_INPUTS,
+                &VERIFYING_KEY,
+            ),
+            Err(Groth16Error::DecompressingG1Failed(1))
+        );
+    }
+
     #[test]
     fn wrong_proof_verification_should_not_succeed() {
         let proof_a = PROOF[0..64].try_into().unwrap();
@@ -454,6 +1085,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn gnark_convention_negates_proof_a() {
+        let proof_a: [u8; 64] = PROOF[0..64].try_into().unwrap();
+        let proof_b = PROOF[64..192].try_into().unwrap();
+        let proof_c = PROOF[192..256].try_into().unwrap();
+        let verifier =
+            Groth16Verifier::new(&proof_a, &proof_b, &proof_c, &PUBLIC_INPUTS, &VERIFYING_KEY)
+                .unwrap();
+        verifier
+            .with_convention(ProverConvention::RawA)
+            .verify()
+            .unwrap();
+        // snarkjs' proof.json with a as proven
+        Groth16Verifier::new(&proof_a, &proof_b, &proof_c, &PUBLIC_INPUTS, &VERIFYING_KEY)
+            .unwrap()
+            .with_proof_a_negation()
+            .verify()
+            .unwrap();
+
+        let negated_a = Bn254::g1_negate(&proof_a).unwrap();
+        assert_eq!(Bn254::g1_negate(&negated_a).unwrap(), proof_a);
+        let mut verifier = Groth16Verifier::new(
+            &negated_a,
+            &proof_b,
+            &proof_c,
+            &PUBLIC_INPUTS,
+            &VERIFYING_KEY,
+        )
+        .unwrap()
+        .with_convention(ProverConvention::RawA);
+        assert_eq!(
+            verifier.verify(),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+    }
+
     #[test]
     fn public_input_greater_than_field_size_should_not_suceed() {
         let proof_a = PROOF[0..64].try_into().unwrap();
@@ -478,4 +1145,18 @@ mod tests {
             Err(Groth16Error::PublicInputGreaterThanFieldSize)
         );
     }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_keys_roundtrip_packed_bytes() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let data: Vec<u8> = (0..2000u32).map(|i| (i * 31 % 251) as u8).collect();
+        let vk = Groth16Verifyingkey::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        assert_eq!(vk.nr_pubinputs + 1, vk.vk_ic.len());
+
+        let mut packed = vec![0u8; Groth16Verifyingkey::packed_size(vk.nr_pubinputs)];
+        vk.write_packed_bytes(&mut packed).unwrap();
+        assert_eq!(Groth16Verifyingkey::from_packed_bytes(&packed), Ok(vk));
+    }
 }
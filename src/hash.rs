@@ -0,0 +1,188 @@
+//! Keccak256, SHA-256 and Poseidon hashing to BN254 field elements
+//!
+//! On chain the `sol_keccak256`, `sol_sha256` and `sol_poseidon` syscalls are
+//! used, off chain the `sha3`, `sha2` and `light-poseidon` crates, so programs
+//! and clients derive identical field elements. Poseidon needs the `poseidon`
+//! feature.
+
+#[cfg(feature = "poseidon")]
+use crate::errors::Groth16Error;
+use crate::fr;
+
+/// Keccak256 of the concatenation of `vals`
+pub fn keccak256(vals: &[&[u8]]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+
+    #[cfg(target_os = "solana")]
+    unsafe {
+        crate::syscalls::sol_keccak256(
+            vals as *const _ as *const u8,
+            vals.len() as u64,
+            hash.as_mut_ptr(),
+        );
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    {
+        use sha3::{Digest, Keccak256};
+        let mut hasher = Keccak256::new();
+        for val in vals {
+            hasher.update(val);
+        }
+        hash.copy_from_slice(&hasher.finalize());
+    }
+
+    hash
+}
+
+/// SHA-256 of the concatenation of `vals`
+pub fn sha256(vals: &[&[u8]]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+
+    #[cfg(target_os = "solana")]
+    unsafe {
+        crate::syscalls::sol_sha256(
+            vals as *const _ as *const u8,
+            vals.len() as u64,
+            hash.as_mut_ptr(),
+        );
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    {
+        use sha2::{Digest, Sha256};
+        let mut hasher = Sha256::new();
+        for val in vals {
+            hasher.update(val);
+        }
+        hash.copy_from_slice(&hasher.finalize());
+    }
+
+    hash
+}
+
+/// Hashes `vals` to a big-endian BN254 field element
+///
+/// The first byte of the keccak256 hash is zeroed so the result is always
+/// smaller than the field modulus, leaving 248 bits of the hash.
+pub fn hashv_to_bn254_field_size_be(vals: &[&[u8]]) -> [u8; 32] {
+    let mut hash = keccak256(vals);
+    hash[0] = 0;
+    hash
+}
+
+/// Hashes `vals` to a big-endian BN254 scalar, keccak256 reduced modulo r
+///
+/// The reduction matches `uint256(keccak256(...)) % r` of Solidity verifiers,
+/// circuits that expose a hash of their inputs usually expect this form.
+pub fn hashv_to_bn254_scalar_be(vals: &[&[u8]]) -> [u8; 32] {
+    fr::reduce_be_bytes(&keccak256(vals))
+}
+
+/// Maximum number of inputs of [`poseidon`]
+#[cfg(feature = "poseidon")]
+pub const POSEIDON_MAX_INPUTS: usize = 12;
+
+/// Poseidon hash of `inputs` with circom's BN254 x^5 parameters, big-endian
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidPublicInputsLength)` - If there are no or more
+///   than [`POSEIDON_MAX_INPUTS`] inputs
+/// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If an input is
+///   not smaller than the field size
+#[cfg(feature = "poseidon")]
+pub fn poseidon(inputs: &[[u8; 32]]) -> Result<[u8; 32], Groth16Error> {
+    if inputs.is_empty() || inputs.len() > POSEIDON_MAX_INPUTS {
+        return Err(Groth16Error::InvalidPublicInputsLength);
+    }
+    if !inputs
+        .iter()
+        .all(crate::groth16::is_less_than_bn254_field_size_be)
+    {
+        return Err(Groth16Error::PublicInputGreaterThanFieldSize);
+    }
+
+    #[cfg(target_os = "solana")]
+    {
+        let vals: alloc::vec::Vec<&[u8]> = inputs.iter().map(|input| input.as_slice()).collect();
+        let mut hash = [0u8; 32];
+        // Bn254X5 parameters, big-endian
+        let result = unsafe {
+            crate::syscalls::sol_poseidon(
+                0,
+                0,
+                vals.as_ptr() as *const u8,
+                vals.len() as u64,
+                hash.as_mut_ptr(),
+            )
+        };
+        if result != 0 {
+            return Err(Groth16Error::PublicInputGreaterThanFieldSize);
+        }
+        Ok(hash)
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    {
+        use light_poseidon::{Poseidon, PoseidonBytesHasher};
+        let vals: alloc::vec::Vec<&[u8]> = inputs.iter().map(|input| input.as_slice()).collect();
+        Poseidon::<ark_bn254::Fr>::new_circom(inputs.len())
+            .and_then(|mut hasher| hasher.hash_bytes_be(&vals))
+            .map_err(|_| Groth16Error::PublicInputGreaterThanFieldSize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groth16::is_less_than_bn254_field_size_be;
+    use ark_ff::{BigInteger, PrimeField};
+
+    #[test]
+    fn keccak256_and_field_reduction() {
+        // keccak256("")
+        assert_eq!(
+            keccak256(&[]),
+            [
+                0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7,
+                0x03, 0xc0, 0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04,
+                0x5d, 0x85, 0xa4, 0x70
+            ]
+        );
+        assert_eq!(keccak256(&[b"ab", b"c"]), keccak256(&[b"abc"]));
+
+        let element = hashv_to_bn254_field_size_be(&[b"abc"]);
+        assert_eq!(element[0], 0);
+        assert_eq!(element[1..], keccak256(&[b"abc"])[1..]);
+        assert!(is_less_than_bn254_field_size_be(&element));
+
+        for vals in [&[&b"abc"[..]][..], &[b"inputs", &[0xff; 64]]] {
+            let scalar = hashv_to_bn254_scalar_be(vals);
+            let expected = ark_bn254::Fr::from_be_bytes_mod_order(&keccak256(vals));
+            assert_eq!(scalar.to_vec(), expected.into_bigint().to_bytes_be());
+        }
+    }
+
+    #[cfg(feature = "poseidon")]
+    #[test]
+    fn poseidon_matches_circomlib() {
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let mut two = [0u8; 32];
+        two[31] = 2;
+        // circomlibjs poseidon([1, 2])
+        assert_eq!(
+            poseidon(&[one, two]).unwrap(),
+            [
+                0x11, 0x5c, 0xc0, 0xf5, 0xe7, 0xd6, 0x90, 0x41, 0x3d, 0xf6, 0x4c, 0x6b, 0x96, 0x62,
+                0xe9, 0xcf, 0x2a, 0x36, 0x17, 0xf2, 0x74, 0x32, 0x45, 0x51, 0x9e, 0x19, 0x60, 0x7a,
+                0x44, 0x17, 0x18, 0x9a
+            ]
+        );
+        assert_eq!(poseidon(&[]), Err(Groth16Error::InvalidPublicInputsLength));
+        assert_eq!(
+            poseidon(&[[0xff; 32]]),
+            Err(Groth16Error::PublicInputGreaterThanFieldSize)
+        );
+    }
+}
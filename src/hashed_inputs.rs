@@ -0,0 +1,192 @@
+//! Verification of circuits exposing a hash of their inputs
+//!
+//! Preparing public inputs costs a G1 multiplication and addition per input.
+//! A circuit with many inputs instead takes them as private signals and
+//! exposes a single public input, their hash. The program hashes the raw
+//! inputs it holds and verifies against the hash, so verification costs the
+//! same for any number of inputs:
+//!
+//! ```text
+//! public_input = uint256(keccak256(input_0 | input_1 | ...)) % r
+//! ```
+//!
+//! With the `poseidon` feature, circuits hashing with circom's Poseidon are
+//! verified with [`verify_with_poseidon_inputs`], see [`poseidon_hash_inputs`].
+
+use crate::errors::Groth16Error;
+use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+use crate::hash::hashv_to_bn254_scalar_be;
+#[cfg(feature = "poseidon")]
+use crate::hash::{poseidon, POSEIDON_MAX_INPUTS};
+
+/// The single public input of a circuit hashing `inputs` with keccak256
+///
+/// The inputs are concatenated as they are, without length prefixes.
+pub fn keccak_hash_inputs(inputs: &[&[u8]]) -> [u8; 32] {
+    hashv_to_bn254_scalar_be(inputs)
+}
+
+/// Verifies a proof whose only public input is [`keccak_hash_inputs`] of `inputs`
+///
+/// # Arguments
+/// * `inputs` - Raw inputs, hashed in order
+/// * `verifyingkey` - Verifying key of a circuit with one public input
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidPublicInputsLength)` - If the key takes more
+///   than one public input
+pub fn verify_with_hashed_inputs(
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    inputs: &[&[u8]],
+    verifyingkey: &Groth16Verifyingkey,
+) -> Result<(), Groth16Error> {
+    let public_inputs = [keccak_hash_inputs(inputs)];
+    Groth16Verifier::new(proof_a, proof_b, proof_c, &public_inputs, verifyingkey)?.verify()
+}
+
+/// The single public input of a circuit hashing `inputs` with Poseidon
+///
+/// Up to [`POSEIDON_MAX_INPUTS`] inputs are hashed at once, `poseidon(inputs)`.
+/// Longer inputs are absorbed in chunks, each hashed together with the hash
+/// of the previous ones:
+///
+/// ```text
+/// h = poseidon(inputs[0..12])
+/// h = poseidon(h, inputs[12..23])
+/// ...
+/// ```
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidPublicInputsLength)` - If there are no inputs
+/// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If an input is
+///   not smaller than the field size
+#[cfg(feature = "poseidon")]
+pub fn poseidon_hash_inputs(inputs: &[[u8; 32]]) -> Result<[u8; 32], Groth16Error> {
+    let (first, rest) = inputs.split_at(inputs.len().min(POSEIDON_MAX_INPUTS));
+    let mut hash = poseidon(first)?;
+    let mut chunk = [[0u8; 32]; POSEIDON_MAX_INPUTS];
+    for inputs in rest.chunks(POSEIDON_MAX_INPUTS - 1) {
+        chunk[0] = hash;
+        chunk[1..=inputs.len()].copy_from_slice(inputs);
+        hash = poseidon(&chunk[..=inputs.len()])?;
+    }
+    Ok(hash)
+}
+
+/// Verifies a proof whose only public input is [`poseidon_hash_inputs`] of `inputs`
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidPublicInputsLength)` - If there are no inputs
+///   or the key takes more than one public input
+/// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If an input is
+///   not smaller than the field size
+#[cfg(feature = "poseidon")]
+pub fn verify_with_poseidon_inputs(
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    inputs: &[[u8; 32]],
+    verifyingkey: &Groth16Verifyingkey,
+) -> Result<(), Groth16Error> {
+    let public_inputs = [poseidon_hash_inputs(inputs)?];
+    Groth16Verifier::new(proof_a, proof_b, proof_c, &public_inputs, verifyingkey)?.verify()
+}
+
+#[cfg(all(test, feature = "test-vectors"))]
+mod tests {
+    use super::*;
+    use crate::test_vectors::generate_for_circuit;
+    use ark_bn254::Fr;
+    use ark_ff::PrimeField;
+    use ark_relations::lc;
+    use ark_relations::r1cs::{
+        ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable,
+    };
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+    /// Exposes its witness as the only public input
+    #[derive(Clone, Copy, Default)]
+    struct HashCircuit {
+        hash: Option<Fr>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for HashCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let hash = self.hash.ok_or(SynthesisError::AssignmentMissing);
+            let witness = cs.new_witness_variable(|| hash)?;
+            let input = cs.new_input_variable(|| hash)?;
+            cs.enforce_constraint(lc!() + witness, lc!() + Variable::One, lc!() + input)
+        }
+    }
+
+    #[test]
+    fn hashed_inputs_verify() {
+        let inputs: [&[u8]; 3] = [b"recipient", &[7u8; 32], &1000u64.to_le_bytes()];
+        let hash = Fr::from_be_bytes_mod_order(&keccak_hash_inputs(&inputs));
+        let vector = generate_for_circuit(
+            HashCircuit::default(),
+            HashCircuit { hash: Some(hash) },
+            &[hash],
+            &mut StdRng::seed_from_u64(0),
+        );
+        let vk = vector.verifying_key();
+        let verify = |inputs: &[&[u8]]| {
+            verify_with_hashed_inputs(
+                &vector.proof_a,
+                &vector.proof_b,
+                &vector.proof_c,
+                inputs,
+                &vk,
+            )
+        };
+        verify(&inputs).unwrap();
+        assert_eq!(
+            verify(&inputs[..2]),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+    }
+
+    #[cfg(feature = "poseidon")]
+    #[test]
+    fn poseidon_inputs_verify() {
+        let inputs: Vec<[u8; 32]> = (0..20u8)
+            .map(|i| {
+                let mut input = [0u8; 32];
+                input[31] = i;
+                input
+            })
+            .collect();
+        let hash = poseidon_hash_inputs(&inputs).unwrap();
+        let mut chained = [[0u8; 32]; 9];
+        chained[0] = poseidon(&inputs[..12]).unwrap();
+        chained[1..].copy_from_slice(&inputs[12..]);
+        assert_eq!(hash, poseidon(&chained).unwrap());
+        assert_eq!(poseidon_hash_inputs(&inputs[..3]), poseidon(&inputs[..3]));
+
+        let hash = Fr::from_be_bytes_mod_order(&hash);
+        let vector = generate_for_circuit(
+            HashCircuit::default(),
+            HashCircuit { hash: Some(hash) },
+            &[hash],
+            &mut StdRng::seed_from_u64(1),
+        );
+        let vk = vector.verifying_key();
+        let verify = |inputs: &[[u8; 32]]| {
+            verify_with_poseidon_inputs(
+                &vector.proof_a,
+                &vector.proof_b,
+                &vector.proof_c,
+                inputs,
+                &vk,
+            )
+        };
+        verify(&inputs).unwrap();
+        assert_eq!(
+            verify(&inputs[..19]),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+        assert_eq!(verify(&[]), Err(Groth16Error::InvalidPublicInputsLength));
+    }
+}
@@ -0,0 +1,212 @@
+//! Public inputs from typed values
+//!
+//! The verifier takes public inputs as big-endian scalars smaller than the
+//! BN254 scalar field modulus. [`PublicInputsBuilder`] encodes the values
+//! circuits usually expose, in the order they are pushed:
+//!
+//! ```rust,ignore
+//! let public_inputs = PublicInputsBuilder::new()
+//!     .push_u64(amount)
+//!     .push_pubkey(&recipient)
+//!     .push_decimal(&public_json[0])
+//!     .push_le_bytes(&circom_signal)
+//!     .build::<5>()?;
+//! ```
+//!
+//! Values that do not fit are rejected, never reduced: an input that wraps
+//! around the modulus would verify as a different value than the one the
+//! caller meant. The first error is kept and returned by
+//! [`PublicInputsBuilder::build`], so pushes chain without `?`.
+
+use crate::errors::Groth16Error;
+use crate::fr;
+use crate::proof::PublicInputs;
+use crate::Pubkey;
+use alloc::vec::Vec;
+
+/// Builds big-endian public inputs from typed values
+#[derive(Debug, Default, PartialEq)]
+pub struct PublicInputsBuilder {
+    inputs: Vec<[u8; 32]>,
+    error: Option<Groth16Error>,
+}
+
+impl PublicInputsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(mut self, input: Result<[u8; 32], Groth16Error>) -> Self {
+        match input {
+            Ok(input) if fr::is_canonical(&input) => self.inputs.push(input),
+            Ok(_) => self.fail(Groth16Error::PublicInputGreaterThanFieldSize),
+            Err(error) => self.fail(error),
+        }
+        self
+    }
+
+    fn fail(&mut self, error: Groth16Error) {
+        self.error.get_or_insert(error);
+    }
+
+    pub fn push_bool(self, value: bool) -> Self {
+        self.push_u64(value as u64)
+    }
+
+    pub fn push_u64(self, value: u64) -> Self {
+        self.push_u128(value as u128)
+    }
+
+    pub fn push_u128(self, value: u128) -> Self {
+        let mut input = [0u8; 32];
+        input[16..].copy_from_slice(&value.to_be_bytes());
+        self.push(Ok(input))
+    }
+
+    /// A big-endian field element, such as a hash the circuit recomputes
+    ///
+    /// Fails with `PublicInputGreaterThanFieldSize` if it is not smaller than
+    /// the modulus, see [`push_split_bytes`](Self::push_split_bytes) for
+    /// arbitrary 32-byte values.
+    pub fn push_be_bytes(self, value: &[u8; 32]) -> Self {
+        self.push(Ok(*value))
+    }
+
+    /// A little-endian field element, as circom's witness files and
+    /// `Fr::to_bytes` of arkworks store them
+    pub fn push_le_bytes(self, value: &[u8; 32]) -> Self {
+        let mut input = *value;
+        input.reverse();
+        self.push(Ok(input))
+    }
+
+    /// A non-negative decimal integer, as in snarkjs' `public.json`
+    ///
+    /// Fails with `InvalidPublicInput` if it is not a decimal integer.
+    pub fn push_decimal(self, value: &str) -> Self {
+        self.push(fr::from_decimal_str(value))
+    }
+
+    /// Any 32-byte value as two inputs, its high and its low 128 bits
+    ///
+    /// Public keys and SHA-256 or keccak256 hashes exceed the modulus for
+    /// most values, circuits take them as two halves instead.
+    pub fn push_split_bytes(self, value: &[u8; 32]) -> Self {
+        let high = u128::from_be_bytes(value[..16].try_into().unwrap());
+        let low = u128::from_be_bytes(value[16..].try_into().unwrap());
+        self.push_u128(high).push_u128(low)
+    }
+
+    /// A public key as two inputs, see [`push_split_bytes`](Self::push_split_bytes)
+    pub fn push_pubkey(self, pubkey: &Pubkey) -> Self {
+        self.push_split_bytes(pubkey)
+    }
+
+    /// Number of inputs pushed so far
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// The inputs pushed, for [`Groth16Verifier::new`](crate::groth16::Groth16Verifier::new)
+    ///
+    /// # Returns
+    /// * `Err(error)` - The first error of a push
+    pub fn into_vec(self) -> Result<Vec<[u8; 32]>, Groth16Error> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.inputs),
+        }
+    }
+
+    /// # Returns
+    /// * `Err(error)` - The first error of a push
+    /// * `Err(Groth16Error::InvalidPublicInputsLength)` - If not exactly
+    ///   `NR_INPUTS` inputs were pushed
+    pub fn build<const NR_INPUTS: usize>(self) -> Result<PublicInputs<NR_INPUTS>, Groth16Error> {
+        let inputs: [[u8; 32]; NR_INPUTS] = self
+            .into_vec()?
+            .try_into()
+            .map_err(|_| Groth16Error::InvalidPublicInputsLength)?;
+        PublicInputs::new(inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_typed_values() {
+        let mut le = [0u8; 32];
+        le[0] = 7;
+        let pubkey = [0xff; 32];
+        let inputs = PublicInputsBuilder::new()
+            .push_bool(true)
+            .push_u64(7)
+            .push_u128(u128::MAX)
+            .push_decimal("7")
+            .push_le_bytes(&le)
+            .push_pubkey(&pubkey)
+            .build::<7>()
+            .unwrap();
+        let mut seven = [0u8; 32];
+        seven[31] = 7;
+        let mut max = [0u8; 32];
+        max[16..].fill(0xff);
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert_eq!(
+            inputs.as_array(),
+            &[one, seven, max, seven, seven, max, max]
+        );
+
+        // r - 1 is the largest field element
+        let largest =
+            "21888242871839275222246405745257275088548364400416034343698204186575808495616";
+        let mut modulus_minus_one = fr::SCALAR_FIELD_MODULUS;
+        modulus_minus_one[31] -= 1;
+        assert_eq!(
+            PublicInputsBuilder::new().push_decimal(largest).into_vec(),
+            Ok(vec![modulus_minus_one])
+        );
+    }
+
+    #[test]
+    fn rejects_values_outside_the_field() {
+        let modulus =
+            "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+        for builder in [
+            PublicInputsBuilder::new().push_decimal(modulus),
+            PublicInputsBuilder::new().push_decimal(&"9".repeat(80)),
+            PublicInputsBuilder::new().push_be_bytes(&[0xff; 32]),
+            PublicInputsBuilder::new().push_le_bytes(&[0xff; 32]),
+        ] {
+            assert_eq!(
+                builder.into_vec(),
+                Err(Groth16Error::PublicInputGreaterThanFieldSize)
+            );
+        }
+        for invalid in ["", "-1", "+1", "0x10", "1 "] {
+            assert_eq!(
+                PublicInputsBuilder::new().push_decimal(invalid).into_vec(),
+                Err(Groth16Error::InvalidPublicInput)
+            );
+        }
+
+        // The first error is kept, later pushes do not clear it
+        let builder = PublicInputsBuilder::new()
+            .push_decimal("x")
+            .push_be_bytes(&[0xff; 32])
+            .push_u64(1);
+        assert_eq!(builder.len(), 1);
+        assert_eq!(builder.build::<1>(), Err(Groth16Error::InvalidPublicInput));
+        assert_eq!(
+            PublicInputsBuilder::new().push_u64(1).build::<2>(),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+    }
+}
@@ -0,0 +1,235 @@
+//! Instruction data of a verify instruction
+//!
+//! A canonical layout of a proof and its public inputs in instruction data,
+//! so clients and programs slice the same offsets:
+//!
+//! | offset | size           | field                                           |
+//! |--------|----------------|-------------------------------------------------|
+//! | 0      | 1              | version, [`VERIFY_IX_DATA_VERSION`]             |
+//! | 1      | 1              | flags, [`FLAG_COMPRESSED_PROOF`]                |
+//! | 2      | 2              | number of public inputs, u16 LE                 |
+//! | 4      | 256 or 128     | proof `a \| b \| c`, uncompressed or compressed |
+//! | ...    | 32 per input   | public inputs, big-endian                       |
+//!
+//! Points are in the syscall encoding with proof a negated, as [`Proof`]
+//! holds them, compressed points as [`crate::decompression`] takes them.
+//! Programs prepend their own discriminator and pass the rest to
+//! [`decode_verify_ix_data`], which borrows the proof and inputs without
+//! copying:
+//!
+//! ```rust,ignore
+//! let data = decode_verify_ix_data(&instruction_data[8..])?;
+//! data.verify::<2>(&VERIFYINGKEY)?;
+//! ```
+
+use crate::decompression::{DecompressedProof, COMPRESSED_PROOF_SIZE};
+use crate::errors::Groth16Error;
+use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+use crate::proof::{Proof, PROOF_SIZE};
+use alloc::vec::Vec;
+
+/// Version of the layout written by [`encode_verify_ix_data`]
+pub const VERIFY_IX_DATA_VERSION: u8 = 1;
+
+/// Flag set if the proof points are compressed
+pub const FLAG_COMPRESSED_PROOF: u8 = 1;
+
+/// Size of the header before the proof
+pub const VERIFY_IX_DATA_HEADER_SIZE: usize = 4;
+
+/// The proof of a verify instruction, borrowed from its data
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifyIxProof<'a> {
+    Uncompressed(&'a [u8; PROOF_SIZE]),
+    Compressed(&'a [u8; COMPRESSED_PROOF_SIZE]),
+}
+
+/// A decoded verify instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifyIxData<'a> {
+    pub proof: VerifyIxProof<'a>,
+    pub public_inputs: &'a [[u8; 32]],
+}
+
+impl VerifyIxData<'_> {
+    /// The uncompressed proof points, decompressed with the syscalls if needed
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::DecompressingG1Failed)` - If proof a or c does not decompress
+    /// * `Err(Groth16Error::DecompressingG2Failed)` - If proof b does not decompress
+    pub fn proof_points(&self) -> Result<DecompressedProof, Groth16Error> {
+        match self.proof {
+            VerifyIxProof::Uncompressed(bytes) => {
+                let proof = Proof::try_from(&bytes[..])?;
+                Ok(DecompressedProof {
+                    proof_a: proof.a.0,
+                    proof_b: proof.b.0,
+                    proof_c: proof.c.0,
+                })
+            }
+            VerifyIxProof::Compressed(bytes) => DecompressedProof::from_compressed_bytes(bytes),
+        }
+    }
+
+    /// Decompresses the proof if needed and verifies it
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidPublicInputsLength)` - If the instruction
+    ///   does not hold `NR_INPUTS` inputs
+    /// * The errors of [`Groth16Verifier::verify`] and of decompression
+    pub fn verify<const NR_INPUTS: usize>(
+        &self,
+        verifyingkey: &Groth16Verifyingkey,
+    ) -> Result<(), Groth16Error> {
+        let public_inputs: &[[u8; 32]; NR_INPUTS] = self
+            .public_inputs
+            .try_into()
+            .map_err(|_| Groth16Error::InvalidPublicInputsLength)?;
+        let proof = self.proof_points()?;
+        Groth16Verifier::new(
+            &proof.proof_a,
+            &proof.proof_b,
+            &proof.proof_c,
+            public_inputs,
+            verifyingkey,
+        )?
+        .verify()
+    }
+}
+
+fn encode(flags: u8, proof: &[u8], public_inputs: &[[u8; 32]]) -> Result<Vec<u8>, Groth16Error> {
+    let nr_inputs =
+        u16::try_from(public_inputs.len()).map_err(|_| Groth16Error::InvalidPublicInputsLength)?;
+    let mut data =
+        Vec::with_capacity(VERIFY_IX_DATA_HEADER_SIZE + proof.len() + 32 * public_inputs.len());
+    data.extend_from_slice(&[VERIFY_IX_DATA_VERSION, flags]);
+    data.extend_from_slice(&nr_inputs.to_le_bytes());
+    data.extend_from_slice(proof);
+    data.extend(public_inputs.iter().flatten());
+    Ok(data)
+}
+
+/// Encodes an uncompressed proof and its public inputs
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidPublicInputsLength)` - If there are more than
+///   `u16::MAX` inputs
+pub fn encode_verify_ix_data(
+    proof: &Proof,
+    public_inputs: &[[u8; 32]],
+) -> Result<Vec<u8>, Groth16Error> {
+    encode(0, &proof.to_bytes(), public_inputs)
+}
+
+/// Encodes a compressed proof, `a (32) | b (64) | c (32)`, and its public inputs
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidPublicInputsLength)` - If there are more than
+///   `u16::MAX` inputs
+pub fn encode_compressed_verify_ix_data(
+    proof: &[u8; COMPRESSED_PROOF_SIZE],
+    public_inputs: &[[u8; 32]],
+) -> Result<Vec<u8>, Groth16Error> {
+    encode(FLAG_COMPRESSED_PROOF, proof, public_inputs)
+}
+
+/// Decodes the data written by [`encode_verify_ix_data`] or
+/// [`encode_compressed_verify_ix_data`]
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidInstructionData)` - If the version or flags
+///   are unknown or the length does not match the header
+pub fn decode_verify_ix_data(data: &[u8]) -> Result<VerifyIxData<'_>, Groth16Error> {
+    let (header, rest) = data
+        .split_first_chunk::<VERIFY_IX_DATA_HEADER_SIZE>()
+        .ok_or(Groth16Error::InvalidInstructionData)?;
+    let [version, flags, nr_inputs @ ..] = *header;
+    if version != VERIFY_IX_DATA_VERSION || flags & !FLAG_COMPRESSED_PROOF != 0 {
+        return Err(Groth16Error::InvalidInstructionData);
+    }
+    let nr_inputs = u16::from_le_bytes(nr_inputs) as usize;
+    let (proof, inputs) = if flags & FLAG_COMPRESSED_PROOF != 0 {
+        let (proof, inputs) = rest
+            .split_first_chunk::<COMPRESSED_PROOF_SIZE>()
+            .ok_or(Groth16Error::InvalidInstructionData)?;
+        (VerifyIxProof::Compressed(proof), inputs)
+    } else {
+        let (proof, inputs) = rest
+            .split_first_chunk::<PROOF_SIZE>()
+            .ok_or(Groth16Error::InvalidInstructionData)?;
+        (VerifyIxProof::Uncompressed(proof), inputs)
+    };
+    let (public_inputs, remainder) = inputs.as_chunks::<32>();
+    if public_inputs.len() != nr_inputs || !remainder.is_empty() {
+        return Err(Groth16Error::InvalidInstructionData);
+    }
+    Ok(VerifyIxData {
+        proof,
+        public_inputs,
+    })
+}
+
+#[cfg(all(test, feature = "test-vectors"))]
+mod tests {
+    use super::*;
+    use crate::bn254::{alt_bn128_g1_compress, alt_bn128_g2_compress};
+    use crate::proof::{ProofA, ProofB, ProofC};
+    use crate::test_vectors::generate;
+
+    #[test]
+    fn roundtrips_and_verifies() {
+        let vector = generate(0);
+        let vk = vector.verifying_key();
+        let proof = Proof {
+            a: ProofA(vector.proof_a),
+            b: ProofB(vector.proof_b),
+            c: ProofC(vector.proof_c),
+        };
+        let data = encode_verify_ix_data(&proof, &vector.public_inputs).unwrap();
+        assert_eq!(data.len(), 4 + 256 + 2 * 32);
+        let decoded = decode_verify_ix_data(&data).unwrap();
+        assert_eq!(
+            decoded.proof,
+            VerifyIxProof::Uncompressed(&proof.to_bytes())
+        );
+        assert_eq!(decoded.public_inputs, vector.public_inputs);
+        decoded.verify::<2>(&vk).unwrap();
+        assert_eq!(
+            decoded.verify::<1>(&vk),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+
+        let compressed: [u8; COMPRESSED_PROOF_SIZE] = [
+            &alt_bn128_g1_compress(&vector.proof_a).unwrap()[..],
+            &alt_bn128_g2_compress(&vector.proof_b).unwrap(),
+            &alt_bn128_g1_compress(&vector.proof_c).unwrap(),
+        ]
+        .concat()
+        .try_into()
+        .unwrap();
+        let data = encode_compressed_verify_ix_data(&compressed, &vector.public_inputs).unwrap();
+        let decoded = decode_verify_ix_data(&data).unwrap();
+        assert_eq!(decoded.proof, VerifyIxProof::Compressed(&compressed));
+        decoded.verify::<2>(&vk).unwrap();
+    }
+
+    #[test]
+    fn rejects_malformed_data() {
+        let proof = Proof::try_from(&[0u8; PROOF_SIZE][..]).unwrap();
+        let data = encode_verify_ix_data(&proof, &[[1u8; 32]]).unwrap();
+        decode_verify_ix_data(&data).unwrap();
+        for malformed in [
+            &data[..3],
+            &data[..data.len() - 1],
+            &[&data[..], &[0]].concat(),
+            &[&[2][..], &data[1..]].concat(),
+            &[&[1, 2][..], &data[2..]].concat(),
+            &[&[1, 0, 2, 0][..], &data[4..]].concat(),
+        ] {
+            assert_eq!(
+                decode_verify_ix_data(malformed),
+                Err(Groth16Error::InvalidInstructionData)
+            );
+        }
+    }
+}
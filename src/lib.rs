@@ -45,7 +45,7 @@
 //!
 //! See functional test for a running example how to use this library.
 
-#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
 #[macro_use]
 extern crate alloc;
@@ -53,13 +53,117 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+/// Logs `label` and the remaining compute units with the `cu-log` feature
+macro_rules! cu_log {
+    ($label:expr) => {
+        #[cfg(feature = "cu-log")]
+        crate::cu_log::log($label);
+    };
+}
+
+/// A 32 byte account address, the same type as Pinocchio's `Pubkey`
+pub type Pubkey = [u8; 32];
+
+#[cfg(feature = "pinocchio")]
+pub mod account_compression;
+pub mod account_inputs;
+pub mod batch;
 pub mod bn254;
+pub mod commitment;
+#[cfg(feature = "compat")]
+pub mod compat;
+pub mod compressed_ic;
+pub mod compute_units;
+#[cfg(feature = "cu-log")]
+pub mod cu_log;
+pub mod curve;
 pub mod decompression;
+pub mod domain;
 pub mod errors;
+pub mod fr;
 pub mod groth16;
+pub mod hash;
+pub mod hashed_inputs;
+pub mod inputs_builder;
+pub mod ix_data;
+pub mod nullifier;
+pub mod proof;
+pub mod proof_account;
+pub mod receipt;
+pub mod recursion;
+pub mod resumable;
+pub mod settlement;
+pub mod syscall_features;
+#[cfg(target_os = "solana")]
+mod syscalls;
+pub mod transcript;
+pub mod validation;
+pub mod verifier_registry;
+pub mod vk_registry;
+pub mod vk_upgrade;
 
 #[cfg(all(feature = "vk", feature = "std"))]
 pub mod vk_parser;
 
 #[cfg(feature = "circom")]
 pub mod proof_parser;
+
+#[cfg(feature = "circom")]
+pub mod arkworks;
+
+#[cfg(feature = "prover")]
+pub mod prover;
+
+#[cfg(feature = "trace")]
+pub mod trace;
+
+#[cfg(feature = "bls12-381")]
+pub mod bls12_381;
+
+#[cfg(feature = "bls12-381")]
+pub mod bellman;
+
+#[cfg(feature = "plonk")]
+pub mod plonk;
+
+#[cfg(feature = "noir")]
+pub mod noir;
+
+#[cfg(feature = "paranoid")]
+pub mod paranoid;
+
+#[cfg(feature = "diagnostics")]
+pub mod diagnostics;
+
+#[cfg(feature = "strict")]
+pub mod strict;
+
+#[cfg(feature = "client")]
+pub mod client;
+
+#[cfg(feature = "litesvm")]
+pub mod svm;
+
+#[cfg(all(not(target_os = "solana"), any(test, feature = "mock-syscalls")))]
+pub mod mock;
+
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+
+#[cfg(feature = "test-vectors")]
+pub mod differential;
+
+#[cfg(feature = "test-vectors")]
+pub mod conformance;
+
+#[cfg(feature = "test-fixtures")]
+pub mod test_fixtures;
+
+#[cfg(feature = "test-util")]
+pub mod test_util;
+
+#[cfg(feature = "test-fixtures")]
+pub mod corruption;
+
+#[cfg(feature = "proptest")]
+pub mod strategies;
@@ -0,0 +1,761 @@
+//! Pure-Rust implementations of the alt_bn128 syscalls
+//!
+//! Enabled with the `mock-syscalls` feature (and always in this crate's unit
+//! tests) on non-Solana targets. The functions mirror the semantics of the
+//! runtime's `sol_alt_bn128_group_op` and `sol_alt_bn128_compression`
+//! syscalls on top of arkworks, so the complete verifier runs under a plain
+//! `cargo test`:
+//!
+//! * inputs and outputs are big-endian, G2 coordinates are ordered `c1 | c0`
+//! * the all-zero encoding is the point at infinity
+//! * inputs shorter than the operation size are zero padded
+//! * the return value is 0 on success and 1 on error, as for the syscalls
+//!
+//! Error handling paths can be exercised with [`inject_fault`], which makes
+//! the n-th following syscall of the current thread fail or return a
+//! corrupted result.
+//!
+//! Every syscall charges the compute units of the runtime's fee schedule, see
+//! [`compute_units`](crate::compute_units), to a per-thread meter. With a
+//! limit set by [`set_compute_unit_limit`], a syscall exceeding it panics like
+//! the program would abort on chain, so unit tests catch compute budget
+//! regressions:
+//!
+//! ```rust,ignore
+//! mock::set_compute_unit_limit(Some(200_000));
+//! verifier.verify()?;
+//! assert!(mock::compute_units_consumed() < 150_000);
+//! ```
+//!
+//! Only syscalls are metered. Tests of programs doing significant work around
+//! them charge it with [`consume_compute_units`].
+
+use crate::bn254::{
+    convert_endianness, ALT_BN128_G1_ADD, ALT_BN128_G1_COMPRESS, ALT_BN128_G1_DECOMPRESS,
+    ALT_BN128_G1_MUL, ALT_BN128_G1_SUB, ALT_BN128_G2_ADD, ALT_BN128_G2_COMPRESS,
+    ALT_BN128_G2_DECOMPRESS, ALT_BN128_G2_MUL, ALT_BN128_G2_SUB, ALT_BN128_PAIRING,
+};
+use crate::compute_units::{
+    pairing_cost, ALT_BN128_ADDITION_COST, ALT_BN128_G1_COMPRESS_COST,
+    ALT_BN128_G1_DECOMPRESS_COST, ALT_BN128_G2_COMPRESS_COST, ALT_BN128_G2_DECOMPRESS_COST,
+    ALT_BN128_MULTIPLICATION_COST,
+};
+use ark_bn254::{Bn254, Fq, Fq12, Fq2, G1Affine, G2Affine};
+use ark_ec::pairing::Pairing;
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger256, One};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Valid, Validate};
+
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+
+const SUCCESS: u64 = 0;
+const ERROR: u64 = 1;
+
+/// Fault injected into a mocked syscall
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// The syscall returns this code and leaves the output untouched
+    ReturnCode(u64),
+    /// The syscall succeeds but the last byte of its output is flipped
+    CorruptOutput,
+}
+
+#[derive(Default)]
+struct FaultState {
+    calls: usize,
+    faults: Vec<(usize, Fault)>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Meter {
+    consumed: u64,
+    limit: Option<u64>,
+}
+
+std::thread_local! {
+    static FAULTS: RefCell<FaultState> = RefCell::new(FaultState::default());
+    static METER: Cell<Meter> = const {
+        Cell::new(Meter {
+            consumed: 0,
+            limit: None,
+        })
+    };
+}
+
+/// Injects `fault` into the syscall `n` calls from now on the current thread
+///
+/// # Arguments
+/// * `n` - 0 for the next syscall, 1 for the one after, ...
+/// * `fault` - How the syscall fails
+pub fn inject_fault(n: usize, fault: Fault) {
+    FAULTS.with(|state| {
+        let mut state = state.borrow_mut();
+        let call = state.calls + n;
+        state.faults.push((call, fault));
+    });
+}
+
+/// Removes all pending faults of the current thread and resets the call count
+pub fn clear_faults() {
+    FAULTS.with(|state| *state.borrow_mut() = FaultState::default());
+}
+
+/// Number of mocked syscalls made on the current thread since the last [`clear_faults`]
+pub fn syscall_count() -> usize {
+    FAULTS.with(|state| state.borrow().calls)
+}
+
+/// Sets the compute unit limit of the current thread, `None` to disable it
+///
+/// Also resets the consumed compute units.
+pub fn set_compute_unit_limit(limit: Option<u64>) {
+    METER.with(|meter| meter.set(Meter { consumed: 0, limit }));
+}
+
+/// Compute units charged on the current thread since the last [`set_compute_unit_limit`]
+pub fn compute_units_consumed() -> u64 {
+    METER.with(|meter| meter.get().consumed)
+}
+
+/// Charges `units` to the meter of the current thread
+///
+/// # Panics
+/// If the limit set by [`set_compute_unit_limit`] is exceeded
+pub fn consume_compute_units(units: u64) {
+    let Meter { consumed, limit } = METER.with(|meter| {
+        let mut state = meter.get();
+        state.consumed = state.consumed.saturating_add(units);
+        meter.set(state);
+        state
+    });
+    if let Some(limit) = limit.filter(|limit| consumed > *limit) {
+        panic!("exceeded compute unit limit: consumed {consumed} of {limit} compute units");
+    }
+}
+
+fn next_fault() -> Option<Fault> {
+    FAULTS.with(|state| {
+        let mut state = state.borrow_mut();
+        let call = state.calls;
+        state.calls += 1;
+        let index = state.faults.iter().position(|(at, _)| *at == call)?;
+        Some(state.faults.remove(index).1)
+    })
+}
+
+/// Mock of `sol_alt_bn128_group_op`
+///
+/// # Arguments
+/// * `op` - 0 / 1 for G1 addition / subtraction, 2 for G1 multiplication,
+///   3 for the pairing, 4 / 5 / 6 for G2 addition / subtraction / multiplication
+/// * `input` - Big-endian operation input
+/// * `result` - Output buffer, 64 bytes for G1, 128 bytes for G2, 32 bytes for pairing
+///
+/// The G2 operations consume no compute units, their cost is not final yet.
+pub fn sol_alt_bn128_group_op(op: u64, input: &[u8], result: &mut [u8]) -> u64 {
+    consume_compute_units(match op {
+        ALT_BN128_G1_ADD | ALT_BN128_G1_SUB => ALT_BN128_ADDITION_COST,
+        ALT_BN128_G1_MUL => ALT_BN128_MULTIPLICATION_COST,
+        ALT_BN128_PAIRING => pairing_cost(input.len() / 192),
+        _ => 0,
+    });
+    let output = match op {
+        ALT_BN128_G1_ADD => g1_add(input).map(|point| point.to_vec()),
+        ALT_BN128_G1_SUB => g1_sub(input).map(|point| point.to_vec()),
+        ALT_BN128_G1_MUL => g1_mul(input).map(|point| point.to_vec()),
+        ALT_BN128_PAIRING => pairing(input).map(|result| result.to_vec()),
+        ALT_BN128_G2_ADD => g2_add(input).map(|point| point.to_vec()),
+        ALT_BN128_G2_SUB => g2_sub(input).map(|point| point.to_vec()),
+        ALT_BN128_G2_MUL => g2_mul(input).map(|point| point.to_vec()),
+        _ => None,
+    };
+    write_output(output, result)
+}
+
+/// Mock of `sol_alt_bn128_compression`
+///
+/// # Arguments
+/// * `op` - 0 / 1 to compress / decompress G1, 2 / 3 to compress / decompress G2
+/// * `input` - Big-endian point
+/// * `result` - Output buffer of the size of the resulting encoding
+pub fn sol_alt_bn128_compression(op: u64, input: &[u8], result: &mut [u8]) -> u64 {
+    consume_compute_units(match op {
+        ALT_BN128_G1_COMPRESS => ALT_BN128_G1_COMPRESS_COST,
+        ALT_BN128_G1_DECOMPRESS => ALT_BN128_G1_DECOMPRESS_COST,
+        ALT_BN128_G2_COMPRESS => ALT_BN128_G2_COMPRESS_COST,
+        ALT_BN128_G2_DECOMPRESS => ALT_BN128_G2_DECOMPRESS_COST,
+        _ => 0,
+    });
+    let output = match op {
+        ALT_BN128_G1_COMPRESS => input
+            .try_into()
+            .ok()
+            .and_then(g1_compress)
+            .map(|point| point.to_vec()),
+        ALT_BN128_G1_DECOMPRESS => input
+            .try_into()
+            .ok()
+            .and_then(g1_decompress)
+            .map(|point| point.to_vec()),
+        ALT_BN128_G2_COMPRESS => input
+            .try_into()
+            .ok()
+            .and_then(g2_compress)
+            .map(|point| point.to_vec()),
+        ALT_BN128_G2_DECOMPRESS => input
+            .try_into()
+            .ok()
+            .and_then(g2_decompress)
+            .map(|point| point.to_vec()),
+        _ => None,
+    };
+    write_output(output, result)
+}
+
+/// Mock of `sol_curve_group_op` for the BLS12-381 G1 addition and multiplication
+///
+/// # Arguments
+/// * `curve_id` - `BLS12_381_G1_BE`, other curves fail
+/// * `op` - 0 for addition, 2 for multiplication
+/// * `left` - First point, or the 32-byte big-endian scalar of a multiplication
+/// * `right` - Second point, or the point of a multiplication
+/// * `result` - 96-byte output buffer
+#[cfg(feature = "bls12-381")]
+pub fn sol_curve_group_op(
+    curve_id: u64,
+    op: u64,
+    left: &[u8],
+    right: &[u8],
+    result: &mut [u8],
+) -> u64 {
+    use crate::bls12_381::{BLS12_381_G1_BE, GROUP_OP_ADD, GROUP_OP_MUL};
+    use crate::compute_units::{BLS12_381_G1_ADD_COST, BLS12_381_G1_MUL_COST};
+
+    consume_compute_units(match (curve_id, op) {
+        (BLS12_381_G1_BE, GROUP_OP_ADD) => BLS12_381_G1_ADD_COST,
+        (BLS12_381_G1_BE, GROUP_OP_MUL) => BLS12_381_G1_MUL_COST,
+        _ => 0,
+    });
+    let output = match (curve_id, op) {
+        (BLS12_381_G1_BE, GROUP_OP_ADD) => bls12_381::g1_add(left, right),
+        (BLS12_381_G1_BE, GROUP_OP_MUL) => bls12_381::g1_mul(left, right),
+        _ => None,
+    };
+    write_output(output.map(|point| point.to_vec()), result)
+}
+
+/// Mock of the SIMD-0388 `sol_curve_pairing_map` for BLS12-381
+///
+/// # Arguments
+/// * `curve_id` - `BLS12_381_BE`, other curves fail
+/// * `num_pairs` - Number of pairs, at most 8
+/// * `g1_points` - `num_pairs` concatenated G1 points
+/// * `g2_points` - `num_pairs` concatenated G2 points
+/// * `result` - 576-byte output buffer for the big-endian target group element
+#[cfg(feature = "bls12-381")]
+pub fn sol_curve_pairing_map(
+    curve_id: u64,
+    num_pairs: u64,
+    g1_points: &[u8],
+    g2_points: &[u8],
+    result: &mut [u8],
+) -> u64 {
+    use crate::bls12_381::BLS12_381_BE;
+    use crate::compute_units::bls12_381_pairing_cost;
+
+    if curve_id != BLS12_381_BE {
+        return write_output(None, result);
+    }
+    consume_compute_units(bls12_381_pairing_cost(num_pairs as usize));
+    let output = bls12_381::pairing(num_pairs as usize, g1_points, g2_points);
+    write_output(output, result)
+}
+
+#[cfg(feature = "bls12-381")]
+mod bls12_381 {
+    use crate::bls12_381::{G1_SIZE, G2_SIZE, SCALAR_SIZE};
+    use alloc::vec::Vec;
+    use ark_bls12_381::{Bls12_381, Fr, G1Affine, G2Affine};
+    use ark_ec::pairing::Pairing;
+    use ark_ec::AffineRepr;
+    use ark_ff::{BigInteger256, PrimeField};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+
+    const MAX_PAIRS: usize = 8;
+
+    /// Parses a big-endian Zcash encoded point, checking the subgroup only if `subgroup`
+    fn g1_from_be(bytes: &[u8], subgroup: bool) -> Option<G1Affine> {
+        let bytes: &[u8; G1_SIZE] = bytes.try_into().ok()?;
+        let point = G1Affine::deserialize_with_mode(&bytes[..], Compress::No, Validate::No).ok()?;
+        (point.is_on_curve() && (!subgroup || point.is_in_correct_subgroup_assuming_on_curve()))
+            .then_some(point)
+    }
+
+    fn g2_from_be(bytes: &[u8]) -> Option<G2Affine> {
+        let bytes: &[u8; G2_SIZE] = bytes.try_into().ok()?;
+        let point = G2Affine::deserialize_with_mode(&bytes[..], Compress::No, Validate::No).ok()?;
+        (point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()).then_some(point)
+    }
+
+    fn g1_to_be(point: G1Affine) -> Option<[u8; G1_SIZE]> {
+        let mut bytes = [0u8; G1_SIZE];
+        point.serialize_uncompressed(&mut bytes[..]).ok()?;
+        Some(bytes)
+    }
+
+    pub(super) fn g1_add(left: &[u8], right: &[u8]) -> Option<[u8; G1_SIZE]> {
+        let p = g1_from_be(left, false)?;
+        let q = g1_from_be(right, false)?;
+        g1_to_be((p + q).into())
+    }
+
+    pub(super) fn g1_mul(scalar: &[u8], point: &[u8]) -> Option<[u8; G1_SIZE]> {
+        let mut scalar_le: [u8; SCALAR_SIZE] = scalar.try_into().ok()?;
+        scalar_le.reverse();
+        let scalar = BigInteger256::deserialize_uncompressed_unchecked(&scalar_le[..]).ok()?;
+        Fr::from_bigint(scalar)?;
+        g1_to_be(g1_from_be(point, true)?.mul_bigint(scalar).into())
+    }
+
+    pub(super) fn pairing(num_pairs: usize, g1_points: &[u8], g2_points: &[u8]) -> Option<Vec<u8>> {
+        if num_pairs > MAX_PAIRS
+            || g1_points.len() != num_pairs * G1_SIZE
+            || g2_points.len() != num_pairs * G2_SIZE
+        {
+            return None;
+        }
+        let g1 = g1_points
+            .chunks(G1_SIZE)
+            .map(|point| g1_from_be(point, true))
+            .collect::<Option<Vec<_>>>()?;
+        let g2 = g2_points
+            .chunks(G2_SIZE)
+            .map(g2_from_be)
+            .collect::<Option<Vec<_>>>()?;
+        let mut result = Vec::new();
+        Bls12_381::multi_pairing(g1, g2)
+            .0
+            .serialize_uncompressed(&mut result)
+            .ok()?;
+        result.reverse();
+        Some(result)
+    }
+}
+
+fn write_output(output: Option<Vec<u8>>, result: &mut [u8]) -> u64 {
+    let fault = next_fault();
+    if let Some(Fault::ReturnCode(code)) = fault {
+        return code;
+    }
+    match output {
+        Some(output) if output.len() == result.len() => {
+            result.copy_from_slice(&output);
+            if let (Some(Fault::CorruptOutput), Some(last)) = (fault, result.last_mut()) {
+                *last ^= 1;
+            }
+            SUCCESS
+        }
+        _ => ERROR,
+    }
+}
+
+fn padded<const N: usize>(input: &[u8]) -> Option<[u8; N]> {
+    if input.len() > N {
+        return None;
+    }
+    let mut padded = [0u8; N];
+    padded[..input.len()].copy_from_slice(input);
+    Some(padded)
+}
+
+pub(crate) fn g1_from_be(bytes: &[u8; 64]) -> Option<G1Affine> {
+    if *bytes == [0u8; 64] {
+        return Some(G1Affine::zero());
+    }
+    let le = convert_endianness::<32, 64>(bytes);
+    let x = Fq::deserialize_uncompressed(&le[..32]).ok()?;
+    let y = Fq::deserialize_uncompressed(&le[32..]).ok()?;
+    let point = G1Affine::new_unchecked(x, y);
+    point.check().ok()?;
+    Some(point)
+}
+
+fn g1_to_be(point: G1Affine) -> Option<[u8; 64]> {
+    let Some((x, y)) = point.xy() else {
+        return Some([0u8; 64]);
+    };
+    let mut le = [0u8; 64];
+    x.serialize_uncompressed(&mut le[..32]).ok()?;
+    y.serialize_uncompressed(&mut le[32..]).ok()?;
+    Some(convert_endianness::<32, 64>(&le))
+}
+
+pub(crate) fn g2_from_be(bytes: &[u8; 128]) -> Option<G2Affine> {
+    if *bytes == [0u8; 128] {
+        return Some(G2Affine::zero());
+    }
+    let le = convert_endianness::<64, 128>(bytes);
+    let x = Fq2::deserialize_uncompressed(&le[..64]).ok()?;
+    let y = Fq2::deserialize_uncompressed(&le[64..]).ok()?;
+    let point = G2Affine::new_unchecked(x, y);
+    point.check().ok()?;
+    Some(point)
+}
+
+fn g2_to_be(point: G2Affine) -> Option<[u8; 128]> {
+    let Some((x, y)) = point.xy() else {
+        return Some([0u8; 128]);
+    };
+    let mut le = [0u8; 128];
+    x.serialize_uncompressed(&mut le[..64]).ok()?;
+    y.serialize_uncompressed(&mut le[64..]).ok()?;
+    Some(convert_endianness::<64, 128>(&le))
+}
+
+fn g1_add(input: &[u8]) -> Option<[u8; 64]> {
+    let input = padded::<128>(input)?;
+    let p = g1_from_be(input[..64].try_into().unwrap())?;
+    let q = g1_from_be(input[64..].try_into().unwrap())?;
+    g1_to_be((p + q).into())
+}
+
+fn g1_mul(input: &[u8]) -> Option<[u8; 64]> {
+    let input = padded::<96>(input)?;
+    let p = g1_from_be(input[..64].try_into().unwrap())?;
+    let mut scalar_le: [u8; 32] = input[64..].try_into().unwrap();
+    scalar_le.reverse();
+    let scalar = BigInteger256::deserialize_uncompressed_unchecked(&scalar_le[..]).ok()?;
+    g1_to_be(p.mul_bigint(scalar).into())
+}
+
+fn g1_sub(input: &[u8]) -> Option<[u8; 64]> {
+    let input = padded::<128>(input)?;
+    let p = g1_from_be(input[..64].try_into().unwrap())?;
+    let q = g1_from_be(input[64..].try_into().unwrap())?;
+    g1_to_be((p - q).into())
+}
+
+fn g2_add(input: &[u8]) -> Option<[u8; 128]> {
+    let input = padded::<256>(input)?;
+    let p = g2_from_be(input[..128].try_into().unwrap())?;
+    let q = g2_from_be(input[128..].try_into().unwrap())?;
+    g2_to_be((p + q).into())
+}
+
+fn g2_sub(input: &[u8]) -> Option<[u8; 128]> {
+    let input = padded::<256>(input)?;
+    let p = g2_from_be(input[..128].try_into().unwrap())?;
+    let q = g2_from_be(input[128..].try_into().unwrap())?;
+    g2_to_be((p - q).into())
+}
+
+fn g2_mul(input: &[u8]) -> Option<[u8; 128]> {
+    let input = padded::<160>(input)?;
+    let p = g2_from_be(input[..128].try_into().unwrap())?;
+    let mut scalar_le: [u8; 32] = input[128..].try_into().unwrap();
+    scalar_le.reverse();
+    let scalar = BigInteger256::deserialize_uncompressed_unchecked(&scalar_le[..]).ok()?;
+    g2_to_be(p.mul_bigint(scalar).into())
+}
+
+fn pairing(input: &[u8]) -> Option<[u8; 32]> {
+    if !input.len().is_multiple_of(192) {
+        return None;
+    }
+    let mut g1 = Vec::with_capacity(input.len() / 192);
+    let mut g2 = Vec::with_capacity(input.len() / 192);
+    for element in input.chunks(192) {
+        g1.push(g1_from_be(element[..64].try_into().unwrap())?);
+        g2.push(g2_from_be(element[64..].try_into().unwrap())?);
+    }
+    let mut result = [0u8; 32];
+    if Bn254::multi_pairing(g1, g2).0 == Fq12::one() {
+        result[31] = 1;
+    }
+    Some(result)
+}
+
+fn g1_compress(bytes: &[u8; 64]) -> Option<[u8; 32]> {
+    if *bytes == [0u8; 64] {
+        return Some([0u8; 32]);
+    }
+    let point = g1_from_be(bytes)?;
+    let mut le = [0u8; 32];
+    point.serialize_with_mode(&mut le[..], Compress::Yes).ok()?;
+    le.reverse();
+    Some(le)
+}
+
+fn g1_decompress(bytes: &[u8; 32]) -> Option<[u8; 64]> {
+    if *bytes == [0u8; 32] {
+        return Some([0u8; 64]);
+    }
+    let mut le = *bytes;
+    le.reverse();
+    let point = G1Affine::deserialize_with_mode(&le[..], Compress::Yes, Validate::No).ok()?;
+    g1_to_be(point)
+}
+
+fn g2_compress(bytes: &[u8; 128]) -> Option<[u8; 64]> {
+    if *bytes == [0u8; 128] {
+        return Some([0u8; 64]);
+    }
+    let point = g2_from_be(bytes)?;
+    let mut le = [0u8; 64];
+    point.serialize_with_mode(&mut le[..], Compress::Yes).ok()?;
+    le.reverse();
+    Some(le)
+}
+
+fn g2_decompress(bytes: &[u8; 64]) -> Option<[u8; 128]> {
+    if *bytes == [0u8; 64] {
+        return Some([0u8; 128]);
+    }
+    let mut le = *bytes;
+    le.reverse();
+    let point = G2Affine::deserialize_with_mode(&le[..], Compress::Yes, Validate::No).ok()?;
+    g2_to_be(point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::CurveGroup;
+
+    fn generator_be() -> [u8; 64] {
+        g1_to_be(G1Affine::generator()).unwrap()
+    }
+
+    #[test]
+    fn group_ops_match_arkworks() {
+        let g = generator_be();
+        let mut doubled = [0u8; 64];
+        assert_eq!(
+            sol_alt_bn128_group_op(ALT_BN128_G1_ADD, &[g, g].concat(), &mut doubled),
+            SUCCESS
+        );
+        let mut scalar = [0u8; 32];
+        scalar[31] = 2;
+        let mut multiplied = [0u8; 64];
+        assert_eq!(
+            sol_alt_bn128_group_op(
+                ALT_BN128_G1_MUL,
+                &[&g[..], &scalar].concat(),
+                &mut multiplied
+            ),
+            SUCCESS
+        );
+        assert_eq!(doubled, multiplied);
+        assert_eq!(
+            doubled,
+            g1_to_be((G1Affine::generator() + G1Affine::generator()).into_affine()).unwrap()
+        );
+
+        // e(g, h) * e(-g, h) == 1
+        let h = g2_to_be(G2Affine::generator()).unwrap();
+        let neg_g = g1_to_be(-G1Affine::generator()).unwrap();
+        let mut result = [0u8; 32];
+        assert_eq!(
+            sol_alt_bn128_group_op(
+                ALT_BN128_PAIRING,
+                &[&g[..], &h, &neg_g, &h].concat(),
+                &mut result
+            ),
+            SUCCESS
+        );
+        assert_eq!(result[31], 1);
+        assert_eq!(
+            sol_alt_bn128_group_op(ALT_BN128_PAIRING, &[&g[..], &h].concat(), &mut result),
+            SUCCESS
+        );
+        assert_eq!(result[31], 0);
+
+        // Not on the curve
+        let mut invalid = g;
+        invalid[63] ^= 1;
+        assert_eq!(
+            sol_alt_bn128_group_op(ALT_BN128_G1_ADD, &[invalid, g].concat(), &mut doubled),
+            ERROR
+        );
+    }
+
+    #[test]
+    fn subtraction_and_g2_ops_match_arkworks() {
+        use crate::bn254::{
+            alt_bn128_addition, alt_bn128_g1_subtraction, alt_bn128_g2_addition,
+            alt_bn128_g2_multiplication, alt_bn128_g2_subtraction,
+        };
+
+        // g - (-g) == g + g
+        let g = generator_be();
+        let neg_g = g1_to_be(-G1Affine::generator()).unwrap();
+        assert_eq!(
+            alt_bn128_g1_subtraction(&[g, neg_g].concat()).unwrap(),
+            alt_bn128_addition(&[g, g].concat()).unwrap()
+        );
+
+        // h + h == 2 * h and 2 * h - h == h
+        let h = g2_to_be(G2Affine::generator()).unwrap();
+        let mut scalar = [0u8; 32];
+        scalar[31] = 2;
+        let doubled = alt_bn128_g2_addition(&[h, h].concat()).unwrap();
+        assert_eq!(
+            alt_bn128_g2_multiplication(&[&h[..], &scalar].concat()).unwrap(),
+            doubled
+        );
+        assert_eq!(
+            alt_bn128_g2_subtraction(&[&doubled[..], &h].concat()).unwrap(),
+            h
+        );
+
+        let mut invalid = h;
+        invalid[127] ^= 1;
+        assert_eq!(
+            alt_bn128_g2_addition(&[invalid, h].concat()),
+            Err(crate::errors::Groth16Error::G2OperationFailed(ERROR))
+        );
+        assert_eq!(
+            alt_bn128_g2_multiplication(&[0u8; 161]),
+            Err(crate::errors::Groth16Error::G2OperationFailed(0))
+        );
+    }
+
+    #[test]
+    fn pairing_batch_checks_each_product() {
+        use crate::bn254::{alt_bn128_pairing_batch, ALT_BN128_PAIRING_MAX_PAIRS};
+
+        // e(g, h) * e(-g, h) == 1, e(g, h) != 1
+        let g = generator_be();
+        let neg_g = g1_to_be(-G1Affine::generator()).unwrap();
+        let h = g2_to_be(G2Affine::generator()).unwrap();
+        let one = [&g[..], &h, &neg_g, &h].concat();
+        let not_one = [&g[..], &h].concat();
+        assert_eq!(alt_bn128_pairing_batch(&[&one, &one]), Ok(true));
+        assert_eq!(alt_bn128_pairing_batch(&[&one, &not_one]), Ok(false));
+        assert_eq!(alt_bn128_pairing_batch(&[]), Ok(true));
+
+        // The product of both is one, yet neither alone is
+        let split = [&g[..], &h].concat();
+        let rest = [&neg_g[..], &h].concat();
+        assert_eq!(alt_bn128_pairing_batch(&[&split, &rest]), Ok(false));
+
+        let too_large = vec![0u8; (ALT_BN128_PAIRING_MAX_PAIRS + 1) * 192];
+        assert_eq!(
+            alt_bn128_pairing_batch(&[&one, &too_large]),
+            Err(crate::errors::Groth16Error::PairingInputTooLarge)
+        );
+    }
+
+    #[test]
+    fn compression_roundtrip() {
+        let g1 = generator_be();
+        let mut compressed = [0u8; 32];
+        let mut decompressed = [0u8; 64];
+        assert_eq!(
+            sol_alt_bn128_compression(ALT_BN128_G1_COMPRESS, &g1, &mut compressed),
+            SUCCESS
+        );
+        assert_eq!(
+            sol_alt_bn128_compression(ALT_BN128_G1_DECOMPRESS, &compressed, &mut decompressed),
+            SUCCESS
+        );
+        assert_eq!(decompressed, g1);
+
+        let g2 = g2_to_be(G2Affine::generator()).unwrap();
+        let mut compressed = [0u8; 64];
+        let mut decompressed = [0u8; 128];
+        assert_eq!(
+            sol_alt_bn128_compression(ALT_BN128_G2_COMPRESS, &g2, &mut compressed),
+            SUCCESS
+        );
+        assert_eq!(
+            sol_alt_bn128_compression(ALT_BN128_G2_DECOMPRESS, &compressed, &mut decompressed),
+            SUCCESS
+        );
+        assert_eq!(decompressed, g2);
+    }
+
+    #[test]
+    fn injected_faults() {
+        let g = generator_be();
+        let input = [g, g].concat();
+        let mut expected = [0u8; 64];
+        assert_eq!(
+            sol_alt_bn128_group_op(ALT_BN128_G1_ADD, &input, &mut expected),
+            SUCCESS
+        );
+
+        clear_faults();
+        inject_fault(1, Fault::ReturnCode(7));
+        inject_fault(2, Fault::CorruptOutput);
+        let mut result = [0u8; 64];
+        assert_eq!(
+            sol_alt_bn128_group_op(ALT_BN128_G1_ADD, &input, &mut result),
+            SUCCESS
+        );
+        assert_eq!(result, expected);
+        let mut result = [0u8; 64];
+        assert_eq!(
+            sol_alt_bn128_group_op(ALT_BN128_G1_ADD, &input, &mut result),
+            7
+        );
+        assert_eq!(result, [0u8; 64]);
+        assert_eq!(
+            sol_alt_bn128_group_op(ALT_BN128_G1_ADD, &input, &mut result),
+            SUCCESS
+        );
+        assert_eq!(result[..63], expected[..63]);
+        assert_eq!(result[63], expected[63] ^ 1);
+        assert_eq!(syscall_count(), 3);
+
+        // A failing syscall surfaces as the wrapper's error
+        inject_fault(0, Fault::ReturnCode(ERROR));
+        assert_eq!(
+            crate::bn254::alt_bn128_g1_decompress(&[0u8; 32]),
+            Err(crate::errors::Groth16Error::DecompressingG1Failed(ERROR))
+        );
+        clear_faults();
+    }
+
+    #[test]
+    fn compute_units_are_metered() {
+        use crate::compute_units::estimate_verify_compute_units;
+        use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+
+        let vk_ic = [[0u8; 64]; 3];
+        let vk = Groth16Verifyingkey {
+            nr_pubinputs: 2,
+            vk_alpha_g1: [0u8; 64],
+            vk_beta_g2: [0u8; 128],
+            vk_gamma_g2: [0u8; 128],
+            vk_delta_g2: [0u8; 128],
+            vk_ic: &vk_ic,
+        };
+        let inputs = [[1u8; 32], [2u8; 32]];
+        let verify = || {
+            Groth16Verifier::new(&[0u8; 64], &[0u8; 128], &[0u8; 64], &inputs, &vk)
+                .unwrap()
+                .verify()
+        };
+
+        set_compute_unit_limit(None);
+        verify().unwrap();
+        let consumed = compute_units_consumed();
+        assert_eq!(
+            consumed,
+            2 * (ALT_BN128_MULTIPLICATION_COST + ALT_BN128_ADDITION_COST) + pairing_cost(4)
+        );
+        assert!(consumed < estimate_verify_compute_units(2));
+
+        set_compute_unit_limit(Some(consumed));
+        verify().unwrap();
+        set_compute_unit_limit(Some(consumed - 1));
+        let exceeded = std::panic::catch_unwind(verify).unwrap_err();
+        assert!(exceeded
+            .downcast_ref::<String>()
+            .unwrap()
+            .starts_with("exceeded compute unit limit"));
+        set_compute_unit_limit(None);
+    }
+}
@@ -0,0 +1,542 @@
+//! Noir circuits proven with a gnark Groth16 backend
+//!
+//! Noir compiles to ACIR, which Groth16 backends built on gnark prove over
+//! BN254. They export the verifying key and proof in gnark's raw binary
+//! serialization, `WriteRawTo`:
+//!
+//! * points are uncompressed big-endian, G2 coordinates ordered `c1 | c0` as
+//!   for the syscalls, the identity has the flag `0x40` in its first byte
+//! * the key is `alpha_g1 | beta_g1 | beta_g2 | gamma_g2 | delta_g1 |
+//!   delta_g2 | nr_k u32 BE | k`, where `k` are the IC points
+//! * the proof is `a | b | c`, with a as proven
+//!
+//! Keys of circuits using gnark's commitments carry more data after `k`,
+//! which is not supported. Proofs are verified with
+//! [`ProverConvention::RawA`], the verifier negates a.
+//!
+//! The public inputs come from the circuit's ABI in the `nargo compile`
+//! artifact: [`NoirAbi`] flattens the public parameters, in declaration order,
+//! followed by the return value, into field elements, the order the backend
+//! expects them in.
+//!
+//! ```rust,ignore
+//! let abi = NoirAbi::from_json(include_str!("../target/circuit.json"))?;
+//! let public_inputs = abi.encode_public_inputs(&inputs, Some(&return_value))?;
+//! ```
+
+use crate::errors::Groth16Error;
+use crate::groth16::{
+    is_less_than_bn254_field_size_be, Groth16Verifier, Groth16Verifyingkey, ProverConvention,
+};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use num_bigint::{BigInt, BigUint, Sign};
+use serde_json::Value;
+
+/// Size of a proof without commitments in gnark's raw serialization
+pub const GNARK_PROOF_SIZE: usize = 64 + 128 + 64;
+
+const INFINITY_FLAG: u8 = 0x40;
+const FLAG_MASK: u8 = 0xc0;
+
+fn gnark_point<const N: usize>(bytes: &[u8]) -> Result<[u8; N], Groth16Error> {
+    let point: [u8; N] = bytes
+        .try_into()
+        .map_err(|_| Groth16Error::ProofConversionError)?;
+    match point[0] & FLAG_MASK {
+        0 => Ok(point),
+        INFINITY_FLAG if point[0] == INFINITY_FLAG && point[1..].iter().all(|b| *b == 0) => {
+            Ok([0u8; N])
+        }
+        _ => Err(Groth16Error::ProofConversionError),
+    }
+}
+
+/// Uncompressed `(proof_a, proof_b, proof_c)`
+pub type GnarkProof = ([u8; 64], [u8; 128], [u8; 64]);
+
+/// Splits a raw gnark proof into `(proof_a, proof_b, proof_c)`, proof a not negated
+///
+/// # Returns
+/// * `Err(Groth16Error::ProofConversionError)` - If the proof is too short,
+///   a point is compressed or the proof has commitments
+pub fn proof_from_gnark_bytes(bytes: &[u8]) -> Result<GnarkProof, Groth16Error> {
+    if bytes.len() < GNARK_PROOF_SIZE {
+        return Err(Groth16Error::ProofConversionError);
+    }
+    // An empty commitment list is written as a zero length
+    let commitments = &bytes[GNARK_PROOF_SIZE..];
+    if commitments.len() >= 4 && commitments[..4] != [0u8; 4] {
+        return Err(Groth16Error::ProofConversionError);
+    }
+    Ok((
+        gnark_point(&bytes[..64])?,
+        gnark_point(&bytes[64..192])?,
+        gnark_point(&bytes[192..256])?,
+    ))
+}
+
+/// A verifying key read from gnark's raw serialization
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NoirVerifyingKey {
+    pub vk_alpha_g1: [u8; 64],
+    pub vk_beta_g2: [u8; 128],
+    pub vk_gamma_g2: [u8; 128],
+    pub vk_delta_g2: [u8; 128],
+    pub vk_ic: Vec<[u8; 64]>,
+}
+
+impl NoirVerifyingKey {
+    /// Parses a key written by gnark's `VerifyingKey.WriteRawTo`
+    ///
+    /// `beta_g1` and `delta_g1` are only used by the prover and skipped.
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::ProofConversionError)` - If the key is truncated,
+    ///   has no IC points or a point is compressed
+    pub fn from_gnark_bytes(bytes: &[u8]) -> Result<Self, Groth16Error> {
+        const K_OFFSET: usize = 3 * 64 + 3 * 128 + 4;
+        if bytes.len() < K_OFFSET {
+            return Err(Groth16Error::ProofConversionError);
+        }
+        let nr_k = u32::from_be_bytes(bytes[K_OFFSET - 4..K_OFFSET].try_into().unwrap()) as usize;
+        let k = nr_k
+            .checked_mul(64)
+            .and_then(|len| bytes.get(K_OFFSET..K_OFFSET + len))
+            .ok_or(Groth16Error::ProofConversionError)?;
+        if nr_k == 0 {
+            return Err(Groth16Error::ProofConversionError);
+        }
+        Ok(NoirVerifyingKey {
+            vk_alpha_g1: gnark_point(&bytes[..64])?,
+            vk_beta_g2: gnark_point(&bytes[128..256])?,
+            vk_gamma_g2: gnark_point(&bytes[256..384])?,
+            vk_delta_g2: gnark_point(&bytes[448..576])?,
+            vk_ic: k.chunks(64).map(gnark_point).collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Borrows the key as taken by [`Groth16Verifier`]
+    pub fn as_verifyingkey(&self) -> Groth16Verifyingkey<'_> {
+        Groth16Verifyingkey {
+            nr_pubinputs: self.vk_ic.len() - 1,
+            vk_alpha_g1: self.vk_alpha_g1,
+            vk_beta_g2: self.vk_beta_g2,
+            vk_gamma_g2: self.vk_gamma_g2,
+            vk_delta_g2: self.vk_delta_g2,
+            vk_ic: &self.vk_ic,
+        }
+    }
+}
+
+/// Verifies a raw gnark proof of a Noir circuit
+///
+/// # Arguments
+/// * `proof` - The proof as serialized by the backend
+/// * `public_inputs` - Big-endian public inputs, see [`NoirAbi::encode_public_inputs`]
+/// * `verifyingkey` - The circuit's key, see [`NoirVerifyingKey`]
+pub fn verify_noir_proof<const NR_INPUTS: usize>(
+    proof: &[u8],
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+    verifyingkey: &Groth16Verifyingkey,
+) -> Result<(), Groth16Error> {
+    let (proof_a, proof_b, proof_c) = proof_from_gnark_bytes(proof)?;
+    Groth16Verifier::new(&proof_a, &proof_b, &proof_c, public_inputs, verifyingkey)?
+        .with_convention(ProverConvention::RawA)
+        .verify()
+}
+
+/// A type of the Noir ABI
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NoirAbiType {
+    Field,
+    Boolean,
+    Integer {
+        signed: bool,
+        width: u32,
+    },
+    Array {
+        length: usize,
+        element: Box<NoirAbiType>,
+    },
+    String {
+        length: usize,
+    },
+    Struct {
+        fields: Vec<(String, NoirAbiType)>,
+    },
+    Tuple {
+        fields: Vec<NoirAbiType>,
+    },
+}
+
+impl NoirAbiType {
+    fn from_json(value: &Value) -> Result<Self, Groth16Error> {
+        let length = |value: &Value| {
+            value["length"]
+                .as_u64()
+                .map(|length| length as usize)
+                .ok_or(Groth16Error::ProofConversionError)
+        };
+        Ok(match value["kind"].as_str() {
+            Some("field") => NoirAbiType::Field,
+            Some("boolean") => NoirAbiType::Boolean,
+            Some("integer") => NoirAbiType::Integer {
+                signed: value["sign"] == "signed",
+                width: value["width"]
+                    .as_u64()
+                    .filter(|width| (1..=128).contains(width))
+                    .ok_or(Groth16Error::ProofConversionError)? as u32,
+            },
+            Some("array") => NoirAbiType::Array {
+                length: length(value)?,
+                element: Box::new(NoirAbiType::from_json(&value["type"])?),
+            },
+            Some("string") => NoirAbiType::String {
+                length: length(value)?,
+            },
+            Some("struct") => NoirAbiType::Struct {
+                fields: json_array(&value["fields"])?
+                    .iter()
+                    .map(|field| {
+                        let name = field["name"]
+                            .as_str()
+                            .ok_or(Groth16Error::ProofConversionError)?;
+                        Ok((name.into(), NoirAbiType::from_json(&field["type"])?))
+                    })
+                    .collect::<Result<_, Groth16Error>>()?,
+            },
+            Some("tuple") => NoirAbiType::Tuple {
+                fields: json_array(&value["fields"])?
+                    .iter()
+                    .map(NoirAbiType::from_json)
+                    .collect::<Result<_, _>>()?,
+            },
+            _ => return Err(Groth16Error::ProofConversionError),
+        })
+    }
+
+    /// Number of field elements a value of this type flattens to
+    pub fn nr_fields(&self) -> usize {
+        match self {
+            NoirAbiType::Field | NoirAbiType::Boolean | NoirAbiType::Integer { .. } => 1,
+            NoirAbiType::Array { length, element } => length * element.nr_fields(),
+            NoirAbiType::String { length } => *length,
+            NoirAbiType::Struct { fields } => fields.iter().map(|(_, ty)| ty.nr_fields()).sum(),
+            NoirAbiType::Tuple { fields } => fields.iter().map(NoirAbiType::nr_fields).sum(),
+        }
+    }
+
+    fn encode(&self, value: &Value, out: &mut Vec<[u8; 32]>) -> Result<(), Groth16Error> {
+        match self {
+            NoirAbiType::Field => out.push(field_element(value)?),
+            NoirAbiType::Boolean => {
+                let mut element = [0u8; 32];
+                element[31] = match value {
+                    Value::Bool(value) => *value as u8,
+                    _ => match field_element(value)? {
+                        element if element[..31] == [0u8; 31] && element[31] <= 1 => element[31],
+                        _ => return Err(Groth16Error::PublicInputMismatch),
+                    },
+                };
+                out.push(element);
+            }
+            NoirAbiType::Integer { signed, width } => {
+                out.push(integer_element(value, *signed, *width)?)
+            }
+            NoirAbiType::Array { length, element } => {
+                let values = json_array(value).map_err(|_| Groth16Error::PublicInputMismatch)?;
+                if values.len() != *length {
+                    return Err(Groth16Error::PublicInputMismatch);
+                }
+                for value in values {
+                    element.encode(value, out)?;
+                }
+            }
+            NoirAbiType::String { length } => {
+                let value = value.as_str().ok_or(Groth16Error::PublicInputMismatch)?;
+                if value.len() != *length {
+                    return Err(Groth16Error::PublicInputMismatch);
+                }
+                out.extend(value.bytes().map(|byte| {
+                    let mut element = [0u8; 32];
+                    element[31] = byte;
+                    element
+                }));
+            }
+            NoirAbiType::Struct { fields } => {
+                for (name, ty) in fields {
+                    let field = value.get(name).ok_or(Groth16Error::PublicInputMismatch)?;
+                    ty.encode(field, out)?;
+                }
+            }
+            NoirAbiType::Tuple { fields } => {
+                let values = json_array(value).map_err(|_| Groth16Error::PublicInputMismatch)?;
+                if values.len() != fields.len() {
+                    return Err(Groth16Error::PublicInputMismatch);
+                }
+                for (ty, value) in fields.iter().zip(values) {
+                    ty.encode(value, out)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn json_array(value: &Value) -> Result<&Vec<Value>, Groth16Error> {
+    value.as_array().ok_or(Groth16Error::ProofConversionError)
+}
+
+/// Parses a decimal or `0x` hex string or a JSON integer
+fn json_integer(value: &Value) -> Result<BigInt, Groth16Error> {
+    let parsed = match value {
+        Value::Number(number) => number.as_i64().map(BigInt::from),
+        Value::String(string) => {
+            let (negative, digits) = match string.strip_prefix('-') {
+                Some(digits) => (true, digits),
+                None => (false, string.as_str()),
+            };
+            let magnitude = match digits.strip_prefix("0x") {
+                Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16),
+                None => BigUint::parse_bytes(digits.as_bytes(), 10),
+            };
+            magnitude.map(|magnitude| {
+                let sign = if negative { Sign::Minus } else { Sign::Plus };
+                BigInt::from_biguint(sign, magnitude)
+            })
+        }
+        _ => None,
+    };
+    parsed.ok_or(Groth16Error::PublicInputMismatch)
+}
+
+fn to_element(value: &BigUint) -> Result<[u8; 32], Groth16Error> {
+    let bytes = value.to_bytes_be();
+    if bytes.len() > 32 {
+        return Err(Groth16Error::PublicInputGreaterThanFieldSize);
+    }
+    let mut element = [0u8; 32];
+    element[32 - bytes.len()..].copy_from_slice(&bytes);
+    if !is_less_than_bn254_field_size_be(&element) {
+        return Err(Groth16Error::PublicInputGreaterThanFieldSize);
+    }
+    Ok(element)
+}
+
+fn field_element(value: &Value) -> Result<[u8; 32], Groth16Error> {
+    let value = json_integer(value)?
+        .to_biguint()
+        .ok_or(Groth16Error::PublicInputMismatch)?;
+    to_element(&value)
+}
+
+/// Integers are range checked to their width, negative ones encoded in two's complement
+fn integer_element(value: &Value, signed: bool, width: u32) -> Result<[u8; 32], Groth16Error> {
+    let value = json_integer(value)?;
+    let modulus = BigInt::from(1u8) << width;
+    let (min, max) = if signed {
+        let half = BigInt::from(1u8) << (width - 1);
+        (-half.clone(), half)
+    } else {
+        (BigInt::from(0u8), modulus.clone())
+    };
+    if value < min || value >= max {
+        return Err(Groth16Error::PublicInputMismatch);
+    }
+    let value = if value.sign() == Sign::Minus {
+        value + modulus
+    } else {
+        value
+    };
+    to_element(&value.to_biguint().unwrap())
+}
+
+/// Public part of a Noir circuit's ABI
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NoirAbi {
+    /// Public parameters in declaration order
+    pub public_parameters: Vec<(String, NoirAbiType)>,
+    /// Type of the return value, which is public
+    pub return_type: Option<NoirAbiType>,
+}
+
+impl NoirAbi {
+    /// Reads the ABI of a `nargo compile` artifact, or of its `abi` object
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::ProofConversionError)` - If the JSON is not a Noir ABI
+    pub fn from_json(json: &str) -> Result<Self, Groth16Error> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|_| Groth16Error::ProofConversionError)?;
+        let abi = value.get("abi").unwrap_or(&value);
+        let mut public_parameters = Vec::new();
+        for parameter in json_array(&abi["parameters"])? {
+            if parameter["visibility"] != "public" {
+                continue;
+            }
+            let name = parameter["name"]
+                .as_str()
+                .ok_or(Groth16Error::ProofConversionError)?;
+            public_parameters.push((name.into(), NoirAbiType::from_json(&parameter["type"])?));
+        }
+        let return_type = match &abi["return_type"] {
+            Value::Null => None,
+            return_type => Some(NoirAbiType::from_json(&return_type["abi_type"])?),
+        };
+        Ok(NoirAbi {
+            public_parameters,
+            return_type,
+        })
+    }
+
+    /// Number of public inputs of the circuit
+    pub fn nr_public_inputs(&self) -> usize {
+        self.public_parameters
+            .iter()
+            .map(|(_, ty)| ty.nr_fields())
+            .chain(self.return_type.iter().map(NoirAbiType::nr_fields))
+            .sum()
+    }
+
+    /// Flattens the values of the public parameters and the return value
+    ///
+    /// # Arguments
+    /// * `inputs` - JSON object of the circuit's inputs by parameter name, as
+    ///   in `Prover.toml`; private parameters are ignored
+    /// * `return_value` - The return value, required if the circuit has one
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::PublicInputMismatch)` - If a value is missing or
+    ///   does not match its type
+    /// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If a field
+    ///   element is not smaller than the field size
+    pub fn encode_public_inputs(
+        &self,
+        inputs: &Value,
+        return_value: Option<&Value>,
+    ) -> Result<Vec<[u8; 32]>, Groth16Error> {
+        let mut encoded = Vec::with_capacity(self.nr_public_inputs());
+        for (name, ty) in &self.public_parameters {
+            let value = inputs.get(name).ok_or(Groth16Error::PublicInputMismatch)?;
+            ty.encode(value, &mut encoded)?;
+        }
+        match (&self.return_type, return_value) {
+            (Some(ty), Some(value)) => ty.encode(value, &mut encoded)?,
+            (None, None) => {}
+            _ => return Err(Groth16Error::PublicInputMismatch),
+        }
+        Ok(encoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ABI: &str = r#"{"noir_version":"1.0.0","abi":{"parameters":[
+        {"name":"secret","type":{"kind":"field"},"visibility":"private"},
+        {"name":"root","type":{"kind":"field"},"visibility":"public"},
+        {"name":"pair","type":{"kind":"struct","path":"Pair","fields":[
+            {"name":"delta","type":{"kind":"integer","sign":"signed","width":8}},
+            {"name":"flags","type":{"kind":"array","length":2,"type":{"kind":"boolean"}}}
+        ]},"visibility":"public"}],
+        "return_type":{"abi_type":{"kind":"integer","sign":"unsigned","width":64},"visibility":"public"},
+        "error_types":{}}}"#;
+
+    fn element(value: u8) -> [u8; 32] {
+        let mut element = [0u8; 32];
+        element[31] = value;
+        element
+    }
+
+    #[test]
+    fn abi_flattens_public_inputs() {
+        let abi = NoirAbi::from_json(ABI).unwrap();
+        assert_eq!(abi.nr_public_inputs(), 5);
+        let inputs = json!({
+            "secret": "7",
+            "root": "0x2a",
+            "pair": {"delta": "-1", "flags": [true, "0"]},
+        });
+        assert_eq!(
+            abi.encode_public_inputs(&inputs, Some(&json!(9))).unwrap(),
+            [
+                element(42),
+                element(255),
+                element(1),
+                element(0),
+                element(9)
+            ]
+        );
+
+        let out_of_range = json!({"root": "1", "pair": {"delta": "128", "flags": [true, false]}});
+        assert_eq!(
+            abi.encode_public_inputs(&out_of_range, Some(&json!(9))),
+            Err(Groth16Error::PublicInputMismatch)
+        );
+        assert_eq!(
+            abi.encode_public_inputs(&inputs, None),
+            Err(Groth16Error::PublicInputMismatch)
+        );
+    }
+
+    #[cfg(feature = "test-vectors")]
+    #[test]
+    fn gnark_exports_verify() {
+        use crate::curve::{Bn254, PairingCurve};
+
+        let vector = crate::test_vectors::generate(0);
+        let mut vk_bytes = Vec::new();
+        for point in [
+            &vector.vk_alpha_g1[..],
+            &vector.vk_alpha_g1,
+            &vector.vk_beta_g2,
+            &vector.vk_gamma_g2,
+            &vector.vk_alpha_g1,
+            &vector.vk_delta_g2,
+            &(vector.vk_ic.len() as u32).to_be_bytes(),
+        ] {
+            vk_bytes.extend_from_slice(point);
+        }
+        for ic in &vector.vk_ic {
+            vk_bytes.extend_from_slice(ic);
+        }
+        let vk = NoirVerifyingKey::from_gnark_bytes(&vk_bytes).unwrap();
+
+        let mut proof = Bn254::g1_negate(&vector.proof_a).unwrap().to_vec();
+        proof.extend_from_slice(&vector.proof_b);
+        proof.extend_from_slice(&vector.proof_c);
+        proof.extend_from_slice(&[0u8; 4]);
+
+        let abi = NoirAbi::from_json(
+            r#"{"parameters":[
+                {"name":"x","type":{"kind":"field"},"visibility":"public"},
+                {"name":"y","type":{"kind":"field"},"visibility":"public"}],
+                "return_type":null}"#,
+        )
+        .unwrap();
+        let hex = |input: &[u8; 32]| {
+            let digits: String = input.iter().map(|b| format!("{b:02x}")).collect();
+            format!("0x{digits}")
+        };
+        let inputs = json!({
+            "x": hex(&vector.public_inputs[0]),
+            "y": hex(&vector.public_inputs[1]),
+        });
+        let public_inputs: [[u8; 32]; 2] = abi
+            .encode_public_inputs(&inputs, None)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        verify_noir_proof(&proof, &public_inputs, &vk.as_verifyingkey()).unwrap();
+
+        proof[256] = 1;
+        assert_eq!(
+            verify_noir_proof(&proof, &public_inputs, &vk.as_verifyingkey()),
+            Err(Groth16Error::ProofConversionError)
+        );
+    }
+}
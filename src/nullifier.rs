@@ -0,0 +1,484 @@
+//! Nullifier set helpers
+//!
+//! Privacy applications reveal a nullifier as a public input of every spend
+//! proof and must reject a nullifier that has been seen before. This module
+//! provides two on-chain storage layouts with `insert_if_absent` semantics:
+//!
+//! * [`NullifierPage`] - an exact set stored in PDA pages. A nullifier is
+//!   routed to page `page_index_for(nullifier, nr_pages)` and kept sorted
+//!   inside that page.
+//! * [`NullifierBloomFilter`] - a probabilistic set stored in a single
+//!   account. It never forgets a nullifier, but may reject a fresh one with a
+//!   false positive, so size it for the expected number of spends.
+//!
+//! Both types operate on raw account data so they can be used from any
+//! program framework; the `*_account` helpers add the owner and writable
+//! checks for Pinocchio `AccountInfo`s.
+
+use crate::errors::Groth16Error;
+use crate::fr;
+#[cfg(feature = "pinocchio")]
+use crate::Pubkey;
+#[cfg(feature = "pinocchio")]
+use pinocchio::account_info::AccountInfo;
+#[cfg(feature = "pinocchio")]
+use pinocchio::pubkey::try_find_program_address;
+
+/// Seed prefix of nullifier page PDAs: `[NULLIFIER_PAGE_SEED, page_index.to_le_bytes()]`
+pub const NULLIFIER_PAGE_SEED: &[u8] = b"nullifier_page";
+
+/// Discriminator stored in the first 8 bytes of a nullifier page account
+pub const NULLIFIER_PAGE_DISCRIMINATOR: [u8; 8] = *b"nullpage";
+
+/// Discriminator stored in the first 8 bytes of a bloom filter account
+pub const NULLIFIER_BLOOM_DISCRIMINATOR: [u8; 8] = *b"nullblom";
+
+// discriminator (8) + page index (2) + padding (2) + count (4)
+const PAGE_HEADER_SIZE: usize = 16;
+// discriminator (8) + nr hashes (1) + padding (3) + count (4)
+const BLOOM_HEADER_SIZE: usize = 16;
+const NULLIFIER_SIZE: usize = 32;
+
+/// Returns the account size of a nullifier page holding `capacity` nullifiers
+pub const fn nullifier_page_size(capacity: usize) -> usize {
+    PAGE_HEADER_SIZE + capacity * NULLIFIER_SIZE
+}
+
+/// Returns the account size of a bloom filter with `nr_bits` bits
+pub const fn nullifier_bloom_filter_size(nr_bits: usize) -> usize {
+    BLOOM_HEADER_SIZE + nr_bits.div_ceil(8)
+}
+
+/// Selects the page a nullifier is stored in
+///
+/// Uses the two least significant bytes of the big-endian nullifier, which
+/// are uniformly distributed for hash-derived nullifiers.
+///
+/// # Arguments
+/// * `nullifier` - Big-endian nullifier
+/// * `nr_pages` - Total number of pages of the set
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidAccountData)` - If `nr_pages` is 0
+pub fn page_index_for(nullifier: &[u8; 32], nr_pages: u16) -> Result<u16, Groth16Error> {
+    u16::from_be_bytes([nullifier[30], nullifier[31]])
+        .checked_rem(nr_pages)
+        .ok_or(Groth16Error::InvalidAccountData)
+}
+
+/// Reads the nullifier at `index` from the public inputs of a proof
+///
+/// The nullifier must be a canonical field element. `n` and `n + r` verify
+/// as the same input with `verify_unchecked`, but are different bytes and
+/// would be stored, and routed, as two nullifiers.
+///
+/// # Returns
+/// * `Ok(&[u8; 32])` - The nullifier
+/// * `Err(Groth16Error::InvalidPublicInputsLength)` - If `index` is out of bounds
+/// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If the nullifier
+///   is not smaller than the scalar field modulus
+pub fn nullifier_from_public_inputs(
+    public_inputs: &[[u8; 32]],
+    index: usize,
+) -> Result<&[u8; 32], Groth16Error> {
+    let nullifier = public_inputs
+        .get(index)
+        .ok_or(Groth16Error::InvalidPublicInputsLength)?;
+    if !fr::is_canonical(nullifier) {
+        return Err(Groth16Error::PublicInputGreaterThanFieldSize);
+    }
+    Ok(nullifier)
+}
+
+/// An exact nullifier set page backed by account data
+///
+/// Layout: `discriminator (8) | page index u16 LE | padding (2) | count u32 LE | nullifiers`
+/// where the nullifiers are stored sorted so lookups are a binary search.
+pub struct NullifierPage<'a> {
+    data: &'a mut [u8],
+}
+
+impl<'a> NullifierPage<'a> {
+    /// Initializes a zeroed account buffer as an empty page
+    pub fn initialize(data: &'a mut [u8], page_index: u16) -> Result<Self, Groth16Error> {
+        if data.len() < nullifier_page_size(1) || data[..8] != [0u8; 8] {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        data[..8].copy_from_slice(&NULLIFIER_PAGE_DISCRIMINATOR);
+        data[8..10].copy_from_slice(&page_index.to_le_bytes());
+        data[12..16].copy_from_slice(&0u32.to_le_bytes());
+        Ok(NullifierPage { data })
+    }
+
+    /// Loads an initialized page
+    pub fn from_bytes(data: &'a mut [u8]) -> Result<Self, Groth16Error> {
+        if data.len() < PAGE_HEADER_SIZE || data[..8] != NULLIFIER_PAGE_DISCRIMINATOR {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        let page = NullifierPage { data };
+        if page.len() > page.capacity() {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        Ok(page)
+    }
+
+    pub fn page_index(&self) -> u16 {
+        u16::from_le_bytes([self.data[8], self.data[9]])
+    }
+
+    pub fn len(&self) -> usize {
+        u32::from_le_bytes(self.data[12..16].try_into().unwrap()) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        (self.data.len() - PAGE_HEADER_SIZE) / NULLIFIER_SIZE
+    }
+
+    fn entry(&self, index: usize) -> &[u8] {
+        let start = PAGE_HEADER_SIZE + index * NULLIFIER_SIZE;
+        &self.data[start..start + NULLIFIER_SIZE]
+    }
+
+    /// Binary search over the sorted entries, returns the insertion position on a miss
+    fn search(&self, nullifier: &[u8; 32]) -> Result<usize, usize> {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.entry(mid).cmp(&nullifier[..]) {
+                core::cmp::Ordering::Less => low = mid + 1,
+                core::cmp::Ordering::Greater => high = mid,
+                core::cmp::Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(low)
+    }
+
+    pub fn contains(&self, nullifier: &[u8; 32]) -> bool {
+        self.search(nullifier).is_ok()
+    }
+
+    /// Inserts a nullifier into the page
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the nullifier was not present and has been inserted
+    /// * `Err(Groth16Error::NullifierAlreadySpent)` - If the nullifier is present
+    /// * `Err(Groth16Error::NullifierSetFull)` - If the page has no free slot
+    pub fn insert_if_absent(&mut self, nullifier: &[u8; 32]) -> Result<(), Groth16Error> {
+        let position = match self.search(nullifier) {
+            Ok(_) => return Err(Groth16Error::NullifierAlreadySpent),
+            Err(position) => position,
+        };
+        let len = self.len();
+        if len == self.capacity() {
+            return Err(Groth16Error::NullifierSetFull);
+        }
+
+        let start = PAGE_HEADER_SIZE + position * NULLIFIER_SIZE;
+        let end = PAGE_HEADER_SIZE + len * NULLIFIER_SIZE;
+        self.data.copy_within(start..end, start + NULLIFIER_SIZE);
+        self.data[start..start + NULLIFIER_SIZE].copy_from_slice(nullifier);
+        self.data[12..16].copy_from_slice(&(len as u32 + 1).to_le_bytes());
+        Ok(())
+    }
+}
+
+/// A probabilistic nullifier set backed by account data
+///
+/// Layout: `discriminator (8) | nr hashes u8 | padding (3) | count u32 LE | bits`.
+/// Bit positions are derived by double hashing over the low 16 bytes of the
+/// nullifier, which is sound for nullifiers that are outputs of a hash function.
+pub struct NullifierBloomFilter<'a> {
+    data: &'a mut [u8],
+}
+
+impl<'a> NullifierBloomFilter<'a> {
+    /// Initializes a zeroed account buffer as an empty bloom filter
+    pub fn initialize(data: &'a mut [u8], nr_hashes: u8) -> Result<Self, Groth16Error> {
+        if data.len() <= BLOOM_HEADER_SIZE || data[..8] != [0u8; 8] || nr_hashes == 0 {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        data[..8].copy_from_slice(&NULLIFIER_BLOOM_DISCRIMINATOR);
+        data[8] = nr_hashes;
+        data[12..16].copy_from_slice(&0u32.to_le_bytes());
+        Ok(NullifierBloomFilter { data })
+    }
+
+    /// Loads an initialized bloom filter
+    pub fn from_bytes(data: &'a mut [u8]) -> Result<Self, Groth16Error> {
+        if data.len() <= BLOOM_HEADER_SIZE
+            || data[..8] != NULLIFIER_BLOOM_DISCRIMINATOR
+            || data[8] == 0
+        {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        Ok(NullifierBloomFilter { data })
+    }
+
+    pub fn nr_hashes(&self) -> u8 {
+        self.data[8]
+    }
+
+    /// Number of nullifiers inserted so far
+    pub fn len(&self) -> usize {
+        u32::from_le_bytes(self.data[12..16].try_into().unwrap()) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn nr_bits(&self) -> u64 {
+        ((self.data.len() - BLOOM_HEADER_SIZE) * 8) as u64
+    }
+
+    fn bit_positions(&self, nullifier: &[u8; 32]) -> impl Iterator<Item = u64> {
+        bloom_bit_positions(nullifier, self.nr_hashes(), self.nr_bits())
+    }
+
+    fn bit(&self, position: u64) -> bool {
+        self.data[BLOOM_HEADER_SIZE + (position / 8) as usize] & (1 << (position % 8)) != 0
+    }
+
+    /// Returns true if the nullifier may have been inserted
+    pub fn may_contain(&self, nullifier: &[u8; 32]) -> bool {
+        self.bit_positions(nullifier).all(|position| self.bit(position))
+    }
+
+    /// Inserts a nullifier into the filter
+    ///
+    /// # Returns
+    /// * `Ok(())` - If at least one bit of the nullifier was unset
+    /// * `Err(Groth16Error::NullifierAlreadySpent)` - If all bits were set, this
+    ///   includes false positives
+    pub fn insert_if_absent(&mut self, nullifier: &[u8; 32]) -> Result<(), Groth16Error> {
+        if self.may_contain(nullifier) {
+            return Err(Groth16Error::NullifierAlreadySpent);
+        }
+        for position in bloom_bit_positions(nullifier, self.nr_hashes(), self.nr_bits()) {
+            self.data[BLOOM_HEADER_SIZE + (position / 8) as usize] |= 1 << (position % 8);
+        }
+        let len = self.len() as u32;
+        self.data[12..16].copy_from_slice(&len.saturating_add(1).to_le_bytes());
+        Ok(())
+    }
+}
+
+fn bloom_bit_positions(
+    nullifier: &[u8; 32],
+    nr_hashes: u8,
+    nr_bits: u64,
+) -> impl Iterator<Item = u64> {
+    let h1 = u64::from_be_bytes(nullifier[16..24].try_into().unwrap());
+    let h2 = u64::from_be_bytes(nullifier[24..32].try_into().unwrap()) | 1;
+    (0..nr_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % nr_bits)
+}
+
+#[cfg(feature = "pinocchio")]
+fn check_set_account(account: &AccountInfo, program_id: &Pubkey) -> Result<(), Groth16Error> {
+    if !account.is_owned_by(program_id) {
+        return Err(Groth16Error::InvalidAccountOwner);
+    }
+    if !account.is_writable() {
+        return Err(Groth16Error::AccountNotWritable);
+    }
+    Ok(())
+}
+
+/// Inserts the nullifier at `index` of the public inputs into a page account
+///
+/// Checks that the account is owned by `program_id`, is writable and is the
+/// page the nullifier is routed to for a set of `nr_pages` pages. The caller
+/// remains responsible for checking the page account address, see
+/// [`insert_nullifier_into_page_account_checked`].
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidAccountData)` - If `nr_pages` is 0 or the
+///   account is not the nullifier's page
+#[cfg(feature = "pinocchio")]
+pub fn insert_nullifier_into_page_account(
+    account: &AccountInfo,
+    program_id: &Pubkey,
+    nr_pages: u16,
+    public_inputs: &[[u8; 32]],
+    index: usize,
+) -> Result<(), Groth16Error> {
+    check_set_account(account, program_id)?;
+    let nullifier = nullifier_from_public_inputs(public_inputs, index)?;
+    let mut data = account
+        .try_borrow_mut_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    let mut page = NullifierPage::from_bytes(&mut data)?;
+    if page.page_index() != page_index_for(nullifier, nr_pages)? {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    page.insert_if_absent(nullifier)
+}
+
+/// [`insert_nullifier_into_page_account`] with an address check
+///
+/// The page must be the PDA `[NULLIFIER_PAGE_SEED, page_index.to_le_bytes()]`
+/// of `program_id` for the page the nullifier is routed to, so a fresh page
+/// account cannot be passed to spend a nullifier twice.
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidAccountData)` - If `nr_pages` is 0 or the
+///   account is not the PDA of the nullifier's page
+#[cfg(feature = "pinocchio")]
+pub fn insert_nullifier_into_page_account_checked(
+    account: &AccountInfo,
+    program_id: &Pubkey,
+    nr_pages: u16,
+    public_inputs: &[[u8; 32]],
+    index: usize,
+) -> Result<(), Groth16Error> {
+    let nullifier = nullifier_from_public_inputs(public_inputs, index)?;
+    let page_index = page_index_for(nullifier, nr_pages)?;
+    let (address, _) =
+        try_find_program_address(&[NULLIFIER_PAGE_SEED, &page_index.to_le_bytes()], program_id)
+            .ok_or(Groth16Error::InvalidAccountData)?;
+    if address != *account.key() {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    insert_nullifier_into_page_account(account, program_id, nr_pages, public_inputs, index)
+}
+
+/// Inserts the nullifier at `index` of the public inputs into a bloom filter account
+///
+/// Checks that the account is owned by `program_id` and is writable.
+#[cfg(feature = "pinocchio")]
+pub fn insert_nullifier_into_bloom_filter_account(
+    account: &AccountInfo,
+    program_id: &Pubkey,
+    public_inputs: &[[u8; 32]],
+    index: usize,
+) -> Result<(), Groth16Error> {
+    check_set_account(account, program_id)?;
+    let nullifier = nullifier_from_public_inputs(public_inputs, index)?;
+    let mut data = account
+        .try_borrow_mut_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    NullifierBloomFilter::from_bytes(&mut data)?.insert_if_absent(nullifier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pseudo-random nullifier derived with splitmix64
+    fn nullifier(seed: u8) -> [u8; 32] {
+        let mut state = seed as u64;
+        let mut nullifier = [0u8; 32];
+        for chunk in nullifier.chunks_mut(8) {
+            state = state.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            chunk.copy_from_slice(&(z ^ (z >> 31)).to_be_bytes());
+        }
+        nullifier
+    }
+
+    #[test]
+    fn page_insert_if_absent() {
+        let mut data = vec![0u8; nullifier_page_size(4)];
+        let mut page = NullifierPage::initialize(&mut data, 3).unwrap();
+        assert_eq!(page.page_index(), 3);
+        assert_eq!(page.capacity(), 4);
+
+        for seed in [9u8, 1, 5, 7] {
+            page.insert_if_absent(&nullifier(seed)).unwrap();
+        }
+        assert_eq!(page.len(), 4);
+        for seed in [9u8, 1, 5, 7] {
+            assert!(page.contains(&nullifier(seed)));
+            assert_eq!(
+                page.insert_if_absent(&nullifier(seed)),
+                Err(Groth16Error::NullifierAlreadySpent)
+            );
+        }
+        assert!(!page.contains(&nullifier(2)));
+        assert_eq!(
+            page.insert_if_absent(&nullifier(2)),
+            Err(Groth16Error::NullifierSetFull)
+        );
+
+        // Entries stay sorted and survive a reload.
+        let page = NullifierPage::from_bytes(&mut data).unwrap();
+        for i in 1..page.len() {
+            assert!(page.entry(i - 1) < page.entry(i));
+        }
+    }
+
+    #[test]
+    fn page_rejects_invalid_data() {
+        let mut data = vec![0u8; nullifier_page_size(2)];
+        assert_eq!(
+            NullifierPage::from_bytes(&mut data).err(),
+            Some(Groth16Error::InvalidAccountData)
+        );
+        NullifierPage::initialize(&mut data, 0).unwrap();
+        assert_eq!(
+            NullifierPage::initialize(&mut data, 0).err(),
+            Some(Groth16Error::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn bloom_filter_insert_if_absent() {
+        let mut data = vec![0u8; nullifier_bloom_filter_size(4096)];
+        let mut filter = NullifierBloomFilter::initialize(&mut data, 4).unwrap();
+        assert_eq!(filter.nr_bits(), 4096);
+
+        for seed in 0..32u8 {
+            filter.insert_if_absent(&nullifier(seed)).unwrap();
+        }
+        assert_eq!(filter.len(), 32);
+        for seed in 0..32u8 {
+            assert!(filter.may_contain(&nullifier(seed)));
+            assert_eq!(
+                filter.insert_if_absent(&nullifier(seed)),
+                Err(Groth16Error::NullifierAlreadySpent)
+            );
+        }
+    }
+
+    #[test]
+    fn nullifier_routing() {
+        let inputs = [
+            fr::reduce_be_bytes(&nullifier(1)),
+            fr::reduce_be_bytes(&nullifier(2)),
+        ];
+        assert_eq!(nullifier_from_public_inputs(&inputs, 1), Ok(&inputs[1]));
+        assert_eq!(
+            nullifier_from_public_inputs(&inputs, 2),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+
+        // 5 + r is the field element 5, it must not be spent a second time
+        let mut unreduced = fr::SCALAR_FIELD_MODULUS;
+        unreduced[31] += 5;
+        assert_eq!(fr::reduce_be_bytes(&unreduced), fr::from_u64(5));
+        assert_eq!(
+            nullifier_from_public_inputs(&[fr::from_u64(5)], 0),
+            Ok(&fr::from_u64(5))
+        );
+        assert_eq!(
+            nullifier_from_public_inputs(&[unreduced], 0),
+            Err(Groth16Error::PublicInputGreaterThanFieldSize)
+        );
+
+        let mut value = [0u8; 32];
+        value[30] = 1;
+        value[31] = 2;
+        assert_eq!(page_index_for(&value, 1000), Ok(258));
+        assert_eq!(page_index_for(&value, 16), Ok(2));
+        assert_eq!(
+            page_index_for(&value, 0),
+            Err(Groth16Error::InvalidAccountData)
+        );
+    }
+}
@@ -0,0 +1,190 @@
+//! Verification on a second, independent implementation
+//!
+//! [`verify_arkworks`] checks a Groth16 proof over BN254 entirely in arkworks:
+//! it parses the big-endian encodings itself, checks that every point is on
+//! the curve and in the prime order subgroup, prepares the public inputs with
+//! arkworks' group arithmetic and evaluates the pairing product with
+//! `Bn254::multi_pairing`. It shares no code with the syscall path beyond the
+//! verifying key.
+//!
+//! [`Groth16Verifier::verify_paranoid`](crate::groth16::Groth16Verifier::verify_paranoid)
+//! runs both paths and accepts a proof only if both do. If exactly one of them
+//! accepts, one implementation is faulty and the verifier fails with
+//! [`Groth16Error::VerifierDisagreement`], which callers should alert on
+//! rather than treat as an invalid proof.
+//!
+//! The arkworks pairing costs far more than a transaction's compute budget,
+//! the mode is meant for off-chain signers and light clients. Off-chain the
+//! syscall path runs on the `mock-syscalls` implementations, which are also
+//! arkworks based but go through this crate's encoding, input preparation and
+//! negation code, the parts most likely to be wrong.
+
+use crate::bn254::convert_endianness;
+use crate::errors::Groth16Error;
+use crate::groth16::{Groth16Verifyingkey, ProverConvention};
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::pairing::Pairing;
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger256, PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, Valid};
+
+fn g1_from_be(bytes: &[u8; 64]) -> Result<G1Affine, Groth16Error> {
+    if *bytes == [0u8; 64] {
+        return Ok(G1Affine::zero());
+    }
+    let le = convert_endianness::<32, 64>(bytes);
+    let x = Fq::deserialize_uncompressed(&le[..32])
+        .map_err(|_| Groth16Error::ProofVerificationFailed)?;
+    let y = Fq::deserialize_uncompressed(&le[32..])
+        .map_err(|_| Groth16Error::ProofVerificationFailed)?;
+    let point = G1Affine::new_unchecked(x, y);
+    point
+        .check()
+        .map_err(|_| Groth16Error::ProofVerificationFailed)?;
+    Ok(point)
+}
+
+fn g2_from_be(bytes: &[u8; 128]) -> Result<G2Affine, Groth16Error> {
+    if *bytes == [0u8; 128] {
+        return Ok(G2Affine::zero());
+    }
+    let le = convert_endianness::<64, 128>(bytes);
+    let x = Fq2::deserialize_uncompressed(&le[..64])
+        .map_err(|_| Groth16Error::ProofVerificationFailed)?;
+    let y = Fq2::deserialize_uncompressed(&le[64..])
+        .map_err(|_| Groth16Error::ProofVerificationFailed)?;
+    let point = G2Affine::new_unchecked(x, y);
+    point
+        .check()
+        .map_err(|_| Groth16Error::ProofVerificationFailed)?;
+    Ok(point)
+}
+
+fn fr_from_be(bytes: &[u8; 32]) -> Result<Fr, Groth16Error> {
+    let mut le = *bytes;
+    le.reverse();
+    let bigint = BigInteger256::deserialize_uncompressed_unchecked(&le[..])
+        .map_err(|_| Groth16Error::PublicInputGreaterThanFieldSize)?;
+    Fr::from_bigint(bigint).ok_or(Groth16Error::PublicInputGreaterThanFieldSize)
+}
+
+/// Verifies a proof with arkworks only, without any syscall
+///
+/// # Arguments
+/// * `proof_a`, `proof_b`, `proof_c` - The proof, encoded as for [`crate::groth16::Groth16Verifier`]
+/// * `public_inputs` - Big-endian public inputs, rejected if not smaller than the field size
+/// * `verifyingkey` - The verifying key
+/// * `convention` - Whether proof a is already negated
+///
+/// # Returns
+/// * `Err(Groth16Error::ProofVerificationFailed)` - If a point is invalid or the pairing check fails
+/// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If an input is not a field element
+pub fn verify_arkworks<const NR_INPUTS: usize>(
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+    verifyingkey: &Groth16Verifyingkey,
+    convention: ProverConvention,
+) -> Result<(), Groth16Error> {
+    if verifyingkey.vk_ic.len() != NR_INPUTS + 1 {
+        return Err(Groth16Error::InvalidPublicInputsLength);
+    }
+    let mut prepared_inputs = G1Projective::from(g1_from_be(&verifyingkey.vk_ic[0])?);
+    for (input, ic) in public_inputs.iter().zip(&verifyingkey.vk_ic[1..]) {
+        prepared_inputs += g1_from_be(ic)? * fr_from_be(input)?;
+    }
+
+    let proof_a = g1_from_be(proof_a)?;
+    let proof_a = match convention {
+        ProverConvention::NegatedA => proof_a,
+        ProverConvention::RawA => -proof_a,
+    };
+    let g1_points = [
+        proof_a,
+        prepared_inputs.into(),
+        g1_from_be(proof_c)?,
+        g1_from_be(&verifyingkey.vk_alpha_g1)?,
+    ];
+    let g2_points = [
+        g2_from_be(proof_b)?,
+        g2_from_be(&verifyingkey.vk_gamma_g2)?,
+        g2_from_be(&verifyingkey.vk_delta_g2)?,
+        g2_from_be(&verifyingkey.vk_beta_g2)?,
+    ];
+    if Bn254::multi_pairing(g1_points, g2_points).is_zero() {
+        Ok(())
+    } else {
+        Err(Groth16Error::ProofVerificationFailed)
+    }
+}
+
+/// Combines the results of the syscall and the arkworks path
+///
+/// # Returns
+/// * `Ok(())` - If both accept
+/// * `Err(Groth16Error::VerifierDisagreement)` - If exactly one accepts
+/// * The syscall path's error - If both reject
+pub fn require_agreement(
+    syscall_result: Result<(), Groth16Error>,
+    arkworks_result: Result<(), Groth16Error>,
+) -> Result<(), Groth16Error> {
+    match (syscall_result, arkworks_result) {
+        (Ok(()), Ok(())) => Ok(()),
+        (Err(error), Err(_)) => Err(error),
+        _ => Err(Groth16Error::VerifierDisagreement),
+    }
+}
+
+#[cfg(all(test, feature = "test-vectors"))]
+mod tests {
+    use super::*;
+    use crate::bn254::PinocchioSyscalls;
+    use crate::curve::Bn254With;
+    use crate::groth16::CurveVerifier;
+    use crate::mock::{clear_faults, inject_fault, Fault};
+    use crate::test_vectors::generate;
+
+    #[test]
+    fn paths_must_agree() {
+        let vector = generate(0);
+        let vk = vector.verifying_key();
+        // Faults are injected into the mock syscalls, whatever the default backend
+        let mock_vk = vk.with_backend::<PinocchioSyscalls>();
+        let inputs: [[u8; 32]; 2] = vector.public_inputs.clone().try_into().unwrap();
+        let verifier = || {
+            CurveVerifier::<2, Bn254With<PinocchioSyscalls>>::new(
+                &vector.proof_a,
+                &vector.proof_b,
+                &vector.proof_c,
+                &inputs,
+                &mock_vk,
+            )
+            .unwrap()
+        };
+        verifier().verify_paranoid().unwrap();
+
+        let mut wrong_inputs = inputs;
+        wrong_inputs.swap(0, 1);
+        assert_eq!(
+            verify_arkworks(
+                &vector.proof_a,
+                &vector.proof_b,
+                &vector.proof_c,
+                &wrong_inputs,
+                &vk,
+                ProverConvention::NegatedA
+            ),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+
+        // A faulty pairing syscall rejects the valid proof
+        clear_faults();
+        inject_fault(2 * inputs.len(), Fault::CorruptOutput);
+        assert_eq!(
+            verifier().verify_paranoid(),
+            Err(Groth16Error::VerifierDisagreement)
+        );
+        clear_faults();
+    }
+}
@@ -0,0 +1,760 @@
+//! PLONK verification of snarkjs proofs over BN254
+//!
+//! Verifies proofs of the snarkjs `plonk` protocol with the alt_bn128
+//! syscalls. The challenges, the linearisation and the final opening check
+//! are those of the snarkjs verifier and of the `PlonkVerifier` contract it
+//! exports, so a proof generated with `snarkjs plonk prove` verifies
+//! unchanged:
+//!
+//! ```rust,ignore
+//! use pinocchio_groth16::plonk::{PlonkProof, PlonkVerifier};
+//!
+//! let proof = PlonkProof::from_bytes(&instruction_data[..PlonkProof::SIZE])?;
+//! PlonkVerifier::new(&proof, &public_inputs, &VERIFYINGKEY)?.verify()?;
+//! ```
+//!
+//! The proof is the 768-byte calldata of the contract, big-endian:
+//! `A | B | C | Z | T1 | T2 | T3 | Wxi | Wxiw` as G1 points followed by the
+//! evaluations `eval_a | eval_b | eval_c | eval_s1 | eval_s2 | eval_zw`. The
+//! verifying key is generated from snarkjs' `verification_key.json` by the
+//! [`vk_parser`](crate::vk_parser) in `CodegenMode::Deterministic`.
+//!
+//! Verification takes 18 G1 multiplications, 20 additions and a pairing of
+//! two pairs, about 125,000 compute units of syscalls plus the scalar field
+//! arithmetic in between.
+
+use crate::bn254::{alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing};
+use crate::errors::Groth16Error;
+use crate::groth16::is_less_than_bn254_field_size_be;
+use crate::hash::keccak256;
+use alloc::vec::Vec;
+use ark_bn254::{Fq, Fr};
+use ark_ff::{batch_inversion, AdditiveGroup, BigInteger, Field, One, PrimeField, Zero};
+
+/// Generator of G1, big-endian
+pub const G1_GENERATOR: [u8; 64] = {
+    let mut generator = [0u8; 64];
+    generator[31] = 1;
+    generator[63] = 2;
+    generator
+};
+
+/// Generator of G2, big-endian with coordinates ordered `c1 | c0`
+pub const G2_GENERATOR: [u8; 128] = [
+    0x19, 0x8e, 0x93, 0x93, 0x92, 0x0d, 0x48, 0x3a, 0x72, 0x60, 0xbf, 0xb7, 0x31, 0xfb, 0x5d, 0x25,
+    0xf1, 0xaa, 0x49, 0x33, 0x35, 0xa9, 0xe7, 0x12, 0x97, 0xe4, 0x85, 0xb7, 0xae, 0xf3, 0x12, 0xc2,
+    0x18, 0x00, 0xde, 0xef, 0x12, 0x1f, 0x1e, 0x76, 0x42, 0x6a, 0x00, 0x66, 0x5e, 0x5c, 0x44, 0x79,
+    0x67, 0x43, 0x22, 0xd4, 0xf7, 0x5e, 0xda, 0xdd, 0x46, 0xde, 0xbd, 0x5c, 0xd9, 0x92, 0xf6, 0xed,
+    0x09, 0x06, 0x89, 0xd0, 0x58, 0x5f, 0xf0, 0x75, 0xec, 0x9e, 0x99, 0xad, 0x69, 0x0c, 0x33, 0x95,
+    0xbc, 0x4b, 0x31, 0x33, 0x70, 0xb3, 0x8e, 0xf3, 0x55, 0xac, 0xda, 0xdc, 0xd1, 0x22, 0x97, 0x5b,
+    0x12, 0xc8, 0x5e, 0xa5, 0xdb, 0x8c, 0x6d, 0xeb, 0x4a, 0xab, 0x71, 0x80, 0x8d, 0xcb, 0x40, 0x8f,
+    0xe3, 0xd1, 0xe7, 0x69, 0x0c, 0x43, 0xd3, 0x7b, 0x4c, 0xe6, 0xcc, 0x01, 0x66, 0xfa, 0x7d, 0xaa,
+];
+
+/// A snarkjs PLONK verifying key
+#[derive(PartialEq, Eq, Debug)]
+pub struct PlonkVerifyingkey {
+    pub nr_pubinputs: usize,
+    /// Log2 of the domain size
+    pub power: u32,
+    pub k1: [u8; 32],
+    pub k2: [u8; 32],
+    /// Generator of the domain, `w` in snarkjs
+    pub omega: [u8; 32],
+    pub qm: [u8; 64],
+    pub ql: [u8; 64],
+    pub qr: [u8; 64],
+    pub qo: [u8; 64],
+    pub qc: [u8; 64],
+    pub s1: [u8; 64],
+    pub s2: [u8; 64],
+    pub s3: [u8; 64],
+    /// `[tau]_2` of the setup
+    pub x_2: [u8; 128],
+}
+
+/// A snarkjs PLONK proof
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PlonkProof {
+    pub a: [u8; 64],
+    pub b: [u8; 64],
+    pub c: [u8; 64],
+    pub z: [u8; 64],
+    pub t1: [u8; 64],
+    pub t2: [u8; 64],
+    pub t3: [u8; 64],
+    pub wxi: [u8; 64],
+    pub wxiw: [u8; 64],
+    pub eval_a: [u8; 32],
+    pub eval_b: [u8; 32],
+    pub eval_c: [u8; 32],
+    pub eval_s1: [u8; 32],
+    pub eval_s2: [u8; 32],
+    pub eval_zw: [u8; 32],
+}
+
+impl PlonkProof {
+    /// Size of the encoded proof
+    pub const SIZE: usize = 9 * 64 + 6 * 32;
+
+    /// Parses the big-endian encoding of the snarkjs contract calldata
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::ProofConversionError)` - If `bytes` is not [`Self::SIZE`] bytes long
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Groth16Error> {
+        if bytes.len() != Self::SIZE {
+            return Err(Groth16Error::ProofConversionError);
+        }
+        let g1 = |i: usize| -> [u8; 64] { bytes[i * 64..(i + 1) * 64].try_into().unwrap() };
+        let eval = |i: usize| -> [u8; 32] {
+            bytes[9 * 64 + i * 32..9 * 64 + (i + 1) * 32]
+                .try_into()
+                .unwrap()
+        };
+        Ok(PlonkProof {
+            a: g1(0),
+            b: g1(1),
+            c: g1(2),
+            z: g1(3),
+            t1: g1(4),
+            t2: g1(5),
+            t3: g1(6),
+            wxi: g1(7),
+            wxiw: g1(8),
+            eval_a: eval(0),
+            eval_b: eval(1),
+            eval_c: eval(2),
+            eval_s1: eval(3),
+            eval_s2: eval(4),
+            eval_zw: eval(5),
+        })
+    }
+
+    /// Writes the encoding parsed by [`Self::from_bytes`]
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0u8; Self::SIZE];
+        let parts: [&[u8]; 15] = [
+            &self.a,
+            &self.b,
+            &self.c,
+            &self.z,
+            &self.t1,
+            &self.t2,
+            &self.t3,
+            &self.wxi,
+            &self.wxiw,
+            &self.eval_a,
+            &self.eval_b,
+            &self.eval_c,
+            &self.eval_s1,
+            &self.eval_s2,
+            &self.eval_zw,
+        ];
+        let mut offset = 0;
+        for part in parts {
+            bytes[offset..offset + part.len()].copy_from_slice(part);
+            offset += part.len();
+        }
+        bytes
+    }
+}
+
+/// Fiat-Shamir transcript of snarkjs' `Keccak256Transcript`
+///
+/// Points are hashed as big-endian `x | y`, the point at infinity with the
+/// infinity flag `0x40` in the first byte as ffjavascript serializes it.
+/// Challenges are the keccak256 hash reduced modulo the scalar field, and
+/// each challenge starts a new transcript.
+struct Transcript {
+    data: Vec<u8>,
+}
+
+impl Transcript {
+    fn new() -> Self {
+        Transcript { data: Vec::new() }
+    }
+
+    fn add_point(&mut self, point: &[u8; 64]) {
+        if *point == [0u8; 64] {
+            let mut infinity = [0u8; 64];
+            infinity[0] = 0x40;
+            self.data.extend_from_slice(&infinity);
+        } else {
+            self.data.extend_from_slice(point);
+        }
+    }
+
+    fn add_scalar(&mut self, scalar: &Fr) {
+        self.data.extend_from_slice(&fr_to_be(scalar));
+    }
+
+    fn challenge(&mut self) -> Fr {
+        let hash = keccak256(&[&self.data]);
+        self.data.clear();
+        Fr::from_be_bytes_mod_order(&hash)
+    }
+}
+
+fn fr_to_be(scalar: &Fr) -> [u8; 32] {
+    scalar.into_bigint().to_bytes_be().try_into().unwrap()
+}
+
+/// Parses a canonical big-endian scalar
+fn fr_from_be(bytes: &[u8; 32], error: Groth16Error) -> Result<Fr, Groth16Error> {
+    if !is_less_than_bn254_field_size_be(bytes) {
+        return Err(error);
+    }
+    Ok(Fr::from_be_bytes_mod_order(bytes))
+}
+
+fn g1_add(left: &[u8; 64], right: &[u8; 64]) -> Result<[u8; 64], Groth16Error> {
+    alt_bn128_addition(&[&left[..], &right[..]].concat())?[..]
+        .try_into()
+        .map_err(|_| Groth16Error::PreparingInputsG1AdditionFailed(0))
+}
+
+/// Computes the sum of `scalar * point` over `terms`
+fn linear_combination(terms: &[(&[u8; 64], Fr)]) -> Result<[u8; 64], Groth16Error> {
+    let mut sum = [0u8; 64];
+    for (point, scalar) in terms {
+        let product: [u8; 64] = if scalar.is_one() {
+            **point
+        } else {
+            alt_bn128_multiplication(&[&point[..], &fr_to_be(scalar)].concat())?[..]
+                .try_into()
+                .map_err(|_| Groth16Error::PreparingInputsG1MulFailed(0))?
+        };
+        sum = g1_add(&sum, &product)?;
+    }
+    Ok(sum)
+}
+
+fn g1_negate(point: &[u8; 64]) -> [u8; 64] {
+    let y = -Fq::from_be_bytes_mod_order(&point[32..]);
+    let mut negated = *point;
+    negated[32..].copy_from_slice(&y.into_bigint().to_bytes_be());
+    negated
+}
+
+struct Challenges {
+    beta: Fr,
+    gamma: Fr,
+    alpha: Fr,
+    xi: Fr,
+    v: [Fr; 5],
+    u: Fr,
+}
+
+/// The proof's evaluations
+struct Evaluations {
+    a: Fr,
+    b: Fr,
+    c: Fr,
+    s1: Fr,
+    s2: Fr,
+    zw: Fr,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct PlonkVerifier<'a, const NR_INPUTS: usize> {
+    proof: &'a PlonkProof,
+    public_inputs: &'a [[u8; 32]; NR_INPUTS],
+    verifyingkey: &'a PlonkVerifyingkey,
+}
+
+impl<const NR_INPUTS: usize> PlonkVerifier<'_, NR_INPUTS> {
+    pub fn new<'a>(
+        proof: &'a PlonkProof,
+        public_inputs: &'a [[u8; 32]; NR_INPUTS],
+        verifyingkey: &'a PlonkVerifyingkey,
+    ) -> Result<PlonkVerifier<'a, NR_INPUTS>, Groth16Error> {
+        if verifyingkey.nr_pubinputs != NR_INPUTS {
+            return Err(Groth16Error::InvalidPublicInputsLength);
+        }
+        Ok(PlonkVerifier {
+            proof,
+            public_inputs,
+            verifyingkey,
+        })
+    }
+
+    /// Verifies the proof
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If a public
+    ///   input is not smaller than the scalar field modulus
+    /// * `Err(Groth16Error::ProofVerificationFailed)` - If an evaluation is
+    ///   not smaller than the scalar field modulus or the proof is invalid
+    pub fn verify(&self) -> Result<(), Groth16Error> {
+        let proof = self.proof;
+        let vk = self.verifyingkey;
+        let public_inputs = self
+            .public_inputs
+            .iter()
+            .map(|input| fr_from_be(input, Groth16Error::PublicInputGreaterThanFieldSize))
+            .collect::<Result<Vec<_>, _>>()?;
+        let eval = |bytes| fr_from_be(bytes, Groth16Error::ProofVerificationFailed);
+        let evals = Evaluations {
+            a: eval(&proof.eval_a)?,
+            b: eval(&proof.eval_b)?,
+            c: eval(&proof.eval_c)?,
+            s1: eval(&proof.eval_s1)?,
+            s2: eval(&proof.eval_s2)?,
+            zw: eval(&proof.eval_zw)?,
+        };
+        let k1 = eval(&vk.k1)?;
+        let k2 = eval(&vk.k2)?;
+        let omega = eval(&vk.omega)?;
+        let ch = self.challenges(&public_inputs, &evals);
+
+        // Vanishing polynomial and Lagrange polynomials of the public inputs at xi
+        let mut xin = ch.xi;
+        let mut domain_size = Fr::one();
+        for _ in 0..vk.power {
+            xin.square_in_place();
+            domain_size.double_in_place();
+        }
+        let zh = xin - Fr::one();
+        let mut roots = Vec::with_capacity(NR_INPUTS.max(1));
+        let mut root = Fr::one();
+        for _ in 0..NR_INPUTS.max(1) {
+            roots.push(root);
+            root *= omega;
+        }
+        let mut lagrange: Vec<Fr> = roots
+            .iter()
+            .map(|root| domain_size * (ch.xi - root))
+            .collect();
+        if lagrange.iter().any(Fr::is_zero) {
+            return Err(Groth16Error::ProofVerificationFailed);
+        }
+        batch_inversion(&mut lagrange);
+        for (l, root) in lagrange.iter_mut().zip(&roots) {
+            *l *= *root * zh;
+        }
+        let l1 = lagrange[0];
+        let pi = -public_inputs
+            .iter()
+            .zip(&lagrange)
+            .map(|(input, l)| *input * l)
+            .sum::<Fr>();
+
+        let alpha2 = ch.alpha.square();
+        let e3a = evals.a + ch.beta * evals.s1 + ch.gamma;
+        let e3b = evals.b + ch.beta * evals.s2 + ch.gamma;
+        let r0 = pi - l1 * alpha2 - e3a * e3b * (evals.c + ch.gamma) * evals.zw * ch.alpha;
+
+        let beta_xi = ch.beta * ch.xi;
+        let z_scalar = (evals.a + beta_xi + ch.gamma)
+            * (evals.b + beta_xi * k1 + ch.gamma)
+            * (evals.c + beta_xi * k2 + ch.gamma)
+            * ch.alpha
+            + l1 * alpha2
+            + ch.u;
+        let s3_scalar = e3a * e3b * ch.alpha * ch.beta * evals.zw;
+        let [v1, v2, v3, v4, v5] = ch.v;
+        let e = -r0
+            + v1 * evals.a
+            + v2 * evals.b
+            + v3 * evals.c
+            + v4 * evals.s1
+            + v5 * evals.s2
+            + ch.u * evals.zw;
+
+        // [Wxi + u * Wxiw]_1 and [xi * Wxi + u * xi * w * Wxiw + F - E]_1
+        let a1 = linear_combination(&[(&proof.wxi, Fr::one()), (&proof.wxiw, ch.u)])?;
+        let b1 = linear_combination(&[
+            (&proof.wxi, ch.xi),
+            (&proof.wxiw, ch.u * ch.xi * omega),
+            (&vk.qm, evals.a * evals.b),
+            (&vk.ql, evals.a),
+            (&vk.qr, evals.b),
+            (&vk.qo, evals.c),
+            (&vk.qc, Fr::one()),
+            (&proof.z, z_scalar),
+            (&vk.s3, -s3_scalar),
+            (&proof.t1, -zh),
+            (&proof.t2, -zh * xin),
+            (&proof.t3, -zh * xin.square()),
+            (&proof.a, v1),
+            (&proof.b, v2),
+            (&proof.c, v3),
+            (&vk.s1, v4),
+            (&vk.s2, v5),
+            (&G1_GENERATOR, -e),
+        ])?;
+
+        let pairing_input = [
+            g1_negate(&a1).as_slice(),
+            vk.x_2.as_slice(),
+            b1.as_slice(),
+            G2_GENERATOR.as_slice(),
+        ]
+        .concat();
+        let pairing_res = alt_bn128_pairing(&pairing_input)?;
+        if pairing_res[31] != 1 {
+            return Err(Groth16Error::ProofVerificationFailed);
+        }
+        Ok(())
+    }
+
+    fn challenges(&self, public_inputs: &[Fr], evals: &Evaluations) -> Challenges {
+        let proof = self.proof;
+        let vk = self.verifyingkey;
+        let mut transcript = Transcript::new();
+        for point in [
+            &vk.qm, &vk.ql, &vk.qr, &vk.qo, &vk.qc, &vk.s1, &vk.s2, &vk.s3,
+        ] {
+            transcript.add_point(point);
+        }
+        for input in public_inputs {
+            transcript.add_scalar(input);
+        }
+        for point in [&proof.a, &proof.b, &proof.c] {
+            transcript.add_point(point);
+        }
+        let beta = transcript.challenge();
+
+        transcript.add_scalar(&beta);
+        let gamma = transcript.challenge();
+
+        transcript.add_scalar(&beta);
+        transcript.add_scalar(&gamma);
+        transcript.add_point(&proof.z);
+        let alpha = transcript.challenge();
+
+        transcript.add_scalar(&alpha);
+        for point in [&proof.t1, &proof.t2, &proof.t3] {
+            transcript.add_point(point);
+        }
+        let xi = transcript.challenge();
+
+        transcript.add_scalar(&xi);
+        for eval in [
+            &evals.a, &evals.b, &evals.c, &evals.s1, &evals.s2, &evals.zw,
+        ] {
+            transcript.add_scalar(eval);
+        }
+        let v1 = transcript.challenge();
+        let mut v = [v1; 5];
+        for i in 1..5 {
+            v[i] = v[i - 1] * v1;
+        }
+
+        transcript.add_point(&proof.wxi);
+        transcript.add_point(&proof.wxiw);
+        let u = transcript.challenge();
+
+        Challenges {
+            beta,
+            gamma,
+            alpha,
+            xi,
+            v,
+            u,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{G1Affine, G2Affine};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::{
+        DenseUVPolynomial, EvaluationDomain, Evaluations as DomainEvaluations, Polynomial,
+        Radix2EvaluationDomain,
+    };
+
+    type Poly = DensePolynomial<Fr>;
+
+    const N: usize = 4;
+
+    fn g1_be(point: G1Affine) -> [u8; 64] {
+        let (x, y) = point.xy().unwrap();
+        [x.into_bigint().to_bytes_be(), y.into_bigint().to_bytes_be()]
+            .concat()
+            .try_into()
+            .unwrap()
+    }
+
+    fn g2_be(point: G2Affine) -> [u8; 128] {
+        let (x, y) = point.xy().unwrap();
+        [x.c1, x.c0, y.c1, y.c0]
+            .iter()
+            .flat_map(|c| c.into_bigint().to_bytes_be())
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap()
+    }
+
+    fn constant(value: Fr) -> Poly {
+        Poly::from_coefficients_vec(vec![value])
+    }
+
+    /// A snarkjs style PLONK prover for a four row circuit, with a known tau
+    ///
+    /// Rows: the public input `x`, `a * b = x`, `a + b = 8` and `7 = 7`,
+    /// with copy constraints between the rows. No blinding.
+    fn prove(public_input: u64) -> (PlonkVerifyingkey, PlonkProof) {
+        let domain = Radix2EvaluationDomain::<Fr>::new(N).unwrap();
+        let omega = domain.group_gen();
+        let tau = Fr::from(0x1234_5678u64);
+        let commit = |p: &Poly| g1_be((G1Affine::generator() * p.evaluate(&tau)).into_affine());
+        let interpolate = |values: [i64; N]| {
+            let values = values
+                .iter()
+                .map(|v| {
+                    if *v < 0 {
+                        -Fr::from(v.unsigned_abs())
+                    } else {
+                        Fr::from(*v as u64)
+                    }
+                })
+                .collect();
+            DomainEvaluations::from_vec_and_domain(values, domain).interpolate()
+        };
+        let x = public_input as i64;
+        let wires = [[x, 3, 3, 7], [0, 5, 5, 0], [0, 15, 8, 0]];
+        let (a, b, c) = (
+            interpolate(wires[0]),
+            interpolate(wires[1]),
+            interpolate(wires[2]),
+        );
+        let qm = interpolate([0, 1, 0, 0]);
+        let ql = interpolate([1, 0, 1, 1]);
+        let qr = interpolate([0, 0, 1, 0]);
+        let qo = interpolate([0, -1, -1, 0]);
+        let qc = interpolate([0, 0, 0, -7]);
+        let pi = interpolate([-x, 0, 0, 0]);
+        let l1 = interpolate([1, 0, 0, 0]);
+
+        // Wire (column, row) is labelled k[column] * omega^row
+        let (k1, k2) = (Fr::from(2u8), Fr::from(3u8));
+        let k = [Fr::one(), k1, k2];
+        let label = |(column, row): (usize, usize)| k[column] * omega.pow([row as u64]);
+        let copies = [((0, 0), (2, 1)), ((0, 1), (0, 2)), ((1, 1), (1, 2))];
+        let sigma = |column: usize, row: usize| {
+            let wire = (column, row);
+            let target = copies
+                .iter()
+                .find_map(|&(p, q)| match wire {
+                    w if w == p => Some(q),
+                    w if w == q => Some(p),
+                    _ => None,
+                })
+                .unwrap_or(wire);
+            label(target)
+        };
+        let sigma_values = |column| (0..N).map(|row| sigma(column, row)).collect::<Vec<_>>();
+        let s = [0, 1, 2].map(|column| {
+            DomainEvaluations::from_vec_and_domain(sigma_values(column), domain).interpolate()
+        });
+
+        let vk = PlonkVerifyingkey {
+            nr_pubinputs: 1,
+            power: 2,
+            k1: fr_to_be(&k1),
+            k2: fr_to_be(&k2),
+            omega: fr_to_be(&omega),
+            qm: commit(&qm),
+            ql: commit(&ql),
+            qr: commit(&qr),
+            qo: commit(&qo),
+            qc: commit(&qc),
+            s1: commit(&s[0]),
+            s2: commit(&s[1]),
+            s3: commit(&s[2]),
+            x_2: g2_be((G2Affine::generator() * tau).into_affine()),
+        };
+
+        let mut transcript = Transcript::new();
+        for point in [
+            &vk.qm, &vk.ql, &vk.qr, &vk.qo, &vk.qc, &vk.s1, &vk.s2, &vk.s3,
+        ] {
+            transcript.add_point(point);
+        }
+        transcript.add_scalar(&Fr::from(public_input));
+        let (a_commit, b_commit, c_commit) = (commit(&a), commit(&b), commit(&c));
+        for point in [&a_commit, &b_commit, &c_commit] {
+            transcript.add_point(point);
+        }
+        let beta = transcript.challenge();
+        transcript.add_scalar(&beta);
+        let gamma = transcript.challenge();
+
+        // Grand product of the permutation argument
+        let mut z_values = vec![Fr::one()];
+        for row in 0..N {
+            let mut quotient = Fr::one();
+            for (column, values) in wires.iter().enumerate() {
+                let value = Fr::from(values[row] as u64);
+                quotient *= (value + beta * label((column, row)) + gamma)
+                    / (value + beta * sigma(column, row) + gamma);
+            }
+            z_values.push(z_values[row] * quotient);
+        }
+        assert_eq!(z_values.pop(), Some(Fr::one()));
+        let z = DomainEvaluations::from_vec_and_domain(z_values, domain).interpolate();
+        let z_commit = commit(&z);
+        transcript.add_scalar(&beta);
+        transcript.add_scalar(&gamma);
+        transcript.add_point(&z_commit);
+        let alpha = transcript.challenge();
+
+        // Quotient polynomial
+        let x_poly = Poly::from_coefficients_vec(vec![Fr::zero(), Fr::one()]);
+        let z_omega = Poly::from_coefficients_vec(
+            z.coeffs
+                .iter()
+                .enumerate()
+                .map(|(i, coeff)| *coeff * omega.pow([i as u64]))
+                .collect(),
+        );
+        let g = constant(gamma);
+        let gate =
+            &(&(&a * &b) * &qm) + &(&(&a * &ql) + &(&(&b * &qr) + &(&(&c * &qo) + &(&qc + &pi))));
+        let identity = &(&(&(&a + &(&x_poly * beta)) + &g)
+            * &(&(&b + &(&x_poly * (beta * k1))) + &g))
+            * &(&(&(&c + &(&x_poly * (beta * k2))) + &g) * &z);
+        let permuted = &(&(&(&a + &(&s[0] * beta)) + &g) * &(&(&b + &(&s[1] * beta)) + &g))
+            * &(&(&(&c + &(&s[2] * beta)) + &g) * &z_omega);
+        let numerator = &(&gate + &(&(&identity - &permuted) * alpha))
+            + &(&(&(&z - &constant(Fr::one())) * &l1) * alpha.square());
+        let (t, remainder) = numerator.divide_by_vanishing_poly(domain);
+        assert!(remainder.is_zero());
+        let mut t_coeffs = t.coeffs.clone();
+        t_coeffs.resize(3 * N, Fr::zero());
+        let [t1, t2, t3] =
+            [0, 1, 2].map(|i| Poly::from_coefficients_slice(&t_coeffs[i * N..(i + 1) * N]));
+        let t_commits = [commit(&t1), commit(&t2), commit(&t3)];
+        transcript.add_scalar(&alpha);
+        for point in &t_commits {
+            transcript.add_point(point);
+        }
+        let xi = transcript.challenge();
+
+        let evals = Evaluations {
+            a: a.evaluate(&xi),
+            b: b.evaluate(&xi),
+            c: c.evaluate(&xi),
+            s1: s[0].evaluate(&xi),
+            s2: s[1].evaluate(&xi),
+            zw: z.evaluate(&(xi * omega)),
+        };
+        transcript.add_scalar(&xi);
+        for eval in [
+            &evals.a, &evals.b, &evals.c, &evals.s1, &evals.s2, &evals.zw,
+        ] {
+            transcript.add_scalar(eval);
+        }
+        let v1 = transcript.challenge();
+
+        // Linearisation polynomial, zero at xi
+        let xin = xi.pow([N as u64]);
+        let zh = xin - Fr::one();
+        let l1_xi = l1.evaluate(&xi);
+        let e3a = evals.a + beta * evals.s1 + gamma;
+        let e3b = evals.b + beta * evals.s2 + gamma;
+        let r0 = pi.evaluate(&xi)
+            - l1_xi * alpha.square()
+            - alpha * e3a * e3b * (evals.c + gamma) * evals.zw;
+        let z_scalar = (evals.a + beta * xi + gamma)
+            * (evals.b + beta * k1 * xi + gamma)
+            * (evals.c + beta * k2 * xi + gamma)
+            * alpha
+            + l1_xi * alpha.square();
+        let r = &(&(&(&qm * (evals.a * evals.b)) + &(&ql * evals.a))
+            + &(&(&qr * evals.b) + &(&qo * evals.c)))
+            + &(&(&qc + &(&z * z_scalar))
+                - &(&(&s[2] * (e3a * e3b * alpha * beta * evals.zw))
+                    + &(&(&t1 + &(&(&t2 * xin) + &(&t3 * xin.square()))) * zh)));
+        let r = &r + &constant(r0);
+        assert!(r.evaluate(&xi).is_zero());
+
+        let mut opened = r;
+        let mut v = v1;
+        for (poly, eval) in [
+            (&a, evals.a),
+            (&b, evals.b),
+            (&c, evals.c),
+            (&s[0], evals.s1),
+            (&s[1], evals.s2),
+        ] {
+            opened = &opened + &(&(poly - &constant(eval)) * v);
+            v *= v1;
+        }
+        let linear = |root: Fr| Poly::from_coefficients_vec(vec![-root, Fr::one()]);
+        let wxi = &opened / &linear(xi);
+        let wxiw = &(&z - &constant(evals.zw)) / &linear(xi * omega);
+
+        let proof = PlonkProof {
+            a: a_commit,
+            b: b_commit,
+            c: c_commit,
+            z: z_commit,
+            t1: t_commits[0],
+            t2: t_commits[1],
+            t3: t_commits[2],
+            wxi: commit(&wxi),
+            wxiw: commit(&wxiw),
+            eval_a: fr_to_be(&evals.a),
+            eval_b: fr_to_be(&evals.b),
+            eval_c: fr_to_be(&evals.c),
+            eval_s1: fr_to_be(&evals.s1),
+            eval_s2: fr_to_be(&evals.s2),
+            eval_zw: fr_to_be(&evals.zw),
+        };
+        (vk, proof)
+    }
+
+    #[test]
+    fn verifies_plonk_proof() {
+        let (vk, proof) = prove(15);
+        let inputs = [fr_to_be(&Fr::from(15u8))];
+        let proof = PlonkProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(
+            PlonkVerifier::new(&proof, &inputs, &vk).unwrap().verify(),
+            Ok(())
+        );
+
+        let wrong_input = [fr_to_be(&Fr::from(16u8))];
+        assert_eq!(
+            PlonkVerifier::new(&proof, &wrong_input, &vk)
+                .unwrap()
+                .verify(),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+        let mut tampered = proof.clone();
+        tampered.eval_zw = fr_to_be(&(Fr::from_be_bytes_mod_order(&proof.eval_zw) + Fr::one()));
+        assert_eq!(
+            PlonkVerifier::new(&tampered, &inputs, &vk)
+                .unwrap()
+                .verify(),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+        let mut non_canonical = proof.clone();
+        non_canonical.eval_a = [0xff; 32];
+        assert_eq!(
+            PlonkVerifier::new(&non_canonical, &inputs, &vk)
+                .unwrap()
+                .verify(),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+        assert_eq!(
+            PlonkVerifier::new(&proof, &[], &vk),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+        assert_eq!(
+            PlonkProof::from_bytes(&[0u8; 256]),
+            Err(Groth16Error::ProofConversionError)
+        );
+    }
+
+    #[test]
+    fn generators_match_arkworks() {
+        assert_eq!(G1_GENERATOR, g1_be(G1Affine::generator()));
+        assert_eq!(G2_GENERATOR, g2_be(G2Affine::generator()));
+    }
+}
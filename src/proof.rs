@@ -0,0 +1,427 @@
+//! Typed proofs and public inputs
+//!
+//! [`ProofA`], [`ProofB`] and [`ProofC`] wrap the big-endian points the
+//! verifier takes, so a and c can't be swapped, and [`PublicInputs`] only
+//! holds scalars smaller than the field modulus, which catches most inputs
+//! passed little-endian. With the `borsh` and `serde` features the types
+//! implement their traits, encoded as the raw bytes.
+
+use crate::curve::{Bn254, PairingCurve};
+use crate::errors::Groth16Error;
+use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+
+/// Size of an uncompressed proof, `a (64) | b (128) | c (64)`
+pub const PROOF_SIZE: usize = 256;
+
+/// Flags in the top bits of the first byte of gnark's point encodings
+pub(crate) const GNARK_FLAG_MASK: u8 = 0b11 << 6;
+const GNARK_INFINITY: u8 = 0b01 << 6;
+
+macro_rules! proof_point {
+    ($(#[$doc:meta])* $name:ident, $size:literal, $error:ident) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub struct $name(pub [u8; $size]);
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl TryFrom<&[u8]> for $name {
+            type Error = Groth16Error;
+
+            fn try_from(bytes: &[u8]) -> Result<Self, Groth16Error> {
+                bytes
+                    .try_into()
+                    .map($name)
+                    .map_err(|_| Groth16Error::$error)
+            }
+        }
+
+        #[cfg(feature = "borsh")]
+        impl borsh::BorshSerialize for $name {
+            fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+                writer.write_all(&self.0)
+            }
+        }
+
+        #[cfg(feature = "borsh")]
+        impl borsh::BorshDeserialize for $name {
+            fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+                let mut bytes = [0u8; $size];
+                reader.read_exact(&mut bytes)?;
+                Ok($name(bytes))
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_bytes(&self.0)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_bytes(BytesVisitor::<$size>).map($name)
+            }
+        }
+    };
+}
+
+proof_point!(
+    /// Proof a, negated, a big-endian G1 point
+    ProofA,
+    64,
+    InvalidG1Length
+);
+proof_point!(
+    /// Proof b, a big-endian G2 point
+    ProofB,
+    128,
+    InvalidG2Length
+);
+proof_point!(
+    /// Proof c, a big-endian G1 point
+    ProofC,
+    64,
+    InvalidG1Length
+);
+
+/// Groth16 proof in the encoding of [`PROOF_SIZE`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Proof {
+    pub a: ProofA,
+    pub b: ProofB,
+    pub c: ProofC,
+}
+
+impl Proof {
+    pub fn to_bytes(&self) -> [u8; PROOF_SIZE] {
+        let mut bytes = [0u8; PROOF_SIZE];
+        bytes[..64].copy_from_slice(&self.a.0);
+        bytes[64..192].copy_from_slice(&self.b.0);
+        bytes[192..].copy_from_slice(&self.c.0);
+        bytes
+    }
+
+    /// Reads a proof serialized by gnark, with `WriteRawTo` or `MarshalSolidity`
+    ///
+    /// gnark writes `Ar | Bs | Krs` uncompressed and big-endian with G2
+    /// coordinates ordered `c1 | c0` like the syscalls, but keeps `Ar` as
+    /// proven, so it is negated here. The proof then verifies with
+    /// [`Proof::verify`] against keys exported by gnark, see
+    /// `vk_parser::parse_gnark_vk_to_rust_string`. `WriteRawTo` appends the
+    /// commitments of circuits using them, only an empty list followed by
+    /// an optional proof of knowledge at infinity is accepted, see
+    /// [`crate::commitment`] for proofs with commitments.
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::ProofConversionError)` - If the bytes are too
+    ///   short, a point is flagged compressed or at infinity, the proof has
+    ///   commitments or `Ar` is not canonical
+    pub fn from_gnark_bytes(bytes: &[u8]) -> Result<Self, Groth16Error> {
+        if bytes.len() < PROOF_SIZE {
+            return Err(Groth16Error::ProofConversionError);
+        }
+        let (points, commitments) = bytes.split_at(PROOF_SIZE);
+        // The top two bits of each point's first byte are gnark's encoding flags
+        if [points[0], points[64], points[192]]
+            .iter()
+            .any(|byte| byte & GNARK_FLAG_MASK != 0)
+        {
+            return Err(Groth16Error::ProofConversionError);
+        }
+        let no_commitments = match commitments.len() {
+            0 => true,
+            4 => commitments == [0u8; 4],
+            68 => {
+                commitments[..4] == [0u8; 4]
+                    && commitments[4] == GNARK_INFINITY
+                    && commitments[5..].iter().all(|byte| *byte == 0)
+            }
+            _ => false,
+        };
+        if !no_commitments {
+            return Err(Groth16Error::ProofConversionError);
+        }
+        let mut proof = Proof::try_from(points)?;
+        proof.a =
+            ProofA(Bn254::g1_negate(&proof.a.0).map_err(|_| Groth16Error::ProofConversionError)?);
+        Ok(proof)
+    }
+
+    /// The proof in gnark's `MarshalSolidity` layout, the inverse of
+    /// [`Proof::from_gnark_bytes`]
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::ProofConversionError)` - If proof a is not canonical
+    pub fn to_gnark_bytes(&self) -> Result<[u8; PROOF_SIZE], Groth16Error> {
+        let mut bytes = self.to_bytes();
+        let a = Bn254::g1_negate(&self.a.0).map_err(|_| Groth16Error::ProofConversionError)?;
+        bytes[..64].copy_from_slice(&a);
+        Ok(bytes)
+    }
+
+    /// Verifies the proof, checking the public inputs against the field size
+    pub fn verify<const NR_INPUTS: usize>(
+        &self,
+        public_inputs: &PublicInputs<NR_INPUTS>,
+        verifyingkey: &Groth16Verifyingkey,
+    ) -> Result<(), Groth16Error> {
+        Groth16Verifier::new(
+            &self.a.0,
+            &self.b.0,
+            &self.c.0,
+            &public_inputs.0,
+            verifyingkey,
+        )?
+        .verify()
+    }
+}
+
+impl TryFrom<&[u8]> for Proof {
+    type Error = Groth16Error;
+
+    /// # Returns
+    /// * `Err(Groth16Error::ProofConversionError)` - If `bytes` is not [`PROOF_SIZE`] long
+    fn try_from(bytes: &[u8]) -> Result<Self, Groth16Error> {
+        if bytes.len() != PROOF_SIZE {
+            return Err(Groth16Error::ProofConversionError);
+        }
+        Ok(Proof {
+            a: ProofA::try_from(&bytes[..64])?,
+            b: ProofB::try_from(&bytes[64..192])?,
+            c: ProofC::try_from(&bytes[192..])?,
+        })
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for Proof {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for Proof {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        Ok(Proof {
+            a: ProofA::deserialize_reader(reader)?,
+            b: ProofB::deserialize_reader(reader)?,
+            c: ProofC::deserialize_reader(reader)?,
+        })
+    }
+}
+
+/// `NR_INPUTS` big-endian public inputs, each smaller than the scalar field modulus
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicInputs<const NR_INPUTS: usize>([[u8; 32]; NR_INPUTS]);
+
+impl<const NR_INPUTS: usize> PublicInputs<NR_INPUTS> {
+    /// # Returns
+    /// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If an input is
+    ///   not smaller than the scalar field modulus
+    pub fn new(inputs: [[u8; 32]; NR_INPUTS]) -> Result<Self, Groth16Error> {
+        if !inputs.iter().all(Bn254::is_less_than_scalar_field_size_be) {
+            return Err(Groth16Error::PublicInputGreaterThanFieldSize);
+        }
+        Ok(PublicInputs(inputs))
+    }
+
+    pub fn as_array(&self) -> &[[u8; 32]; NR_INPUTS] {
+        &self.0
+    }
+}
+
+impl<const NR_INPUTS: usize> TryFrom<&[u8]> for PublicInputs<NR_INPUTS> {
+    type Error = Groth16Error;
+
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidPublicInputsLength)` - If `bytes` is not `32 * NR_INPUTS` long
+    /// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If an input is
+    ///   not smaller than the scalar field modulus
+    fn try_from(bytes: &[u8]) -> Result<Self, Groth16Error> {
+        let (inputs, rest) = bytes.as_chunks::<32>();
+        if inputs.len() != NR_INPUTS || !rest.is_empty() {
+            return Err(Groth16Error::InvalidPublicInputsLength);
+        }
+        Self::new(inputs.try_into().unwrap())
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl<const NR_INPUTS: usize> borsh::BorshSerialize for PublicInputs<NR_INPUTS> {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        self.0.iter().try_for_each(|input| writer.write_all(input))
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl<const NR_INPUTS: usize> borsh::BorshDeserialize for PublicInputs<NR_INPUTS> {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let mut inputs = [[0u8; 32]; NR_INPUTS];
+        for input in inputs.iter_mut() {
+            reader.read_exact(input)?;
+        }
+        Self::new(inputs).map_err(|_| {
+            borsh::io::Error::new(
+                borsh::io::ErrorKind::InvalidData,
+                "public input greater than field size",
+            )
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const NR_INPUTS: usize> serde::Serialize for PublicInputs<NR_INPUTS> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeTuple;
+        let mut tuple = serializer.serialize_tuple(NR_INPUTS)?;
+        for input in self.0.iter() {
+            tuple.serialize_element(input)?;
+        }
+        tuple.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const NR_INPUTS: usize> serde::Deserialize<'de> for PublicInputs<NR_INPUTS> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct InputsVisitor<const NR_INPUTS: usize>;
+
+        impl<'de, const NR_INPUTS: usize> serde::de::Visitor<'de> for InputsVisitor<NR_INPUTS> {
+            type Value = PublicInputs<NR_INPUTS>;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                write!(formatter, "{} public inputs", NR_INPUTS)
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut inputs = [[0u8; 32]; NR_INPUTS];
+                for (i, input) in inputs.iter_mut().enumerate() {
+                    *input = seq
+                        .next_element()?
+                        .ok_or_else(|| serde::de::Error::invalid_length(i, &self))?;
+                }
+                PublicInputs::new(inputs)
+                    .map_err(|_| serde::de::Error::custom("public input greater than field size"))
+            }
+        }
+
+        deserializer.deserialize_tuple(NR_INPUTS, InputsVisitor::<NR_INPUTS>)
+    }
+}
+
+/// Accepts `N` bytes, as a byte string or a sequence
+#[cfg(feature = "serde")]
+struct BytesVisitor<const N: usize>;
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::de::Visitor<'de> for BytesVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(formatter, "{} bytes", N)
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        bytes
+            .try_into()
+            .map_err(|_| E::invalid_length(bytes.len(), &self))
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = [0u8; N];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| serde::de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(serde::de::Error::invalid_length(N + 1, &self));
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(all(test, feature = "test-vectors"))]
+mod tests {
+    use super::*;
+    use crate::test_vectors::generate;
+
+    #[test]
+    fn typed_proof_roundtrips_and_verifies() {
+        let vector = generate(0);
+        let mut bytes = [0u8; PROOF_SIZE];
+        bytes[..64].copy_from_slice(&vector.proof_a);
+        bytes[64..192].copy_from_slice(&vector.proof_b);
+        bytes[192..].copy_from_slice(&vector.proof_c);
+        let proof = Proof::try_from(&bytes[..]).unwrap();
+        assert_eq!(proof.to_bytes(), bytes);
+        assert_eq!(
+            Proof::try_from(&bytes[1..]),
+            Err(Groth16Error::ProofConversionError)
+        );
+
+        let inputs: Vec<u8> = vector.public_inputs.concat();
+        let public_inputs = PublicInputs::<2>::try_from(&inputs[..]).unwrap();
+        proof
+            .verify(&public_inputs, &vector.verifying_key())
+            .unwrap();
+        assert_eq!(
+            PublicInputs::<2>::new([[0xff; 32]; 2]),
+            Err(Groth16Error::PublicInputGreaterThanFieldSize)
+        );
+        assert_eq!(
+            PublicInputs::<3>::try_from(&inputs[..]),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+
+        // gnark keeps proof a as proven and may append an empty commitment list
+        let gnark = proof.to_gnark_bytes().unwrap();
+        assert_eq!(gnark[..64], Bn254::g1_negate(&vector.proof_a).unwrap());
+        assert_eq!(Proof::from_gnark_bytes(&gnark), Ok(proof));
+        let mut raw = [&gnark[..], &[0u8; 68]].concat();
+        raw[PROOF_SIZE + 4] = GNARK_INFINITY;
+        assert_eq!(Proof::from_gnark_bytes(&raw), Ok(proof));
+        raw[PROOF_SIZE + 3] = 1;
+        assert_eq!(
+            Proof::from_gnark_bytes(&raw),
+            Err(Groth16Error::ProofConversionError)
+        );
+        let mut compressed = gnark;
+        compressed[0] |= 0b10 << 6;
+        assert_eq!(
+            Proof::from_gnark_bytes(&compressed),
+            Err(Groth16Error::ProofConversionError)
+        );
+
+        #[cfg(feature = "borsh")]
+        {
+            let encoded = borsh::to_vec(&(proof, public_inputs)).unwrap();
+            assert_eq!(encoded, [&bytes[..], &inputs[..]].concat());
+            assert_eq!(
+                borsh::from_slice::<(Proof, PublicInputs<2>)>(&encoded).unwrap(),
+                (proof, public_inputs)
+            );
+        }
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&(proof, public_inputs)).unwrap();
+            assert_eq!(
+                serde_json::from_str::<(Proof, PublicInputs<2>)>(&json).unwrap(),
+                (proof, public_inputs)
+            );
+        }
+    }
+}
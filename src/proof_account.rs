@@ -0,0 +1,412 @@
+//! Proof account helpers
+//!
+//! A proof (and optionally its public inputs) can be written into an account
+//! in one or more transactions and verified by a later instruction that only
+//! references the account. This keeps the state-changing instruction small
+//! and lets anyone crank verification of a submitted proof.
+//!
+//! Account layout:
+//!
+//! | offset | size  | field                                  |
+//! |--------|-------|----------------------------------------|
+//! | 0      | 8     | discriminator `PROOF_ACCOUNT_DISCRIMINATOR` |
+//! | 8      | 32    | authority allowed to write the account |
+//! | 40     | 8     | slot of finalization, u64 LE           |
+//! | 48     | 4     | number of public inputs, u32 LE        |
+//! | 52     | 1     | state, 0 = writing, 1 = finalized      |
+//! | 53     | 3     | padding                                |
+//! | 56     | 256   | proof a, b, c                          |
+//! | 312    | 32 * n| public inputs                          |
+
+use crate::errors::Groth16Error;
+use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+use crate::Pubkey;
+#[cfg(feature = "pinocchio")]
+use pinocchio::account_info::AccountInfo;
+
+/// Seed prefix of proof account PDAs: `[PROOF_ACCOUNT_SEED, authority, nonce]`
+pub const PROOF_ACCOUNT_SEED: &[u8] = b"proof";
+
+/// Discriminator stored in the first 8 bytes of a proof account
+pub const PROOF_ACCOUNT_DISCRIMINATOR: [u8; 8] = *b"g16proof";
+
+/// Offset of the proof bytes, writes of proof data start at this offset
+pub const PROOF_ACCOUNT_PROOF_OFFSET: usize = 56;
+
+/// Offset of the public inputs
+pub const PROOF_ACCOUNT_INPUTS_OFFSET: usize = PROOF_ACCOUNT_PROOF_OFFSET + 256;
+
+const STATE_WRITING: u8 = 0;
+const STATE_FINALIZED: u8 = 1;
+
+/// Returns the account size of a proof account storing `nr_inputs` public inputs
+pub const fn proof_account_size(nr_inputs: usize) -> usize {
+    PROOF_ACCOUNT_INPUTS_OFFSET + nr_inputs * 32
+}
+
+/// [`proof_account_size`], `None` if it overflows `usize`
+///
+/// For counts read from untrusted data, which overflow on 32-bit targets.
+pub const fn checked_proof_account_size(nr_inputs: usize) -> Option<usize> {
+    let Some(inputs_size) = nr_inputs.checked_mul(32) else {
+        return None;
+    };
+    inputs_size.checked_add(PROOF_ACCOUNT_INPUTS_OFFSET)
+}
+
+/// Initializes a zeroed account buffer as a proof account in writing state
+///
+/// # Arguments
+/// * `data` - Account data, at least `proof_account_size(nr_inputs)` bytes
+/// * `authority` - The only key allowed to write and finalize the account
+/// * `nr_inputs` - Number of public inputs stored alongside the proof, may be 0
+pub fn initialize_proof_account(
+    data: &mut [u8],
+    authority: &Pubkey,
+    nr_inputs: u32,
+) -> Result<(), Groth16Error> {
+    let size = checked_proof_account_size(nr_inputs as usize)
+        .ok_or(Groth16Error::InvalidAccountData)?;
+    if data.len() < size || data[..8] != [0u8; 8] {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    data[..8].copy_from_slice(&PROOF_ACCOUNT_DISCRIMINATOR);
+    data[8..40].copy_from_slice(authority);
+    data[40..48].copy_from_slice(&0u64.to_le_bytes());
+    data[48..52].copy_from_slice(&nr_inputs.to_le_bytes());
+    data[52] = STATE_WRITING;
+    Ok(())
+}
+
+fn check_writable_state(data: &[u8], authority: &Pubkey) -> Result<(), Groth16Error> {
+    let account = ProofAccount::from_bytes(data)?;
+    if account.authority() != authority {
+        return Err(Groth16Error::InvalidAuthority);
+    }
+    if account.is_finalized() {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// Writes a chunk of proof and input bytes into a proof account in writing state
+///
+/// `offset` is relative to the start of the proof bytes, so a client can
+/// stream `proof || inputs` in as many chunks as fit its transactions.
+pub fn write_proof_account_chunk(
+    data: &mut [u8],
+    authority: &Pubkey,
+    offset: usize,
+    chunk: &[u8],
+) -> Result<(), Groth16Error> {
+    check_writable_state(data, authority)?;
+    let nr_inputs = ProofAccount::from_bytes(data)?.nr_inputs();
+    let start = PROOF_ACCOUNT_PROOF_OFFSET
+        .checked_add(offset)
+        .ok_or(Groth16Error::InvalidAccountData)?;
+    let end = start
+        .checked_add(chunk.len())
+        .ok_or(Groth16Error::InvalidAccountData)?;
+    if end > proof_account_size(nr_inputs) {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    data[start..end].copy_from_slice(chunk);
+    Ok(())
+}
+
+/// Marks a proof account as finalized at `current_slot`, after which it is
+/// read-only and can be verified until it becomes stale
+pub fn finalize_proof_account(
+    data: &mut [u8],
+    authority: &Pubkey,
+    current_slot: u64,
+) -> Result<(), Groth16Error> {
+    check_writable_state(data, authority)?;
+    data[40..48].copy_from_slice(&current_slot.to_le_bytes());
+    data[52] = STATE_FINALIZED;
+    Ok(())
+}
+
+/// Read-only view of a proof account
+pub struct ProofAccount<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ProofAccount<'a> {
+    /// Loads a proof account from its data, the state is not checked
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, Groth16Error> {
+        if data.len() < PROOF_ACCOUNT_INPUTS_OFFSET || data[..8] != PROOF_ACCOUNT_DISCRIMINATOR {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        let account = ProofAccount { data };
+        let size = checked_proof_account_size(account.nr_inputs())
+            .ok_or(Groth16Error::InvalidAccountData)?;
+        if data.len() < size {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        Ok(account)
+    }
+
+    /// Loads a finalized proof account and checks it is not older than
+    /// `max_age_slots` at `current_slot`
+    pub fn from_bytes_checked(
+        data: &'a [u8],
+        current_slot: u64,
+        max_age_slots: u64,
+    ) -> Result<Self, Groth16Error> {
+        let account = Self::from_bytes(data)?;
+        if !account.is_finalized() {
+            return Err(Groth16Error::ProofAccountNotFinalized);
+        }
+        if current_slot.saturating_sub(account.slot()) > max_age_slots {
+            return Err(Groth16Error::ProofAccountStale);
+        }
+        Ok(account)
+    }
+
+    pub fn authority(&self) -> &'a Pubkey {
+        self.data[8..40].try_into().unwrap()
+    }
+
+    /// Slot at which the account was finalized
+    pub fn slot(&self) -> u64 {
+        u64::from_le_bytes(self.data[40..48].try_into().unwrap())
+    }
+
+    pub fn nr_inputs(&self) -> usize {
+        u32::from_le_bytes(self.data[48..52].try_into().unwrap()) as usize
+    }
+
+    pub fn is_finalized(&self) -> bool {
+        self.data[52] == STATE_FINALIZED
+    }
+
+    pub fn proof_a(&self) -> &'a [u8; 64] {
+        self.data[PROOF_ACCOUNT_PROOF_OFFSET..PROOF_ACCOUNT_PROOF_OFFSET + 64]
+            .try_into()
+            .unwrap()
+    }
+
+    pub fn proof_b(&self) -> &'a [u8; 128] {
+        self.data[PROOF_ACCOUNT_PROOF_OFFSET + 64..PROOF_ACCOUNT_PROOF_OFFSET + 192]
+            .try_into()
+            .unwrap()
+    }
+
+    pub fn proof_c(&self) -> &'a [u8; 64] {
+        self.data[PROOF_ACCOUNT_PROOF_OFFSET + 192..PROOF_ACCOUNT_INPUTS_OFFSET]
+            .try_into()
+            .unwrap()
+    }
+
+    /// Returns the stored public inputs
+    ///
+    /// # Returns
+    /// * `Ok(&[[u8; 32]; NR_INPUTS])` - The public inputs
+    /// * `Err(Groth16Error::InvalidPublicInputsLength)` - If the account does
+    ///   not store exactly `NR_INPUTS` inputs
+    pub fn public_inputs<const NR_INPUTS: usize>(
+        &self,
+    ) -> Result<&'a [[u8; 32]; NR_INPUTS], Groth16Error> {
+        if self.nr_inputs() != NR_INPUTS {
+            return Err(Groth16Error::InvalidPublicInputsLength);
+        }
+        let (inputs, _) = self.data[PROOF_ACCOUNT_INPUTS_OFFSET..].as_chunks::<32>();
+        inputs[..NR_INPUTS]
+            .try_into()
+            .map_err(|_| Groth16Error::InvalidPublicInputsLength)
+    }
+
+    /// Verifies the stored proof against caller-supplied public inputs
+    pub fn verify<const NR_INPUTS: usize>(
+        &self,
+        public_inputs: &[[u8; 32]; NR_INPUTS],
+        verifyingkey: &Groth16Verifyingkey,
+    ) -> Result<(), Groth16Error> {
+        Groth16Verifier::new(
+            self.proof_a(),
+            self.proof_b(),
+            self.proof_c(),
+            public_inputs,
+            verifyingkey,
+        )?
+        .verify()
+    }
+
+    /// Verifies the stored proof against the stored public inputs
+    pub fn verify_with_stored_inputs<const NR_INPUTS: usize>(
+        &self,
+        verifyingkey: &Groth16Verifyingkey,
+    ) -> Result<(), Groth16Error> {
+        self.verify(self.public_inputs::<NR_INPUTS>()?, verifyingkey)
+    }
+}
+
+/// Writes a chunk into a proof account, checking owner, writability and the
+/// authority signature
+#[cfg(feature = "pinocchio")]
+pub fn write_proof_account_chunk_checked(
+    account: &AccountInfo,
+    authority: &AccountInfo,
+    program_id: &Pubkey,
+    offset: usize,
+    chunk: &[u8],
+) -> Result<(), Groth16Error> {
+    check_proof_account(account, program_id)?;
+    if !authority.is_signer() {
+        return Err(Groth16Error::MissingRequiredSignature);
+    }
+    if !account.is_writable() {
+        return Err(Groth16Error::AccountNotWritable);
+    }
+    let mut data = account
+        .try_borrow_mut_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    write_proof_account_chunk(&mut data, authority.key(), offset, chunk)
+}
+
+/// Finalizes a proof account, checking owner, writability and the authority signature
+#[cfg(feature = "pinocchio")]
+pub fn finalize_proof_account_checked(
+    account: &AccountInfo,
+    authority: &AccountInfo,
+    program_id: &Pubkey,
+    current_slot: u64,
+) -> Result<(), Groth16Error> {
+    check_proof_account(account, program_id)?;
+    if !authority.is_signer() {
+        return Err(Groth16Error::MissingRequiredSignature);
+    }
+    if !account.is_writable() {
+        return Err(Groth16Error::AccountNotWritable);
+    }
+    let mut data = account
+        .try_borrow_mut_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    finalize_proof_account(&mut data, authority.key(), current_slot)
+}
+
+/// Verifies the proof and public inputs stored in a proof account
+///
+/// # Arguments
+/// * `account` - The proof account, must be owned by `program_id`
+/// * `program_id` - The program that owns proof accounts
+/// * `current_slot` - Usually `Clock::get()?.slot`
+/// * `max_age_slots` - Maximum number of slots since finalization
+/// * `verifyingkey` - The verifying key of the circuit
+#[cfg(feature = "pinocchio")]
+pub fn verify_proof_account<const NR_INPUTS: usize>(
+    account: &AccountInfo,
+    program_id: &Pubkey,
+    current_slot: u64,
+    max_age_slots: u64,
+    verifyingkey: &Groth16Verifyingkey,
+) -> Result<(), Groth16Error> {
+    check_proof_account(account, program_id)?;
+    let data = account
+        .try_borrow_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    ProofAccount::from_bytes_checked(&data, current_slot, max_age_slots)?
+        .verify_with_stored_inputs::<NR_INPUTS>(verifyingkey)
+}
+
+#[cfg(feature = "pinocchio")]
+fn check_proof_account(account: &AccountInfo, program_id: &Pubkey) -> Result<(), Groth16Error> {
+    if !account.is_owned_by(program_id) {
+        return Err(Groth16Error::InvalidAccountOwner);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AUTHORITY: Pubkey = [7u8; 32];
+
+    fn proof_bytes() -> [u8; 256] {
+        core::array::from_fn(|i| i as u8)
+    }
+
+    fn written_account(nr_inputs: u32) -> Vec<u8> {
+        let mut data = vec![0u8; proof_account_size(nr_inputs as usize)];
+        initialize_proof_account(&mut data, &AUTHORITY, nr_inputs).unwrap();
+        let mut payload = proof_bytes().to_vec();
+        for i in 0..nr_inputs {
+            payload.extend_from_slice(&[i as u8 + 1; 32]);
+        }
+        // Stream the payload in uneven chunks like a client would.
+        for (i, chunk) in payload.chunks(100).enumerate() {
+            write_proof_account_chunk(&mut data, &AUTHORITY, i * 100, chunk).unwrap();
+        }
+        data
+    }
+
+    #[test]
+    fn write_and_read_proof_account() {
+        let mut data = written_account(2);
+        assert_eq!(
+            ProofAccount::from_bytes_checked(&data, 10, 5).err(),
+            Some(Groth16Error::ProofAccountNotFinalized)
+        );
+        finalize_proof_account(&mut data, &AUTHORITY, 10).unwrap();
+
+        let account = ProofAccount::from_bytes_checked(&data, 15, 5).unwrap();
+        assert_eq!(account.authority(), &AUTHORITY);
+        assert_eq!(account.slot(), 10);
+        let proof = proof_bytes();
+        assert_eq!(account.proof_a()[..], proof[..64]);
+        assert_eq!(account.proof_b()[..], proof[64..192]);
+        assert_eq!(account.proof_c()[..], proof[192..]);
+        assert_eq!(account.public_inputs::<2>().unwrap(), &[[1u8; 32], [2u8; 32]]);
+        assert_eq!(
+            account.public_inputs::<3>().err(),
+            Some(Groth16Error::InvalidPublicInputsLength)
+        );
+    }
+
+    #[test]
+    fn stale_proof_account_is_rejected() {
+        let mut data = written_account(0);
+        finalize_proof_account(&mut data, &AUTHORITY, 10).unwrap();
+        assert_eq!(
+            ProofAccount::from_bytes_checked(&data, 16, 5).err(),
+            Some(Groth16Error::ProofAccountStale)
+        );
+    }
+
+    #[test]
+    fn finalized_proof_account_is_read_only() {
+        let mut data = written_account(1);
+        assert_eq!(
+            write_proof_account_chunk(&mut data, &[8u8; 32], 0, &[0u8; 4]),
+            Err(Groth16Error::InvalidAuthority)
+        );
+        assert_eq!(
+            write_proof_account_chunk(&mut data, &AUTHORITY, 256, &[0u8; 33]),
+            Err(Groth16Error::InvalidAccountData)
+        );
+        finalize_proof_account(&mut data, &AUTHORITY, 1).unwrap();
+        assert_eq!(
+            write_proof_account_chunk(&mut data, &AUTHORITY, 0, &[0u8; 4]),
+            Err(Groth16Error::InvalidAccountData)
+        );
+        assert_eq!(
+            finalize_proof_account(&mut data, &AUTHORITY, 2),
+            Err(Groth16Error::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn corrupted_input_count_is_rejected() {
+        assert_eq!(checked_proof_account_size(2), Some(proof_account_size(2)));
+        assert_eq!(checked_proof_account_size(usize::MAX / 32), None);
+
+        let mut data = written_account(1);
+        data[48..52].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            ProofAccount::from_bytes(&data).err(),
+            Some(Groth16Error::InvalidAccountData)
+        );
+    }
+}
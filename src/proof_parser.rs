@@ -18,20 +18,23 @@
 //!
 //! let (proof_a, proof_b, proof_c) = convert_proof(&proof.proof)?;
 //! ```
+//!
+//! Proofs written by the snarkjs CLI are converted by [`snarkjs`]:
+//!
+//! ```rust,ignore
+//! let proof = proof_from_json(&fs::read_to_string("proof.json")?)?;
+//! let public_inputs = public_inputs_from_json(&fs::read_to_string("public.json")?)?;
+//! ```
 
 #[cfg(feature = "circom")]
 pub mod circom_prover {
     use alloc::vec::Vec;
     use crate::errors::Groth16Error;
-    use ark_serialize::{CanonicalSerialize, Compress};
-    use crate::bn254::convert_endianness;
-    use core::ops::Neg;
 
     /// Convert circom-prover proof to groth16-solana format
     ///
-    /// This follows the exact pattern from groth16.rs test (lines 347-368):
-    /// 1. Serialize with arkworks (outputs LE)
-    /// 2. Convert LE to BE using change_endianness/convert_endianness
+    /// Negates proof a and converts the points to big-endian, see
+    /// [`crate::arkworks`].
     ///
     /// # Arguments
     ///
@@ -40,63 +43,12 @@ pub mod circom_prover {
     /// # Returns
     ///
     /// A triple of (proof_a, proof_b, proof_c) in the format expected by groth16-solana
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if serialization fails or byte conversion fails
     pub fn convert_proof(
         circom_proof: &::circom_prover::prover::circom::Proof,
     ) -> Result<([u8; 64], [u8; 128], [u8; 64]), Groth16Error> {
-        // Convert to arkworks proof
         let ark_proof: ark_groth16::Proof<ark_bn254::Bn254> = circom_proof.clone().into();
-
-        // Serialize proof_a: negate it, serialize to LE, convert to BE
-        let mut proof_a_serialized = [0u8; 65];
-        ark_proof
-            .a
-            .neg()
-            .x
-            .serialize_with_mode(&mut proof_a_serialized[..32], Compress::No)?;
-        ark_proof
-            .a
-            .neg()
-            .y
-            .serialize_with_mode(&mut proof_a_serialized[32..64], Compress::No)?;
-
-        // Convert LE to BE using convert_endianness::<32, 64> (reverses each 32-byte chunk)
-        let proof_a: [u8; 64] = convert_endianness::<32, 64>(
-            &proof_a_serialized[..64]
-                .try_into()
-                .map_err(|_| Groth16Error::ProofConversionError)?,
-        );
-
-        // Serialize proof_b: serialize to LE, convert to BE
-        let mut proof_b_serialized = [0u8; 129];
-        ark_proof
-            .b
-            .serialize_with_mode(&mut proof_b_serialized[..], Compress::No)?;
-
-        // Convert LE to BE using convert_endianness::<64, 128> (reverses each 64-byte chunk)
-        let proof_b: [u8; 128] = convert_endianness::<64, 128>(
-            &proof_b_serialized[..128]
-                .try_into()
-                .map_err(|_| Groth16Error::ProofConversionError)?,
-        );
-
-        // Serialize proof_c: serialize to LE, convert to BE
-        let mut proof_c_serialized = [0u8; 65];
-        ark_proof
-            .c
-            .serialize_with_mode(&mut proof_c_serialized[..], Compress::No)?;
-
-        // Convert LE to BE using convert_endianness::<32, 64> (reverses each 32-byte chunk)
-        let proof_c: [u8; 64] = convert_endianness::<32, 64>(
-            &proof_c_serialized[..64]
-                .try_into()
-                .map_err(|_| Groth16Error::ProofConversionError)?,
-        );
-
-        Ok((proof_a, proof_b, proof_c))
+        let proof = crate::proof::Proof::from(&ark_proof);
+        Ok((proof.a.0, proof.b.0, proof.c.0))
     }
 
     /// Convert uncompressed proof to compressed format
@@ -174,3 +126,152 @@ pub mod circom_prover {
         public_inputs_vec.try_into().unwrap()
     }
 }
+
+/// Conversion of the snarkjs CLI's `proof.json` and `public.json`
+pub mod snarkjs {
+    use crate::curve::{negate_coordinate_be, Bn254, PairingCurve};
+    use crate::errors::Groth16Error;
+    use alloc::vec::Vec;
+    use num_bigint::BigUint;
+    use serde_json::Value;
+
+    /// Size of a proof, `proof_a | proof_b | proof_c`
+    pub const PROOF_SIZE: usize = 256;
+
+    fn decimal(value: &Value) -> Option<BigUint> {
+        let value = value.as_str()?;
+        if value.is_empty() || !value.bytes().all(|digit| digit.is_ascii_digit()) {
+            return None;
+        }
+        BigUint::parse_bytes(value.as_bytes(), 10)
+    }
+
+    /// Big-endian encoding of `value` if it is smaller than `modulus`
+    fn to_be(value: &BigUint, modulus: &[u8]) -> Option<[u8; 32]> {
+        if *value >= BigUint::from_bytes_be(modulus) {
+            return None;
+        }
+        let bytes = value.to_bytes_be();
+        let mut be = [0u8; 32];
+        be[32 - bytes.len()..].copy_from_slice(&bytes);
+        Some(be)
+    }
+
+    fn coordinate(value: &Value) -> Result<[u8; 32], Groth16Error> {
+        decimal(value)
+            .and_then(|value| to_be(&value, Bn254::BASE_FIELD_MODULUS))
+            .ok_or(Groth16Error::ProofConversionError)
+    }
+
+    /// `[x, y, "1"]` to `x | y`
+    fn g1(point: &Value) -> Result<[u8; 64], Groth16Error> {
+        match point.as_array().map(Vec::as_slice) {
+            Some([x, y, z]) if z == "1" => {
+                let mut bytes = [0u8; 64];
+                bytes[..32].copy_from_slice(&coordinate(x)?);
+                bytes[32..].copy_from_slice(&coordinate(y)?);
+                Ok(bytes)
+            }
+            _ => Err(Groth16Error::ProofConversionError),
+        }
+    }
+
+    /// `[[x_c0, x_c1], [y_c0, y_c1], ["1", "0"]]` to `x_c1 | x_c0 | y_c1 | y_c0`
+    fn g2(point: &Value) -> Result<[u8; 128], Groth16Error> {
+        let coordinates = match point.as_array().map(Vec::as_slice) {
+            Some([x, y, z]) if *z == serde_json::json!(["1", "0"]) => [x, y],
+            _ => return Err(Groth16Error::ProofConversionError),
+        };
+        let mut bytes = [0u8; 128];
+        for (chunk, coordinate_pair) in bytes.chunks_mut(64).zip(coordinates) {
+            match coordinate_pair.as_array().map(Vec::as_slice) {
+                Some([c0, c1]) => {
+                    chunk[..32].copy_from_slice(&coordinate(c1)?);
+                    chunk[32..].copy_from_slice(&coordinate(c0)?);
+                }
+                _ => return Err(Groth16Error::ProofConversionError),
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Converts a snarkjs `proof.json` into the verifier's encoding
+    ///
+    /// Coordinates are decimal strings in snarkjs, the verifier takes them
+    /// big-endian with G2 coordinates ordered `c1 | c0`. Proof a is negated.
+    ///
+    /// # Returns
+    /// `proof_a | proof_b | proof_c`, see [`PROOF_SIZE`]
+    /// * `Err(Groth16Error::ProofConversionError)` - If the JSON is malformed,
+    ///   a point is not affine or a coordinate is not a field element
+    pub fn proof_from_json(json: &str) -> Result<[u8; PROOF_SIZE], Groth16Error> {
+        let proof: Value =
+            serde_json::from_str(json).map_err(|_| Groth16Error::ProofConversionError)?;
+        if proof
+            .get("protocol")
+            .is_some_and(|protocol| protocol != "groth16")
+        {
+            return Err(Groth16Error::ProofConversionError);
+        }
+        let mut proof_a = g1(&proof["pi_a"])?;
+        negate_coordinate_be(&mut proof_a[32..], Bn254::BASE_FIELD_MODULUS)?;
+        let mut bytes = [0u8; PROOF_SIZE];
+        bytes[..64].copy_from_slice(&proof_a);
+        bytes[64..192].copy_from_slice(&g2(&proof["pi_b"])?);
+        bytes[192..].copy_from_slice(&g1(&proof["pi_c"])?);
+        Ok(bytes)
+    }
+
+    /// Converts a snarkjs `public.json` into big-endian public inputs
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::ProofConversionError)` - If the JSON is not an array of decimal strings
+    /// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If a signal is
+    ///   not smaller than the field size
+    pub fn public_inputs_from_json(json: &str) -> Result<Vec<[u8; 32]>, Groth16Error> {
+        let signals: Vec<Value> =
+            serde_json::from_str(json).map_err(|_| Groth16Error::ProofConversionError)?;
+        signals
+            .iter()
+            .map(|signal| {
+                let signal = decimal(signal).ok_or(Groth16Error::ProofConversionError)?;
+                to_be(&signal, &Bn254::SCALAR_FIELD_MODULUS)
+                    .ok_or(Groth16Error::PublicInputGreaterThanFieldSize)
+            })
+            .collect()
+    }
+
+    #[cfg(all(test, feature = "test-vectors"))]
+    mod tests {
+        use super::*;
+        use crate::test_vectors::generate;
+
+        #[test]
+        fn snarkjs_json_converts_to_the_verifier_encoding() {
+            let vector = generate(0);
+            let proof = proof_from_json(&vector.snarkjs_proof_json()).unwrap();
+            assert_eq!(
+                proof,
+                *[&vector.proof_a[..], &vector.proof_b, &vector.proof_c].concat()
+            );
+            assert_eq!(
+                public_inputs_from_json(&vector.snarkjs_public_json()).unwrap(),
+                vector.public_inputs
+            );
+
+            let modulus = BigUint::from_bytes_be(&Bn254::SCALAR_FIELD_MODULUS).to_string();
+            assert_eq!(
+                public_inputs_from_json(&format!(r#"["1", "{modulus}"]"#)),
+                Err(Groth16Error::PublicInputGreaterThanFieldSize)
+            );
+            assert_eq!(
+                public_inputs_from_json(r#"["0x01"]"#),
+                Err(Groth16Error::ProofConversionError)
+            );
+            assert_eq!(
+                proof_from_json(&vector.snarkjs_proof_json().replace("groth16", "plonk")),
+                Err(Groth16Error::ProofConversionError)
+            );
+        }
+    }
+}
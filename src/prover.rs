@@ -0,0 +1,183 @@
+//! Groth16 proving from a snarkjs zkey and a witness
+//!
+//! Integration tests and relayers can generate fresh proofs in Rust without
+//! invoking snarkjs. The proof is returned in the encoding taken by
+//! [`Groth16Verifier`](crate::groth16::Groth16Verifier).
+//!
+//! ```rust,ignore
+//! use pinocchio_groth16::prover::prove;
+//!
+//! let zkey = std::fs::read("build/circuit_final.zkey")?;
+//! let witness = circom_prover::witness::generate_witness(witness_fn, inputs_json)
+//!     .join()
+//!     .unwrap();
+//! let proof = prove(&zkey, &witness)?;
+//! let public_inputs: [[u8; 32]; 2] = proof.public_inputs.clone().try_into().unwrap();
+//! Groth16Verifier::new(&proof.proof_a, &proof.proof_b, &proof.proof_c, &public_inputs, &VERIFYINGKEY)?
+//!     .verify()?;
+//! ```
+//!
+//! Circuits transpiled with `rust-witness` go from circuit inputs to the
+//! proof in one call with [`prove_with_rust_witness`]:
+//!
+//! ```rust,ignore
+//! rust_witness::witness!(withdraw);
+//!
+//! let mut inputs = HashMap::new();
+//! inputs.insert("root".to_string(), vec![root.to_string()]);
+//! let proof = prove_with_rust_witness(&zkey, withdraw_witness, &inputs)?;
+//! ```
+
+use crate::errors::Groth16Error;
+use crate::proof_parser::circom_prover::convert_proof;
+use alloc::string::String;
+use alloc::vec::Vec;
+use ark_bn254::{Bn254, Fr};
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use ark_groth16::Groth16;
+use ark_std::rand::{rngs::StdRng, Rng, SeedableRng};
+use circom_prover::prover::ark_circom::{read_zkey, CircomReduction};
+use num_bigint::{BigInt, BigUint, Sign};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::str::FromStr;
+
+/// Proof and public inputs in the encoding of the verifier
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Proof {
+    /// Negated proof a
+    pub proof_a: [u8; 64],
+    pub proof_b: [u8; 128],
+    pub proof_c: [u8; 64],
+    /// Big-endian public inputs
+    pub public_inputs: Vec<[u8; 32]>,
+}
+
+/// Proves the statement of `witness` with the proving key of a zkey
+///
+/// # Arguments
+/// * `zkey_bytes` - Contents of a BN254 snarkjs `.zkey` file
+/// * `witness` - Full witness, starting with the constant 1 followed by the public inputs
+///
+/// # Returns
+/// * `Ok(Proof)` - A fresh proof with randomness from the operating system
+/// * `Err(Groth16Error::InvalidZkey)` - If the zkey cannot be read
+/// * `Err(Groth16Error::ProvingFailed)` - If the witness does not fit the zkey
+pub fn prove(zkey_bytes: &[u8], witness: &[BigUint]) -> Result<Proof, Groth16Error> {
+    prove_with_rng(zkey_bytes, witness, &mut StdRng::from_entropy())
+}
+
+/// [`prove`] with the proof randomness drawn from `rng`
+pub fn prove_with_rng<R: Rng>(
+    zkey_bytes: &[u8],
+    witness: &[BigUint],
+    rng: &mut R,
+) -> Result<Proof, Groth16Error> {
+    let (proving_key, matrices) = read_zkey::<_, Bn254>(&mut Cursor::new(zkey_bytes))
+        .map_err(|_| Groth16Error::InvalidZkey)?;
+    if witness.len() != matrices.num_instance_variables + matrices.num_witness_variables {
+        return Err(Groth16Error::ProvingFailed);
+    }
+    let witness: Vec<Fr> = witness
+        .iter()
+        .map(|value| Fr::from(value.clone()))
+        .collect();
+
+    let r = Fr::rand(rng);
+    let s = Fr::rand(rng);
+    let proof = Groth16::<Bn254, CircomReduction>::create_proof_with_reduction_and_matrices(
+        &proving_key,
+        r,
+        s,
+        &matrices,
+        matrices.num_instance_variables,
+        matrices.num_constraints,
+        &witness,
+    )
+    .map_err(|_| Groth16Error::ProvingFailed)?;
+
+    let (proof_a, proof_b, proof_c) = convert_proof(&proof.into())?;
+    Ok(Proof {
+        proof_a,
+        proof_b,
+        proof_c,
+        public_inputs: witness[1..matrices.num_instance_variables]
+            .iter()
+            .map(|input| input.into_bigint().to_bytes_be().try_into().unwrap())
+            .collect(),
+    })
+}
+
+/// Witness generator defined by `rust_witness::witness!(name)` as `name_witness`
+pub type RustWitnessFn = fn(HashMap<String, Vec<BigInt>>) -> Vec<BigInt>;
+
+/// Computes the witness of `inputs` with `witness_fn` and proves it
+///
+/// # Arguments
+/// * `zkey_bytes` - Contents of the circuit's snarkjs `.zkey` file
+/// * `witness_fn` - The circuit's transpiled witness generator
+/// * `inputs` - Circuit input signals as decimal strings, arrays flattened
+///
+/// # Returns
+/// * `Ok(Proof)` - Proof and public inputs in the encoding of the verifier
+/// * `Err(Groth16Error::ProvingFailed)` - If an input is not a decimal
+///   number or the witness generator rejects the inputs
+pub fn prove_with_rust_witness(
+    zkey_bytes: &[u8],
+    witness_fn: RustWitnessFn,
+    inputs: &HashMap<String, Vec<String>>,
+) -> Result<Proof, Groth16Error> {
+    let inputs = inputs
+        .iter()
+        .map(|(name, values)| {
+            let values = values
+                .iter()
+                .map(|value| BigInt::from_str(value))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((name.clone(), values))
+        })
+        .collect::<Result<HashMap<_, _>, num_bigint::ParseBigIntError>>()
+        .map_err(|_| Groth16Error::ProvingFailed)?;
+    // Generated witness functions panic on missing or malformed signals
+    let witness =
+        std::panic::catch_unwind(|| witness_fn(inputs)).map_err(|_| Groth16Error::ProvingFailed)?;
+
+    let modulus = BigInt::from_biguint(Sign::Plus, Fr::MODULUS.into());
+    let witness: Vec<BigUint> = witness
+        .iter()
+        .map(|value| {
+            let reduced = ((value % &modulus) + &modulus) % &modulus;
+            reduced.to_biguint().unwrap()
+        })
+        .collect();
+    prove(zkey_bytes, &witness)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_zkey_and_inputs_are_rejected() {
+        assert_eq!(
+            prove(b"not a zkey", &[BigUint::from(1u8)]),
+            Err(Groth16Error::InvalidZkey)
+        );
+
+        fn rejecting_witness(_: HashMap<String, Vec<BigInt>>) -> Vec<BigInt> {
+            panic!("missing signal");
+        }
+        let mut inputs = HashMap::new();
+        inputs.insert("a".to_string(), vec!["1".to_string()]);
+        assert_eq!(
+            prove_with_rust_witness(b"", rejecting_witness, &inputs),
+            Err(Groth16Error::ProvingFailed)
+        );
+        inputs.insert("b".to_string(), vec!["0x1".to_string()]);
+        assert_eq!(
+            prove_with_rust_witness(b"", rejecting_witness, &inputs),
+            Err(Groth16Error::ProvingFailed)
+        );
+    }
+}
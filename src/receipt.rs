@@ -0,0 +1,332 @@
+//! Receipt chaining for incremental verification
+//!
+//! A long running computation is proven step by step, one proof per step.
+//! Each verified step leaves a receipt account whose digest the proof of the
+//! next step must expose as a public input, so steps settle in order, none
+//! can be skipped and no step can be replayed into another chain:
+//!
+//! ```text
+//! digest_0 = genesis_digest(chain_id)
+//! digest_i = H(digest_i-1, vk_hash_i, public_inputs_i)   with public_inputs_i[link] = digest_i-1
+//! ```
+//!
+//! `H` is keccak256 with a domain separator, reduced to a field element. The
+//! circuit does not recompute it, it only takes the previous digest as the
+//! public input at the link index; the verifier checks it against the
+//! previous receipt, see [`issue_receipt`].
+//!
+//! Programs create the receipt of step `i` at the PDA
+//! `[RECEIPT_SEED, chain_id, i u64 LE]`, so every step has exactly one
+//! receipt and a chain cannot fork. [`issue_receipt_checked`] rejects
+//! receipt accounts at any other address.
+//!
+//! Account layout:
+//!
+//! | offset | size | field                                       |
+//! |--------|------|---------------------------------------------|
+//! | 0      | 8    | discriminator `RECEIPT_DISCRIMINATOR`       |
+//! | 8      | 32   | chain id                                    |
+//! | 40     | 8    | step, u64 LE                                |
+//! | 48     | 32   | verifying key hash of the step's proof      |
+//! | 80     | 32   | digest, the link input of the next step     |
+
+use crate::errors::Groth16Error;
+use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+use crate::hash::hashv_to_bn254_field_size_be;
+use crate::recursion::vk_hash;
+#[cfg(feature = "pinocchio")]
+use crate::Pubkey;
+use alloc::vec::Vec;
+#[cfg(feature = "pinocchio")]
+use pinocchio::account_info::AccountInfo;
+#[cfg(feature = "pinocchio")]
+use pinocchio::pubkey::try_find_program_address;
+
+/// Seed prefix of receipt PDAs: `[RECEIPT_SEED, chain_id, step u64 LE]`
+pub const RECEIPT_SEED: &[u8] = b"receipt";
+
+/// Discriminator stored in the first 8 bytes of a receipt account
+pub const RECEIPT_DISCRIMINATOR: [u8; 8] = *b"g16recpt";
+
+/// Size of a receipt account
+pub const RECEIPT_ACCOUNT_SIZE: usize = 112;
+
+/// Domain separator of receipt digests
+pub const RECEIPT_DOMAIN: &[u8] = b"pinocchio-groth16/receipt/v1";
+
+/// Digest the first step of the chain `chain_id` links to
+pub fn genesis_digest(chain_id: &[u8; 32]) -> [u8; 32] {
+    hashv_to_bn254_field_size_be(&[RECEIPT_DOMAIN, b"genesis", chain_id])
+}
+
+/// Digest of a verified step
+///
+/// # Arguments
+/// * `previous_digest` - Digest of the previous receipt, or the genesis digest
+/// * `vk_hash` - [`vk_hash`] of the step's verifying key
+/// * `public_inputs` - Public inputs of the step's proof
+pub fn receipt_digest(
+    previous_digest: &[u8; 32],
+    vk_hash: &[u8; 32],
+    public_inputs: &[[u8; 32]],
+) -> [u8; 32] {
+    let mut vals: Vec<&[u8]> = Vec::with_capacity(3 + public_inputs.len());
+    vals.extend_from_slice(&[RECEIPT_DOMAIN, previous_digest, vk_hash]);
+    vals.extend(public_inputs.iter().map(|input| input.as_slice()));
+    hashv_to_bn254_field_size_be(&vals)
+}
+
+/// Read access to a receipt account
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Receipt<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Receipt<'a> {
+    /// Parses a receipt account
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidAccountData)` - If the size or discriminator is wrong
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, Groth16Error> {
+        if data.len() < RECEIPT_ACCOUNT_SIZE || data[..8] != RECEIPT_DISCRIMINATOR {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        Ok(Receipt { data })
+    }
+
+    pub fn chain_id(&self) -> &'a [u8; 32] {
+        self.data[8..40].try_into().unwrap()
+    }
+
+    pub fn step(&self) -> u64 {
+        u64::from_le_bytes(self.data[40..48].try_into().unwrap())
+    }
+
+    pub fn vk_hash(&self) -> &'a [u8; 32] {
+        self.data[48..80].try_into().unwrap()
+    }
+
+    pub fn digest(&self) -> &'a [u8; 32] {
+        self.data[80..112].try_into().unwrap()
+    }
+}
+
+/// Step and digest of the receipt following `previous`
+fn next_link(
+    previous: Option<&[u8]>,
+    chain_id: &[u8; 32],
+) -> Result<(u64, [u8; 32]), Groth16Error> {
+    let Some(previous) = previous else {
+        return Ok((0, genesis_digest(chain_id)));
+    };
+    let previous = Receipt::from_bytes(previous)?;
+    if previous.chain_id() != chain_id {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    let step = previous
+        .step()
+        .checked_add(1)
+        .ok_or(Groth16Error::InvalidAccountData)?;
+    Ok((step, *previous.digest()))
+}
+
+/// Step of the receipt following `previous`, 0 for the first step
+///
+/// The receipt is created at the PDA `[RECEIPT_SEED, chain_id, step u64 LE]`.
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidAccountData)` - If the previous receipt belongs
+///   to another chain
+pub fn next_receipt_step(
+    previous: Option<&[u8]>,
+    chain_id: &[u8; 32],
+) -> Result<u64, Groth16Error> {
+    next_link(previous, chain_id).map(|(step, _)| step)
+}
+
+/// Verifies the next step of a chain and writes its receipt
+///
+/// # Arguments
+/// * `previous` - Data of the previous receipt, `None` for the first step
+/// * `receipt` - Zeroed data of the new receipt account
+/// * `chain_id` - Identifier of the chain, e.g. the key of its state account
+/// * `link_index` - Index of the public input linking to the previous digest
+/// * `verifyingkey` - Verifying key of the step circuit
+///
+/// # Returns
+/// The digest of the new receipt
+/// * `Err(Groth16Error::InvalidAccountData)` - If the previous receipt belongs
+///   to another chain or the new receipt account is not zeroed
+/// * `Err(Groth16Error::PublicInputMismatch)` - If the proof does not link to
+///   the previous digest
+#[allow(clippy::too_many_arguments)]
+pub fn issue_receipt<const NR_INPUTS: usize>(
+    previous: Option<&[u8]>,
+    receipt: &mut [u8],
+    chain_id: &[u8; 32],
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+    link_index: usize,
+    verifyingkey: &Groth16Verifyingkey,
+) -> Result<[u8; 32], Groth16Error> {
+    let (step, previous_digest) = next_link(previous, chain_id)?;
+    let link = public_inputs
+        .get(link_index)
+        .ok_or(Groth16Error::InvalidPublicInputsLength)?;
+    if *link != previous_digest {
+        return Err(Groth16Error::PublicInputMismatch);
+    }
+    if receipt.len() < RECEIPT_ACCOUNT_SIZE
+        || receipt[..RECEIPT_ACCOUNT_SIZE] != [0u8; RECEIPT_ACCOUNT_SIZE]
+    {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+
+    Groth16Verifier::new(proof_a, proof_b, proof_c, public_inputs, verifyingkey)?.verify()?;
+
+    let vk_hash = vk_hash(verifyingkey);
+    let digest = receipt_digest(&previous_digest, &vk_hash, public_inputs);
+    receipt[..8].copy_from_slice(&RECEIPT_DISCRIMINATOR);
+    receipt[8..40].copy_from_slice(chain_id);
+    receipt[40..48].copy_from_slice(&step.to_le_bytes());
+    receipt[48..80].copy_from_slice(&vk_hash);
+    receipt[80..112].copy_from_slice(&digest);
+    Ok(digest)
+}
+
+/// [`issue_receipt`] with owner, writable and address checks
+///
+/// Both receipts must be owned by `program_id`, and `receipt` must be the PDA
+/// of the step that follows `previous`, see the module documentation.
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidAccountData)` - If `receipt` is not the PDA of
+///   the next step
+#[allow(clippy::too_many_arguments)]
+#[cfg(feature = "pinocchio")]
+pub fn issue_receipt_checked<const NR_INPUTS: usize>(
+    previous: Option<&AccountInfo>,
+    receipt: &AccountInfo,
+    program_id: &Pubkey,
+    chain_id: &[u8; 32],
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+    link_index: usize,
+    verifyingkey: &Groth16Verifyingkey,
+) -> Result<[u8; 32], Groth16Error> {
+    if !receipt.is_owned_by(program_id) || previous.is_some_and(|p| !p.is_owned_by(program_id)) {
+        return Err(Groth16Error::InvalidAccountOwner);
+    }
+    if !receipt.is_writable() {
+        return Err(Groth16Error::AccountNotWritable);
+    }
+    let previous_data = previous
+        .map(|previous| previous.try_borrow_data())
+        .transpose()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    let step = next_receipt_step(previous_data.as_deref(), chain_id)?;
+    let (address, _) =
+        try_find_program_address(&[RECEIPT_SEED, chain_id, &step.to_le_bytes()], program_id)
+            .ok_or(Groth16Error::InvalidAccountData)?;
+    if address != *receipt.key() {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    let mut receipt_data = receipt
+        .try_borrow_mut_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    issue_receipt(
+        previous_data.as_deref(),
+        &mut receipt_data,
+        chain_id,
+        proof_a,
+        proof_b,
+        proof_c,
+        public_inputs,
+        link_index,
+        verifyingkey,
+    )
+}
+
+#[cfg(all(test, feature = "test-vectors"))]
+mod tests {
+    use super::*;
+    use crate::test_vectors::{generate_for_circuit, TinyCircuit};
+    use ark_bn254::Fr;
+    use ark_ff::{BigInteger, PrimeField};
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+    const CHAIN_ID: [u8; 32] = [3u8; 32];
+
+    /// A step proving knowledge of `a, b` with `a * b` public, linked by `a + b`
+    fn step(link: &[u8; 32], seed: u64) -> crate::test_vectors::TestVector {
+        let b = Fr::from(seed + 2);
+        let a = Fr::from_be_bytes_mod_order(link) - b;
+        generate_for_circuit(
+            TinyCircuit::default(),
+            TinyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &[a * b, a + b],
+            &mut StdRng::seed_from_u64(seed),
+        )
+    }
+
+    fn issue(
+        previous: Option<&[u8]>,
+        receipt: &mut [u8],
+        vector: &crate::test_vectors::TestVector,
+    ) -> Result<[u8; 32], Groth16Error> {
+        let inputs: [[u8; 32]; 2] = vector.public_inputs.clone().try_into().unwrap();
+        issue_receipt(
+            previous,
+            receipt,
+            &CHAIN_ID,
+            &vector.proof_a,
+            &vector.proof_b,
+            &vector.proof_c,
+            &inputs,
+            1,
+            &vector.verifying_key(),
+        )
+    }
+
+    #[test]
+    fn steps_link_to_the_previous_receipt() {
+        let first_step = step(&genesis_digest(&CHAIN_ID), 0);
+        let mut first = [0u8; RECEIPT_ACCOUNT_SIZE];
+        let digest = issue(None, &mut first, &first_step).unwrap();
+        let receipt = Receipt::from_bytes(&first).unwrap();
+        assert_eq!((receipt.step(), receipt.digest()), (0, &digest));
+        assert_eq!(
+            Fr::from_be_bytes_mod_order(&digest)
+                .into_bigint()
+                .to_bytes_be(),
+            digest
+        );
+
+        // The first step cannot be replayed as the second one
+        let mut second = [0u8; RECEIPT_ACCOUNT_SIZE];
+        assert_eq!(
+            issue(Some(&first), &mut second, &first_step),
+            Err(Groth16Error::PublicInputMismatch)
+        );
+        issue(Some(&first), &mut second, &step(&digest, 1)).unwrap();
+        assert_eq!(Receipt::from_bytes(&second).unwrap().step(), 1);
+        assert_eq!(next_receipt_step(None, &CHAIN_ID), Ok(0));
+        assert_eq!(next_receipt_step(Some(&first), &CHAIN_ID), Ok(1));
+        assert_eq!(next_receipt_step(Some(&second), &CHAIN_ID), Ok(2));
+        assert_eq!(
+            next_receipt_step(Some(&second), &[4u8; 32]),
+            Err(Groth16Error::InvalidAccountData)
+        );
+        assert_eq!(
+            issue(Some(&first), &mut second, &step(&digest, 1)),
+            Err(Groth16Error::InvalidAccountData)
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! Binding of outer proofs to inner verifying keys
+//!
+//! In proof-of-proof designs an outer circuit verifies an inner proof and
+//! exposes the hash of the inner verifying key as a public input. The outer
+//! proof only attests to the inner circuit the program expects if that input
+//! is checked against the expected key, otherwise a prover can substitute a
+//! key for any circuit it likes.
+//!
+//! [`vk_hash`] is the keccak256 hash of the key's big-endian encoding,
+//! `alpha_g1 | beta_g2 | gamma_g2 | delta_g2 | ic`, i.e. of the same bytes
+//! the verifier takes, with G2 coordinates ordered `c1 | c0`. Recursive
+//! circuits expose it either truncated to a single field element or as two
+//! 128-bit limbs, see [`VkHashEncoding`]:
+//!
+//! ```rust,ignore
+//! const INNER_VK_HASH: [u8; 32] = /* vk_hash(&INNER_VERIFYINGKEY) */;
+//!
+//! verify_with_vk_binding(
+//!     &proof_a, &proof_b, &proof_c, &public_inputs,
+//!     0, &INNER_VK_HASH, VkHashEncoding::Limbs, &OUTER_VERIFYINGKEY,
+//! )?;
+//! ```
+
+use crate::errors::Groth16Error;
+use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+use crate::hash::keccak256;
+use alloc::vec::Vec;
+
+/// Keccak256 hash of the big-endian encoding of `vk`
+///
+/// `nr_pubinputs` is not hashed, it is implied by the number of IC points.
+pub fn vk_hash(vk: &Groth16Verifyingkey) -> [u8; 32] {
+    let mut vals: Vec<&[u8]> = vec![
+        &vk.vk_alpha_g1,
+        &vk.vk_beta_g2,
+        &vk.vk_gamma_g2,
+        &vk.vk_delta_g2,
+    ];
+    vals.extend(vk.vk_ic.iter().map(|ic| ic.as_slice()));
+    keccak256(&vals)
+}
+
+/// How a circuit exposes a verifying key hash as public inputs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VkHashEncoding {
+    /// One input, the hash with its first byte zeroed
+    Field,
+    /// Two inputs, the high and the low 16 bytes of the hash
+    Limbs,
+}
+
+impl VkHashEncoding {
+    /// Number of public inputs the hash occupies
+    pub const fn nr_inputs(&self) -> usize {
+        match self {
+            VkHashEncoding::Field => 1,
+            VkHashEncoding::Limbs => 2,
+        }
+    }
+
+    /// Returns the public inputs encoding `vk_hash`, only the first
+    /// [`Self::nr_inputs`] are used
+    pub fn encode(&self, vk_hash: &[u8; 32]) -> [[u8; 32]; 2] {
+        let mut inputs = [[0u8; 32]; 2];
+        match self {
+            VkHashEncoding::Field => {
+                inputs[0] = *vk_hash;
+                inputs[0][0] = 0;
+            }
+            VkHashEncoding::Limbs => {
+                inputs[0][16..].copy_from_slice(&vk_hash[..16]);
+                inputs[1][16..].copy_from_slice(&vk_hash[16..]);
+            }
+        }
+        inputs
+    }
+}
+
+/// Checks that the public inputs starting at `index` encode `inner_vk_hash`
+///
+/// # Returns
+/// * `Err(Groth16Error::PublicInputMismatch)` - If the outer proof commits to
+///   another inner verifying key
+pub fn check_vk_binding<const NR_INPUTS: usize>(
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+    index: usize,
+    inner_vk_hash: &[u8; 32],
+    encoding: VkHashEncoding,
+) -> Result<(), Groth16Error> {
+    let inputs = index
+        .checked_add(encoding.nr_inputs())
+        .and_then(|end| public_inputs.get(index..end))
+        .ok_or(Groth16Error::InvalidPublicInputsLength)?;
+    if inputs != &encoding.encode(inner_vk_hash)[..encoding.nr_inputs()] {
+        return Err(Groth16Error::PublicInputMismatch);
+    }
+    Ok(())
+}
+
+/// Verifies an outer proof after checking that it binds `inner_vk_hash`
+///
+/// # Arguments
+/// * `index` - Position of the first public input of the hash
+/// * `inner_vk_hash` - [`vk_hash`] of the inner verifying key
+/// * `encoding` - How the outer circuit exposes the hash
+/// * `verifyingkey` - Verifying key of the outer circuit
+#[allow(clippy::too_many_arguments)]
+pub fn verify_with_vk_binding<const NR_INPUTS: usize>(
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+    index: usize,
+    inner_vk_hash: &[u8; 32],
+    encoding: VkHashEncoding,
+    verifyingkey: &Groth16Verifyingkey,
+) -> Result<(), Groth16Error> {
+    check_vk_binding(public_inputs, index, inner_vk_hash, encoding)?;
+    Groth16Verifier::new(proof_a, proof_b, proof_c, public_inputs, verifyingkey)?.verify()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groth16::is_less_than_bn254_field_size_be;
+
+    #[test]
+    fn vk_hash_binding() {
+        let vk_ic = [[1u8; 64], [2u8; 64]];
+        let vk = Groth16Verifyingkey {
+            nr_pubinputs: 1,
+            vk_alpha_g1: [3u8; 64],
+            vk_beta_g2: [4u8; 128],
+            vk_gamma_g2: [5u8; 128],
+            vk_delta_g2: [6u8; 128],
+            vk_ic: &vk_ic,
+        };
+        let hash = vk_hash(&vk);
+        let bytes = [
+            &[3u8; 64][..],
+            &[4u8; 128],
+            &[5u8; 128],
+            &[6u8; 128],
+            &[1u8; 64],
+            &[2u8; 64],
+        ]
+        .concat();
+        assert_eq!(hash, keccak256(&[&bytes]));
+        let other_vk = Groth16Verifyingkey {
+            vk_ic: &vk_ic[..1],
+            nr_pubinputs: 0,
+            ..vk
+        };
+        assert_ne!(vk_hash(&other_vk), hash);
+
+        let [field, _] = VkHashEncoding::Field.encode(&hash);
+        assert!(is_less_than_bn254_field_size_be(&field));
+        let [high, low] = VkHashEncoding::Limbs.encode(&hash);
+        assert_eq!([&high[16..], &low[16..]].concat(), hash);
+
+        let inputs = [[7u8; 32], high, low, field];
+        check_vk_binding(&inputs, 1, &hash, VkHashEncoding::Limbs).unwrap();
+        check_vk_binding(&inputs, 3, &hash, VkHashEncoding::Field).unwrap();
+        assert_eq!(
+            check_vk_binding(&inputs, 0, &hash, VkHashEncoding::Limbs),
+            Err(Groth16Error::PublicInputMismatch)
+        );
+        assert_eq!(
+            check_vk_binding(&inputs, 3, &hash, VkHashEncoding::Limbs),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+        assert_eq!(
+            verify_with_vk_binding(
+                &[0u8; 64],
+                &[0u8; 128],
+                &[0u8; 64],
+                &inputs,
+                1,
+                &vk_hash(&other_vk),
+                VkHashEncoding::Limbs,
+                &vk,
+            ),
+            Err(Groth16Error::PublicInputMismatch)
+        );
+    }
+}
@@ -0,0 +1,569 @@
+//! Verification across several instructions
+//!
+//! Preparing the public inputs costs a G1 multiplication and addition per
+//! input, so with many inputs preparation and pairing don't fit in one
+//! instruction. A [`VerificationState`] stores the proof, the inputs and the
+//! partially prepared inputs in an account. Each [`verify_step`] prepares up
+//! to `inputs_per_step` inputs, the step after the last input runs the
+//! pairing check and marks the state verified.
+//!
+//! The state is bound to the [`vk_hash`] of its verifying key, a step with
+//! another key fails with [`Groth16Error::VerifyingKeyMismatch`]. The status
+//! is read from the account data, so it is only as trustworthy as the
+//! account's owner: programs step with [`verify_step_checked`], which checks
+//! that the program owns the account.
+//!
+//! Programs that keep the inputs elsewhere, or receive them over several
+//! instructions, prepare them with a [`PreparedInputsAccumulator`] instead.
+//! It consumes inputs in chunks, prepares as many as fit a compute unit
+//! budget and estimates the units the rest of the verification needs.
+//!
+//! Account layout:
+//!
+//! | offset | size   | field                                        |
+//! |--------|--------|----------------------------------------------|
+//! | 0      | 8      | discriminator `VERIFICATION_STATE_DISCRIMINATOR` |
+//! | 8      | 32     | verifying key hash                           |
+//! | 40     | 4      | number of public inputs, u32 LE              |
+//! | 44     | 4      | number of prepared inputs, u32 LE            |
+//! | 48     | 1      | status, 0 = preparing, 1 = verified          |
+//! | 49     | 7      | padding                                      |
+//! | 56     | 256    | proof a, b, c                                |
+//! | 312    | 64     | prepared public inputs                       |
+//! | 376    | 32 * n | public inputs                                |
+
+use crate::compute_units::{estimate_verify_compute_units, max_prepared_inputs};
+use crate::curve::{Bn254, PairingCurve};
+use crate::errors::Groth16Error;
+use crate::groth16::{check_pairing, Groth16Verifyingkey, PreparedInputs};
+use crate::recursion::vk_hash;
+#[cfg(feature = "pinocchio")]
+use crate::Pubkey;
+use alloc::vec::Vec;
+#[cfg(feature = "pinocchio")]
+use pinocchio::account_info::AccountInfo;
+
+/// Discriminator stored in the first 8 bytes of a verification state account
+pub const VERIFICATION_STATE_DISCRIMINATOR: [u8; 8] = *b"g16vstat";
+
+/// Offset of the public inputs
+pub const VERIFICATION_STATE_INPUTS_OFFSET: usize = 376;
+
+const STATUS_PREPARING: u8 = 0;
+const STATUS_VERIFIED: u8 = 1;
+
+/// Returns the account size of a verification state of `nr_inputs` public inputs
+pub const fn verification_state_size(nr_inputs: usize) -> usize {
+    VERIFICATION_STATE_INPUTS_OFFSET + nr_inputs * 32
+}
+
+/// [`verification_state_size`], `None` if it overflows `usize`
+///
+/// For counts read from untrusted data, which overflow on 32-bit targets.
+pub const fn checked_verification_state_size(nr_inputs: usize) -> Option<usize> {
+    let Some(inputs_size) = nr_inputs.checked_mul(32) else {
+        return None;
+    };
+    inputs_size.checked_add(VERIFICATION_STATE_INPUTS_OFFSET)
+}
+
+/// Progress of a resumable verification
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationStatus {
+    /// `remaining` public inputs are not prepared yet, or 0 if only the
+    /// pairing check is left
+    Pending { remaining: usize },
+    /// The proof is valid
+    Verified,
+}
+
+/// Proof, public inputs and progress of a resumable verification
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerificationState {
+    vk_hash: [u8; 32],
+    prepared: usize,
+    verified: bool,
+    proof_a: [u8; 64],
+    proof_b: [u8; 128],
+    proof_c: [u8; 64],
+    prepared_public_inputs: [u8; 64],
+    public_inputs: Vec<[u8; 32]>,
+}
+
+impl VerificationState {
+    /// Starts the verification of a proof, no group operation is run yet
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidPublicInputsLength)` - If `verifyingkey`
+    ///   takes another number of public inputs
+    /// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If an input is
+    ///   not smaller than the scalar field modulus
+    pub fn new(
+        proof_a: &[u8; 64],
+        proof_b: &[u8; 128],
+        proof_c: &[u8; 64],
+        public_inputs: &[[u8; 32]],
+        verifyingkey: &Groth16Verifyingkey,
+    ) -> Result<Self, Groth16Error> {
+        if public_inputs.len() + 1 != verifyingkey.vk_ic.len() {
+            return Err(Groth16Error::InvalidPublicInputsLength);
+        }
+        if !public_inputs
+            .iter()
+            .all(Bn254::is_less_than_scalar_field_size_be)
+        {
+            return Err(Groth16Error::PublicInputGreaterThanFieldSize);
+        }
+        Ok(VerificationState {
+            vk_hash: vk_hash(verifyingkey),
+            prepared: 0,
+            verified: false,
+            proof_a: *proof_a,
+            proof_b: *proof_b,
+            proof_c: *proof_c,
+            prepared_public_inputs: verifyingkey.vk_ic[0],
+            public_inputs: public_inputs.to_vec(),
+        })
+    }
+
+    /// Loads a state from its account data
+    ///
+    /// The status is taken from the data as is, the caller checks that its
+    /// program owns the account, see [`verify_step_checked`].
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidAccountData)` - If the size, discriminator
+    ///   or progress is invalid
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Groth16Error> {
+        if data.len() < VERIFICATION_STATE_INPUTS_OFFSET
+            || data[..8] != VERIFICATION_STATE_DISCRIMINATOR
+        {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        let nr_inputs = u32::from_le_bytes(data[40..44].try_into().unwrap()) as usize;
+        let prepared = u32::from_le_bytes(data[44..48].try_into().unwrap()) as usize;
+        let verified = match data[48] {
+            STATUS_PREPARING => false,
+            STATUS_VERIFIED => true,
+            _ => return Err(Groth16Error::InvalidAccountData),
+        };
+        let size =
+            checked_verification_state_size(nr_inputs).ok_or(Groth16Error::InvalidAccountData)?;
+        if prepared > nr_inputs || data.len() < size {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        let (public_inputs, _) = data[VERIFICATION_STATE_INPUTS_OFFSET..].as_chunks::<32>();
+        Ok(VerificationState {
+            vk_hash: data[8..40].try_into().unwrap(),
+            prepared,
+            verified,
+            proof_a: data[56..120].try_into().unwrap(),
+            proof_b: data[120..248].try_into().unwrap(),
+            proof_c: data[248..312].try_into().unwrap(),
+            prepared_public_inputs: data[312..376].try_into().unwrap(),
+            public_inputs: public_inputs[..nr_inputs].to_vec(),
+        })
+    }
+
+    /// Writes the state into account data of at least [`verification_state_size`] bytes
+    pub fn write_to(&self, data: &mut [u8]) -> Result<(), Groth16Error> {
+        let size = verification_state_size(self.public_inputs.len());
+        if data.len() < size {
+            return Err(Groth16Error::InvalidAccountData);
+        }
+        data[..8].copy_from_slice(&VERIFICATION_STATE_DISCRIMINATOR);
+        data[8..40].copy_from_slice(&self.vk_hash);
+        data[40..44].copy_from_slice(&(self.public_inputs.len() as u32).to_le_bytes());
+        data[44..48].copy_from_slice(&(self.prepared as u32).to_le_bytes());
+        data[48] = if self.verified {
+            STATUS_VERIFIED
+        } else {
+            STATUS_PREPARING
+        };
+        data[49..56].fill(0);
+        data[56..120].copy_from_slice(&self.proof_a);
+        data[120..248].copy_from_slice(&self.proof_b);
+        data[248..312].copy_from_slice(&self.proof_c);
+        data[312..376].copy_from_slice(&self.prepared_public_inputs);
+        for (chunk, input) in data[VERIFICATION_STATE_INPUTS_OFFSET..size]
+            .chunks_exact_mut(32)
+            .zip(&self.public_inputs)
+        {
+            chunk.copy_from_slice(input);
+        }
+        Ok(())
+    }
+
+    pub fn vk_hash(&self) -> &[u8; 32] {
+        &self.vk_hash
+    }
+
+    pub fn public_inputs(&self) -> &[[u8; 32]] {
+        &self.public_inputs
+    }
+
+    pub fn status(&self) -> VerificationStatus {
+        if self.verified {
+            VerificationStatus::Verified
+        } else {
+            VerificationStatus::Pending {
+                remaining: self.public_inputs.len() - self.prepared,
+            }
+        }
+    }
+
+    /// Estimated compute units of the remaining steps, 0 once verified
+    pub fn remaining_compute_units(&self) -> u64 {
+        match self.status() {
+            VerificationStatus::Pending { remaining } => estimate_verify_compute_units(remaining),
+            VerificationStatus::Verified => 0,
+        }
+    }
+}
+
+/// Public inputs prepared incrementally, in chunks
+///
+/// Inputs are fed in order, every [`feed`](Self::feed) adds
+/// `vk_ic[i + 1] * input[i]` for the next inputs to the prepared point. Once
+/// all inputs of the key are fed, [`finish`](Self::finish) returns the
+/// [`PreparedInputs`] to verify with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PreparedInputsAccumulator<'a> {
+    verifyingkey: &'a Groth16Verifyingkey<'a>,
+    prepared: usize,
+    prepared_public_inputs: [u8; 64],
+}
+
+impl<'a> PreparedInputsAccumulator<'a> {
+    /// Starts preparing the inputs of `verifyingkey`, no group operation is run yet
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidPublicInputsLength)` - If the key has no IC points
+    pub fn new(verifyingkey: &'a Groth16Verifyingkey<'a>) -> Result<Self, Groth16Error> {
+        let Some(ic_0) = verifyingkey.vk_ic.first() else {
+            return Err(Groth16Error::InvalidPublicInputsLength);
+        };
+        Ok(PreparedInputsAccumulator {
+            verifyingkey,
+            prepared: 0,
+            prepared_public_inputs: *ic_0,
+        })
+    }
+
+    /// Prepares the next `public_inputs`
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidPublicInputsLength)` - If the key takes
+    ///   fewer inputs than fed so far
+    /// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If an input is
+    ///   not smaller than the scalar field modulus
+    pub fn feed(&mut self, public_inputs: &[[u8; 32]]) -> Result<(), Groth16Error> {
+        if public_inputs.len() > self.remaining() {
+            return Err(Groth16Error::InvalidPublicInputsLength);
+        }
+        if !public_inputs
+            .iter()
+            .all(Bn254::is_less_than_scalar_field_size_be)
+        {
+            return Err(Groth16Error::PublicInputGreaterThanFieldSize);
+        }
+        if public_inputs.is_empty() {
+            return Ok(());
+        }
+        let end = self.prepared + public_inputs.len();
+        let sum = Bn254::msm_g1(
+            &self.verifyingkey.vk_ic[self.prepared + 1..end + 1],
+            public_inputs,
+        )?;
+        self.prepared_public_inputs = Bn254::g1_add(&sum, &self.prepared_public_inputs)?;
+        self.prepared = end;
+        Ok(())
+    }
+
+    /// Prepares as many of `public_inputs` as fit in `compute_units`
+    ///
+    /// # Returns
+    /// The number of inputs prepared, the caller feeds the rest later
+    /// * The errors of [`feed`](Self::feed)
+    pub fn feed_within_budget(
+        &mut self,
+        public_inputs: &[[u8; 32]],
+        compute_units: u64,
+    ) -> Result<usize, Groth16Error> {
+        let nr_inputs = public_inputs.len().min(max_prepared_inputs(compute_units));
+        self.feed(&public_inputs[..nr_inputs])?;
+        Ok(nr_inputs)
+    }
+
+    /// Number of inputs prepared so far
+    pub fn prepared(&self) -> usize {
+        self.prepared
+    }
+
+    /// Number of inputs of the key not fed yet
+    pub fn remaining(&self) -> usize {
+        self.verifyingkey.vk_ic.len() - 1 - self.prepared
+    }
+
+    /// Estimated compute units of preparing the remaining inputs and the
+    /// pairing check, see [`estimate_verify_compute_units`]
+    pub fn remaining_compute_units(&self) -> u64 {
+        estimate_verify_compute_units(self.remaining())
+    }
+
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidPublicInputsLength)` - If not all inputs
+    ///   of the key were fed
+    pub fn finish(self) -> Result<PreparedInputs, Groth16Error> {
+        if self.remaining() != 0 {
+            return Err(Groth16Error::InvalidPublicInputsLength);
+        }
+        Ok(PreparedInputs(self.prepared_public_inputs))
+    }
+}
+
+/// Advances a verification by one instruction's worth of work
+///
+/// Prepares up to `inputs_per_step` public inputs. Once all are prepared, the
+/// next step runs the pairing check. Steps on a verified state only check the
+/// key, so a program learns whether the proof is verified for its circuit.
+///
+/// # Arguments
+/// * `state` - The state, written back to its account by the caller
+/// * `verifyingkey` - The key the state was created with
+/// * `inputs_per_step` - Inputs to prepare in this step, at least 1
+///
+/// # Returns
+/// The status after the step
+/// * `Err(Groth16Error::VerifyingKeyMismatch)` - If `verifyingkey` is not the
+///   key of the state
+/// * `Err(Groth16Error::ProofVerificationFailed)` - If the pairing check fails
+pub fn verify_step(
+    state: &mut VerificationState,
+    verifyingkey: &Groth16Verifyingkey,
+    inputs_per_step: usize,
+) -> Result<VerificationStatus, Groth16Error> {
+    if vk_hash(verifyingkey) != state.vk_hash
+        || state.public_inputs.len() + 1 != verifyingkey.vk_ic.len()
+    {
+        return Err(Groth16Error::VerifyingKeyMismatch);
+    }
+    if state.verified {
+        return Ok(VerificationStatus::Verified);
+    }
+
+    if state.prepared < state.public_inputs.len() {
+        let end = state
+            .prepared
+            .saturating_add(inputs_per_step.max(1))
+            .min(state.public_inputs.len());
+        let mut accumulator = PreparedInputsAccumulator {
+            verifyingkey,
+            prepared: state.prepared,
+            prepared_public_inputs: state.prepared_public_inputs,
+        };
+        accumulator.feed(&state.public_inputs[state.prepared..end])?;
+        state.prepared = accumulator.prepared;
+        state.prepared_public_inputs = accumulator.prepared_public_inputs;
+        return Ok(state.status());
+    }
+
+    check_pairing::<Bn254>(
+        &state.proof_a,
+        &state.proof_b,
+        &state.proof_c,
+        &state.prepared_public_inputs,
+        verifyingkey,
+    )?;
+    state.verified = true;
+    Ok(VerificationStatus::Verified)
+}
+
+/// [`verify_step`] on a verification state account, with owner and writable checks
+///
+/// Loads the state, runs the step and writes it back.
+///
+/// # Returns
+/// The status after the step
+/// * `Err(Groth16Error::InvalidAccountOwner)` - If `program_id` does not own the account
+/// * `Err(Groth16Error::AccountNotWritable)` - If the account is not writable
+/// * The errors of [`VerificationState::from_bytes`] and [`verify_step`]
+#[cfg(feature = "pinocchio")]
+pub fn verify_step_checked(
+    account: &AccountInfo,
+    program_id: &Pubkey,
+    verifyingkey: &Groth16Verifyingkey,
+    inputs_per_step: usize,
+) -> Result<VerificationStatus, Groth16Error> {
+    if !account.is_owned_by(program_id) {
+        return Err(Groth16Error::InvalidAccountOwner);
+    }
+    if !account.is_writable() {
+        return Err(Groth16Error::AccountNotWritable);
+    }
+    let mut data = account
+        .try_borrow_mut_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    let mut state = VerificationState::from_bytes(&data)?;
+    let status = verify_step(&mut state, verifyingkey, inputs_per_step)?;
+    state.write_to(&mut data)?;
+    Ok(status)
+}
+
+#[cfg(all(test, feature = "test-vectors"))]
+mod tests {
+    use super::*;
+    use crate::test_vectors::generate;
+
+    #[test]
+    fn steps_through_account_data_verify_the_proof() {
+        let vector = generate(0);
+        let vk = vector.verifying_key();
+        let state = VerificationState::new(
+            &vector.proof_a,
+            &vector.proof_b,
+            &vector.proof_c,
+            &vector.public_inputs,
+            &vk,
+        )
+        .unwrap();
+        let mut account = vec![0u8; verification_state_size(vector.public_inputs.len())];
+        state.write_to(&mut account).unwrap();
+
+        let mut steps = 0;
+        loop {
+            let mut state = VerificationState::from_bytes(&account).unwrap();
+            let status = verify_step(&mut state, &vk, 1).unwrap();
+            state.write_to(&mut account).unwrap();
+            steps += 1;
+            if status == VerificationStatus::Verified {
+                break;
+            }
+        }
+        assert_eq!(steps, vector.public_inputs.len() + 1);
+        assert_eq!(
+            VerificationState::from_bytes(&account).unwrap().status(),
+            VerificationStatus::Verified
+        );
+
+        let other = generate(1);
+        let other_vk = other.verifying_key();
+        let mut state = VerificationState::new(
+            &other.proof_a,
+            &other.proof_b,
+            &other.proof_c,
+            &other.public_inputs,
+            &other_vk,
+        )
+        .unwrap();
+        assert_eq!(
+            verify_step(&mut state, &vk, 1),
+            Err(Groth16Error::VerifyingKeyMismatch)
+        );
+
+        // A verified state is verified for its own key only
+        let mut state = VerificationState::from_bytes(&account).unwrap();
+        assert_eq!(
+            verify_step(&mut state, &vk, 1),
+            Ok(VerificationStatus::Verified)
+        );
+        assert_eq!(
+            verify_step(&mut state, &other_vk, 1),
+            Err(Groth16Error::VerifyingKeyMismatch)
+        );
+    }
+
+    #[test]
+    fn accumulator_prepares_inputs_in_chunks() {
+        use crate::compute_units::PREPARE_INPUT_COST;
+        use crate::groth16::verify_with_prepared_inputs;
+
+        let vector = generate(3);
+        let vk = vector.verifying_key();
+        let empty_vk = Groth16Verifyingkey { vk_ic: &[], ..vk };
+        assert_eq!(
+            PreparedInputsAccumulator::new(&empty_vk),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+        let mut accumulator = PreparedInputsAccumulator::new(&vk).unwrap();
+        assert_eq!(
+            accumulator.remaining_compute_units(),
+            estimate_verify_compute_units(2)
+        );
+        assert_eq!(
+            accumulator.feed_within_budget(&vector.public_inputs, PREPARE_INPUT_COST + 1),
+            Ok(1)
+        );
+        assert_eq!(accumulator.remaining(), 1);
+        assert_eq!(
+            accumulator.finish(),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+        assert_eq!(
+            accumulator.feed(&vector.public_inputs),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+        accumulator.feed(&vector.public_inputs[1..]).unwrap();
+
+        let prepared = accumulator.finish().unwrap();
+        assert_eq!(
+            prepared,
+            PreparedInputs::new(&vector.public_inputs, &vk).unwrap()
+        );
+        verify_with_prepared_inputs(
+            &vector.proof_a,
+            &vector.proof_b,
+            &vector.proof_c,
+            &prepared,
+            &vk,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn invalid_proof_fails_in_the_last_step() {
+        let vector = generate(2);
+        let vk = vector.verifying_key();
+        let mut proof_c = vector.proof_c;
+        proof_c.copy_from_slice(&vector.proof_a);
+        let mut state = VerificationState::new(
+            &vector.proof_a,
+            &vector.proof_b,
+            &proof_c,
+            &vector.public_inputs,
+            &vk,
+        )
+        .unwrap();
+        assert_eq!(
+            verify_step(&mut state, &vk, usize::MAX),
+            Ok(VerificationStatus::Pending { remaining: 0 })
+        );
+        assert_eq!(
+            verify_step(&mut state, &vk, usize::MAX),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+    }
+
+    #[test]
+    fn corrupted_input_count_is_rejected() {
+        assert_eq!(checked_verification_state_size(usize::MAX / 32), None);
+
+        let vector = generate(0);
+        let vk = vector.verifying_key();
+        let state = VerificationState::new(
+            &vector.proof_a,
+            &vector.proof_b,
+            &vector.proof_c,
+            &vector.public_inputs,
+            &vk,
+        )
+        .unwrap();
+        let mut account = vec![0u8; verification_state_size(vector.public_inputs.len())];
+        state.write_to(&mut account).unwrap();
+        account[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            VerificationState::from_bytes(&account).err(),
+            Some(Groth16Error::InvalidAccountData)
+        );
+    }
+}
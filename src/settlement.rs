@@ -0,0 +1,433 @@
+//! Rollup settlement pipeline
+//!
+//! A sequencer settles batches of state transition proofs. Every proof
+//! exposes the state root it starts from and the root it ends in as public
+//! inputs, see [`RootChaining`]. A batch is settled in three steps that may
+//! span any number of transactions:
+//!
+//! 1. [`begin_batch`] fixes the number of proofs in the batch,
+//! 2. [`settle_proofs`] verifies the next proofs, as many per transaction as
+//!    the compute budget allows, see [`max_proofs_per_transaction`], and
+//!    checks that each proof starts from the root the previous one ended in,
+//! 3. [`finalize_batch`] commits the last root once all proofs are verified
+//!    and returns a [`SettlementSummary`] for the program to log or return.
+//!
+//! Until the batch is finalized the settled root does not change, so a batch
+//! that cannot be completed is dropped with [`abort_batch`]. The summary
+//! digest is a keccak256 chain over the proofs and public inputs of the
+//! batch, so indexers can check which transitions were settled.
+//!
+//! Account layout:
+//!
+//! | offset | size | field                                           |
+//! |--------|------|-------------------------------------------------|
+//! | 0      | 8    | discriminator `SETTLEMENT_DISCRIMINATOR`        |
+//! | 8      | 32   | authority, the sequencer                        |
+//! | 40     | 32   | settled state root                              |
+//! | 72     | 8    | number of settled batches, u64 LE               |
+//! | 80     | 1    | state, 0 = idle, 1 = batch in progress          |
+//! | 81     | 3    | padding                                         |
+//! | 84     | 4    | proofs of the batch, u32 LE                     |
+//! | 88     | 4    | verified proofs of the batch, u32 LE            |
+//! | 92     | 4    | padding                                         |
+//! | 96     | 32   | state root after the last verified proof        |
+//! | 128    | 32   | digest of the verified proofs                   |
+
+use crate::batch::BatchProof;
+use crate::compute_units::estimate_verify_compute_units;
+use crate::errors::Groth16Error;
+use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+use crate::hash::keccak256;
+use crate::Pubkey;
+use alloc::vec::Vec;
+#[cfg(feature = "pinocchio")]
+use pinocchio::account_info::AccountInfo;
+
+/// Discriminator stored in the first 8 bytes of a settlement account
+pub const SETTLEMENT_DISCRIMINATOR: [u8; 8] = *b"g16settl";
+
+/// Size of a settlement account
+pub const SETTLEMENT_ACCOUNT_SIZE: usize = 160;
+
+/// Compute units of root checks and digest hashing per proof
+pub const SETTLE_OVERHEAD_PER_PROOF: u64 = 2_000;
+
+const STATE_IDLE: u8 = 0;
+const STATE_IN_PROGRESS: u8 = 1;
+
+/// Positions of the state roots in the public inputs of a transition proof
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RootChaining {
+    /// Index of the root the transition starts from
+    pub old_root_index: usize,
+    /// Index of the root the transition ends in
+    pub new_root_index: usize,
+}
+
+/// Result of a settled batch
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SettlementSummary {
+    /// Index of the batch, starting at 0
+    pub batch_index: u64,
+    /// Settled root before the batch
+    pub previous_root: [u8; 32],
+    /// Settled root after the batch
+    pub new_root: [u8; 32],
+    /// Number of proofs in the batch
+    pub nr_proofs: u32,
+    /// Digest of the proofs and public inputs of the batch
+    pub digest: [u8; 32],
+}
+
+impl SettlementSummary {
+    /// Size of [`Self::to_bytes`]
+    pub const SIZE: usize = 108;
+
+    /// `batch_index u64 LE | previous_root | new_root | nr_proofs u32 LE | digest`
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0u8; Self::SIZE];
+        bytes[..8].copy_from_slice(&self.batch_index.to_le_bytes());
+        bytes[8..40].copy_from_slice(&self.previous_root);
+        bytes[40..72].copy_from_slice(&self.new_root);
+        bytes[72..76].copy_from_slice(&self.nr_proofs.to_le_bytes());
+        bytes[76..].copy_from_slice(&self.digest);
+        bytes
+    }
+}
+
+/// Number of transition proofs with `nr_inputs` public inputs [`settle_proofs`]
+/// verifies within `compute_unit_limit`
+pub const fn max_proofs_per_transaction(nr_inputs: usize, compute_unit_limit: u32) -> usize {
+    (compute_unit_limit as u64
+        / (estimate_verify_compute_units(nr_inputs) + SETTLE_OVERHEAD_PER_PROOF)) as usize
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn check_authority(data: &[u8], authority: &Pubkey) -> Result<(), Groth16Error> {
+    if data.len() < SETTLEMENT_ACCOUNT_SIZE || data[..8] != SETTLEMENT_DISCRIMINATOR {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    if data[8..40] != authority[..] {
+        return Err(Groth16Error::InvalidAuthority);
+    }
+    Ok(())
+}
+
+fn check_state(data: &[u8], state: u8) -> Result<(), Groth16Error> {
+    if data[80] != state {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// Initializes a zeroed account buffer with the genesis state root
+pub fn initialize_settlement_account(
+    data: &mut [u8],
+    authority: &Pubkey,
+    genesis_root: &[u8; 32],
+) -> Result<(), Groth16Error> {
+    if data.len() < SETTLEMENT_ACCOUNT_SIZE || data[..8] != [0u8; 8] {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    data[..8].copy_from_slice(&SETTLEMENT_DISCRIMINATOR);
+    data[8..40].copy_from_slice(authority);
+    data[40..72].copy_from_slice(genesis_root);
+    data[72..SETTLEMENT_ACCOUNT_SIZE].fill(0);
+    Ok(())
+}
+
+/// Settled state root of a settlement account
+pub fn settled_root(data: &[u8]) -> Result<&[u8; 32], Groth16Error> {
+    if data.len() < SETTLEMENT_ACCOUNT_SIZE || data[..8] != SETTLEMENT_DISCRIMINATOR {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    Ok(data[40..72].try_into().unwrap())
+}
+
+/// Starts a batch of `nr_proofs` transition proofs from the settled root
+pub fn begin_batch(
+    data: &mut [u8],
+    authority: &Pubkey,
+    nr_proofs: u32,
+) -> Result<(), Groth16Error> {
+    check_authority(data, authority)?;
+    check_state(data, STATE_IDLE)?;
+    if nr_proofs == 0 {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    data[80] = STATE_IN_PROGRESS;
+    data[84..88].copy_from_slice(&nr_proofs.to_le_bytes());
+    data[88..92].copy_from_slice(&0u32.to_le_bytes());
+    data.copy_within(40..72, 96);
+    data[128..160].fill(0);
+    Ok(())
+}
+
+/// Verifies the next `proofs` of the batch in progress
+///
+/// # Arguments
+/// * `proofs` - The next proofs of the batch, in transition order
+/// * `chaining` - Positions of the state roots in the public inputs
+/// * `verifyingkey` - Verifying key of the transition circuit
+///
+/// # Returns
+/// * `Err(Groth16Error::PublicInputMismatch)` - If a proof does not start
+///   from the root the previous proof ended in
+/// * `Err(Groth16Error::InvalidAccountData)` - If no batch is in progress or
+///   `proofs` exceeds the remaining proofs of the batch
+pub fn settle_proofs<const NR_INPUTS: usize>(
+    data: &mut [u8],
+    authority: &Pubkey,
+    proofs: &[BatchProof<NR_INPUTS>],
+    chaining: RootChaining,
+    verifyingkey: &Groth16Verifyingkey,
+) -> Result<(), Groth16Error> {
+    check_authority(data, authority)?;
+    check_state(data, STATE_IN_PROGRESS)?;
+    if chaining.old_root_index >= NR_INPUTS || chaining.new_root_index >= NR_INPUTS {
+        return Err(Groth16Error::InvalidPublicInputsLength);
+    }
+    let verified = read_u32(data, 88);
+    let remaining = read_u32(data, 84).saturating_sub(verified);
+    if proofs.len() > remaining as usize {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+
+    let mut root: [u8; 32] = data[96..128].try_into().unwrap();
+    let mut digest: [u8; 32] = data[128..160].try_into().unwrap();
+    for proof in proofs {
+        if proof.public_inputs[chaining.old_root_index] != root {
+            return Err(Groth16Error::PublicInputMismatch);
+        }
+        Groth16Verifier::new(
+            proof.proof_a,
+            proof.proof_b,
+            proof.proof_c,
+            proof.public_inputs,
+            verifyingkey,
+        )?
+        .verify()?;
+        root = proof.public_inputs[chaining.new_root_index];
+
+        let mut vals: Vec<&[u8]> = vec![&digest, proof.proof_a, proof.proof_b, proof.proof_c];
+        vals.extend(proof.public_inputs.iter().map(|input| input.as_slice()));
+        digest = keccak256(&vals);
+    }
+
+    data[88..92].copy_from_slice(&(verified + proofs.len() as u32).to_le_bytes());
+    data[96..128].copy_from_slice(&root);
+    data[128..160].copy_from_slice(&digest);
+    Ok(())
+}
+
+/// Commits the root of a completely verified batch
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidAccountData)` - If no batch is in progress or
+///   not all of its proofs are verified
+pub fn finalize_batch(
+    data: &mut [u8],
+    authority: &Pubkey,
+) -> Result<SettlementSummary, Groth16Error> {
+    check_authority(data, authority)?;
+    check_state(data, STATE_IN_PROGRESS)?;
+    let nr_proofs = read_u32(data, 84);
+    if read_u32(data, 88) != nr_proofs {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    let batch_index = u64::from_le_bytes(data[72..80].try_into().unwrap());
+    let summary = SettlementSummary {
+        batch_index,
+        previous_root: data[40..72].try_into().unwrap(),
+        new_root: data[96..128].try_into().unwrap(),
+        nr_proofs,
+        digest: data[128..160].try_into().unwrap(),
+    };
+    data.copy_within(96..128, 40);
+    data[72..80].copy_from_slice(&(batch_index + 1).to_le_bytes());
+    data[80..SETTLEMENT_ACCOUNT_SIZE].fill(0);
+    Ok(summary)
+}
+
+/// Drops the batch in progress, the settled root is unchanged
+pub fn abort_batch(data: &mut [u8], authority: &Pubkey) -> Result<(), Groth16Error> {
+    check_authority(data, authority)?;
+    check_state(data, STATE_IN_PROGRESS)?;
+    data[80..SETTLEMENT_ACCOUNT_SIZE].fill(0);
+    Ok(())
+}
+
+/// Borrows the data of a settlement account after owner, writable and signer checks
+#[cfg(feature = "pinocchio")]
+fn with_settlement_account<T>(
+    account: &AccountInfo,
+    authority: &AccountInfo,
+    program_id: &Pubkey,
+    f: impl FnOnce(&mut [u8], &Pubkey) -> Result<T, Groth16Error>,
+) -> Result<T, Groth16Error> {
+    if !account.is_owned_by(program_id) {
+        return Err(Groth16Error::InvalidAccountOwner);
+    }
+    if !account.is_writable() {
+        return Err(Groth16Error::AccountNotWritable);
+    }
+    if !authority.is_signer() {
+        return Err(Groth16Error::MissingRequiredSignature);
+    }
+    let mut data = account
+        .try_borrow_mut_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    f(&mut data, authority.key())
+}
+
+/// [`begin_batch`] with owner, writable and signer checks
+#[cfg(feature = "pinocchio")]
+pub fn begin_batch_checked(
+    account: &AccountInfo,
+    authority: &AccountInfo,
+    program_id: &Pubkey,
+    nr_proofs: u32,
+) -> Result<(), Groth16Error> {
+    with_settlement_account(account, authority, program_id, |data, authority| {
+        begin_batch(data, authority, nr_proofs)
+    })
+}
+
+/// [`settle_proofs`] with owner, writable and signer checks
+#[cfg(feature = "pinocchio")]
+pub fn settle_proofs_checked<const NR_INPUTS: usize>(
+    account: &AccountInfo,
+    authority: &AccountInfo,
+    program_id: &Pubkey,
+    proofs: &[BatchProof<NR_INPUTS>],
+    chaining: RootChaining,
+    verifyingkey: &Groth16Verifyingkey,
+) -> Result<(), Groth16Error> {
+    with_settlement_account(account, authority, program_id, |data, authority| {
+        settle_proofs(data, authority, proofs, chaining, verifyingkey)
+    })
+}
+
+/// [`finalize_batch`] with owner, writable and signer checks
+#[cfg(feature = "pinocchio")]
+pub fn finalize_batch_checked(
+    account: &AccountInfo,
+    authority: &AccountInfo,
+    program_id: &Pubkey,
+) -> Result<SettlementSummary, Groth16Error> {
+    with_settlement_account(account, authority, program_id, finalize_batch)
+}
+
+/// [`abort_batch`] with owner, writable and signer checks
+#[cfg(feature = "pinocchio")]
+pub fn abort_batch_checked(
+    account: &AccountInfo,
+    authority: &AccountInfo,
+    program_id: &Pubkey,
+) -> Result<(), Groth16Error> {
+    with_settlement_account(account, authority, program_id, abort_batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_units::MAX_COMPUTE_UNIT_LIMIT;
+
+    const AUTHORITY: Pubkey = [1u8; 32];
+    const CHAINING: RootChaining = RootChaining {
+        old_root_index: 0,
+        new_root_index: 1,
+    };
+
+    #[test]
+    fn batch_lifecycle_and_root_chaining() {
+        let vk_ic = [[0u8; 64]; 3];
+        let vk = Groth16Verifyingkey {
+            nr_pubinputs: 2,
+            vk_alpha_g1: [0u8; 64],
+            vk_beta_g2: [0u8; 128],
+            vk_gamma_g2: [0u8; 128],
+            vk_delta_g2: [0u8; 128],
+            vk_ic: &vk_ic,
+        };
+        let mut data = [0u8; SETTLEMENT_ACCOUNT_SIZE];
+        initialize_settlement_account(&mut data, &AUTHORITY, &[0u8; 32]).unwrap();
+        assert_eq!(
+            finalize_batch(&mut data, &AUTHORITY),
+            Err(Groth16Error::InvalidAccountData)
+        );
+        assert_eq!(
+            begin_batch(&mut data, &[2u8; 32], 2),
+            Err(Groth16Error::InvalidAuthority)
+        );
+        begin_batch(&mut data, &AUTHORITY, 2).unwrap();
+
+        // The identity key accepts identity proofs with any inputs
+        let first_inputs = [[0u8; 32], [7u8; 32]];
+        let second_inputs = [[7u8; 32], [9u8; 32]];
+        let proof = |public_inputs| BatchProof {
+            proof_a: &[0u8; 64],
+            proof_b: &[0u8; 128],
+            proof_c: &[0u8; 64],
+            public_inputs,
+        };
+        assert_eq!(
+            settle_proofs(
+                &mut data,
+                &AUTHORITY,
+                &[proof(&second_inputs)],
+                CHAINING,
+                &vk
+            ),
+            Err(Groth16Error::PublicInputMismatch)
+        );
+        settle_proofs(
+            &mut data,
+            &AUTHORITY,
+            &[proof(&first_inputs)],
+            CHAINING,
+            &vk,
+        )
+        .unwrap();
+        assert_eq!(
+            finalize_batch(&mut data, &AUTHORITY),
+            Err(Groth16Error::InvalidAccountData)
+        );
+        assert_eq!(
+            settle_proofs(
+                &mut data,
+                &AUTHORITY,
+                &[proof(&second_inputs), proof(&second_inputs)],
+                CHAINING,
+                &vk
+            ),
+            Err(Groth16Error::InvalidAccountData)
+        );
+        settle_proofs(
+            &mut data,
+            &AUTHORITY,
+            &[proof(&second_inputs)],
+            CHAINING,
+            &vk,
+        )
+        .unwrap();
+        assert_eq!(settled_root(&data).unwrap(), &[0u8; 32]);
+
+        let summary = finalize_batch(&mut data, &AUTHORITY).unwrap();
+        assert_eq!(summary.batch_index, 0);
+        assert_eq!(summary.previous_root, [0u8; 32]);
+        assert_eq!(summary.new_root, [9u8; 32]);
+        assert_eq!(summary.nr_proofs, 2);
+        assert_eq!(settled_root(&data).unwrap(), &[9u8; 32]);
+        assert_eq!(summary.to_bytes()[40..72], [9u8; 32]);
+
+        begin_batch(&mut data, &AUTHORITY, 1).unwrap();
+        abort_batch(&mut data, &AUTHORITY).unwrap();
+        assert_eq!(settled_root(&data).unwrap(), &[9u8; 32]);
+
+        assert!(max_proofs_per_transaction(2, MAX_COMPUTE_UNIT_LIMIT) >= 4);
+    }
+}
@@ -0,0 +1,259 @@
+//! proptest strategies for proofs, public inputs and verifying keys
+//!
+//! Every value comes in a valid flavour and a near-valid flavour. Near-valid
+//! values mix valid values with the edge cases parsers and validators have
+//! to reject or handle explicitly:
+//!
+//! * field elements at the modulus boundary and non-canonical encodings `x + p`
+//! * the all-zero point at infinity
+//! * points that are not on the curve
+//! * packed verifying keys with a wrong length or input count
+//!
+//! ```rust,ignore
+//! use pinocchio_groth16::strategies;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     #[test]
+//!     fn rejects_non_canonical_inputs(input in strategies::public_input()) {
+//!         let _ = my_program::check_input(&input);
+//!     }
+//! }
+//! ```
+
+use crate::test_vectors::{fr_to_be, g1_to_be, g2_to_be, TestVector};
+use alloc::vec::Vec;
+use ark_bn254::{Fq, Fr, G1Affine, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{PrimeField, Zero};
+use num_bigint::BigUint;
+use proptest::prelude::*;
+
+fn modulus() -> BigUint {
+    BigUint::from(Fq::MODULUS)
+}
+
+fn biguint_to_be(value: &BigUint) -> Option<[u8; 32]> {
+    let bytes = value.to_bytes_be();
+    if bytes.len() > 32 {
+        return None;
+    }
+    let mut be = [0u8; 32];
+    be[32 - bytes.len()..].copy_from_slice(&bytes);
+    Some(be)
+}
+
+/// Adds the field modulus to a big-endian coordinate if the sum fits into 32 bytes
+fn non_canonical(be: &[u8; 32]) -> Option<[u8; 32]> {
+    biguint_to_be(&(BigUint::from_bytes_be(be) + modulus()))
+}
+
+/// Canonical big-endian field elements, i.e. valid public inputs
+pub fn field_element() -> impl Strategy<Value = [u8; 32]> {
+    any::<[u8; 32]>().prop_map(|bytes| fr_to_be(&Fr::from_be_bytes_mod_order(&bytes)))
+}
+
+/// 0, 1, p - 1, p, p + 1 and 2^256 - 1 of the scalar field
+pub fn boundary_field_element() -> impl Strategy<Value = [u8; 32]> {
+    let p = BigUint::from(Fr::MODULUS);
+    let values: Vec<[u8; 32]> = [
+        BigUint::from(0u8),
+        BigUint::from(1u8),
+        &p - 1u8,
+        p.clone(),
+        &p + 1u8,
+    ]
+    .iter()
+    .filter_map(biguint_to_be)
+    .chain([[0xffu8; 32]])
+    .collect();
+    proptest::sample::select(values)
+}
+
+/// Encodings `x + p` of scalar field elements, which are not canonical
+pub fn non_canonical_field_element() -> impl Strategy<Value = [u8; 32]> {
+    let p = BigUint::from(Fr::MODULUS);
+    field_element().prop_filter_map("does not fit into 32 bytes", move |be| {
+        biguint_to_be(&(BigUint::from_bytes_be(&be) + &p))
+    })
+}
+
+/// Mostly canonical public inputs with boundary and non-canonical values
+pub fn public_input() -> impl Strategy<Value = [u8; 32]> {
+    prop_oneof![
+        6 => field_element(),
+        1 => boundary_field_element(),
+        1 => non_canonical_field_element(),
+    ]
+}
+
+/// Arrays of [`public_input`]s
+pub fn public_inputs<const N: usize>() -> impl Strategy<Value = [[u8; 32]; N]> {
+    proptest::array::uniform(public_input())
+}
+
+/// Big-endian G1 points in the prime order group, without the identity
+pub fn g1_point() -> impl Strategy<Value = [u8; 64]> {
+    any::<[u8; 32]>()
+        .prop_map(|scalar| Fr::from_be_bytes_mod_order(&scalar))
+        .prop_filter("identity", |scalar| !scalar.is_zero())
+        .prop_map(|scalar| g1_to_be(&(G1Affine::generator() * scalar).into_affine()))
+}
+
+/// Big-endian G2 points in the prime order group, without the identity
+pub fn g2_point() -> impl Strategy<Value = [u8; 128]> {
+    any::<[u8; 32]>()
+        .prop_map(|scalar| Fr::from_be_bytes_mod_order(&scalar))
+        .prop_filter("identity", |scalar| !scalar.is_zero())
+        .prop_map(|scalar| g2_to_be(&(G2Affine::generator() * scalar).into_affine()))
+}
+
+/// G1 points mixed with the identity, off-curve points and non-canonical x coordinates
+pub fn g1_point_near_valid() -> impl Strategy<Value = [u8; 64]> {
+    prop_oneof![
+        4 => g1_point(),
+        1 => Just([0u8; 64]),
+        1 => g1_point().prop_map(|mut point| {
+            point[63] ^= 1;
+            point
+        }),
+        1 => g1_point().prop_filter_map("does not fit into 32 bytes", |mut point| {
+            let x = non_canonical(point[..32].try_into().unwrap())?;
+            point[..32].copy_from_slice(&x);
+            Some(point)
+        }),
+    ]
+}
+
+/// G2 points mixed with the identity, off-curve points and non-canonical coordinates
+pub fn g2_point_near_valid() -> impl Strategy<Value = [u8; 128]> {
+    prop_oneof![
+        4 => g2_point(),
+        1 => Just([0u8; 128]),
+        1 => g2_point().prop_map(|mut point| {
+            point[127] ^= 1;
+            point
+        }),
+        1 => g2_point().prop_filter_map("does not fit into 32 bytes", |mut point| {
+            let x_c1 = non_canonical(point[..32].try_into().unwrap())?;
+            point[..32].copy_from_slice(&x_c1);
+            Some(point)
+        }),
+    ]
+}
+
+/// `(proof_a, proof_b, proof_c)` of valid points that do not verify under any known key
+pub fn proof() -> impl Strategy<Value = ([u8; 64], [u8; 128], [u8; 64])> {
+    (g1_point(), g2_point(), g1_point())
+}
+
+/// `(proof_a, proof_b, proof_c)` built from [`g1_point_near_valid`] and [`g2_point_near_valid`]
+pub fn proof_near_valid() -> impl Strategy<Value = ([u8; 64], [u8; 128], [u8; 64])> {
+    (
+        g1_point_near_valid(),
+        g2_point_near_valid(),
+        g1_point_near_valid(),
+    )
+}
+
+/// Valid proofs of the statement of `vector`, obtained by rerandomizing its proof
+pub fn valid_proof(vector: TestVector) -> impl Strategy<Value = TestVector> {
+    any::<u64>().prop_map(move |seed| vector.rerandomize(seed))
+}
+
+/// Packed verifying keys of valid points with up to `max_nr_pubinputs` inputs
+pub fn packed_verifying_key(max_nr_pubinputs: usize) -> impl Strategy<Value = Vec<u8>> {
+    (
+        g1_point(),
+        g2_point(),
+        g2_point(),
+        g2_point(),
+        proptest::collection::vec(g1_point(), 1..=max_nr_pubinputs + 1),
+    )
+        .prop_map(|(alpha, beta, gamma, delta, ic)| {
+            let mut packed = Vec::with_capacity(4 + 64 + 3 * 128 + ic.len() * 64);
+            packed.extend_from_slice(&((ic.len() - 1) as u32).to_le_bytes());
+            packed.extend_from_slice(&alpha);
+            packed.extend_from_slice(&beta);
+            packed.extend_from_slice(&gamma);
+            packed.extend_from_slice(&delta);
+            for point in &ic {
+                packed.extend_from_slice(point);
+            }
+            packed
+        })
+}
+
+/// Packed verifying keys that are truncated, extended or carry a wrong input count
+pub fn packed_verifying_key_near_valid(max_nr_pubinputs: usize) -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        packed_verifying_key(max_nr_pubinputs),
+        (
+            packed_verifying_key(max_nr_pubinputs),
+            any::<prop::sample::Index>()
+        )
+            .prop_map(|(mut packed, index)| {
+                packed.truncate(index.index(packed.len()));
+                packed
+            }),
+        (packed_verifying_key(max_nr_pubinputs), 1u8..=64).prop_map(|(mut packed, extra)| {
+            packed.extend(core::iter::repeat_n(0, extra as usize));
+            packed
+        }),
+        (packed_verifying_key(max_nr_pubinputs), any::<u32>()).prop_map(|(mut packed, count)| {
+            packed[..4].copy_from_slice(&count.to_le_bytes());
+            packed
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decompression::decompress_g1;
+    use crate::errors::Groth16Error;
+    use crate::groth16::{is_less_than_bn254_field_size_be, Groth16Verifyingkey};
+    use crate::test_vectors::generate;
+    use ark_serialize::{CanonicalSerialize, Compress};
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn field_size_check_matches_modulus(input in public_input()) {
+            let canonical = BigUint::from_bytes_be(&input) < BigUint::from(Fr::MODULUS);
+            prop_assert_eq!(is_less_than_bn254_field_size_be(&input), canonical);
+        }
+
+        #[test]
+        fn packed_verifying_key_parsing(packed in packed_verifying_key_near_valid(4)) {
+            match Groth16Verifyingkey::from_packed_bytes(&packed) {
+                Ok(vk) => {
+                    prop_assert_eq!(packed.len(), Groth16Verifyingkey::packed_size(vk.nr_pubinputs));
+                    let mut out = vec![0u8; packed.len()];
+                    prop_assert_eq!(vk.write_packed_bytes(&mut out), Ok(packed.len()));
+                    prop_assert_eq!(out, packed);
+                }
+                Err(error) => prop_assert_eq!(error, Groth16Error::InvalidAccountData),
+            }
+        }
+
+        #[test]
+        fn g1_decompression_roundtrip(point in g1_point()) {
+            let affine = crate::test_vectors::g1_from_be(&point);
+            let mut compressed = [0u8; 32];
+            affine.serialize_with_mode(&mut compressed[..], Compress::Yes).unwrap();
+            compressed.reverse();
+            prop_assert_eq!(decompress_g1(&compressed), Ok(point));
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(4))]
+
+        #[test]
+        fn rerandomized_proofs_verify(vector in valid_proof(generate(0))) {
+            prop_assert_eq!(vector.verify(), Ok(()));
+        }
+    }
+}
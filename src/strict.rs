@@ -0,0 +1,109 @@
+//! Strict canonical encoding of proofs
+//!
+//! The syscalls accept the all-zero encoding of the point at infinity for
+//! every proof point, and the encodings they accept are defined by the
+//! runtime, not by this crate. Protocols that use proof bytes as uniqueness
+//! material enable the `strict` feature and verify with
+//! [`Groth16Verifier::verify_strict`](crate::groth16::Groth16Verifier::verify_strict),
+//! which runs [`check_canonical_proof`] before any syscall. The accepted
+//! encoding is exactly:
+//!
+//! * a and c: `x | y`, b: `x.c1 | x.c0 | y.c1 | y.c0`, all big-endian
+//! * every coordinate smaller than the base field modulus q
+//! * no point at infinity, i.e. not all coordinates zero
+//!
+//! Together with the curve checks of the syscalls every point then has a
+//! single accepted encoding. The checks are byte comparisons and cost a few
+//! hundred compute units.
+//!
+//! This does not make the proof itself unique: anyone holding a valid Groth16
+//! proof can re-randomize it into another valid proof of the same public
+//! inputs without knowing the witness. Uniqueness has to come from the public
+//! inputs, such as a nullifier, see [`crate::nullifier`], never from the
+//! proof bytes.
+
+use crate::curve::{Bn254, PairingCurve};
+use crate::errors::Groth16Error;
+
+fn check_coordinates(point: &[u8]) -> Result<(), Groth16Error> {
+    if point
+        .chunks_exact(32)
+        .any(|coordinate| coordinate >= Bn254::BASE_FIELD_MODULUS)
+    {
+        return Err(Groth16Error::NonCanonicalCoordinate);
+    }
+    if point.iter().all(|byte| *byte == 0) {
+        return Err(Groth16Error::PointAtInfinity);
+    }
+    Ok(())
+}
+
+/// Checks that `point` is a canonical G1 encoding other than infinity
+///
+/// # Returns
+/// * `Err(Groth16Error::NonCanonicalCoordinate)` - If x or y is not smaller
+///   than the base field modulus
+/// * `Err(Groth16Error::PointAtInfinity)` - If the point is all zero
+pub fn check_canonical_g1(point: &[u8; 64]) -> Result<(), Groth16Error> {
+    check_coordinates(point)
+}
+
+/// Checks that `point` is a canonical G2 encoding other than infinity
+///
+/// # Returns
+/// * `Err(Groth16Error::NonCanonicalCoordinate)` - If a coordinate is not
+///   smaller than the base field modulus
+/// * `Err(Groth16Error::PointAtInfinity)` - If the point is all zero
+pub fn check_canonical_g2(point: &[u8; 128]) -> Result<(), Groth16Error> {
+    check_coordinates(point)
+}
+
+/// Checks the three points of a proof, see [`check_canonical_g1`] and
+/// [`check_canonical_g2`]
+pub fn check_canonical_proof(
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+) -> Result<(), Groth16Error> {
+    check_canonical_g1(proof_a)?;
+    check_canonical_g2(proof_b)?;
+    check_canonical_g1(proof_c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_non_canonical_encodings() {
+        let mut g1 = [0u8; 64];
+        g1[31] = 1;
+        g1[63] = 2;
+        let mut g2 = [0u8; 128];
+        g2[31] = 1;
+        check_canonical_proof(&g1, &g2, &g1).unwrap();
+
+        assert_eq!(
+            check_canonical_proof(&[0u8; 64], &g2, &g1),
+            Err(Groth16Error::PointAtInfinity)
+        );
+        assert_eq!(
+            check_canonical_proof(&g1, &[0u8; 128], &g1),
+            Err(Groth16Error::PointAtInfinity)
+        );
+
+        // y + q encodes the same coordinate as y
+        let mut unreduced = g1;
+        unreduced[32..].copy_from_slice(Bn254::BASE_FIELD_MODULUS);
+        assert_eq!(
+            check_canonical_proof(&g1, &g2, &unreduced),
+            Err(Groth16Error::NonCanonicalCoordinate)
+        );
+        let mut unreduced = g2;
+        unreduced[96..].copy_from_slice(Bn254::BASE_FIELD_MODULUS);
+        assert_eq!(
+            check_canonical_g2(&unreduced),
+            Err(Groth16Error::NonCanonicalCoordinate)
+        );
+    }
+}
@@ -0,0 +1,337 @@
+//! LiteSVM helpers for integration tests of verifier programs
+//!
+//! Runs compiled programs in-process instead of against a validator. Loading
+//! programs, checking which alt_bn128 syscalls the runtime provides, sending
+//! verify instructions and reading the consumed compute units take one call
+//! each:
+//!
+//! ```rust,ignore
+//! use pinocchio_groth16::svm::{verify_instruction_data, VerifierSvm};
+//!
+//! let mut svm = VerifierSvm::new();
+//! let program_id = svm.load_program("target/deploy/my_verifier.so")?;
+//! assert!(svm.syscalls().alt_bn128);
+//!
+//! let data = verify_instruction_data(&proof_a, &proof_b, &proof_c, &public_inputs);
+//! let execution = svm.invoke(program_id, &data, vec![]).unwrap();
+//! assert!(execution.compute_units_consumed < 200_000);
+//! ```
+//!
+//! The harness program in `tests/differential` verifies a proof of any
+//! circuit passed with its verifying key, so a circuit's correctness and
+//! cost are regression tested without writing a program:
+//!
+//! ```rust,ignore
+//! let program_id = svm.load_program(HARNESS_PROGRAM_PATH)?;
+//! let compute_units = svm.measure_fixture(program_id, &MERKLE_PROOF).unwrap();
+//! assert!(compute_units < 120_000);
+//! ```
+
+use crate::errors::Groth16Error;
+use crate::groth16::Groth16Verifyingkey;
+use crate::ix_data::encode_verify_ix_data;
+use crate::proof::Proof;
+#[cfg(feature = "test-fixtures")]
+use crate::proof::{ProofA, ProofB, ProofC};
+use crate::syscall_features::{ENABLE_ALT_BN128_COMPRESSION_SYSCALL, ENABLE_ALT_BN128_SYSCALL};
+use agave_feature_set::FeatureSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+use litesvm::types::TransactionMetadata;
+use litesvm::LiteSVM;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use std::path::Path;
+
+/// Lamports airdropped to the payer
+pub const PAYER_LAMPORTS: u64 = 100_000_000_000;
+
+/// alt_bn128 syscalls provided by the runtime
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SyscallAvailability {
+    /// Addition, multiplication and pairing
+    pub alt_bn128: bool,
+    /// G1 and G2 compression and decompression
+    pub alt_bn128_compression: bool,
+}
+
+/// Outcome of a transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Execution {
+    pub compute_units_consumed: u64,
+    pub logs: Vec<String>,
+    /// The transaction error, `None` if the transaction succeeded
+    pub error: Option<String>,
+}
+
+impl Execution {
+    fn new(meta: TransactionMetadata, error: Option<String>) -> Self {
+        Execution {
+            compute_units_consumed: meta.compute_units_consumed,
+            logs: meta.logs,
+            error,
+        }
+    }
+}
+
+/// A LiteSVM runtime with a funded payer
+pub struct VerifierSvm {
+    pub svm: LiteSVM,
+    pub payer: Keypair,
+    syscalls: SyscallAvailability,
+}
+
+impl Default for VerifierSvm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VerifierSvm {
+    /// Creates a runtime with all features enabled
+    pub fn new() -> Self {
+        Self::with_feature_set(FeatureSet::all_enabled())
+    }
+
+    /// Creates a runtime without the alt_bn128 compression syscalls
+    ///
+    /// Tests the fallback of programs on clusters where compression is not
+    /// activated, see [`syscall_features`](crate::syscall_features).
+    pub fn without_compression_syscall() -> Self {
+        let mut feature_set = FeatureSet::all_enabled();
+        feature_set.deactivate(&Pubkey::new_from_array(
+            ENABLE_ALT_BN128_COMPRESSION_SYSCALL,
+        ));
+        Self::with_feature_set(feature_set)
+    }
+
+    /// Creates a runtime with the features of `feature_set`, e.g. those of a cluster
+    pub fn with_feature_set(feature_set: FeatureSet) -> Self {
+        let syscalls = SyscallAvailability {
+            alt_bn128: feature_set.is_active(&Pubkey::new_from_array(ENABLE_ALT_BN128_SYSCALL)),
+            alt_bn128_compression: feature_set.is_active(&Pubkey::new_from_array(
+                ENABLE_ALT_BN128_COMPRESSION_SYSCALL,
+            )),
+        };
+        let mut svm = LiteSVM::default()
+            .with_feature_set(feature_set)
+            .with_builtins()
+            .with_lamports(u64::MAX / 2)
+            .with_sysvars()
+            .with_default_programs()
+            .with_sigverify(true)
+            .with_blockhash_check(true);
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), PAYER_LAMPORTS)
+            .expect("airdrop to payer");
+        VerifierSvm {
+            svm,
+            payer,
+            syscalls,
+        }
+    }
+
+    /// Loads the program binary at `path` under a new program id
+    ///
+    /// # Returns
+    /// * `Ok(Pubkey)` - The program id
+    /// * `Err(String)` - If the binary is missing or cannot be loaded
+    pub fn load_program(&mut self, path: impl AsRef<Path>) -> Result<Pubkey, String> {
+        let program_id = Pubkey::new_unique();
+        self.load_program_at(program_id, path)?;
+        Ok(program_id)
+    }
+
+    /// Loads the program binary at `path` under `program_id`
+    pub fn load_program_at(
+        &mut self,
+        program_id: Pubkey,
+        path: impl AsRef<Path>,
+    ) -> Result<(), String> {
+        let path = path.as_ref();
+        self.svm
+            .add_program_from_file(program_id, path)
+            .map_err(|e| format!("{}: {e}, run `cargo build-sbf` first", path.display()))
+    }
+
+    /// Returns the alt_bn128 syscalls enabled in the runtime's feature set
+    pub fn syscalls(&self) -> SyscallAvailability {
+        self.syscalls
+    }
+
+    /// Sends `instructions` in one transaction signed by the payer
+    ///
+    /// Expires the blockhash afterwards, so identical transactions can be sent again.
+    ///
+    /// # Returns
+    /// * `Ok(Execution)` - If the transaction succeeded
+    /// * `Err(Execution)` - If it failed, with the error and the logs up to the failure
+    pub fn send(&mut self, instructions: &[Instruction]) -> Result<Execution, Execution> {
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            self.svm.latest_blockhash(),
+        );
+        let result = self.svm.send_transaction(tx);
+        self.svm.expire_blockhash();
+        match result {
+            Ok(meta) => Ok(Execution::new(meta, None)),
+            Err(failed) => Err(Execution::new(
+                failed.meta,
+                Some(format!("{:?}", failed.err)),
+            )),
+        }
+    }
+
+    /// Invokes `program_id` with `data` and `accounts`
+    pub fn invoke(
+        &mut self,
+        program_id: Pubkey,
+        data: &[u8],
+        accounts: Vec<AccountMeta>,
+    ) -> Result<Execution, Execution> {
+        self.send(&[Instruction::new_with_bytes(program_id, data, accounts)])
+    }
+}
+
+/// Instruction data `proof_a | proof_b | proof_c | public inputs`
+pub fn verify_instruction_data(
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    public_inputs: &[[u8; 32]],
+) -> Vec<u8> {
+    let mut data = Vec::with_capacity(256 + public_inputs.len() * 32);
+    data.extend_from_slice(proof_a);
+    data.extend_from_slice(proof_b);
+    data.extend_from_slice(proof_c);
+    for input in public_inputs {
+        data.extend_from_slice(input);
+    }
+    data
+}
+
+/// Path of the harness program built by
+/// `cargo build-sbf --manifest-path tests/differential/Cargo.toml`, relative
+/// to the repository root
+pub const HARNESS_PROGRAM_PATH: &str = "target/deploy/differential_verifier.so";
+
+/// Instruction data of the harness program, `verifying key | ix_data`
+///
+/// The key in its binary encoding, see [`Groth16Verifyingkey::to_bytes`],
+/// followed by [`encode_verify_ix_data`]. The program verifies proofs of any
+/// number of public inputs.
+pub fn harness_instruction_data(
+    verifyingkey: &Groth16Verifyingkey,
+    proof: &Proof,
+    public_inputs: &[[u8; 32]],
+) -> Result<Vec<u8>, Groth16Error> {
+    let mut data = verifyingkey.to_bytes()?;
+    data.extend(encode_verify_ix_data(proof, public_inputs)?);
+    Ok(data)
+}
+
+impl VerifierSvm {
+    /// Verifies a proof with the harness program loaded as `program_id`
+    ///
+    /// # Returns
+    /// * `Ok(compute_units)` - The compute units the verification consumed
+    /// * `Err(Execution)` - If the proof does not verify, with the logs
+    pub fn measure_verify(
+        &mut self,
+        program_id: Pubkey,
+        verifyingkey: &Groth16Verifyingkey,
+        proof: &Proof,
+        public_inputs: &[[u8; 32]],
+    ) -> Result<u64, Execution> {
+        let data =
+            harness_instruction_data(verifyingkey, proof, public_inputs).map_err(|error| {
+                Execution {
+                    compute_units_consumed: 0,
+                    logs: Vec::new(),
+                    error: Some(format!("{error:?}")),
+                }
+            })?;
+        self.invoke(program_id, &data, vec![])
+            .map(|execution| execution.compute_units_consumed)
+    }
+
+    /// Verifies a fixture with the harness program, see [`measure_verify`](Self::measure_verify)
+    #[cfg(feature = "test-fixtures")]
+    pub fn measure_fixture<const N: usize>(
+        &mut self,
+        program_id: Pubkey,
+        fixture: &crate::test_fixtures::Fixture<N>,
+    ) -> Result<u64, Execution> {
+        let proof = Proof {
+            a: ProofA(fixture.proof_a),
+            b: ProofB(fixture.proof_b),
+            c: ProofC(fixture.proof_c),
+        };
+        self.measure_verify(
+            program_id,
+            &fixture.verifying_key,
+            &proof,
+            &fixture.public_inputs,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn syscall_availability_follows_feature_set() {
+        let svm = VerifierSvm::new();
+        assert_eq!(
+            svm.syscalls(),
+            SyscallAvailability {
+                alt_bn128: true,
+                alt_bn128_compression: true,
+            }
+        );
+        assert_eq!(
+            VerifierSvm::without_compression_syscall().syscalls(),
+            SyscallAvailability {
+                alt_bn128: true,
+                alt_bn128_compression: false,
+            }
+        );
+    }
+
+    #[test]
+    fn missing_program_and_failed_transactions_are_reported() {
+        let mut svm = VerifierSvm::new();
+        let error = svm.load_program("target/deploy/missing.so").unwrap_err();
+        assert!(error.contains("cargo build-sbf"));
+
+        let execution = svm
+            .invoke(Pubkey::new_unique(), &[1, 2, 3], vec![])
+            .unwrap_err();
+        assert!(execution.error.is_some());
+
+        let vk_ic = [[0u8; 64]; 2];
+        let vk = Groth16Verifyingkey {
+            nr_pubinputs: 1,
+            vk_alpha_g1: [0u8; 64],
+            vk_beta_g2: [0u8; 128],
+            vk_gamma_g2: [0u8; 128],
+            vk_delta_g2: [0u8; 128],
+            vk_ic: &vk_ic,
+        };
+        let proof = Proof::try_from(&[0u8; 256][..]).unwrap();
+        let data = harness_instruction_data(&vk, &proof, &[[1u8; 32]]).unwrap();
+        assert_eq!(
+            data.len(),
+            Groth16Verifyingkey::binary_size(1) + 4 + 256 + 32
+        );
+        assert!(svm
+            .measure_verify(Pubkey::new_unique(), &vk, &proof, &[[1u8; 32]])
+            .is_err());
+    }
+}
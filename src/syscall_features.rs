@@ -0,0 +1,205 @@
+//! Runtime detection of syscall feature gates
+//!
+//! Clusters activate syscalls at different times, so mainnet-beta, devnet
+//! and a local validator may disagree on whether e.g. the alt_bn128
+//! compression syscalls are available. Programs cannot query the feature set directly, but every feature has an
+//! account owned by the feature program that stores its activation slot once
+//! activated. Passing that account to the program lets one build pick the
+//! compressed or uncompressed verification path at runtime:
+//!
+//! ```rust,ignore
+//! let features = SyscallFeatures::load(Some(compression_feature_account))?;
+//! verify_proof::<N>(&features, &proof, &public_inputs, &VERIFYINGKEY)?;
+//! ```
+//!
+//! Clients read the same accounts with `getAccountInfo` to decide whether to
+//! submit a compressed proof.
+
+use crate::decompression::{decompress_g1, decompress_g2};
+use crate::errors::Groth16Error;
+use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+use crate::Pubkey;
+#[cfg(feature = "pinocchio")]
+use pinocchio::account_info::AccountInfo;
+
+/// Feature program id, `Feature111111111111111111111111111111111111`
+pub const FEATURE_PROGRAM_ID: Pubkey = [
+    3, 192, 160, 205, 203, 6, 210, 218, 239, 174, 130, 209, 111, 238, 122, 207, 97, 236, 115, 123,
+    35, 72, 27, 33, 148, 106, 118, 112, 0, 0, 0, 0,
+];
+
+/// `enable_alt_bn128_syscall`, `A16q37opZdQMCbe5qJ6xpBB9usykfv8jZaMkxvZQi4GJ`
+pub const ENABLE_ALT_BN128_SYSCALL: Pubkey = [
+    133, 191, 197, 182, 222, 30, 239, 170, 164, 134, 204, 108, 106, 128, 218, 237, 148, 123, 24,
+    172, 127, 124, 194, 93, 249, 88, 74, 134, 152, 179, 255, 123,
+];
+
+/// `enable_alt_bn128_compression_syscall`, `EJJewYSddEEtSZHiqugnvhQHiWyZKjkFDQASd7oKSagn`
+pub const ENABLE_ALT_BN128_COMPRESSION_SYSCALL: Pubkey = [
+    197, 150, 195, 241, 93, 91, 181, 155, 16, 95, 244, 214, 65, 44, 105, 226, 220, 140, 167, 223,
+    111, 186, 39, 96, 251, 62, 91, 152, 70, 228, 180, 239,
+];
+
+/// `enable_bls12_381_syscall`, `b1sgUiJ3qu7hYm3tNDyyqZNQd6gLGJmJppnLNa93PCQ`
+///
+/// Gates the BLS12-381 syscalls used by [`bls12_381`](crate::bls12_381).
+pub const ENABLE_BLS12_381_SYSCALL: Pubkey = [
+    8, 182, 188, 75, 47, 238, 17, 158, 27, 68, 209, 106, 73, 19, 26, 39, 188, 229, 94, 180, 227,
+    174, 21, 91, 40, 10, 119, 56, 71, 218, 75, 221,
+];
+
+/// Returns true if feature account data marks the feature as activated
+///
+/// Feature accounts store a bincode `Option<u64>` activation slot.
+pub fn is_feature_data_active(data: &[u8]) -> bool {
+    data.len() >= 9 && data[0] == 1
+}
+
+/// Checks whether the feature `feature_id` is active on the current cluster
+///
+/// # Returns
+/// * `Ok(true)` - If the account is the activated feature account
+/// * `Ok(false)` - If the feature account does not exist or is pending
+/// * `Err(Groth16Error::InvalidAccountData)` - If `account` is not the account of `feature_id`
+#[cfg(feature = "pinocchio")]
+pub fn is_feature_active(account: &AccountInfo, feature_id: &Pubkey) -> Result<bool, Groth16Error> {
+    if account.key() != feature_id {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    if !account.is_owned_by(&FEATURE_PROGRAM_ID) {
+        return Ok(false);
+    }
+    let data = account
+        .try_borrow_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    Ok(is_feature_data_active(&data))
+}
+
+/// Syscalls used by the verifier that are feature gated
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SyscallFeatures {
+    pub alt_bn128_compression: bool,
+}
+
+impl SyscallFeatures {
+    /// Loads the active syscalls from feature accounts
+    ///
+    /// # Arguments
+    /// * `compression_feature` - The `ENABLE_ALT_BN128_COMPRESSION_SYSCALL`
+    ///   account, `None` treats compression as inactive
+    #[cfg(feature = "pinocchio")]
+    pub fn load(compression_feature: Option<&AccountInfo>) -> Result<Self, Groth16Error> {
+        let alt_bn128_compression = match compression_feature {
+            Some(account) => is_feature_active(account, &ENABLE_ALT_BN128_COMPRESSION_SYSCALL)?,
+            None => false,
+        };
+        Ok(SyscallFeatures {
+            alt_bn128_compression,
+        })
+    }
+}
+
+/// A proof as submitted by a client, proof a is negated in both encodings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofEncoding<'a> {
+    Uncompressed {
+        proof_a: &'a [u8; 64],
+        proof_b: &'a [u8; 128],
+        proof_c: &'a [u8; 64],
+    },
+    Compressed {
+        proof_a: &'a [u8; 32],
+        proof_b: &'a [u8; 64],
+        proof_c: &'a [u8; 32],
+    },
+}
+
+impl ProofEncoding<'_> {
+    /// Parses a proof by length, 256 bytes uncompressed or 128 bytes compressed
+    pub fn from_bytes(bytes: &[u8]) -> Result<ProofEncoding<'_>, Groth16Error> {
+        match bytes.len() {
+            256 => Ok(ProofEncoding::Uncompressed {
+                proof_a: bytes[..64].try_into().unwrap(),
+                proof_b: bytes[64..192].try_into().unwrap(),
+                proof_c: bytes[192..].try_into().unwrap(),
+            }),
+            128 => Ok(ProofEncoding::Compressed {
+                proof_a: bytes[..32].try_into().unwrap(),
+                proof_b: bytes[32..96].try_into().unwrap(),
+                proof_c: bytes[96..].try_into().unwrap(),
+            }),
+            _ => Err(Groth16Error::InvalidG1Length),
+        }
+    }
+}
+
+/// Verifies a proof, decompressing it only if the compression syscalls are active
+///
+/// # Returns
+/// * `Err(Groth16Error::SyscallNotActive)` - If the proof is compressed and
+///   the compression syscalls are not active, the client should resubmit
+///   the proof uncompressed
+pub fn verify_proof<const NR_INPUTS: usize>(
+    features: &SyscallFeatures,
+    proof: &ProofEncoding,
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+    verifyingkey: &Groth16Verifyingkey,
+) -> Result<(), Groth16Error> {
+    match *proof {
+        ProofEncoding::Uncompressed {
+            proof_a,
+            proof_b,
+            proof_c,
+        } => Groth16Verifier::new(proof_a, proof_b, proof_c, public_inputs, verifyingkey)?.verify(),
+        ProofEncoding::Compressed {
+            proof_a,
+            proof_b,
+            proof_c,
+        } => {
+            if !features.alt_bn128_compression {
+                return Err(Groth16Error::SyscallNotActive);
+            }
+            let proof_a = decompress_g1(proof_a)?;
+            let proof_b = decompress_g2(proof_b)?;
+            let proof_c = decompress_g1(proof_c)?;
+            Groth16Verifier::new(&proof_a, &proof_b, &proof_c, public_inputs, verifyingkey)?
+                .verify()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feature_data_and_compressed_fallback() {
+        assert!(!is_feature_data_active(&[]));
+        assert!(!is_feature_data_active(&[0u8; 9]));
+        let mut activated = [0u8; 9];
+        activated[0] = 1;
+        activated[1..].copy_from_slice(&1234u64.to_le_bytes());
+        assert!(is_feature_data_active(&activated));
+
+        let compressed = [0u8; 128];
+        let proof = ProofEncoding::from_bytes(&compressed).unwrap();
+        assert!(matches!(proof, ProofEncoding::Compressed { .. }));
+        assert_eq!(
+            ProofEncoding::from_bytes(&[0u8; 100]),
+            Err(Groth16Error::InvalidG1Length)
+        );
+
+        let vk = Groth16Verifyingkey {
+            nr_pubinputs: 0,
+            vk_alpha_g1: [0u8; 64],
+            vk_beta_g2: [0u8; 128],
+            vk_gamma_g2: [0u8; 128],
+            vk_delta_g2: [0u8; 128],
+            vk_ic: &[[0u8; 64]],
+        };
+        assert_eq!(
+            verify_proof::<0>(&SyscallFeatures::default(), &proof, &[], &vk),
+            Err(Groth16Error::SyscallNotActive)
+        );
+    }
+}
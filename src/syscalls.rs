@@ -0,0 +1,26 @@
+//! The Solana syscalls the verifier calls on chain
+//!
+//! Declared by Pinocchio with the default `pinocchio` feature, or by
+//! `solana-define-syscall` with the `solana-program` feature for programs
+//! built without Pinocchio.
+
+#[cfg(feature = "pinocchio")]
+pub(crate) use pinocchio::syscalls::*;
+
+#[cfg(all(not(feature = "pinocchio"), feature = "solana-program"))]
+pub(crate) use solana_define_syscall::definitions::*;
+
+#[cfg(not(any(feature = "pinocchio", feature = "solana-program")))]
+compile_error!("building for Solana requires the `pinocchio` or the `solana-program` feature");
+
+/// Logs `message` with `sol_log_`
+#[cfg(any(feature = "cu-log", feature = "trace"))]
+pub(crate) fn log(message: &str) {
+    unsafe { sol_log_(message.as_ptr(), message.len() as u64) };
+}
+
+/// Logs the remaining compute units with `sol_log_compute_units_`
+#[cfg(feature = "cu-log")]
+pub(crate) fn log_compute_units() {
+    unsafe { sol_log_compute_units_() };
+}
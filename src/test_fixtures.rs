@@ -0,0 +1,253 @@
+//! Pre-built circuits with matching verifying keys, proofs and public inputs
+//!
+//! Downstream crates can test their verification paths against real Groth16
+//! proofs without the circom and snarkjs toolchain. The fixtures are proofs of
+//! the arkworks circuits in [`test_vectors`](crate::test_vectors) over a toy
+//! MiMC style hash that is cheap to prove and not meant for production:
+//!
+//! * [`HASH_PREIMAGE`] - knowledge of a preimage `x` of `toy_hash(x, 0)`
+//! * [`MERKLE_PROOF`] - membership of a leaf in a tree of depth 2
+//!
+//! The constants are generated with `test_vectors::generate_hash_preimage(0)`
+//! and `test_vectors::generate_merkle_proof(0)`.
+//!
+//! ```rust,ignore
+//! use pinocchio_groth16::test_fixtures::MERKLE_PROOF;
+//!
+//! let [root, leaf] = MERKLE_PROOF.public_inputs;
+//! my_program::withdraw(&MERKLE_PROOF.proof_a, &MERKLE_PROOF.proof_b, &MERKLE_PROOF.proof_c, root, leaf)?;
+//! ```
+
+use crate::errors::Groth16Error;
+use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+
+/// Verifying key, proof and `N` public inputs of a circuit
+///
+/// All values are big-endian, proof a is negated.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Fixture<const N: usize> {
+    pub verifying_key: Groth16Verifyingkey<'static>,
+    pub proof_a: [u8; 64],
+    pub proof_b: [u8; 128],
+    pub proof_c: [u8; 64],
+    pub public_inputs: [[u8; 32]; N],
+}
+
+impl<const N: usize> Fixture<N> {
+    /// Verifies the proof of the fixture
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the proof is valid, which it is unless the fixture was modified
+    /// * `Err(Groth16Error)` - If verification fails
+    pub fn verify(&self) -> Result<(), Groth16Error> {
+        Groth16Verifier::new(
+            &self.proof_a,
+            &self.proof_b,
+            &self.proof_c,
+            &self.public_inputs,
+            &self.verifying_key,
+        )?
+        .verify()
+    }
+}
+
+/// Proof of knowledge of a preimage, public inputs `[toy_hash(preimage, 0)]`
+pub const HASH_PREIMAGE: Fixture<1> = Fixture {
+    verifying_key: Groth16Verifyingkey {
+        nr_pubinputs: 1,
+        vk_alpha_g1: [
+            24, 1, 73, 235, 67, 83, 52, 71, 1, 241, 94, 166, 149, 119, 169, 146, 17, 236, 151, 160,
+            186, 1, 153, 111, 123, 149, 224, 218, 111, 204, 62, 242, 37, 221, 163, 134, 176, 91,
+            146, 97, 44, 57, 251, 254, 96, 52, 195, 83, 224, 209, 26, 121, 234, 175, 120, 88, 19,
+            116, 141, 107, 41, 249, 150, 48,
+        ],
+        vk_beta_g2: [
+            9, 136, 144, 138, 118, 199, 73, 76, 133, 112, 227, 230, 199, 222, 186, 220, 166, 18,
+            254, 121, 190, 236, 227, 83, 202, 109, 4, 12, 33, 254, 19, 115, 0, 39, 240, 101, 181,
+            189, 229, 232, 68, 245, 38, 240, 153, 231, 41, 133, 124, 9, 71, 218, 233, 78, 130, 187,
+            241, 166, 99, 71, 81, 184, 126, 161, 45, 164, 157, 227, 169, 218, 94, 17, 29, 136, 80,
+            5, 207, 118, 136, 171, 28, 179, 140, 226, 196, 118, 202, 42, 131, 110, 12, 137, 23, 43,
+            179, 211, 12, 19, 26, 132, 45, 34, 67, 22, 11, 154, 208, 52, 85, 35, 224, 99, 189, 31,
+            81, 203, 142, 67, 176, 120, 46, 111, 108, 28, 249, 216, 18, 21,
+        ],
+        vk_gamma_g2: [
+            1, 0, 130, 238, 184, 191, 18, 80, 187, 12, 169, 126, 25, 16, 124, 127, 182, 76, 237,
+            183, 129, 240, 20, 186, 238, 117, 35, 141, 251, 40, 135, 223, 28, 225, 240, 65, 179,
+            211, 179, 208, 21, 63, 39, 105, 245, 97, 199, 155, 44, 216, 145, 173, 199, 8, 58, 105,
+            212, 191, 194, 54, 211, 24, 123, 197, 2, 205, 120, 38, 4, 45, 247, 63, 81, 63, 149,
+            103, 214, 142, 134, 78, 53, 197, 35, 177, 47, 239, 70, 146, 118, 179, 228, 243, 139,
+            24, 168, 101, 35, 150, 239, 47, 78, 164, 211, 154, 52, 128, 157, 41, 68, 83, 191, 240,
+            96, 250, 84, 185, 199, 115, 75, 89, 240, 186, 231, 55, 209, 61, 93, 72,
+        ],
+        vk_delta_g2: [
+            45, 226, 247, 15, 125, 104, 234, 54, 210, 203, 134, 141, 24, 240, 59, 180, 16, 57, 244,
+            133, 69, 215, 14, 14, 249, 75, 38, 147, 123, 20, 229, 255, 40, 67, 91, 112, 191, 135,
+            109, 71, 244, 131, 73, 170, 244, 62, 210, 161, 70, 176, 198, 48, 214, 35, 116, 54, 226,
+            29, 17, 14, 2, 215, 189, 102, 10, 5, 86, 68, 49, 239, 171, 69, 112, 9, 250, 12, 68, 2,
+            181, 127, 201, 165, 239, 189, 11, 205, 45, 225, 249, 143, 82, 175, 5, 53, 73, 45, 34,
+            221, 86, 166, 38, 140, 19, 40, 150, 110, 189, 92, 222, 91, 70, 206, 197, 116, 56, 228,
+            203, 235, 31, 124, 147, 152, 142, 64, 142, 40, 47, 63,
+        ],
+        vk_ic: &[
+            [
+                29, 18, 133, 146, 61, 220, 87, 26, 174, 147, 29, 102, 93, 201, 234, 46, 78, 244,
+                204, 236, 171, 228, 253, 212, 87, 180, 60, 63, 73, 103, 165, 26, 5, 147, 141, 164,
+                252, 56, 22, 210, 237, 147, 174, 13, 244, 30, 169, 249, 62, 240, 138, 97, 215, 212,
+                153, 146, 41, 150, 138, 56, 59, 253, 63, 231,
+            ],
+            [
+                31, 242, 153, 120, 94, 119, 178, 146, 63, 185, 26, 234, 56, 222, 119, 30, 83, 36,
+                193, 160, 82, 238, 47, 255, 98, 69, 48, 116, 58, 123, 107, 215, 40, 7, 178, 86,
+                169, 99, 9, 124, 40, 144, 194, 78, 198, 202, 229, 175, 180, 253, 17, 228, 125, 144,
+                144, 1, 255, 201, 109, 122, 102, 124, 93, 206,
+            ],
+        ],
+    },
+    proof_a: [
+        28, 21, 122, 80, 112, 189, 166, 252, 41, 174, 255, 93, 220, 153, 201, 219, 236, 170, 45,
+        205, 103, 49, 231, 5, 14, 113, 180, 186, 0, 97, 11, 240, 6, 150, 191, 136, 161, 147, 138,
+        74, 21, 200, 222, 180, 132, 140, 213, 110, 152, 135, 233, 73, 143, 225, 119, 178, 159, 105,
+        152, 63, 180, 68, 251, 65,
+    ],
+    proof_b: [
+        14, 218, 143, 151, 91, 156, 59, 170, 200, 38, 123, 108, 49, 209, 71, 0, 142, 185, 194, 206,
+        58, 236, 93, 116, 134, 71, 196, 12, 173, 234, 173, 27, 39, 11, 248, 88, 22, 173, 46, 47,
+        134, 43, 66, 187, 58, 18, 54, 114, 3, 180, 49, 25, 124, 184, 217, 84, 249, 152, 73, 149,
+        21, 88, 177, 77, 7, 37, 85, 91, 49, 159, 123, 183, 215, 225, 110, 67, 236, 44, 154, 55,
+        213, 8, 234, 194, 66, 77, 124, 219, 177, 186, 229, 91, 105, 199, 33, 129, 12, 2, 47, 67,
+        105, 161, 115, 243, 156, 240, 58, 250, 18, 224, 82, 183, 149, 10, 179, 132, 154, 239, 77,
+        200, 167, 237, 208, 192, 64, 151, 206, 173,
+    ],
+    proof_c: [
+        27, 141, 96, 140, 34, 146, 119, 16, 160, 18, 106, 110, 219, 241, 246, 215, 144, 55, 232,
+        154, 11, 36, 233, 92, 8, 224, 103, 180, 221, 183, 182, 140, 38, 184, 46, 199, 147, 138,
+        102, 168, 92, 41, 247, 228, 67, 146, 156, 228, 71, 254, 131, 4, 230, 55, 180, 197, 218, 38,
+        100, 202, 103, 66, 33, 4,
+    ],
+    public_inputs: [[
+        33, 217, 64, 160, 30, 243, 154, 199, 27, 166, 125, 88, 241, 6, 80, 6, 89, 147, 192, 73,
+        182, 46, 185, 168, 62, 78, 5, 71, 20, 209, 38, 6,
+    ]],
+};
+
+/// Proof of membership in a tree of depth 2, public inputs `[root, leaf]`
+pub const MERKLE_PROOF: Fixture<2> = Fixture {
+    verifying_key: Groth16Verifyingkey {
+        nr_pubinputs: 2,
+        vk_alpha_g1: [
+            15, 195, 176, 190, 109, 182, 30, 214, 102, 186, 8, 124, 13, 184, 79, 46, 205, 192, 137,
+            117, 26, 156, 112, 237, 212, 117, 36, 125, 151, 210, 112, 93, 2, 168, 192, 242, 217,
+            250, 188, 1, 247, 235, 217, 177, 224, 105, 21, 51, 79, 220, 58, 138, 229, 44, 111, 7,
+            18, 239, 143, 13, 158, 41, 114, 136,
+        ],
+        vk_beta_g2: [
+            2, 100, 14, 145, 13, 14, 208, 143, 88, 107, 121, 2, 59, 171, 144, 129, 110, 55, 216,
+            105, 178, 14, 249, 159, 114, 196, 34, 177, 131, 214, 194, 195, 28, 99, 58, 46, 157,
+            247, 218, 99, 44, 180, 88, 202, 220, 131, 70, 123, 204, 128, 250, 249, 26, 227, 217,
+            127, 147, 250, 255, 196, 187, 194, 230, 208, 42, 231, 129, 180, 235, 108, 198, 220,
+            181, 94, 119, 118, 58, 186, 164, 69, 224, 195, 60, 175, 90, 198, 243, 94, 186, 81, 45,
+            254, 111, 74, 89, 125, 2, 13, 136, 241, 214, 29, 13, 108, 33, 13, 157, 166, 236, 171,
+            41, 185, 81, 151, 174, 231, 230, 118, 204, 218, 133, 57, 242, 160, 126, 161, 88, 99,
+        ],
+        vk_gamma_g2: [
+            36, 147, 89, 244, 208, 153, 193, 192, 34, 36, 163, 40, 219, 44, 185, 44, 94, 251, 76,
+            169, 126, 75, 43, 137, 159, 218, 20, 130, 101, 204, 172, 87, 23, 131, 228, 129, 4, 44,
+            250, 101, 245, 137, 16, 41, 38, 229, 98, 89, 67, 136, 117, 147, 200, 45, 190, 220, 189,
+            164, 104, 255, 29, 159, 71, 183, 43, 46, 231, 213, 53, 126, 110, 0, 157, 4, 240, 252,
+            200, 112, 44, 250, 254, 191, 49, 98, 168, 74, 205, 190, 176, 239, 95, 12, 84, 210, 251,
+            38, 2, 237, 222, 45, 179, 117, 135, 29, 140, 213, 226, 144, 157, 139, 199, 24, 230, 8,
+            213, 101, 206, 187, 40, 132, 98, 185, 86, 215, 191, 225, 182, 236,
+        ],
+        vk_delta_g2: [
+            10, 103, 232, 129, 59, 162, 45, 54, 51, 101, 119, 207, 72, 189, 67, 239, 178, 111, 160,
+            209, 93, 101, 156, 76, 105, 153, 52, 27, 50, 140, 158, 57, 13, 250, 102, 246, 187, 160,
+            249, 29, 9, 104, 229, 201, 97, 95, 64, 124, 81, 251, 242, 41, 67, 0, 236, 207, 177, 86,
+            254, 4, 91, 40, 163, 133, 0, 107, 165, 35, 164, 92, 184, 116, 240, 41, 71, 62, 42, 128,
+            49, 192, 9, 176, 167, 213, 41, 169, 122, 35, 251, 178, 216, 163, 28, 235, 235, 61, 10,
+            118, 61, 177, 193, 240, 178, 30, 146, 17, 181, 227, 182, 110, 42, 121, 188, 157, 200,
+            78, 138, 218, 60, 248, 97, 222, 155, 170, 149, 234, 64, 136,
+        ],
+        vk_ic: &[
+            [
+                17, 228, 83, 145, 48, 173, 108, 2, 195, 128, 242, 213, 64, 235, 97, 145, 73, 85,
+                122, 110, 248, 23, 149, 29, 133, 71, 228, 182, 202, 243, 0, 110, 33, 144, 126, 66,
+                70, 114, 101, 208, 159, 157, 199, 161, 177, 81, 203, 105, 155, 1, 185, 171, 91,
+                215, 18, 50, 77, 15, 158, 167, 106, 175, 18, 202,
+            ],
+            [
+                8, 32, 107, 138, 84, 166, 227, 151, 96, 12, 170, 22, 84, 211, 122, 80, 116, 131,
+                30, 100, 243, 185, 242, 97, 181, 43, 215, 211, 34, 10, 237, 242, 0, 191, 124, 233,
+                12, 103, 44, 18, 36, 188, 107, 46, 187, 254, 57, 131, 60, 55, 57, 146, 202, 188,
+                118, 66, 233, 212, 121, 51, 42, 99, 127, 37,
+            ],
+            [
+                47, 30, 242, 14, 229, 108, 182, 6, 79, 20, 53, 67, 253, 92, 194, 29, 166, 192, 243,
+                32, 152, 230, 191, 216, 37, 38, 14, 131, 132, 128, 127, 176, 38, 63, 227, 85, 19,
+                84, 136, 96, 173, 70, 153, 123, 229, 197, 180, 80, 196, 35, 5, 248, 96, 221, 22,
+                78, 117, 67, 24, 154, 177, 100, 94, 145,
+            ],
+        ],
+    },
+    proof_a: [
+        47, 18, 3, 39, 217, 174, 211, 126, 218, 173, 4, 242, 79, 233, 98, 65, 27, 75, 46, 132, 88,
+        168, 11, 89, 50, 139, 46, 144, 219, 84, 143, 72, 32, 64, 244, 208, 154, 75, 4, 69, 32, 125,
+        236, 73, 208, 214, 146, 76, 63, 134, 33, 183, 152, 215, 112, 212, 131, 163, 45, 41, 224,
+        165, 14, 104,
+    ],
+    proof_b: [
+        42, 64, 183, 96, 84, 167, 57, 124, 102, 231, 120, 192, 141, 211, 251, 254, 254, 6, 197,
+        109, 217, 164, 148, 208, 186, 159, 39, 125, 28, 28, 89, 216, 16, 61, 101, 221, 208, 214,
+        76, 2, 107, 122, 181, 6, 144, 10, 106, 205, 255, 181, 53, 47, 127, 60, 157, 231, 158, 135,
+        199, 112, 87, 127, 136, 241, 47, 86, 214, 5, 182, 194, 176, 52, 225, 5, 197, 207, 179, 151,
+        108, 4, 2, 134, 35, 108, 93, 241, 144, 92, 158, 31, 23, 183, 150, 66, 145, 2, 4, 187, 17,
+        56, 182, 241, 178, 47, 170, 126, 112, 239, 253, 61, 196, 112, 24, 237, 156, 42, 47, 161,
+        154, 143, 76, 90, 72, 56, 85, 69, 69, 79,
+    ],
+    proof_c: [
+        36, 163, 105, 60, 245, 239, 229, 84, 23, 156, 111, 137, 217, 235, 244, 35, 207, 199, 230,
+        151, 33, 12, 212, 67, 92, 192, 216, 171, 122, 240, 102, 174, 45, 98, 12, 118, 216, 137,
+        108, 246, 56, 119, 45, 105, 213, 204, 159, 31, 222, 81, 132, 164, 47, 157, 144, 70, 83, 91,
+        134, 163, 19, 72, 153, 90,
+    ],
+    public_inputs: [
+        [
+            34, 21, 65, 195, 122, 240, 117, 233, 179, 174, 7, 16, 232, 199, 74, 227, 48, 39, 1,
+            237, 231, 34, 202, 56, 213, 36, 90, 97, 22, 54, 50, 103,
+        ],
+        [
+            32, 164, 157, 8, 133, 123, 97, 7, 45, 123, 183, 166, 228, 78, 167, 133, 43, 88, 121,
+            198, 134, 136, 216, 210, 11, 47, 235, 231, 227, 31, 93, 167,
+        ],
+    ],
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixtures_verify() {
+        HASH_PREIMAGE.verify().unwrap();
+        MERKLE_PROOF.verify().unwrap();
+
+        let mut wrong = MERKLE_PROOF;
+        wrong.public_inputs.swap(0, 1);
+        assert!(wrong.verify().is_err());
+    }
+
+    #[cfg(feature = "test-vectors")]
+    #[test]
+    fn fixtures_match_generator() {
+        use crate::test_vectors::{generate_hash_preimage, generate_merkle_proof, TestVector};
+
+        fn assert_matches<const N: usize>(fixture: &Fixture<N>, vector: &TestVector) {
+            assert_eq!(vector.verifying_key(), fixture.verifying_key);
+            assert_eq!(vector.proof_a, fixture.proof_a);
+            assert_eq!(vector.proof_b, fixture.proof_b);
+            assert_eq!(vector.proof_c, fixture.proof_c);
+            assert_eq!(vector.public_inputs, fixture.public_inputs);
+        }
+        assert_matches(&HASH_PREIMAGE, &generate_hash_preimage(0));
+        assert_matches(&MERKLE_PROOF, &generate_merkle_proof(0));
+    }
+}
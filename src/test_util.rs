@@ -0,0 +1,132 @@
+//! Round-trip assertions for serialization tests
+//!
+//! Downstream crates wrapping the proof, verifying key and public input
+//! encodings call these from their own tests to inherit the guarantees the
+//! crate's tests check:
+//!
+//! ```rust,ignore
+//! use pinocchio_groth16::test_util::*;
+//!
+//! assert_roundtrip_borsh(&MyProof::from(&vector));
+//! assert_roundtrip_snarkjs(&vector);
+//! assert_endianness_involution::<32, 64>(&vector.proof_a);
+//! ```
+
+use crate::bn254::convert_endianness;
+use crate::test_vectors::TestVector;
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::fmt::Debug;
+use serde_json::Value;
+
+/// Asserts that `value` survives borsh serialization unchanged
+///
+/// Also asserts that serialization is deterministic and that the encoding
+/// is rejected if truncated or followed by trailing bytes.
+pub fn assert_roundtrip_borsh<T>(value: &T)
+where
+    T: BorshSerialize + BorshDeserialize + PartialEq + Debug,
+{
+    let bytes = borsh::to_vec(value).expect("borsh serialization");
+    let decoded = T::try_from_slice(&bytes).expect("borsh deserialization");
+    assert_eq!(&decoded, value, "borsh roundtrip changed the value");
+    assert_eq!(
+        borsh::to_vec(&decoded).unwrap(),
+        bytes,
+        "borsh serialization is not deterministic"
+    );
+    if let Some((_, truncated)) = bytes.split_last() {
+        assert!(
+            T::try_from_slice(truncated).is_err(),
+            "truncated borsh encoding is accepted"
+        );
+    }
+    let mut trailing = bytes;
+    trailing.push(0);
+    assert!(
+        T::try_from_slice(&trailing).is_err(),
+        "borsh encoding with trailing bytes is accepted"
+    );
+}
+
+/// Asserts that `vector` survives the snarkjs JSON encoding unchanged
+///
+/// Writes `verification_key.json`, `proof.json` and `public.json`, parses
+/// them with [`TestVector::from_snarkjs_json`] and writes them again.
+pub fn assert_roundtrip_snarkjs(vector: &TestVector) {
+    let json = |vector: &TestVector| {
+        [
+            vector.snarkjs_verification_key_json(),
+            vector.snarkjs_proof_json(),
+            vector.snarkjs_public_json(),
+        ]
+    };
+    let [verification_key, proof, public] = json(vector);
+    let parsed = TestVector::from_snarkjs_json(&verification_key, &proof, &public)
+        .expect("parsing snarkjs JSON");
+    assert_eq!(&parsed, vector, "snarkjs roundtrip changed the vector");
+
+    for (written, rewritten) in json(vector).iter().zip(json(&parsed)) {
+        let written: Value = serde_json::from_str(written).unwrap();
+        let rewritten: Value = serde_json::from_str(&rewritten).unwrap();
+        assert_eq!(written, rewritten, "snarkjs JSON changed in the roundtrip");
+    }
+}
+
+/// Asserts that converting the endianness of `bytes` reverses every chunk and is an involution
+///
+/// # Arguments
+/// * `CHUNK_SIZE` - 32 for field elements and G1 coordinates, 64 for G2 coordinates
+pub fn assert_endianness_involution<const CHUNK_SIZE: usize, const ARRAY_SIZE: usize>(
+    bytes: &[u8; ARRAY_SIZE],
+) {
+    let converted = convert_endianness::<CHUNK_SIZE, ARRAY_SIZE>(bytes);
+    for (chunk, converted) in bytes.chunks(CHUNK_SIZE).zip(converted.chunks(CHUNK_SIZE)) {
+        assert!(
+            chunk.iter().eq(converted.iter().rev()),
+            "chunk is not reversed"
+        );
+    }
+    assert_eq!(
+        &convert_endianness::<CHUNK_SIZE, ARRAY_SIZE>(&converted),
+        bytes,
+        "endianness conversion is not an involution"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_vectors::{generate, generate_merkle_proof};
+
+    #[test]
+    fn crate_encodings_roundtrip() {
+        for vector in [generate(0), generate_merkle_proof(1)] {
+            assert_roundtrip_snarkjs(&vector);
+            assert_endianness_involution::<32, 64>(&vector.proof_a);
+            assert_endianness_involution::<64, 128>(&vector.proof_b);
+            assert_endianness_involution::<32, 256>(&vector.proof_be());
+            assert_roundtrip_borsh(&vector.proof_be());
+            assert_roundtrip_borsh(&vector.public_inputs);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "truncated borsh encoding is accepted")]
+    fn lenient_borsh_encoding_is_caught() {
+        #[derive(Debug, PartialEq)]
+        struct Lenient(u8);
+        impl BorshSerialize for Lenient {
+            fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+                writer.write_all(&[self.0])
+            }
+        }
+        impl BorshDeserialize for Lenient {
+            fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+                let mut byte = [0u8; 1];
+                let read = reader.read(&mut byte)?;
+                Ok(Lenient(if read == 0 { 0 } else { byte[0] }))
+            }
+        }
+        assert_roundtrip_borsh(&Lenient(7));
+    }
+}
@@ -0,0 +1,777 @@
+//! Golden test vectors for a tiny built-in circuit
+//!
+//! [`generate`] runs an arkworks Groth16 setup and prover for [`TinyCircuit`]
+//! from a seed and returns the verifying key, proof and public inputs in
+//! every encoding the crate and its tooling understand:
+//!
+//! * big-endian, proof a negated, as taken by [`Groth16Verifier`]
+//! * little-endian, as serialized by arkworks
+//! * compressed big-endian, as taken by the [`decompression`](crate::decompression) functions
+//! * the packed verifying key encoding of [`Groth16Verifyingkey`]
+//! * snarkjs `proof.json`, `public.json` and `verification_key.json`
+//!
+//! The same seed always yields the same vector, so downstream crates can
+//! regenerate their fixtures instead of copying them from other repos.
+//!
+//! ```rust,ignore
+//! let vector = pinocchio_groth16::test_vectors::generate(0);
+//! let vk = vector.verifying_key();
+//! let inputs: [[u8; 32]; 2] = vector.public_inputs.clone().try_into().unwrap();
+//! Groth16Verifier::new(&vector.proof_a, &vector.proof_b, &vector.proof_c, &inputs, &vk)?
+//!     .verify()?;
+//! ```
+
+use crate::bn254::convert_endianness;
+use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, Field, PrimeField, UniformRand};
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+use ark_relations::lc;
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystemRef, LinearCombination, SynthesisError, Variable,
+};
+use ark_serialize::{CanonicalSerialize, Compress};
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use num_bigint::BigUint;
+use serde_json::{json, Value};
+
+/// Number of public inputs of [`TinyCircuit`]
+pub const NR_PUBLIC_INPUTS: usize = 2;
+
+/// Proves knowledge of `a` and `b` with the public inputs `a * b` and `a + b`
+///
+/// Generic over the scalar field so the circuit also runs on other curves.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TinyCircuit<F = Fr> {
+    pub a: Option<F>,
+    pub b: Option<F>,
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for TinyCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let a_value = self.a.ok_or(SynthesisError::AssignmentMissing);
+        let b_value = self.b.ok_or(SynthesisError::AssignmentMissing);
+
+        let a = cs.new_witness_variable(|| a_value)?;
+        let b = cs.new_witness_variable(|| b_value)?;
+        let product = cs.new_input_variable(|| Ok(a_value? * b_value?))?;
+        let sum = cs.new_input_variable(|| Ok(a_value? + b_value?))?;
+
+        cs.enforce_constraint(lc!() + a, lc!() + b, lc!() + product)?;
+        cs.enforce_constraint(lc!() + a + b, lc!() + Variable::One, lc!() + sum)?;
+        Ok(())
+    }
+}
+
+/// Rounds of [`toy_hash`]
+pub const TOY_HASH_ROUNDS: u64 = 10;
+
+/// Depth of the tree of [`MerkleProofCircuit`]
+pub const MERKLE_DEPTH: usize = 2;
+
+/// MiMC style hash `E_b(a) + a` with `x^5` rounds and round constants `0..TOY_HASH_ROUNDS`
+///
+/// Cheap to prove and only meant for fixtures, the parameters are not secure.
+pub fn toy_hash(a: Fr, b: Fr) -> Fr {
+    let mut x = a;
+    for i in 0..TOY_HASH_ROUNDS {
+        let t = x + b + Fr::from(i);
+        x = t.square().square() * t;
+    }
+    x + b + a
+}
+
+/// Root of the tree of depth [`MERKLE_DEPTH`] containing `leaf`
+///
+/// `index_bits[i]` is set if the node at level `i` is the right child.
+pub fn toy_merkle_root(
+    leaf: Fr,
+    siblings: &[Fr; MERKLE_DEPTH],
+    index_bits: &[bool; MERKLE_DEPTH],
+) -> Fr {
+    siblings
+        .iter()
+        .zip(index_bits)
+        .fold(leaf, |node, (sibling, is_right)| {
+            if *is_right {
+                toy_hash(*sibling, node)
+            } else {
+                toy_hash(node, *sibling)
+            }
+        })
+}
+
+type Lc = (LinearCombination<Fr>, Option<Fr>);
+
+fn witness(cs: &ConstraintSystemRef<Fr>, value: Option<Fr>) -> Result<Variable, SynthesisError> {
+    cs.new_witness_variable(|| value.ok_or(SynthesisError::AssignmentMissing))
+}
+
+fn toy_hash_constraints(
+    cs: &ConstraintSystemRef<Fr>,
+    a: &Lc,
+    b: &Lc,
+) -> Result<Lc, SynthesisError> {
+    let mut x = a.clone();
+    for i in 0..TOY_HASH_ROUNDS {
+        let t = x.0 + &b.0 + (Fr::from(i), Variable::One);
+        let t_value = x.1.zip(b.1).map(|(x, b)| x + b + Fr::from(i));
+        let t2 = witness(cs, t_value.map(|t| t.square()))?;
+        let t4 = witness(cs, t_value.map(|t| t.square().square()))?;
+        let t5_value = t_value.map(|t| t.square().square() * t);
+        let t5 = witness(cs, t5_value)?;
+        cs.enforce_constraint(t.clone(), t.clone(), lc!() + t2)?;
+        cs.enforce_constraint(lc!() + t2, lc!() + t2, lc!() + t4)?;
+        cs.enforce_constraint(lc!() + t4, t, lc!() + t5)?;
+        x = (lc!() + t5, t5_value);
+    }
+    let value = x.1.zip(a.1).zip(b.1).map(|((x, a), b)| x + b + a);
+    Ok((x.0 + &b.0 + &a.0, value))
+}
+
+fn enforce_input(cs: &ConstraintSystemRef<Fr>, value: Lc) -> Result<(), SynthesisError> {
+    let input = cs.new_input_variable(|| value.1.ok_or(SynthesisError::AssignmentMissing))?;
+    cs.enforce_constraint(value.0, lc!() + Variable::One, lc!() + input)
+}
+
+/// Proves knowledge of a preimage of the public input `toy_hash(preimage, 0)`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HashPreimageCircuit {
+    pub preimage: Option<Fr>,
+}
+
+impl ConstraintSynthesizer<Fr> for HashPreimageCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let preimage = witness(&cs, self.preimage)?;
+        let hash = toy_hash_constraints(
+            &cs,
+            &(lc!() + preimage, self.preimage),
+            &(lc!(), Some(Fr::from(0u8))),
+        )?;
+        enforce_input(&cs, hash)
+    }
+}
+
+/// Proves membership of the public leaf in the tree with the public root
+///
+/// Public inputs: `[root, leaf]`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MerkleProofCircuit {
+    pub leaf: Option<Fr>,
+    pub siblings: [Option<Fr>; MERKLE_DEPTH],
+    pub index_bits: [Option<bool>; MERKLE_DEPTH],
+}
+
+impl ConstraintSynthesizer<Fr> for MerkleProofCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let leaf = witness(&cs, self.leaf)?;
+        let mut node: Lc = (lc!() + leaf, self.leaf);
+        for (sibling_value, bit_value) in self.siblings.iter().zip(self.index_bits) {
+            let sibling = witness(&cs, *sibling_value)?;
+            let bit_value = bit_value.map(Fr::from);
+            let bit = witness(&cs, bit_value)?;
+            cs.enforce_constraint(lc!() + bit, lc!() + Variable::One - bit, lc!())?;
+
+            // swap = bit * (sibling - node), left = node + swap, right = sibling - swap
+            let swap_value = bit_value
+                .zip(*sibling_value)
+                .zip(node.1)
+                .map(|((bit, sibling), node)| bit * (sibling - node));
+            let swap = witness(&cs, swap_value)?;
+            cs.enforce_constraint(lc!() + bit, lc!() + sibling - &node.0, lc!() + swap)?;
+            let left = (
+                node.0.clone() + swap,
+                node.1.zip(swap_value).map(|(node, swap)| node + swap),
+            );
+            let right = (
+                lc!() + sibling - swap,
+                sibling_value
+                    .zip(swap_value)
+                    .map(|(sibling, swap)| sibling - swap),
+            );
+            node = toy_hash_constraints(&cs, &left, &right)?;
+        }
+        enforce_input(&cs, node)?;
+        enforce_input(&cs, (lc!() + leaf, self.leaf))
+    }
+}
+
+/// Generates a vector of [`HashPreimageCircuit`] for a random preimage
+pub fn generate_hash_preimage(seed: u64) -> TestVector {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let preimage = Fr::rand(&mut rng);
+    let circuit = HashPreimageCircuit {
+        preimage: Some(preimage),
+    };
+    let hash = toy_hash(preimage, Fr::from(0u8));
+    generate_for_circuit(HashPreimageCircuit::default(), circuit, &[hash], &mut rng)
+}
+
+/// Generates a vector of [`MerkleProofCircuit`] for a random leaf and path
+pub fn generate_merkle_proof(seed: u64) -> TestVector {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let leaf = Fr::rand(&mut rng);
+    let siblings = [Fr::rand(&mut rng), Fr::rand(&mut rng)];
+    let index_bits = [bool::rand(&mut rng), bool::rand(&mut rng)];
+    let circuit = MerkleProofCircuit {
+        leaf: Some(leaf),
+        siblings: siblings.map(Some),
+        index_bits: index_bits.map(Some),
+    };
+    let root = toy_merkle_root(leaf, &siblings, &index_bits);
+    generate_for_circuit(
+        MerkleProofCircuit::default(),
+        circuit,
+        &[root, leaf],
+        &mut rng,
+    )
+}
+
+/// Ways of turning a valid proof into a well-formed invalid one
+///
+/// Every replacement is a point of the prime order subgroup or a canonical
+/// field element, so the invalid vectors pass all length, curve and range
+/// checks and are only rejected by the pairing check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Invalidation {
+    /// Random proof a, b and c
+    RandomProof,
+    /// Random proof a
+    WrongA,
+    /// Random proof b
+    WrongB,
+    /// Random proof c
+    WrongC,
+    /// Random public inputs
+    MismatchedInputs,
+}
+
+impl Invalidation {
+    pub const ALL: [Invalidation; 5] = [
+        Invalidation::RandomProof,
+        Invalidation::WrongA,
+        Invalidation::WrongB,
+        Invalidation::WrongC,
+        Invalidation::MismatchedInputs,
+    ];
+}
+
+/// Generates one invalid vector per [`Invalidation`] from the vector of `seed`
+pub fn generate_invalid(seed: u64) -> Vec<(Invalidation, TestVector)> {
+    let valid = generate(seed);
+    Invalidation::ALL
+        .iter()
+        .map(|invalidation| (*invalidation, valid.invalidate(*invalidation, seed)))
+        .collect()
+}
+
+/// Matched verifying key, proof and public inputs of a circuit
+///
+/// The fields hold the big-endian encodings used by [`Groth16Verifier`],
+/// the methods derive the other encodings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestVector {
+    pub vk_alpha_g1: [u8; 64],
+    pub vk_beta_g2: [u8; 128],
+    pub vk_gamma_g2: [u8; 128],
+    pub vk_delta_g2: [u8; 128],
+    pub vk_ic: Vec<[u8; 64]>,
+    /// Negated proof a
+    pub proof_a: [u8; 64],
+    pub proof_b: [u8; 128],
+    pub proof_c: [u8; 64],
+    pub public_inputs: Vec<[u8; 32]>,
+}
+
+/// Generates the test vector of `seed`
+///
+/// The witness, the trusted setup and the proof randomness are all derived
+/// from `seed`.
+pub fn generate(seed: u64) -> TestVector {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let a = Fr::rand(&mut rng);
+    let b = Fr::rand(&mut rng);
+    let circuit = TinyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    generate_for_circuit(TinyCircuit::default(), circuit, &[a * b, a + b], &mut rng)
+}
+
+/// Runs the setup with `setup_circuit` and proves `circuit`
+///
+/// # Arguments
+/// * `setup_circuit` - The circuit without assignment
+/// * `circuit` - The circuit with a satisfying assignment
+/// * `public_inputs` - Public inputs of the assignment
+/// * `rng` - Source of the toxic waste and the proof randomness
+pub fn generate_for_circuit<C: ConstraintSynthesizer<Fr>>(
+    setup_circuit: C,
+    circuit: C,
+    public_inputs: &[Fr],
+    rng: &mut StdRng,
+) -> TestVector {
+    let pk = Groth16::<Bn254>::generate_random_parameters_with_reduction(setup_circuit, rng)
+        .expect("circuit setup");
+    let proof = Groth16::<Bn254>::create_random_proof_with_reduction(circuit, &pk, rng)
+        .expect("satisfying assignment");
+
+    let vk = pk.vk;
+    TestVector {
+        vk_alpha_g1: g1_to_be(&vk.alpha_g1),
+        vk_beta_g2: g2_to_be(&vk.beta_g2),
+        vk_gamma_g2: g2_to_be(&vk.gamma_g2),
+        vk_delta_g2: g2_to_be(&vk.delta_g2),
+        vk_ic: vk.gamma_abc_g1.iter().map(g1_to_be).collect(),
+        proof_a: g1_to_be(&-proof.a),
+        proof_b: g2_to_be(&proof.b),
+        proof_c: g1_to_be(&proof.c),
+        public_inputs: public_inputs.iter().map(fr_to_be).collect(),
+    }
+}
+
+impl TestVector {
+    /// Borrows the verifying key
+    pub fn verifying_key(&self) -> Groth16Verifyingkey<'_> {
+        Groth16Verifyingkey {
+            nr_pubinputs: self.vk_ic.len() - 1,
+            vk_alpha_g1: self.vk_alpha_g1,
+            vk_beta_g2: self.vk_beta_g2,
+            vk_gamma_g2: self.vk_gamma_g2,
+            vk_delta_g2: self.vk_delta_g2,
+            vk_ic: &self.vk_ic,
+        }
+    }
+
+    /// Packed verifying key, see [`Groth16Verifyingkey::write_packed_bytes`]
+    pub fn packed_verifying_key(&self) -> Vec<u8> {
+        let mut packed = vec![0u8; Groth16Verifyingkey::packed_size(self.vk_ic.len() - 1)];
+        self.verifying_key()
+            .write_packed_bytes(&mut packed)
+            .expect("buffer of the packed size");
+        packed
+    }
+
+    /// `proof_a | proof_b | proof_c`, big-endian
+    pub fn proof_be(&self) -> [u8; 256] {
+        let mut proof = [0u8; 256];
+        proof[..64].copy_from_slice(&self.proof_a);
+        proof[64..192].copy_from_slice(&self.proof_b);
+        proof[192..].copy_from_slice(&self.proof_c);
+        proof
+    }
+
+    /// `proof_a | proof_b | proof_c`, little-endian
+    pub fn proof_le(&self) -> [u8; 256] {
+        let mut proof = [0u8; 256];
+        proof[..64].copy_from_slice(&convert_endianness::<32, 64>(&self.proof_a));
+        proof[64..192].copy_from_slice(&convert_endianness::<64, 128>(&self.proof_b));
+        proof[192..].copy_from_slice(&convert_endianness::<32, 64>(&self.proof_c));
+        proof
+    }
+
+    /// `proof_a | proof_b | proof_c`, compressed big-endian
+    pub fn proof_compressed(&self) -> [u8; 128] {
+        let mut proof = [0u8; 128];
+        proof[..32].copy_from_slice(&g1_compress_be(&self.proof_a));
+        proof[32..96].copy_from_slice(&g2_compress_be(&self.proof_b));
+        proof[96..].copy_from_slice(&g1_compress_be(&self.proof_c));
+        proof
+    }
+
+    /// Public inputs, little-endian
+    pub fn public_inputs_le(&self) -> Vec<[u8; 32]> {
+        self.public_inputs
+            .iter()
+            .map(|input| {
+                let mut input = *input;
+                input.reverse();
+                input
+            })
+            .collect()
+    }
+
+    /// Returns a fresh proof of the same statement under the same key
+    ///
+    /// Rerandomizing is much cheaper than [`generate`], use it to obtain many
+    /// distinct valid proofs.
+    pub fn rerandomize(&self, seed: u64) -> TestVector {
+        let vk = VerifyingKey::<Bn254> {
+            alpha_g1: g1_from_be(&self.vk_alpha_g1),
+            beta_g2: g2_from_be(&self.vk_beta_g2),
+            gamma_g2: g2_from_be(&self.vk_gamma_g2),
+            delta_g2: g2_from_be(&self.vk_delta_g2),
+            gamma_abc_g1: self.vk_ic.iter().map(g1_from_be).collect(),
+        };
+        let proof = Proof::<Bn254> {
+            a: -g1_from_be(&self.proof_a),
+            b: g2_from_be(&self.proof_b),
+            c: g1_from_be(&self.proof_c),
+        };
+        let proof =
+            Groth16::<Bn254>::rerandomize_proof(&vk, &proof, &mut StdRng::seed_from_u64(seed));
+        TestVector {
+            proof_a: g1_to_be(&-proof.a),
+            proof_b: g2_to_be(&proof.b),
+            proof_c: g1_to_be(&proof.c),
+            ..self.clone()
+        }
+    }
+
+    /// Applies `invalidation` with replacement values derived from `seed`
+    pub fn invalidate(&self, invalidation: Invalidation, seed: u64) -> TestVector {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut g1 = || g1_to_be(&(G1Affine::generator() * Fr::rand(&mut rng)).into_affine());
+        let mut vector = self.clone();
+        match invalidation {
+            Invalidation::RandomProof => {
+                vector.proof_a = g1();
+                vector.proof_c = g1();
+                vector.proof_b = random_g2(&mut rng);
+            }
+            Invalidation::WrongA => vector.proof_a = g1(),
+            Invalidation::WrongB => vector.proof_b = random_g2(&mut rng),
+            Invalidation::WrongC => vector.proof_c = g1(),
+            Invalidation::MismatchedInputs => {
+                for input in vector.public_inputs.iter_mut() {
+                    *input = fr_to_be(&Fr::rand(&mut rng));
+                }
+            }
+        }
+        vector
+    }
+
+    /// Verifies the vector with [`Groth16Verifier`]
+    pub fn verify(&self) -> Result<(), crate::errors::Groth16Error> {
+        match self.public_inputs.len() {
+            1 => self.verify_with::<1>(),
+            2 => self.verify_with::<2>(),
+            3 => self.verify_with::<3>(),
+            4 => self.verify_with::<4>(),
+            _ => Err(crate::errors::Groth16Error::InvalidPublicInputsLength),
+        }
+    }
+
+    fn verify_with<const N: usize>(&self) -> Result<(), crate::errors::Groth16Error> {
+        let public_inputs: [[u8; 32]; N] = self.public_inputs.clone().try_into().unwrap();
+        let vk = self.verifying_key();
+        Groth16Verifier::new(
+            &self.proof_a,
+            &self.proof_b,
+            &self.proof_c,
+            &public_inputs,
+            &vk,
+        )?
+        .verify()
+    }
+
+    /// snarkjs `proof.json`, proof a is not negated
+    pub fn snarkjs_proof_json(&self) -> String {
+        let proof_a = g1_from_be(&self.proof_a);
+        json!({
+            "pi_a": g1_to_json(&-proof_a),
+            "pi_b": g2_to_json(&g2_from_be(&self.proof_b)),
+            "pi_c": g1_to_json(&g1_from_be(&self.proof_c)),
+            "protocol": "groth16",
+            "curve": "bn128",
+        })
+        .to_string()
+    }
+
+    /// snarkjs `public.json`
+    pub fn snarkjs_public_json(&self) -> String {
+        let signals: Vec<String> = self
+            .public_inputs
+            .iter()
+            .map(|input| BigUint::from_bytes_be(input).to_string())
+            .collect();
+        json!(signals).to_string()
+    }
+
+    /// snarkjs `verification_key.json`
+    pub fn snarkjs_verification_key_json(&self) -> String {
+        let ic: Vec<Value> = self
+            .vk_ic
+            .iter()
+            .map(|ic| g1_to_json(&g1_from_be(ic)))
+            .collect();
+        json!({
+            "protocol": "groth16",
+            "curve": "bn128",
+            "nPublic": self.vk_ic.len() - 1,
+            "vk_alpha_1": g1_to_json(&g1_from_be(&self.vk_alpha_g1)),
+            "vk_beta_2": g2_to_json(&g2_from_be(&self.vk_beta_g2)),
+            "vk_gamma_2": g2_to_json(&g2_from_be(&self.vk_gamma_g2)),
+            "vk_delta_2": g2_to_json(&g2_from_be(&self.vk_delta_g2)),
+            "IC": ic,
+        })
+        .to_string()
+    }
+
+    /// Parses the snarkjs `verification_key.json`, `proof.json` and `public.json`
+    ///
+    /// Inverse of the `snarkjs_*_json` methods. Checks the fields snarkjs
+    /// writes to identify the proof system, so a version that changes them
+    /// fails here instead of in verification.
+    ///
+    /// # Returns
+    /// * `Err(String)` - If a file is malformed or the files do not match
+    pub fn from_snarkjs_json(
+        verification_key: &str,
+        proof: &str,
+        public: &str,
+    ) -> Result<TestVector, String> {
+        let parse = |json: &str| serde_json::from_str::<Value>(json).map_err(|e| e.to_string());
+        let vk = parse(verification_key)?;
+        let proof = parse(proof)?;
+        let public = parse(public)?;
+
+        for (file, json) in [("verification_key.json", &vk), ("proof.json", &proof)] {
+            if json["protocol"] != "groth16" {
+                return Err(format!("{file}: protocol is {}", json["protocol"]));
+            }
+            if !json["curve"].is_null() && json["curve"] != "bn128" {
+                return Err(format!("{file}: curve is {}", json["curve"]));
+            }
+        }
+        let public = public
+            .as_array()
+            .ok_or("public.json is not an array")?
+            .iter()
+            .map(decimal_from_json)
+            .collect::<Result<Vec<_>, _>>()?;
+        let vk_ic = vk["IC"]
+            .as_array()
+            .ok_or("verification_key.json: IC is not an array")?
+            .iter()
+            .map(g1_from_json)
+            .collect::<Result<Vec<_>, _>>()?;
+        if vk["nPublic"].as_u64() != Some(public.len() as u64) || vk_ic.len() != public.len() + 1 {
+            return Err(format!(
+                "nPublic {}, {} IC points and {} public inputs do not match",
+                vk["nPublic"],
+                vk_ic.len(),
+                public.len()
+            ));
+        }
+
+        Ok(TestVector {
+            vk_alpha_g1: g1_from_json(&vk["vk_alpha_1"])?,
+            vk_beta_g2: g2_from_json(&vk["vk_beta_2"])?,
+            vk_gamma_g2: g2_from_json(&vk["vk_gamma_2"])?,
+            vk_delta_g2: g2_from_json(&vk["vk_delta_2"])?,
+            vk_ic,
+            proof_a: g1_to_be(&-g1_from_be(&g1_from_json(&proof["pi_a"])?)),
+            proof_b: g2_from_json(&proof["pi_b"])?,
+            proof_c: g1_from_json(&proof["pi_c"])?,
+            public_inputs: public,
+        })
+    }
+}
+
+pub(crate) fn fr_to_be(value: &Fr) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&value.into_bigint().to_bytes_be());
+    bytes
+}
+
+pub(crate) fn g1_to_be(point: &G1Affine) -> [u8; 64] {
+    let mut le = [0u8; 64];
+    point
+        .x
+        .serialize_with_mode(&mut le[..32], Compress::No)
+        .unwrap();
+    point
+        .y
+        .serialize_with_mode(&mut le[32..], Compress::No)
+        .unwrap();
+    convert_endianness::<32, 64>(&le)
+}
+
+pub(crate) fn g2_to_be(point: &G2Affine) -> [u8; 128] {
+    let mut le = [0u8; 128];
+    point
+        .x
+        .serialize_with_mode(&mut le[..64], Compress::No)
+        .unwrap();
+    point
+        .y
+        .serialize_with_mode(&mut le[64..], Compress::No)
+        .unwrap();
+    convert_endianness::<64, 128>(&le)
+}
+
+pub(crate) fn g1_from_be(bytes: &[u8; 64]) -> G1Affine {
+    use ark_serialize::CanonicalDeserialize;
+    let le = convert_endianness::<32, 64>(bytes);
+    let x = Fq::deserialize_uncompressed(&le[..32]).unwrap();
+    let y = Fq::deserialize_uncompressed(&le[32..]).unwrap();
+    G1Affine::new(x, y)
+}
+
+pub(crate) fn g2_from_be(bytes: &[u8; 128]) -> G2Affine {
+    use ark_serialize::CanonicalDeserialize;
+    let le = convert_endianness::<64, 128>(bytes);
+    let x = Fq2::deserialize_uncompressed(&le[..64]).unwrap();
+    let y = Fq2::deserialize_uncompressed(&le[64..]).unwrap();
+    G2Affine::new(x, y)
+}
+
+fn g1_compress_be(bytes: &[u8; 64]) -> [u8; 32] {
+    let mut compressed = [0u8; 32];
+    g1_from_be(bytes)
+        .serialize_with_mode(&mut compressed[..], Compress::Yes)
+        .unwrap();
+    compressed.reverse();
+    compressed
+}
+
+fn g2_compress_be(bytes: &[u8; 128]) -> [u8; 64] {
+    let mut compressed = [0u8; 64];
+    g2_from_be(bytes)
+        .serialize_with_mode(&mut compressed[..], Compress::Yes)
+        .unwrap();
+    compressed.reverse();
+    compressed
+}
+
+fn fq_to_decimal(value: &Fq) -> String {
+    BigUint::from(value.into_bigint()).to_string()
+}
+
+fn random_g2(rng: &mut StdRng) -> [u8; 128] {
+    g2_to_be(&(G2Affine::generator() * Fr::rand(rng)).into_affine())
+}
+
+fn decimal_from_json(value: &Value) -> Result<[u8; 32], String> {
+    let decimal = value
+        .as_str()
+        .ok_or_else(|| format!("expected a decimal string, got {value}"))?;
+    let bytes = decimal
+        .parse::<BigUint>()
+        .map_err(|e| format!("invalid decimal {decimal}: {e}"))?
+        .to_bytes_be();
+    if bytes.len() > 32 {
+        return Err(format!("{decimal} does not fit into 32 bytes"));
+    }
+    let mut be = [0u8; 32];
+    be[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(be)
+}
+
+fn g1_from_json(value: &Value) -> Result<[u8; 64], String> {
+    match value.as_array().map(Vec::as_slice) {
+        Some([x, y, z]) if z == "1" => {
+            let mut point = [0u8; 64];
+            point[..32].copy_from_slice(&decimal_from_json(x)?);
+            point[32..].copy_from_slice(&decimal_from_json(y)?);
+            Ok(point)
+        }
+        _ => Err(format!("expected a G1 point [x, y, \"1\"], got {value}")),
+    }
+}
+
+/// snarkjs writes `[c0, c1]`, the verifier takes `c1 | c0`
+fn g2_from_json(value: &Value) -> Result<[u8; 128], String> {
+    match value.as_array().map(Vec::as_slice) {
+        Some([x, y, z]) if z[0] == "1" && z[1] == "0" => {
+            let mut point = [0u8; 128];
+            for (i, coordinate) in [x, y].iter().enumerate() {
+                point[i * 64..i * 64 + 32].copy_from_slice(&decimal_from_json(&coordinate[1])?);
+                point[i * 64 + 32..i * 64 + 64]
+                    .copy_from_slice(&decimal_from_json(&coordinate[0])?);
+            }
+            Ok(point)
+        }
+        _ => Err(format!(
+            "expected a G2 point [x, y, [\"1\", \"0\"]], got {value}"
+        )),
+    }
+}
+
+fn g1_to_json(point: &G1Affine) -> Value {
+    let (x, y) = point.xy().expect("point is not infinity");
+    json!([fq_to_decimal(&x), fq_to_decimal(&y), "1"])
+}
+
+fn g2_to_json(point: &G2Affine) -> Value {
+    let (x, y) = point.xy().expect("point is not infinity");
+    json!([
+        [fq_to_decimal(&x.c0), fq_to_decimal(&x.c1)],
+        [fq_to_decimal(&y.c0), fq_to_decimal(&y.c1)],
+        ["1", "0"]
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decompression::{decompress_g1, decompress_g2};
+
+    #[test]
+    fn generated_vector_verifies_in_every_encoding() {
+        let vector = generate(7);
+        assert_eq!(vector, generate(7));
+        assert_ne!(vector.proof_a, generate(8).proof_a);
+        vector.verify().unwrap();
+
+        let vk = vector.verifying_key();
+        assert_eq!(
+            Groth16Verifyingkey::from_packed_bytes(&vector.packed_verifying_key()).unwrap(),
+            vk
+        );
+
+        let compressed = vector.proof_compressed();
+        assert_eq!(
+            decompress_g1(&compressed[..32].try_into().unwrap()).unwrap(),
+            vector.proof_a
+        );
+        assert_eq!(
+            decompress_g2(&compressed[32..96].try_into().unwrap()).unwrap(),
+            vector.proof_b
+        );
+        assert_eq!(
+            decompress_g1(&compressed[96..].try_into().unwrap()).unwrap(),
+            vector.proof_c
+        );
+        assert_eq!(
+            convert_endianness::<64, 128>(&vector.proof_le()[64..192].try_into().unwrap()),
+            vector.proof_b
+        );
+
+        let public: Vec<String> = serde_json::from_str(&vector.snarkjs_public_json()).unwrap();
+        assert_eq!(public.len(), NR_PUBLIC_INPUTS);
+        let proof: Value = serde_json::from_str(&vector.snarkjs_proof_json()).unwrap();
+        assert_eq!(proof["pi_a"][2], "1");
+        let vk_json: Value = serde_json::from_str(&vector.snarkjs_verification_key_json()).unwrap();
+        assert_eq!(
+            vk_json["IC"].as_array().unwrap().len(),
+            NR_PUBLIC_INPUTS + 1
+        );
+
+        let rerandomized = vector.rerandomize(1);
+        assert_ne!(rerandomized.proof_a, vector.proof_a);
+        rerandomized.verify().unwrap();
+
+        let mut wrong = vector.clone();
+        wrong.public_inputs.swap(0, 1);
+        assert!(wrong.verify().is_err());
+    }
+
+    #[test]
+    fn invalid_vectors_fail_the_pairing_check() {
+        let invalid = generate_invalid(3);
+        assert_eq!(invalid, generate_invalid(3));
+        assert_eq!(invalid.len(), Invalidation::ALL.len());
+        for (invalidation, vector) in invalid {
+            assert_eq!(
+                vector.verify(),
+                Err(crate::errors::Groth16Error::ProofVerificationFailed),
+                "{invalidation:?}"
+            );
+        }
+    }
+}
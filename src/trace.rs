@@ -0,0 +1,148 @@
+//! Trace of intermediate verification values
+//!
+//! With the `trace` feature, [`Groth16Verifier`](crate::groth16::Groth16Verifier)
+//! records the verifying key, the proof, every public input, the prepared
+//! inputs after each input, the assembled pairing buffer and the pairing
+//! result in hex. On Solana the values are logged with `sol_log`, on the host
+//! they are printed to stderr or collected with [`capture`] to diff them
+//! against a reference implementation:
+//!
+//! ```rust,ignore
+//! let (result, events) = trace::capture(|| verifier.verify());
+//! let prepared = events.iter().find(|event| event.label == "prepared_inputs").unwrap();
+//! assert_eq!(prepared.bytes, arkworks_prepared_inputs_be);
+//! ```
+//!
+//! Tracing costs compute units and is meant for debugging builds only.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt::Write;
+
+/// One recorded value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceEvent {
+    pub label: String,
+    pub bytes: Vec<u8>,
+}
+
+std::thread_local! {
+    static CAPTURED: RefCell<Option<Vec<TraceEvent>>> = const { RefCell::new(None) };
+}
+
+/// Lowercase hex of `bytes`
+pub fn hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        write!(hex, "{byte:02x}").unwrap();
+    }
+    hex
+}
+
+/// Records `bytes` under `label`
+pub(crate) fn record(label: &str, bytes: &[u8]) {
+    let captured = CAPTURED.with(|captured| {
+        captured.borrow_mut().as_mut().map(|events| {
+            events.push(TraceEvent {
+                label: label.into(),
+                bytes: bytes.to_vec(),
+            })
+        })
+    });
+    if captured.is_none() {
+        #[cfg(target_os = "solana")]
+        crate::syscalls::log(&format!("groth16 trace {label}: {}", hex(bytes)));
+        #[cfg(not(target_os = "solana"))]
+        std::eprintln!("groth16 trace {label}: {}", hex(bytes));
+    }
+}
+
+/// Runs `f` and returns the events it records on the current thread instead of printing them
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, Vec<TraceEvent>) {
+    let previous = CAPTURED.with(|captured| captured.borrow_mut().replace(Vec::new()));
+    let result = f();
+    let events =
+        CAPTURED.with(|captured| core::mem::replace(&mut *captured.borrow_mut(), previous));
+    (result, events.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+
+    #[test]
+    fn verification_stages_are_recorded() {
+        // All-zero points are the identity, for which the pairing check passes
+        let vk_ic = [[0u8; 64]; 3];
+        let vk = Groth16Verifyingkey {
+            nr_pubinputs: 2,
+            vk_alpha_g1: [0u8; 64],
+            vk_beta_g2: [0u8; 128],
+            vk_gamma_g2: [0u8; 128],
+            vk_delta_g2: [0u8; 128],
+            vk_ic: &vk_ic,
+        };
+        let inputs = [[1u8; 32], [2u8; 32]];
+        let (result, events) = capture(|| {
+            Groth16Verifier::new(&[0u8; 64], &[0u8; 128], &[0u8; 64], &inputs, &vk)
+                .unwrap()
+                .verify()
+        });
+        assert_eq!(result, Ok(()));
+
+        let labels: Vec<&str> = events.iter().map(|event| event.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "vk.alpha_g1",
+                "vk.beta_g2",
+                "vk.gamma_g2",
+                "vk.delta_g2",
+                "vk.ic[0]",
+                "vk.ic[1]",
+                "vk.ic[2]",
+                "proof.a",
+                "proof.b",
+                "proof.c",
+                "public_inputs[0]",
+                "public_inputs[1]",
+                "msm after point 0",
+                "msm after point 1",
+                "prepared_inputs",
+                "pairing_input",
+                "pairing_result",
+            ]
+        );
+        assert_eq!(events[10].bytes, [1u8; 32]);
+        assert_eq!(events[15].bytes.len(), 4 * 192);
+        assert_eq!(hex(&events[16].bytes[30..]), "0001");
+    }
+
+    #[cfg(feature = "test-vectors")]
+    #[test]
+    fn prepared_inputs_match_arkworks() {
+        use crate::test_vectors::{g1_to_be, generate};
+        use ark_bn254::{Bn254, Fr};
+        use ark_ff::PrimeField;
+
+        let vector = generate(5);
+        let (result, events) = capture(|| vector.verify());
+        assert_eq!(result, Ok(()));
+
+        let vk = crate::differential::arkworks_verifying_key(&vector).unwrap();
+        let inputs: Vec<Fr> = vector
+            .public_inputs
+            .iter()
+            .map(|input| Fr::from_be_bytes_mod_order(input))
+            .collect();
+        let pvk = ark_groth16::prepare_verifying_key(&vk);
+        let prepared = ark_groth16::Groth16::<Bn254>::prepare_inputs(&pvk, &inputs).unwrap();
+        let traced = events
+            .iter()
+            .find(|event| event.label == "prepared_inputs")
+            .unwrap();
+        assert_eq!(traced.bytes, g1_to_be(&prepared.into()));
+    }
+}
@@ -0,0 +1,212 @@
+//! Fiat-Shamir transcripts
+//!
+//! A [`Transcript`] absorbs labelled scalars, curve points and byte strings
+//! and squeezes challenges from everything absorbed so far. Protocols that
+//! combine a Groth16 proof with a sigma protocol or a commitment opening
+//! derive their challenges from one transcript, on chain and off chain.
+//!
+//! Everything is absorbed as BN254 scalar field elements, so the same
+//! transcript can be recomputed inside a circuit:
+//!
+//! * a scalar is one element and must be smaller than the field size
+//! * a point is split into 16-byte big-endian limbs, one element each, which
+//!   works for the coordinates of any curve
+//! * a byte string is its length followed by 31-byte chunks
+//!
+//! Each operation replaces the state with `H(state, tag, elements)`, where
+//! `tag` is the keccak256 hash of its label reduced to the field, see
+//! [`hashv_to_bn254_field_size_be`]. Labels therefore separate both the
+//! protocol, passed to [`Transcript::new`], and each message in it. Challenges
+//! are the new state and always smaller than the field size.
+//!
+//! [`KeccakTranscript`] is the cheapest on chain. [`PoseidonTranscript`],
+//! with the `poseidon` feature, is the one to use when a circuit verifies the
+//! transcript; it hashes at most ten elements per `sol_poseidon` call.
+//!
+//! ```rust,ignore
+//! let mut transcript = KeccakTranscript::new(b"my-protocol/v1");
+//! transcript.append_point(b"commitment", &commitment);
+//! transcript.append_scalar(b"public-input", &input)?;
+//! let challenge = transcript.challenge_scalar(b"challenge");
+//! ```
+
+use crate::errors::Groth16Error;
+use crate::groth16::is_less_than_bn254_field_size_be;
+use crate::hash::hashv_to_bn254_field_size_be;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+/// Bytes of a byte string absorbed per field element
+const MESSAGE_CHUNK_SIZE: usize = 31;
+/// Bytes of a point coordinate absorbed per field element
+const LIMB_SIZE: usize = 16;
+
+/// Hash function of a transcript
+pub trait TranscriptHash {
+    /// Hashes the state, the tag of a label and field elements to the next state
+    ///
+    /// All inputs are big-endian field elements, the output must be one too.
+    fn compress(state: &[u8; 32], tag: &[u8; 32], elements: &[[u8; 32]]) -> [u8; 32];
+}
+
+/// keccak256, reduced to 248 bits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Keccak256;
+
+impl TranscriptHash for Keccak256 {
+    fn compress(state: &[u8; 32], tag: &[u8; 32], elements: &[[u8; 32]]) -> [u8; 32] {
+        let mut vals: Vec<&[u8]> = Vec::with_capacity(2 + elements.len());
+        vals.extend_from_slice(&[state, tag]);
+        vals.extend(elements.iter().map(|element| element.as_slice()));
+        hashv_to_bn254_field_size_be(&vals)
+    }
+}
+
+/// Poseidon with circom's BN254 parameters
+#[cfg(feature = "poseidon")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Poseidon;
+
+#[cfg(feature = "poseidon")]
+impl TranscriptHash for Poseidon {
+    fn compress(state: &[u8; 32], tag: &[u8; 32], elements: &[[u8; 32]]) -> [u8; 32] {
+        use crate::hash::{poseidon, POSEIDON_MAX_INPUTS};
+
+        let mut state = *state;
+        let mut inputs = [[0u8; 32]; POSEIDON_MAX_INPUTS];
+        let mut chunks = elements.chunks(POSEIDON_MAX_INPUTS - 2).peekable();
+        loop {
+            let chunk = chunks.next().unwrap_or(&[]);
+            inputs[0] = state;
+            inputs[1] = *tag;
+            inputs[2..2 + chunk.len()].copy_from_slice(chunk);
+            state = poseidon(&inputs[..2 + chunk.len()]).expect("inputs are field elements");
+            if chunks.peek().is_none() {
+                return state;
+            }
+        }
+    }
+}
+
+/// Fiat-Shamir transcript over the hash `H`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transcript<H: TranscriptHash> {
+    state: [u8; 32],
+    hash: PhantomData<H>,
+}
+
+/// Transcript hashed with keccak256
+pub type KeccakTranscript = Transcript<Keccak256>;
+
+/// Transcript hashed with Poseidon
+#[cfg(feature = "poseidon")]
+pub type PoseidonTranscript = Transcript<Poseidon>;
+
+impl<H: TranscriptHash> Transcript<H> {
+    /// Starts a transcript of the protocol `domain`
+    pub fn new(domain: &[u8]) -> Self {
+        let mut transcript = Transcript {
+            state: [0u8; 32],
+            hash: PhantomData,
+        };
+        transcript.absorb(domain, &[]);
+        transcript
+    }
+
+    fn absorb(&mut self, label: &[u8], elements: &[[u8; 32]]) {
+        let tag = hashv_to_bn254_field_size_be(&[label]);
+        self.state = H::compress(&self.state, &tag, elements);
+    }
+
+    /// Absorbs a big-endian scalar
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If `scalar`
+    ///   is not smaller than the field size
+    pub fn append_scalar(&mut self, label: &[u8], scalar: &[u8; 32]) -> Result<(), Groth16Error> {
+        if !is_less_than_bn254_field_size_be(scalar) {
+            return Err(Groth16Error::PublicInputGreaterThanFieldSize);
+        }
+        self.absorb(label, &[*scalar]);
+        Ok(())
+    }
+
+    /// Absorbs an encoded curve point, e.g. a G1 or G2 point of [`crate::curve::PairingCurve`]
+    pub fn append_point(&mut self, label: &[u8], point: impl AsRef<[u8]>) {
+        let elements: Vec<[u8; 32]> = point
+            .as_ref()
+            .chunks(LIMB_SIZE)
+            .map(|limb| {
+                let mut element = [0u8; 32];
+                element[32 - limb.len()..].copy_from_slice(limb);
+                element
+            })
+            .collect();
+        self.absorb(label, &elements);
+    }
+
+    /// Absorbs a byte string
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        let mut length = [0u8; 32];
+        length[24..].copy_from_slice(&(message.len() as u64).to_be_bytes());
+        let elements: Vec<[u8; 32]> = core::iter::once(length)
+            .chain(message.chunks(MESSAGE_CHUNK_SIZE).map(|chunk| {
+                let mut element = [0u8; 32];
+                element[1..1 + chunk.len()].copy_from_slice(chunk);
+                element
+            }))
+            .collect();
+        self.absorb(label, &elements);
+    }
+
+    /// Squeezes a challenge, a big-endian scalar smaller than the field size
+    pub fn challenge_scalar(&mut self, label: &[u8]) -> [u8; 32] {
+        self.absorb(label, &[]);
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn challenges<H: TranscriptHash>() -> Vec<[u8; 32]> {
+        let run = |domain: &[u8], label: &[u8], message: &[u8], scalar: u8| {
+            let mut transcript = Transcript::<H>::new(domain);
+            transcript.append_point(b"point", [7u8; 64]);
+            transcript.append_message(label, message);
+            transcript.append_scalar(b"scalar", &[scalar; 32]).unwrap();
+            let first = transcript.challenge_scalar(b"challenge");
+            assert_ne!(transcript.challenge_scalar(b"challenge"), first);
+            assert!(is_less_than_bn254_field_size_be(&first));
+            first
+        };
+        let challenges = vec![
+            run(b"domain", b"message", &[1u8; 40], 1),
+            run(b"other domain", b"message", &[1u8; 40], 1),
+            run(b"domain", b"other label", &[1u8; 40], 1),
+            run(b"domain", b"message", &[1u8; 41], 1),
+            run(b"domain", b"message", &[1u8; 40], 2),
+        ];
+        assert_eq!(challenges[0], run(b"domain", b"message", &[1u8; 40], 1));
+        for other in &challenges[1..] {
+            assert_ne!(*other, challenges[0]);
+        }
+        assert_eq!(
+            Transcript::<H>::new(b"domain").append_scalar(b"scalar", &[0xff; 32]),
+            Err(Groth16Error::PublicInputGreaterThanFieldSize)
+        );
+        challenges
+    }
+
+    #[test]
+    fn keccak_transcript_binds_labels_and_messages() {
+        challenges::<Keccak256>();
+    }
+
+    #[cfg(feature = "poseidon")]
+    #[test]
+    fn poseidon_transcript_binds_labels_and_messages() {
+        assert_ne!(challenges::<Poseidon>(), challenges::<Keccak256>());
+    }
+}
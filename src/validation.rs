@@ -0,0 +1,127 @@
+//! Validation of proof points
+//!
+//! The syscalls reject a malformed point with the same error as a wrong
+//! proof, [`Groth16Error::ProofVerificationFailed`]. [`validate_g1`] and
+//! [`validate_g2`] check a point in the big-endian syscall encoding and say
+//! what is wrong with it:
+//!
+//! * [`Groth16Error::NonCanonicalCoordinate`] - A coordinate is not smaller
+//!   than the base field modulus
+//! * [`Groth16Error::PointNotOnCurve`] - The point does not satisfy the curve
+//!   equation
+//! * [`Groth16Error::PointNotInSubgroup`] - The G2 point is on the twist but
+//!   not in the prime order subgroup
+//!
+//! The all zero encoding is the point at infinity and valid. G1 has prime
+//! order, so every point on the curve is in the subgroup. The G2 subgroup
+//! check is a scalar multiplication in software and costs far more compute
+//! units than verification itself, run it off chain or in tests.
+
+use crate::curve::{Bn254, PairingCurve};
+use crate::errors::Groth16Error;
+use ark_bn254::{Fq, Fq2, G1Affine, G2Affine};
+use ark_ff::PrimeField;
+
+fn fq_from_be(bytes: &[u8]) -> Result<Fq, Groth16Error> {
+    if bytes >= Bn254::BASE_FIELD_MODULUS {
+        return Err(Groth16Error::NonCanonicalCoordinate);
+    }
+    Ok(Fq::from_be_bytes_mod_order(bytes))
+}
+
+/// `c1 | c0`, the imaginary part first
+fn fq2_from_be(bytes: &[u8]) -> Result<Fq2, Groth16Error> {
+    let c1 = fq_from_be(&bytes[..32])?;
+    let c0 = fq_from_be(&bytes[32..])?;
+    Ok(Fq2::new(c0, c1))
+}
+
+/// Checks that `point` is a G1 point in the syscall encoding `x | y`
+///
+/// # Returns
+/// * `Err(Groth16Error::NonCanonicalCoordinate)` - If x or y is not smaller
+///   than the base field modulus
+/// * `Err(Groth16Error::PointNotOnCurve)` - If `y^2 != x^3 + 3`
+pub fn validate_g1(point: &[u8; 64]) -> Result<(), Groth16Error> {
+    let x = fq_from_be(&point[..32])?;
+    let y = fq_from_be(&point[32..])?;
+    if *point == [0u8; 64] {
+        return Ok(());
+    }
+    if !G1Affine::new_unchecked(x, y).is_on_curve() {
+        return Err(Groth16Error::PointNotOnCurve);
+    }
+    Ok(())
+}
+
+/// Checks that `point` is a G2 point in the syscall encoding `x.c1 | x.c0 | y.c1 | y.c0`
+///
+/// # Returns
+/// * `Err(Groth16Error::NonCanonicalCoordinate)` - If a coordinate is not
+///   smaller than the base field modulus
+/// * `Err(Groth16Error::PointNotOnCurve)` - If the point is not on the twist
+/// * `Err(Groth16Error::PointNotInSubgroup)` - If the point is not in the
+///   prime order subgroup
+pub fn validate_g2(point: &[u8; 128]) -> Result<(), Groth16Error> {
+    let x = fq2_from_be(&point[..64])?;
+    let y = fq2_from_be(&point[64..])?;
+    if *point == [0u8; 128] {
+        return Ok(());
+    }
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(Groth16Error::PointNotOnCurve);
+    }
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Groth16Error::PointNotInSubgroup);
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "test-vectors"))]
+mod tests {
+    use super::*;
+    use crate::test_vectors::generate;
+    use ark_ec::AffineRepr;
+    use ark_ff::{BigInteger, Field};
+
+    #[test]
+    fn proof_points_validate() {
+        let vector = generate(0);
+        validate_g1(&vector.proof_a).unwrap();
+        validate_g2(&vector.proof_b).unwrap();
+        validate_g1(&vector.proof_c).unwrap();
+        validate_g1(&[0u8; 64]).unwrap();
+        validate_g2(&[0u8; 128]).unwrap();
+
+        let mut non_canonical = vector.proof_a;
+        non_canonical[..32].copy_from_slice(Bn254::BASE_FIELD_MODULUS);
+        assert_eq!(
+            validate_g1(&non_canonical),
+            Err(Groth16Error::NonCanonicalCoordinate)
+        );
+        let mut off_curve = vector.proof_c;
+        off_curve[63] ^= 1;
+        assert_eq!(validate_g1(&off_curve), Err(Groth16Error::PointNotOnCurve));
+        let mut off_curve = vector.proof_b;
+        off_curve[127] ^= 1;
+        assert_eq!(validate_g2(&off_curve), Err(Groth16Error::PointNotOnCurve));
+
+        // A point on the twist outside the subgroup, the twist has cofactor > 1
+        let mut x = Fq2::ONE;
+        let point = loop {
+            if let Some(point) = G2Affine::get_point_from_x_unchecked(x, false) {
+                if !point.is_in_correct_subgroup_assuming_on_curve() {
+                    break point;
+                }
+            }
+            x += Fq2::ONE;
+        };
+        let (x, y) = point.xy().unwrap();
+        let mut bytes = [0u8; 128];
+        for (chunk, coordinate) in bytes.chunks_exact_mut(32).zip([x.c1, x.c0, y.c1, y.c0]) {
+            chunk.copy_from_slice(&coordinate.into_bigint().to_bytes_be());
+        }
+        assert_eq!(validate_g2(&bytes), Err(Groth16Error::PointNotInSubgroup));
+    }
+}
@@ -0,0 +1,108 @@
+//! Dispatch of proofs of several circuits to their verifying keys
+//!
+//! A program accepting proofs of several circuits, e.g. deposit, withdraw
+//! and transfer, maps a circuit id in the instruction to the key to verify
+//! with. [`VerifierRegistry`] holds the keys by id, any `PartialEq` id such as
+//! a `u16` or an 8 byte tag. Keys embedded at compile time build a `const`
+//! registry:
+//!
+//! ```rust,ignore
+//! const REGISTRY: VerifierRegistry<u16> =
+//!     VerifierRegistry::new(&[(DEPOSIT, DEPOSIT_VK), (WITHDRAW, WITHDRAW_VK)]);
+//!
+//! REGISTRY.verify(circuit_id, &proof, &public_inputs)?;
+//! ```
+//!
+//! Keys loaded from accounts with [`Groth16Verifyingkey::from_bytes`] or
+//! [`Groth16Verifyingkey::from_packed_bytes`] borrow the account data, the
+//! registry borrows a local array of them.
+
+use crate::errors::Groth16Error;
+use crate::groth16::{verify_with_prepared_inputs, Groth16Verifyingkey, PreparedInputs};
+use crate::proof::Proof;
+
+/// Verifying keys by circuit id
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifierRegistry<'a, Id> {
+    entries: &'a [(Id, Groth16Verifyingkey<'a>)],
+}
+
+impl<'a, Id: PartialEq> VerifierRegistry<'a, Id> {
+    /// A registry of `entries`, the first entry of an id is used
+    pub const fn new(entries: &'a [(Id, Groth16Verifyingkey<'a>)]) -> Self {
+        VerifierRegistry { entries }
+    }
+
+    /// The verifying key of `circuit_id`
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::UnknownCircuit)` - If no key is registered for the id
+    pub fn get(&self, circuit_id: &Id) -> Result<&Groth16Verifyingkey<'a>, Groth16Error> {
+        self.entries
+            .iter()
+            .find(|(id, _)| id == circuit_id)
+            .map(|(_, vk)| vk)
+            .ok_or(Groth16Error::UnknownCircuit)
+    }
+
+    /// Verifies `proof` with the verifying key of `circuit_id`
+    ///
+    /// # Arguments
+    /// * `circuit_id` - Id of the circuit the proof is of
+    /// * `proof` - The proof, with proof a negated
+    /// * `public_inputs` - As many inputs as the circuit takes
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::UnknownCircuit)` - If no key is registered for the id
+    /// * `Err(Groth16Error::InvalidPublicInputsLength)` - If the circuit takes
+    ///   another number of public inputs
+    /// * `Err(Groth16Error::PublicInputGreaterThanFieldSize)` - If an input is
+    ///   not smaller than the scalar field modulus
+    pub fn verify(
+        &self,
+        circuit_id: Id,
+        proof: &Proof,
+        public_inputs: &[[u8; 32]],
+    ) -> Result<(), Groth16Error> {
+        let vk = self.get(&circuit_id)?;
+        let prepared_inputs = PreparedInputs::new(public_inputs, vk)?;
+        verify_with_prepared_inputs(&proof.a.0, &proof.b.0, &proof.c.0, &prepared_inputs, vk)
+    }
+}
+
+#[cfg(all(test, feature = "test-vectors"))]
+mod tests {
+    use super::*;
+    use crate::proof::{ProofA, ProofB, ProofC};
+    use crate::test_vectors::{generate, generate_merkle_proof};
+
+    #[test]
+    fn proofs_verify_with_the_key_of_their_circuit() {
+        let (tiny, merkle) = (generate(0), generate_merkle_proof(1));
+        let entries = [
+            (*b"tiny\0\0\0\0", tiny.verifying_key()),
+            (*b"merkle\0\0", merkle.verifying_key()),
+        ];
+        let registry = VerifierRegistry::new(&entries);
+        let proof = |vector: &crate::test_vectors::TestVector| Proof {
+            a: ProofA(vector.proof_a),
+            b: ProofB(vector.proof_b),
+            c: ProofC(vector.proof_c),
+        };
+
+        registry
+            .verify(*b"tiny\0\0\0\0", &proof(&tiny), &tiny.public_inputs)
+            .unwrap();
+        registry
+            .verify(*b"merkle\0\0", &proof(&merkle), &merkle.public_inputs)
+            .unwrap();
+        assert_eq!(
+            registry.verify(*b"merkle\0\0", &proof(&tiny), &merkle.public_inputs),
+            Err(Groth16Error::ProofVerificationFailed)
+        );
+        assert_eq!(
+            registry.verify(*b"transfer", &proof(&tiny), &tiny.public_inputs),
+            Err(Groth16Error::UnknownCircuit)
+        );
+    }
+}
@@ -7,7 +7,7 @@
 //! # Example
 //!
 //! ```rust,ignore
-//! use groth16_solana::vk_parser::generate_vk_file;
+//! use pinocchio_groth16::vk_parser::generate_vk_file;
 //!
 //! fn main() {
 //!     generate_vk_file(
@@ -18,6 +18,11 @@
 //! }
 //! ```
 
+pub use crate::groth16::ProverConvention;
+use crate::groth16::VK_BINARY_VERSION;
+use crate::hash::keccak256;
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::CanonicalDeserialize;
 use num_bigint::BigUint;
 use serde::Deserialize;
 use std::fs;
@@ -39,6 +44,8 @@ pub enum VkParseError {
 /// Raw verification key data as it appears in JSON files
 #[derive(Debug, Deserialize)]
 struct RawVerifyingKey {
+    curve: Option<String>,
+
     #[serde(rename = "vk_alpha_1")]
     vk_alpha_1: Vec<String>,
 
@@ -55,6 +62,35 @@ struct RawVerifyingKey {
     ic: Vec<Vec<String>>,
 }
 
+/// Raw PLONK verification key data as it appears in snarkjs JSON files
+#[derive(Debug, Deserialize)]
+struct RawPlonkVerifyingKey {
+    #[serde(rename = "nPublic")]
+    n_public: usize,
+    power: u32,
+    k1: String,
+    k2: String,
+    #[serde(rename = "Qm")]
+    qm: Vec<String>,
+    #[serde(rename = "Ql")]
+    ql: Vec<String>,
+    #[serde(rename = "Qr")]
+    qr: Vec<String>,
+    #[serde(rename = "Qo")]
+    qo: Vec<String>,
+    #[serde(rename = "Qc")]
+    qc: Vec<String>,
+    #[serde(rename = "S1")]
+    s1: Vec<String>,
+    #[serde(rename = "S2")]
+    s2: Vec<String>,
+    #[serde(rename = "S3")]
+    s3: Vec<String>,
+    #[serde(rename = "X_2")]
+    x_2: Vec<Vec<String>>,
+    w: String,
+}
+
 /// Convert a bigint string to little-endian bytes, then reverse to big-endian
 fn bigint_string_to_be_bytes(s: &str, size: usize) -> Result<Vec<u8>, VkParseError> {
     let bigint = s
@@ -72,13 +108,45 @@ fn bigint_string_to_be_bytes(s: &str, size: usize) -> Result<Vec<u8>, VkParseErr
     Ok(padded)
 }
 
-/// Process G1 point: convert bigint to 32-byte BE
-fn process_g1_component(component: &str) -> Result<Vec<u8>, VkParseError> {
-    bigint_string_to_be_bytes(component, 32)
+/// Curve of a verification key, from its `curve` field
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Curve {
+    Bn254,
+    Bls12_381,
+}
+
+impl Curve {
+    /// Keys without a `curve` field are BN254 keys
+    fn from_name(name: Option<&str>) -> Result<Self, VkParseError> {
+        match name {
+            None | Some("bn128") | Some("bn254") => Ok(Curve::Bn254),
+            Some("bls12381") | Some("bls12-381") => Ok(Curve::Bls12_381),
+            Some(name) => Err(VkParseError::InvalidData(format!(
+                "Unsupported curve '{}'",
+                name
+            ))),
+        }
+    }
+
+    /// Size of a base field element in bytes
+    fn field_size(self) -> usize {
+        match self {
+            Curve::Bn254 => 32,
+            Curve::Bls12_381 => 48,
+        }
+    }
+}
+
+/// Process G1 point: convert bigint to `size`-byte BE
+fn process_g1_component(component: &str, size: usize) -> Result<Vec<u8>, VkParseError> {
+    bigint_string_to_be_bytes(component, size)
 }
 
-/// Process G2 point: concatenate two 32-byte LE components, reverse the full 64 bytes, then split
-fn process_g2_component(components: &[String]) -> Result<(Vec<u8>, Vec<u8>), VkParseError> {
+/// Process G2 point: concatenate two `size`-byte LE components, reverse the full buffer, then split
+fn process_g2_component(
+    components: &[String],
+    size: usize,
+) -> Result<(Vec<u8>, Vec<u8>), VkParseError> {
     if components.len() != 2 {
         return Err(VkParseError::InvalidData(format!(
             "G2 component must have exactly 2 elements, got {}",
@@ -96,11 +164,11 @@ fn process_g2_component(components: &[String]) -> Result<(Vec<u8>, Vec<u8>), VkP
         .map_err(|e| VkParseError::InvalidData(format!("Failed to parse bigint: {}", e)))?
         .to_bytes_le();
 
-    // Pad to 32 bytes
+    // Pad to size bytes
     let mut c0_padded = c0_le;
-    c0_padded.resize(32, 0);
+    c0_padded.resize(size, 0);
     let mut c1_padded = c1_le;
-    c1_padded.resize(32, 0);
+    c1_padded.resize(size, 0);
 
     // Concatenate and reverse
     let mut concat = Vec::new();
@@ -109,8 +177,8 @@ fn process_g2_component(components: &[String]) -> Result<(Vec<u8>, Vec<u8>), VkP
     concat.reverse();
 
     // Split back
-    let part0 = concat[0..32].to_vec();
-    let part1 = concat[32..64].to_vec();
+    let part0 = concat[..size].to_vec();
+    let part1 = concat[size..].to_vec();
 
     Ok((part0, part1))
 }
@@ -126,11 +194,17 @@ fn process_g2_component(components: &[String]) -> Result<(Vec<u8>, Vec<u8>), VkP
 /// A String containing the generated Rust code defining a `Groth16Verifyingkey` constant
 pub fn parse_vk_json_to_rust_string(json_content: &str) -> Result<String, VkParseError> {
     let raw_vk: RawVerifyingKey = serde_json::from_str(json_content)?;
+    if Curve::from_name(raw_vk.curve.as_deref())? != Curve::Bn254 {
+        return Err(VkParseError::InvalidData(
+            "Only BN254 keys are supported in CodegenMode::Legacy, use CodegenMode::Deterministic"
+                .to_string(),
+        ));
+    }
 
     let mut output = String::new();
 
     // Header
-    output.push_str("use groth16_solana::groth16::Groth16Verifyingkey;\n\n");
+    output.push_str("use pinocchio_groth16::groth16::Groth16Verifyingkey;\n\n");
     output.push_str(&format!(
         "pub const VERIFYINGKEY: Groth16Verifyingkey = Groth16Verifyingkey {{\n\tnr_pubinputs: {},\n\n",
         raw_vk.ic.len() - 1
@@ -140,7 +214,7 @@ pub fn parse_vk_json_to_rust_string(json_content: &str) -> Result<String, VkPars
     output.push_str("\tvk_alpha_g1: [");
     let mut alpha_bytes = Vec::new();
     for i in 0..raw_vk.vk_alpha_1.len() - 1 {
-        let bytes = process_g1_component(&raw_vk.vk_alpha_1[i])?;
+        let bytes = process_g1_component(&raw_vk.vk_alpha_1[i], 32)?;
         alpha_bytes.extend_from_slice(&bytes);
     }
     output.push_str(
@@ -156,7 +230,7 @@ pub fn parse_vk_json_to_rust_string(json_content: &str) -> Result<String, VkPars
     output.push_str("\tvk_beta_g2: [");
     let mut beta_bytes = Vec::new();
     for i in 0..raw_vk.vk_beta_2.len() - 1 {
-        let (part0, part1) = process_g2_component(&raw_vk.vk_beta_2[i])?;
+        let (part0, part1) = process_g2_component(&raw_vk.vk_beta_2[i], 32)?;
         beta_bytes.extend_from_slice(&part0);
         beta_bytes.extend_from_slice(&part1);
     }
@@ -173,7 +247,7 @@ pub fn parse_vk_json_to_rust_string(json_content: &str) -> Result<String, VkPars
     output.push_str("\tvk_gamma_g2: [");
     let mut gamma_bytes = Vec::new();
     for i in 0..raw_vk.vk_gamma_2.len() - 1 {
-        let (part0, part1) = process_g2_component(&raw_vk.vk_gamma_2[i])?;
+        let (part0, part1) = process_g2_component(&raw_vk.vk_gamma_2[i], 32)?;
         gamma_bytes.extend_from_slice(&part0);
         gamma_bytes.extend_from_slice(&part1);
     }
@@ -190,7 +264,7 @@ pub fn parse_vk_json_to_rust_string(json_content: &str) -> Result<String, VkPars
     output.push_str("\tvk_delta_g2: [");
     let mut delta_bytes = Vec::new();
     for i in 0..raw_vk.vk_delta_2.len() - 1 {
-        let (part0, part1) = process_g2_component(&raw_vk.vk_delta_2[i])?;
+        let (part0, part1) = process_g2_component(&raw_vk.vk_delta_2[i], 32)?;
         delta_bytes.extend_from_slice(&part0);
         delta_bytes.extend_from_slice(&part1);
     }
@@ -209,7 +283,7 @@ pub fn parse_vk_json_to_rust_string(json_content: &str) -> Result<String, VkPars
         output.push_str("\t\t[");
         let mut point_bytes = Vec::new();
         for i in 0..point.len() - 1 {
-            let bytes = process_g1_component(&point[i])?;
+            let bytes = process_g1_component(&point[i], 32)?;
             point_bytes.extend_from_slice(&bytes);
         }
         output.push_str(
@@ -228,6 +302,749 @@ pub fn parse_vk_json_to_rust_string(json_content: &str) -> Result<String, VkPars
     Ok(output)
 }
 
+/// Layout of the generated Rust source
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CodegenMode {
+    /// Tab indented byte lists, as generated by previous releases
+    #[default]
+    Legacy,
+    /// Byte-for-byte reproducible output for golden files and reproducible builds
+    ///
+    /// Bytes are written as fixed-width hex, 16 per line, and the constant is
+    /// marked `#[rustfmt::skip]` so formatting the generated file changes
+    /// nothing. Keys that differ only in JSON formatting, key order or leading
+    /// zeros produce the same source. Coordinates are checked to be in projective
+    /// form with `z == 1` instead of being truncated.
+    ///
+    /// Keys with `"curve": "bls12381"` are only supported in this mode and
+    /// generate a `bls12_381::Bls12Verifyingkey`, which needs the `bls12-381`
+    /// feature. snarkjs PLONK keys, `"protocol": "plonk"`, generate a
+    /// `plonk::PlonkVerifyingkey` with the `plonk` feature.
+    Deterministic,
+}
+
+/// Parse verification key JSON and generate Rust source code in `mode`
+///
+/// # Arguments
+///
+/// * `json_content` - The JSON content as a string
+/// * `mode` - Layout of the generated source
+///
+/// # Returns
+///
+/// A String containing the generated Rust code defining a `Groth16Verifyingkey` constant
+pub fn parse_vk_json_to_rust_string_with_mode(
+    json_content: &str,
+    mode: CodegenMode,
+) -> Result<String, VkParseError> {
+    match mode {
+        CodegenMode::Legacy => parse_vk_json_to_rust_string(json_content),
+        CodegenMode::Deterministic => deterministic_rust_string(json_content),
+    }
+}
+
+/// Parse verification key JSON in the convention of the prover that produced it
+///
+/// With [`ProverConvention::RawA`], keys are gnark's: in the layout of snarkjs
+/// keys, but with the G2 coordinates ordered `[c1, c0]` as gnark serializes
+/// them, and with beta, gamma and delta negated as in gnark's verifier
+/// contract, which takes a as proven. They are converted to the snarkjs form
+/// the verifier takes, so gnark proofs verify with
+/// [`Groth16Verifier::with_convention`].
+///
+/// # Arguments
+///
+/// * `json_content` - The JSON content as a string
+/// * `mode` - Layout of the generated source
+/// * `convention` - Convention of the key
+///
+/// # Returns
+///
+/// A String containing the generated Rust code defining a `Groth16Verifyingkey` constant.
+/// Gnark keys are only supported for BN254 in [`CodegenMode::Deterministic`].
+///
+/// [`Groth16Verifier::with_convention`]: crate::groth16::Groth16Verifier::with_convention
+pub fn parse_vk_json_to_rust_string_with_convention(
+    json_content: &str,
+    mode: CodegenMode,
+    convention: ProverConvention,
+) -> Result<String, VkParseError> {
+    match (mode, convention) {
+        (_, ProverConvention::NegatedA) => {
+            parse_vk_json_to_rust_string_with_mode(json_content, mode)
+        }
+        (CodegenMode::Legacy, ProverConvention::RawA) => Err(VkParseError::InvalidData(
+            "gnark keys are only supported in CodegenMode::Deterministic".to_string(),
+        )),
+        (CodegenMode::Deterministic, ProverConvention::RawA) => {
+            let mut value: serde_json::Value = serde_json::from_str(json_content)?;
+            let raw_vk: RawVerifyingKey = serde_json::from_value(value.clone())?;
+            if Curve::from_name(raw_vk.curve.as_deref())? != Curve::Bn254 {
+                return Err(VkParseError::InvalidData(
+                    "gnark keys are only supported for BN254".to_string(),
+                ));
+            }
+            for (field, point) in [
+                ("vk_beta_2", raw_vk.vk_beta_2),
+                ("vk_gamma_2", raw_vk.vk_gamma_2),
+                ("vk_delta_2", raw_vk.vk_delta_2),
+            ] {
+                value[field] = serde_json::to_value(gnark_g2_to_snarkjs(point)?)?;
+            }
+            deterministic_rust_string(&value.to_string())
+        }
+    }
+}
+
+/// Reorders the coordinates of a negated gnark G2 point to `[c0, c1]` and negates it back
+fn gnark_g2_to_snarkjs(mut point: Vec<Vec<String>>) -> Result<Vec<Vec<String>>, VkParseError> {
+    let modulus: BigUint = ark_bn254::Fq::MODULUS.into();
+    for coordinate in point.iter_mut() {
+        coordinate.reverse();
+    }
+    if point.len() > 1 {
+        for component in point[1].iter_mut() {
+            let value = component
+                .parse::<BigUint>()
+                .map_err(|e| VkParseError::InvalidData(format!("Failed to parse bigint: {}", e)))?;
+            if value >= modulus {
+                return Err(VkParseError::InvalidData(format!(
+                    "G2 coordinate {} is not smaller than the field modulus",
+                    value
+                )));
+            }
+            *component = ((&modulus - value) % &modulus).to_string();
+        }
+    }
+    Ok(point)
+}
+
+fn projective_g1(point: &[String], curve: Curve) -> Result<Vec<u8>, VkParseError> {
+    if point.len() != 3 || point[2].parse::<BigUint>().ok() != Some(BigUint::from(1u8)) {
+        return Err(VkParseError::InvalidData(format!(
+            "G1 point must be [x, y, \"1\"], got {:?}",
+            point
+        )));
+    }
+    let mut bytes = process_g1_component(&point[0], curve.field_size())?;
+    bytes.extend_from_slice(&process_g1_component(&point[1], curve.field_size())?);
+    Ok(bytes)
+}
+
+fn projective_g2(point: &[Vec<String>], curve: Curve) -> Result<Vec<u8>, VkParseError> {
+    let one = Some(BigUint::from(1u8));
+    let zero = Some(BigUint::from(0u8));
+    if point.len() != 3
+        || point[2].len() != 2
+        || point[2][0].parse::<BigUint>().ok() != one
+        || point[2][1].parse::<BigUint>().ok() != zero
+    {
+        return Err(VkParseError::InvalidData(format!(
+            "G2 point must be [x, y, [\"1\", \"0\"]], got {:?}",
+            point
+        )));
+    }
+    let mut bytes = Vec::with_capacity(4 * curve.field_size());
+    for coordinate in &point[..2] {
+        let (part0, part1) = process_g2_component(coordinate, curve.field_size())?;
+        bytes.extend_from_slice(&part0);
+        bytes.extend_from_slice(&part1);
+    }
+    Ok(bytes)
+}
+
+fn push_hex_bytes(output: &mut String, indent: &str, bytes: &[u8]) {
+    for line in bytes.chunks(16) {
+        output.push_str(indent);
+        let line: Vec<String> = line.iter().map(|b| format!("0x{:02x},", b)).collect();
+        output.push_str(&line.join(" "));
+        output.push('\n');
+    }
+}
+
+fn deterministic_rust_string(json_content: &str) -> Result<String, VkParseError> {
+    let value: serde_json::Value = serde_json::from_str(json_content)?;
+    if value.get("protocol").and_then(|protocol| protocol.as_str()) == Some("plonk") {
+        return plonk_rust_string(serde_json::from_value(value)?);
+    }
+    let raw_vk: RawVerifyingKey = serde_json::from_value(value)?;
+    let curve = Curve::from_name(raw_vk.curve.as_deref())?;
+    if raw_vk.ic.is_empty() {
+        return Err(VkParseError::InvalidData(
+            "IC must not be empty".to_string(),
+        ));
+    }
+
+    let ic = raw_vk
+        .ic
+        .iter()
+        .map(|point| projective_g1(point, curve))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(groth16_rust_string(
+        curve,
+        &projective_g1(&raw_vk.vk_alpha_1, curve)?,
+        &projective_g2(&raw_vk.vk_beta_2, curve)?,
+        &projective_g2(&raw_vk.vk_gamma_2, curve)?,
+        &projective_g2(&raw_vk.vk_delta_2, curve)?,
+        &ic,
+        false,
+    ))
+}
+
+/// Deterministic source of a Groth16 key from its encoded points
+fn groth16_rust_string(
+    curve: Curve,
+    alpha_g1: &[u8],
+    beta_g2: &[u8],
+    gamma_g2: &[u8],
+    delta_g2: &[u8],
+    ic: &[Vec<u8>],
+    compressed_ic: bool,
+) -> String {
+    let mut output = String::new();
+    output.push_str("// @generated by pinocchio-groth16 vk_parser, do not edit.\n\n");
+    let (path, name, sized) = match curve {
+        Curve::Bn254 if compressed_ic => ("compressed_ic", "CompressedIcVerifyingkey", None),
+        Curve::Bn254 => ("groth16", "Groth16Verifyingkey", Some("SizedVerifyingkey")),
+        Curve::Bls12_381 => (
+            "bls12_381",
+            "Bls12Verifyingkey",
+            Some("SizedBls12Verifyingkey"),
+        ),
+    };
+    match sized {
+        Some(sized) => output.push_str(&format!(
+            "use pinocchio_groth16::{}::{{{}, {}}};\n\n",
+            path, name, sized
+        )),
+        None => output.push_str(&format!("use pinocchio_groth16::{}::{};\n\n", path, name)),
+    }
+    output.push_str(&format!(
+        "pub const NR_PUBLIC_INPUTS: usize = {};\n\n",
+        ic.len() - 1
+    ));
+    output.push_str("#[rustfmt::skip]\n");
+    output.push_str(&format!("pub const VERIFYINGKEY: {name} = {name} {{\n"));
+    output.push_str(&format!("    nr_pubinputs: {},\n", ic.len() - 1));
+    for (name, bytes) in [
+        ("vk_alpha_g1", alpha_g1),
+        ("vk_beta_g2", beta_g2),
+        ("vk_gamma_g2", gamma_g2),
+        ("vk_delta_g2", delta_g2),
+    ] {
+        output.push_str(&format!("    {}: [\n", name));
+        push_hex_bytes(&mut output, "        ", bytes);
+        output.push_str("    ],\n");
+    }
+    output.push_str("    vk_ic: &[\n");
+    for point in ic {
+        output.push_str("        [\n");
+        push_hex_bytes(&mut output, "            ", point);
+        output.push_str("        ],\n");
+    }
+    output.push_str("    ],\n");
+    output.push_str("};\n");
+    if curve == Curve::Bn254 && !compressed_ic {
+        // Groth16Verifyingkey::fingerprint of the generated key
+        let mut points = vec![alpha_g1, beta_g2, gamma_g2, delta_g2];
+        points.extend(ic.iter().map(Vec::as_slice));
+        let fingerprint = keccak256(&points);
+        output.push_str(&format!(
+            "\npub const VERIFYINGKEY_FORMAT_VERSION: u32 = {};\n",
+            VK_BINARY_VERSION
+        ));
+        output.push_str("\n#[rustfmt::skip]\n");
+        output.push_str("pub const VERIFYINGKEY_FINGERPRINT: [u8; 32] = [\n");
+        push_hex_bytes(&mut output, "    ", &fingerprint);
+        output.push_str("];\n");
+    }
+    if let Some(sized) = sized {
+        // Wrapped as rustfmt does beyond 100 characters
+        let declaration = format!("pub const SIZED_VERIFYINGKEY: {sized}<NR_PUBLIC_INPUTS> =");
+        let value = format!("{sized}::new(VERIFYINGKEY);");
+        match declaration.len() + 1 + value.len() > 100 {
+            true => output.push_str(&format!("\n{declaration}\n    {value}\n")),
+            false => output.push_str(&format!("\n{declaration} {value}\n")),
+        }
+    }
+
+    output
+}
+
+/// Points of a BN254 verifying key, encoded as taken by the syscalls
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct EncodedVerifyingKey {
+    alpha_g1: Vec<u8>,
+    beta_g2: Vec<u8>,
+    gamma_g2: Vec<u8>,
+    delta_g2: Vec<u8>,
+    ic: Vec<Vec<u8>>,
+}
+
+impl EncodedVerifyingKey {
+    /// The binary encoding, see [`parse_vk_json_to_bytes`]
+    fn to_bytes(&self) -> Result<Vec<u8>, VkParseError> {
+        let invalid = || VkParseError::InvalidData("Invalid point size".to_string());
+        let ic = self
+            .ic
+            .iter()
+            .map(|point| <[u8; 64]>::try_from(point.as_slice()).map_err(|_| invalid()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let vk = crate::groth16::Groth16Verifyingkey {
+            nr_pubinputs: ic.len().saturating_sub(1),
+            vk_alpha_g1: self.alpha_g1.as_slice().try_into().map_err(|_| invalid())?,
+            vk_beta_g2: self.beta_g2.as_slice().try_into().map_err(|_| invalid())?,
+            vk_gamma_g2: self.gamma_g2.as_slice().try_into().map_err(|_| invalid())?,
+            vk_delta_g2: self.delta_g2.as_slice().try_into().map_err(|_| invalid())?,
+            vk_ic: &ic,
+        };
+        vk.to_bytes()
+            .map_err(|error| VkParseError::InvalidData(format!("{:?}", error)))
+    }
+
+    fn to_rust_string(&self) -> String {
+        groth16_rust_string(
+            Curve::Bn254,
+            &self.alpha_g1,
+            &self.beta_g2,
+            &self.gamma_g2,
+            &self.delta_g2,
+            &self.ic,
+            false,
+        )
+    }
+
+    /// Source of a [`CompressedIcVerifyingkey`](crate::compressed_ic::CompressedIcVerifyingkey)
+    fn to_compressed_ic_rust_string(&self) -> Result<String, VkParseError> {
+        let ic = self
+            .ic
+            .iter()
+            .map(|point| compress_g1(point))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(groth16_rust_string(
+            Curve::Bn254,
+            &self.alpha_g1,
+            &self.beta_g2,
+            &self.gamma_g2,
+            &self.delta_g2,
+            &ic,
+            true,
+        ))
+    }
+}
+
+/// Compresses a big-endian G1 point as taken by `alt_bn128_g1_decompress`
+///
+/// The x coordinate with the sign of y in the top bit, all zero for the
+/// point at infinity.
+fn compress_g1(point: &[u8]) -> Result<Vec<u8>, VkParseError> {
+    use ark_serialize::{CanonicalSerialize, Compress, Validate};
+
+    if point.iter().all(|byte| *byte == 0) {
+        return Ok(vec![0u8; 32]);
+    }
+    let mut le = point.to_vec();
+    le[..32].reverse();
+    le[32..].reverse();
+    let point = ark_bn254::G1Affine::deserialize_with_mode(&le[..], Compress::No, Validate::Yes)
+        .map_err(|_| VkParseError::InvalidData("IC point is not on the curve".to_string()))?;
+    let mut compressed = Vec::with_capacity(32);
+    point
+        .serialize_with_mode(&mut compressed, Compress::Yes)
+        .map_err(|_| VkParseError::InvalidData("Invalid IC point".to_string()))?;
+    compressed.reverse();
+    Ok(compressed)
+}
+
+/// Parse a BN254 verification key JSON and generate Rust source code of a
+/// [`CompressedIcVerifyingkey`](crate::compressed_ic::CompressedIcVerifyingkey)
+///
+/// IC points are stored compressed to 32 bytes and decompressed when the
+/// inputs are prepared, roughly halving keys of circuits with many inputs at
+/// the cost of a decompression per input. The layout is that of
+/// [`CodegenMode::Deterministic`].
+pub fn parse_vk_json_to_rust_string_with_compressed_ic(
+    json_content: &str,
+) -> Result<String, VkParseError> {
+    parse_snarkjs_vk(json_content)?.to_compressed_ic_rust_string()
+}
+
+/// Parse a BN254 verification key JSON into the binary encoding read by
+/// [`Groth16Verifyingkey::from_bytes`](crate::groth16::Groth16Verifyingkey::from_bytes)
+///
+/// The binary encoding is loaded at runtime, from an account or a file
+/// embedded with `include_bytes!`, instead of being compiled in.
+///
+/// # Arguments
+/// * `json_content` - JSON string of the verification key (snarkjs format)
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` - `VK_BINARY_MAGIC | version | packed key`
+/// * `Err(VkParseError)` - If the key is malformed or not over BN254
+pub fn parse_vk_json_to_bytes(json_content: &str) -> Result<Vec<u8>, VkParseError> {
+    parse_snarkjs_vk(json_content)?.to_bytes()
+}
+
+/// Parses a snarkjs verification key JSON over BN254
+fn parse_snarkjs_vk(json_content: &str) -> Result<EncodedVerifyingKey, VkParseError> {
+    let raw_vk: RawVerifyingKey = serde_json::from_str(json_content)?;
+    if Curve::from_name(raw_vk.curve.as_deref())? != Curve::Bn254 {
+        return Err(VkParseError::InvalidData(
+            "only BN254 keys are supported".to_string(),
+        ));
+    }
+    if raw_vk.ic.is_empty() {
+        return Err(VkParseError::InvalidData(
+            "IC must not be empty".to_string(),
+        ));
+    }
+
+    Ok(EncodedVerifyingKey {
+        alpha_g1: projective_g1(&raw_vk.vk_alpha_1, Curve::Bn254)?,
+        beta_g2: projective_g2(&raw_vk.vk_beta_2, Curve::Bn254)?,
+        gamma_g2: projective_g2(&raw_vk.vk_gamma_2, Curve::Bn254)?,
+        delta_g2: projective_g2(&raw_vk.vk_delta_2, Curve::Bn254)?,
+        ic: raw_vk
+            .ic
+            .iter()
+            .map(|point| projective_g1(point, Curve::Bn254))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Parse a verifying key serialized by arkworks into the binary encoding
+///
+/// The input is `ark_groth16::VerifyingKey<Bn254>` written with
+/// `CanonicalSerialize`, compressed or uncompressed. arkworks keys are used as
+/// computed by the setup, like snarkjs keys, with proof a negated.
+pub fn parse_arkworks_vk_to_bytes(bytes: &[u8]) -> Result<Vec<u8>, VkParseError> {
+    parse_arkworks_vk(bytes)?.to_bytes()
+}
+
+fn parse_arkworks_vk(bytes: &[u8]) -> Result<EncodedVerifyingKey, VkParseError> {
+    use ark_bn254::{G1Affine, G2Affine};
+    use ark_serialize::{Compress, Validate};
+
+    fn read(mut bytes: &[u8], compress: Compress) -> Option<EncodedVerifyingKey> {
+        let reader = &mut bytes;
+        let alpha = G1Affine::deserialize_with_mode(&mut *reader, compress, Validate::Yes).ok()?;
+        let mut g2 = || G2Affine::deserialize_with_mode(&mut *reader, compress, Validate::Yes);
+        let (beta, gamma, delta) = (g2().ok()?, g2().ok()?, g2().ok()?);
+        let ic =
+            Vec::<G1Affine>::deserialize_with_mode(&mut *reader, compress, Validate::Yes).ok()?;
+        if !reader.is_empty() {
+            return None;
+        }
+        Some(EncodedVerifyingKey {
+            alpha_g1: arkworks_g1(&alpha),
+            beta_g2: arkworks_g2(&beta),
+            gamma_g2: arkworks_g2(&gamma),
+            delta_g2: arkworks_g2(&delta),
+            ic: ic.iter().map(arkworks_g1).collect(),
+        })
+    }
+    let vk = read(bytes, Compress::No)
+        .or_else(|| read(bytes, Compress::Yes))
+        .ok_or_else(|| VkParseError::InvalidData("Invalid arkworks verifying key".to_string()))?;
+    if vk.ic.is_empty() {
+        return Err(VkParseError::InvalidData(
+            "gamma_abc_g1 must not be empty".to_string(),
+        ));
+    }
+    Ok(vk)
+}
+
+/// `x | y` big-endian, zero for the point at infinity
+fn arkworks_g1(point: &ark_bn254::G1Affine) -> Vec<u8> {
+    if point.infinity {
+        return vec![0u8; 64];
+    }
+    [
+        point.x.into_bigint().to_bytes_be(),
+        point.y.into_bigint().to_bytes_be(),
+    ]
+    .concat()
+}
+
+/// `x.c1 | x.c0 | y.c1 | y.c0` big-endian, zero for the point at infinity
+fn arkworks_g2(point: &ark_bn254::G2Affine) -> Vec<u8> {
+    if point.infinity {
+        return vec![0u8; 128];
+    }
+    [point.x.c1, point.x.c0, point.y.c1, point.y.c0]
+        .iter()
+        .flat_map(|coordinate| coordinate.into_bigint().to_bytes_be())
+        .collect()
+}
+
+/// Generate Rust source code from a binary verifying key
+///
+/// The inverse of [`parse_vk_json_to_bytes`], in [`CodegenMode::Deterministic`].
+pub fn binary_vk_to_rust_string(bytes: &[u8]) -> Result<String, VkParseError> {
+    let vk = crate::groth16::Groth16Verifyingkey::from_bytes(bytes)
+        .map_err(|_| VkParseError::InvalidData("Invalid binary verifying key".to_string()))?;
+    Ok(EncodedVerifyingKey {
+        alpha_g1: vk.vk_alpha_g1.to_vec(),
+        beta_g2: vk.vk_beta_g2.to_vec(),
+        gamma_g2: vk.vk_gamma_g2.to_vec(),
+        delta_g2: vk.vk_delta_g2.to_vec(),
+        ic: vk.vk_ic.iter().map(|point| point.to_vec()).collect(),
+    }
+    .to_rust_string())
+}
+
+/// Flags in the top bits of the first byte of gnark's point encodings
+const GNARK_FLAG_MASK: u8 = 0b11 << 6;
+const GNARK_COMPRESSED_SMALLEST: u8 = 0b10 << 6;
+const GNARK_COMPRESSED_LARGEST: u8 = 0b11 << 6;
+const GNARK_INFINITY: u8 = 0b01 << 6;
+
+/// Reads a point of `size` uncompressed bytes from `bytes` at `offset`
+///
+/// `WriteTo` compresses points to half their size, flagging which of the two
+/// y is meant, `WriteRawTo` writes them uncompressed. Both flag the identity.
+fn read_gnark_point(
+    bytes: &[u8],
+    offset: &mut usize,
+    size: usize,
+    compressed: bool,
+) -> Result<Vec<u8>, VkParseError> {
+    let len = if compressed { size / 2 } else { size };
+    let mut point = bytes
+        .get(*offset..*offset + len)
+        .ok_or_else(|| VkParseError::InvalidData("gnark key is truncated".to_string()))?
+        .to_vec();
+    *offset += len;
+    let flag = point[0] & GNARK_FLAG_MASK;
+    point[0] &= !GNARK_FLAG_MASK;
+    if flag == GNARK_INFINITY {
+        if point.iter().any(|byte| *byte != 0) {
+            return Err(VkParseError::InvalidData(
+                "gnark point at infinity has nonzero coordinates".to_string(),
+            ));
+        }
+        return Ok(vec![0u8; size]);
+    }
+    let invalid = || VkParseError::InvalidData("Invalid gnark point".to_string());
+    if !compressed {
+        return if flag == 0 { Ok(point) } else { Err(invalid()) };
+    }
+    let largest = match flag {
+        GNARK_COMPRESSED_SMALLEST => false,
+        GNARK_COMPRESSED_LARGEST => true,
+        _ => return Err(invalid()),
+    };
+    let mut le = point.clone();
+    le.reverse();
+    let decompressed = if size == 64 {
+        let x = ark_bn254::Fq::from_bigint(
+            ark_ff::BigInt::deserialize_uncompressed(&le[..]).map_err(|_| invalid())?,
+        )
+        .ok_or_else(invalid)?;
+        let point =
+            ark_bn254::G1Affine::get_point_from_x_unchecked(x, largest).ok_or_else(invalid)?;
+        [
+            point.x.into_bigint().to_bytes_be(),
+            point.y.into_bigint().to_bytes_be(),
+        ]
+        .concat()
+    } else {
+        // c1 | c0, reversed to c0 | c1 little-endian
+        let x = ark_bn254::Fq2::deserialize_uncompressed(&le[..]).map_err(|_| invalid())?;
+        let point =
+            ark_bn254::G2Affine::get_point_from_x_unchecked(x, largest).ok_or_else(invalid)?;
+        if !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(invalid());
+        }
+        [point.x.c1, point.x.c0, point.y.c1, point.y.c0]
+            .iter()
+            .flat_map(|coordinate| coordinate.into_bigint().to_bytes_be())
+            .collect()
+    };
+    Ok(decompressed)
+}
+
+/// Parses gnark's binary verifying key, written by `WriteTo` or `WriteRawTo`
+///
+/// Layout: `alpha_g1 | beta_g1 | beta_g2 | gamma_g2 | delta_g1 | delta_g2 |
+/// nr_k u32 BE | k`, followed by the commitment data of newer versions.
+fn parse_gnark_vk_bytes(bytes: &[u8]) -> Result<EncodedVerifyingKey, VkParseError> {
+    let first = *bytes
+        .first()
+        .ok_or_else(|| VkParseError::InvalidData("gnark key is empty".to_string()))?;
+    // alpha is never the identity, its flags tell the encoding of all points
+    let compressed = first & GNARK_COMPRESSED_SMALLEST != 0;
+    let mut offset = 0;
+    let alpha_g1 = read_gnark_point(bytes, &mut offset, 64, compressed)?;
+    read_gnark_point(bytes, &mut offset, 64, compressed)?;
+    let beta_g2 = read_gnark_point(bytes, &mut offset, 128, compressed)?;
+    let gamma_g2 = read_gnark_point(bytes, &mut offset, 128, compressed)?;
+    read_gnark_point(bytes, &mut offset, 64, compressed)?;
+    let delta_g2 = read_gnark_point(bytes, &mut offset, 128, compressed)?;
+    let nr_k = bytes
+        .get(offset..offset + 4)
+        .map(|len| u32::from_be_bytes(len.try_into().unwrap()) as usize)
+        .ok_or_else(|| VkParseError::InvalidData("gnark key is truncated".to_string()))?;
+    offset += 4;
+    let ic = (0..nr_k)
+        .map(|_| read_gnark_point(bytes, &mut offset, 64, compressed))
+        .collect::<Result<Vec<_>, _>>()?;
+    // Newer versions append the committed wires and the commitment keys,
+    // both empty for circuits without commitments
+    if bytes[offset..].iter().any(|byte| *byte != 0) {
+        return Err(VkParseError::InvalidData(
+            "gnark keys with commitments are not supported".to_string(),
+        ));
+    }
+    Ok(EncodedVerifyingKey {
+        alpha_g1,
+        beta_g2,
+        gamma_g2,
+        delta_g2,
+        ic,
+    })
+}
+
+/// A base field element of gnark's JSON export, a decimal number or string
+fn gnark_json_field(value: &serde_json::Value) -> Result<Vec<u8>, VkParseError> {
+    let digits = match value {
+        serde_json::Value::String(digits) => digits.clone(),
+        serde_json::Value::Number(number) if number.is_u64() => number.to_string(),
+        _ => {
+            return Err(VkParseError::InvalidData(format!(
+                "Invalid gnark field element {}",
+                value
+            )))
+        }
+    };
+    bigint_string_to_be_bytes(&digits, 32)
+}
+
+fn gnark_json_g1(point: &serde_json::Value) -> Result<Vec<u8>, VkParseError> {
+    Ok([
+        gnark_json_field(&point["X"])?,
+        gnark_json_field(&point["Y"])?,
+    ]
+    .concat())
+}
+
+fn gnark_json_g2(point: &serde_json::Value) -> Result<Vec<u8>, VkParseError> {
+    let mut bytes = Vec::with_capacity(128);
+    for coordinate in [&point["X"], &point["Y"]] {
+        bytes.extend(gnark_json_field(&coordinate["A1"])?);
+        bytes.extend(gnark_json_field(&coordinate["A0"])?);
+    }
+    Ok(bytes)
+}
+
+/// Parses gnark's verifying key as marshalled by `encoding/json`
+fn parse_gnark_vk_json(json_content: &str) -> Result<EncodedVerifyingKey, VkParseError> {
+    let value: serde_json::Value = serde_json::from_str(json_content)?;
+    let has_commitments = |field: &str| value[field].as_array().is_some_and(|a| !a.is_empty());
+    if has_commitments("CommitmentKeys") || has_commitments("PublicAndCommitmentCommitted") {
+        return Err(VkParseError::InvalidData(
+            "gnark keys with commitments are not supported".to_string(),
+        ));
+    }
+    let (g1, g2) = (&value["G1"], &value["G2"]);
+    Ok(EncodedVerifyingKey {
+        alpha_g1: gnark_json_g1(&g1["Alpha"])?,
+        beta_g2: gnark_json_g2(&g2["Beta"])?,
+        gamma_g2: gnark_json_g2(&g2["Gamma"])?,
+        delta_g2: gnark_json_g2(&g2["Delta"])?,
+        ic: g1["K"]
+            .as_array()
+            .ok_or_else(|| VkParseError::InvalidData("gnark key has no K".to_string()))?
+            .iter()
+            .map(gnark_json_g1)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Parse a BN254 verifying key exported by gnark and generate Rust source code
+///
+/// Accepts the JSON export of gnark's `VerifyingKey` and its binary
+/// serialization, compressed as written by `WriteTo` or uncompressed as written
+/// by `WriteRawTo`. gnark keeps beta, gamma and delta as computed by the setup
+/// and orders the IC points, `K`, as the public inputs, so the key only needs
+/// re-encoding: G2 coordinates are ordered `c1 | c0` for the syscalls. Proofs
+/// are verified with [`ProverConvention::RawA`]. Keys of circuits using
+/// gnark's commitments are rejected.
+///
+/// # Arguments
+///
+/// * `content` - The JSON or binary export
+///
+/// # Returns
+///
+/// The generated source in [`CodegenMode::Deterministic`]
+pub fn parse_gnark_vk_to_rust_string(content: &[u8]) -> Result<String, VkParseError> {
+    Ok(parse_gnark_vk(content)?.to_rust_string())
+}
+
+fn parse_gnark_vk(content: &[u8]) -> Result<EncodedVerifyingKey, VkParseError> {
+    let is_json = content
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .is_some_and(|byte| *byte == b'{');
+    let vk = if is_json {
+        let json_content = std::str::from_utf8(content)
+            .map_err(|e| VkParseError::InvalidData(format!("Invalid UTF-8: {}", e)))?;
+        parse_gnark_vk_json(json_content)?
+    } else {
+        parse_gnark_vk_bytes(content)?
+    };
+    if vk.ic.is_empty() {
+        return Err(VkParseError::InvalidData("K must not be empty".to_string()));
+    }
+    Ok(vk)
+}
+
+/// Parse a BN254 verifying key exported by gnark into the binary encoding,
+/// see [`parse_gnark_vk_to_rust_string`] and [`parse_vk_json_to_bytes`]
+pub fn parse_gnark_vk_to_bytes(content: &[u8]) -> Result<Vec<u8>, VkParseError> {
+    parse_gnark_vk(content)?.to_bytes()
+}
+
+/// Commitments to zero polynomials, e.g. `Qc`, are the point at infinity `[0, 1, 0]`
+fn plonk_g1(point: &[String]) -> Result<Vec<u8>, VkParseError> {
+    if point.len() == 3 && point[2].parse::<BigUint>().ok() == Some(BigUint::from(0u8)) {
+        return Ok(vec![0u8; 64]);
+    }
+    projective_g1(point, Curve::Bn254)
+}
+
+fn plonk_rust_string(raw_vk: RawPlonkVerifyingKey) -> Result<String, VkParseError> {
+    let mut output = String::new();
+    output.push_str("// @generated by pinocchio-groth16 vk_parser, do not edit.\n\n");
+    output.push_str("use pinocchio_groth16::plonk::PlonkVerifyingkey;\n\n");
+    output.push_str("#[rustfmt::skip]\n");
+    output.push_str("pub const VERIFYINGKEY: PlonkVerifyingkey = PlonkVerifyingkey {\n");
+    output.push_str(&format!("    nr_pubinputs: {},\n", raw_vk.n_public));
+    output.push_str(&format!("    power: {},\n", raw_vk.power));
+    for (name, bytes) in [
+        ("k1", bigint_string_to_be_bytes(&raw_vk.k1, 32)?),
+        ("k2", bigint_string_to_be_bytes(&raw_vk.k2, 32)?),
+        ("omega", bigint_string_to_be_bytes(&raw_vk.w, 32)?),
+        ("qm", plonk_g1(&raw_vk.qm)?),
+        ("ql", plonk_g1(&raw_vk.ql)?),
+        ("qr", plonk_g1(&raw_vk.qr)?),
+        ("qo", plonk_g1(&raw_vk.qo)?),
+        ("qc", plonk_g1(&raw_vk.qc)?),
+        ("s1", plonk_g1(&raw_vk.s1)?),
+        ("s2", plonk_g1(&raw_vk.s2)?),
+        ("s3", plonk_g1(&raw_vk.s3)?),
+        ("x_2", projective_g2(&raw_vk.x_2, Curve::Bn254)?),
+    ] {
+        output.push_str(&format!("    {}: [\n", name));
+        push_hex_bytes(&mut output, "        ", &bytes);
+        output.push_str("    ],\n");
+    }
+    output.push_str("};\n");
+
+    Ok(output)
+}
+
 /// Generate a verification key Rust file from a JSON file
 ///
 /// This is a convenience wrapper that reads the JSON file, parses it,
@@ -243,7 +1060,7 @@ pub fn parse_vk_json_to_rust_string(json_content: &str) -> Result<String, VkPars
 ///
 /// ```rust,ignore
 /// // In build.rs
-/// use groth16_solana::vk_parser::generate_vk_file;
+/// use pinocchio_groth16::vk_parser::generate_vk_file;
 ///
 /// fn main() {
 ///     generate_vk_file(
@@ -273,3 +1090,504 @@ pub fn generate_vk_file(
 
     Ok(())
 }
+
+/// [`generate_vk_file`] with the layout of `mode`
+///
+/// In [`CodegenMode::Deterministic`] an existing output file with the same
+/// content is left untouched, so its modification time does not trigger rebuilds.
+pub fn generate_vk_file_with_mode(
+    json_path: impl AsRef<Path>,
+    output_dir: impl AsRef<Path>,
+    output_filename: &str,
+    mode: CodegenMode,
+) -> Result<(), VkParseError> {
+    let json_content = fs::read_to_string(json_path.as_ref())?;
+    let rust_code = parse_vk_json_to_rust_string_with_mode(&json_content, mode)?;
+
+    fs::create_dir_all(output_dir.as_ref())?;
+    let output_path = output_dir.as_ref().join(output_filename);
+    if mode == CodegenMode::Deterministic
+        && fs::read_to_string(&output_path).ok().as_deref() == Some(rust_code.as_str())
+    {
+        return Ok(());
+    }
+    fs::write(output_path, rust_code)?;
+
+    Ok(())
+}
+
+/// [`generate_vk_file_with_mode`] for a key in `convention`, see
+/// [`parse_vk_json_to_rust_string_with_convention`]
+pub fn generate_vk_file_with_convention(
+    json_path: impl AsRef<Path>,
+    output_dir: impl AsRef<Path>,
+    output_filename: &str,
+    mode: CodegenMode,
+    convention: ProverConvention,
+) -> Result<(), VkParseError> {
+    let json_content = fs::read_to_string(json_path.as_ref())?;
+    let rust_code = parse_vk_json_to_rust_string_with_convention(&json_content, mode, convention)?;
+
+    fs::create_dir_all(output_dir.as_ref())?;
+    let output_path = output_dir.as_ref().join(output_filename);
+    if mode == CodegenMode::Deterministic
+        && fs::read_to_string(&output_path).ok().as_deref() == Some(rust_code.as_str())
+    {
+        return Ok(());
+    }
+    fs::write(output_path, rust_code)?;
+
+    Ok(())
+}
+
+/// Generate a verification key Rust file from a gnark export, see
+/// [`parse_gnark_vk_to_rust_string`]
+///
+/// An existing output file with the same content is left untouched.
+pub fn generate_vk_file_from_gnark(
+    vk_path: impl AsRef<Path>,
+    output_dir: impl AsRef<Path>,
+    output_filename: &str,
+) -> Result<(), VkParseError> {
+    let content = fs::read(vk_path.as_ref())?;
+    let rust_code = parse_gnark_vk_to_rust_string(&content)?;
+
+    fs::create_dir_all(output_dir.as_ref())?;
+    let output_path = output_dir.as_ref().join(output_filename);
+    if fs::read_to_string(&output_path).ok().as_deref() == Some(rust_code.as_str()) {
+        return Ok(());
+    }
+    fs::write(output_path, rust_code)?;
+
+    Ok(())
+}
+
+/// Generate a binary verification key file, see [`parse_vk_json_to_bytes`]
+///
+/// An existing output file with the same content is left untouched.
+pub fn generate_vk_binary_file(
+    json_path: impl AsRef<Path>,
+    output_dir: impl AsRef<Path>,
+    output_filename: &str,
+) -> Result<(), VkParseError> {
+    let json_content = fs::read_to_string(json_path.as_ref())?;
+    let bytes = parse_vk_json_to_bytes(&json_content)?;
+
+    fs::create_dir_all(output_dir.as_ref())?;
+    let output_path = output_dir.as_ref().join(output_filename);
+    if fs::read(&output_path).ok().as_deref() == Some(bytes.as_slice()) {
+        return Ok(());
+    }
+    fs::write(output_path, bytes)?;
+
+    Ok(())
+}
+
+/// Generate a Rust file of a key with compressed IC points, see
+/// [`parse_vk_json_to_rust_string_with_compressed_ic`]
+///
+/// An existing output file with the same content is left untouched.
+pub fn generate_vk_file_with_compressed_ic(
+    json_path: impl AsRef<Path>,
+    output_dir: impl AsRef<Path>,
+    output_filename: &str,
+) -> Result<(), VkParseError> {
+    let json_content = fs::read_to_string(json_path.as_ref())?;
+    let rust_code = parse_vk_json_to_rust_string_with_compressed_ic(&json_content)?;
+
+    fs::create_dir_all(output_dir.as_ref())?;
+    let output_path = output_dir.as_ref().join(output_filename);
+    if fs::read_to_string(&output_path).ok().as_deref() == Some(rust_code.as_str()) {
+        return Ok(());
+    }
+    fs::write(output_path, rust_code)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VK_JSON: &str = r#"{
+        "protocol": "groth16",
+        "curve": "bn128",
+        "nPublic": 1,
+        "vk_alpha_1": ["1", "2", "1"],
+        "vk_beta_2": [["3", "4"], ["5", "6"], ["1", "0"]],
+        "vk_gamma_2": [["7", "8"], ["9", "10"], ["1", "0"]],
+        "vk_delta_2": [["11", "12"], ["13", "14"], ["1", "0"]],
+        "IC": [["15", "16", "1"], ["17", "18", "1"]]
+    }"#;
+
+    #[test]
+    fn deterministic_output_is_stable() {
+        let output =
+            parse_vk_json_to_rust_string_with_mode(VK_JSON, CodegenMode::Deterministic).unwrap();
+        // Reordered keys, other whitespace and leading zeros
+        let reformatted = r#"{"IC":[["015","16","1"],["17","18","01"]],"vk_delta_2":[["11","12"],["13","14"],["1","0"]],
+            "vk_gamma_2":[["7","8"],["9","10"],["1","0"]],"vk_beta_2":[["3","4"],["5","6"],["1","0"]],"vk_alpha_1":["1","2","1"]}"#;
+        assert_eq!(
+            parse_vk_json_to_rust_string_with_mode(reformatted, CodegenMode::Deterministic)
+                .unwrap(),
+            output
+        );
+
+        assert!(output.starts_with("// @generated"));
+        assert!(output.contains("#[rustfmt::skip]\n"));
+        assert!(output.contains("    nr_pubinputs: 1,\n"));
+        // alpha x = 1 is the last byte of the first 32, alpha y = 2 the last of the second
+        let zeros = ["0x00,"; 16].join(" ");
+        let low_byte = |byte: &str| format!("{} {}", ["0x00,"; 15].join(" "), byte);
+        let alpha = format!(
+            "    vk_alpha_g1: [\n        {zeros}\n        {}\n        {zeros}\n        {}\n",
+            low_byte("0x01,"),
+            low_byte("0x02,")
+        );
+        assert!(output.contains(&alpha));
+        assert!(!output.contains('\t'));
+        assert_eq!(output.matches("        [\n").count(), 2);
+
+        // The fingerprint of the generated key
+        let bytes = parse_vk_json_to_bytes(VK_JSON).unwrap();
+        let fingerprint = crate::groth16::Groth16Verifyingkey::from_bytes(&bytes)
+            .unwrap()
+            .fingerprint();
+        let mut expected = String::from("pub const VERIFYINGKEY_FINGERPRINT: [u8; 32] = [\n");
+        push_hex_bytes(&mut expected, "    ", &fingerprint);
+        assert!(output.contains(&expected));
+        assert!(output.contains("pub const VERIFYINGKEY_FORMAT_VERSION: u32 = 1;\n"));
+
+        // The legacy layout is unchanged
+        assert_eq!(
+            parse_vk_json_to_rust_string_with_mode(VK_JSON, CodegenMode::Legacy).unwrap(),
+            parse_vk_json_to_rust_string(VK_JSON).unwrap()
+        );
+    }
+
+    #[test]
+    fn deterministic_mode_rejects_malformed_points() {
+        for json in [
+            VK_JSON.replace(
+                r#""IC": [["15", "16", "1"], ["17", "18", "1"]]"#,
+                r#""IC": []"#,
+            ),
+            VK_JSON.replace(r#"["1", "2", "1"]"#, r#"["1", "2"]"#),
+            VK_JSON.replace(r#"["13", "14"], ["1", "0"]"#, r#"["13", "14"], ["0", "1"]"#),
+        ] {
+            assert!(matches!(
+                parse_vk_json_to_rust_string_with_mode(&json, CodegenMode::Deterministic),
+                Err(VkParseError::InvalidData(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn binary_key_roundtrip() {
+        use crate::errors::Groth16Error;
+        use crate::groth16::{Groth16Verifyingkey, VK_BINARY_MAGIC};
+
+        let bytes = parse_vk_json_to_bytes(VK_JSON).unwrap();
+        assert_eq!(bytes.len(), Groth16Verifyingkey::binary_size(1));
+        assert_eq!(bytes[..4], VK_BINARY_MAGIC);
+        let vk = Groth16Verifyingkey::from_bytes(&bytes).unwrap();
+        assert_eq!(vk.nr_pubinputs, 1);
+        assert_eq!((vk.vk_alpha_g1[31], vk.vk_alpha_g1[63]), (1, 2));
+        assert_eq!(vk.vk_ic.len(), 2);
+        assert_eq!(vk.to_bytes().unwrap(), bytes);
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] ^= 1;
+        assert_eq!(
+            Groth16Verifyingkey::from_bytes(&bad_magic),
+            Err(Groth16Error::InvalidAccountData)
+        );
+        assert!(matches!(
+            parse_vk_json_to_bytes(&VK_JSON.replace("bn128", "bls12381")),
+            Err(VkParseError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn compressed_ic_keys() {
+        // The generator, x = 1 and y = 2, compresses to x without flags
+        let json = VK_JSON.replace(
+            r#""IC": [["15", "16", "1"], ["17", "18", "1"]]"#,
+            r#""IC": [["1", "2", "1"], ["1", "2", "1"]]"#,
+        );
+        let output = parse_vk_json_to_rust_string_with_compressed_ic(&json).unwrap();
+        assert!(
+            output.contains("use pinocchio_groth16::compressed_ic::CompressedIcVerifyingkey;\n")
+        );
+        assert!(output.contains("    nr_pubinputs: 1,\n"));
+        let zeros = ["0x00,"; 16].join(" ");
+        let generator = format!(
+            "        [\n            {zeros}\n            {} 0x01,\n        ],\n",
+            ["0x00,"; 15].join(" ")
+        );
+        assert_eq!(output.matches(&generator).count(), 2);
+
+        // IC points off the curve
+        assert!(matches!(
+            parse_vk_json_to_rust_string_with_compressed_ic(VK_JSON),
+            Err(VkParseError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn arkworks_keys() {
+        use ark_bn254::{G1Affine, G2Affine};
+        use ark_ec::AffineRepr;
+        use ark_serialize::{CanonicalSerialize, Compress};
+
+        let (g1, g2) = (G1Affine::generator(), G2Affine::generator());
+        let ic = vec![g1, G1Affine::zero()];
+        for compress in [Compress::No, Compress::Yes] {
+            let mut serialized = Vec::new();
+            g1.serialize_with_mode(&mut serialized, compress).unwrap();
+            for _ in 0..3 {
+                g2.serialize_with_mode(&mut serialized, compress).unwrap();
+            }
+            ic.serialize_with_mode(&mut serialized, compress).unwrap();
+
+            let bytes = parse_arkworks_vk_to_bytes(&serialized).unwrap();
+            let vk = crate::groth16::Groth16Verifyingkey::from_bytes(&bytes).unwrap();
+            assert_eq!(vk.nr_pubinputs, 1);
+            assert_eq!(vk.vk_alpha_g1.to_vec(), arkworks_g1(&g1));
+            assert_eq!(vk.vk_delta_g2.to_vec(), arkworks_g2(&g2));
+            assert_eq!(vk.vk_ic[1], [0u8; 64]);
+            assert!(binary_vk_to_rust_string(&bytes)
+                .unwrap()
+                .contains("    nr_pubinputs: 1,\n"));
+
+            serialized.push(0);
+            assert!(matches!(
+                parse_arkworks_vk_to_bytes(&serialized),
+                Err(VkParseError::InvalidData(_))
+            ));
+        }
+        // x = 1, y = 2 of the generator
+        assert_eq!(arkworks_g1(&g1)[31], 1);
+        assert_eq!(arkworks_g1(&g1)[63], 2);
+    }
+
+    #[test]
+    fn plonk_keys() {
+        let json = r#"{
+            "protocol": "plonk",
+            "curve": "bn128",
+            "nPublic": 1,
+            "power": 3,
+            "k1": "2",
+            "k2": "3",
+            "Qm": ["1", "2", "1"],
+            "Ql": ["1", "2", "1"],
+            "Qr": ["1", "2", "1"],
+            "Qo": ["1", "2", "1"],
+            "Qc": ["0", "1", "0"],
+            "S1": ["1", "2", "1"],
+            "S2": ["1", "2", "1"],
+            "S3": ["1", "2", "1"],
+            "X_2": [["3", "4"], ["5", "6"], ["1", "0"]],
+            "w": "19540430494807482326159819597004422086093766032135589407132600596362845576832"
+        }"#;
+        let output =
+            parse_vk_json_to_rust_string_with_mode(json, CodegenMode::Deterministic).unwrap();
+        assert!(
+            output.contains("pub const VERIFYINGKEY: PlonkVerifyingkey = PlonkVerifyingkey {\n")
+        );
+        assert!(output.contains("    nr_pubinputs: 1,\n    power: 3,\n    k1: [\n"));
+        let zeros = ["0x00,"; 16].join(" ");
+        assert!(output.contains(&format!(
+            "    qc: [\n{}    ],\n",
+            format!("        {zeros}\n").repeat(4)
+        )));
+        assert!(output.contains("    x_2: [\n"));
+        assert!(output.contains("    omega: [\n        0x2b, 0x33, 0x7d, 0xe1,"));
+    }
+
+    #[test]
+    fn bls12_381_keys_use_48_byte_coordinates() {
+        let json = VK_JSON.replace("bn128", "bls12381");
+        let output =
+            parse_vk_json_to_rust_string_with_mode(&json, CodegenMode::Deterministic).unwrap();
+        assert!(output.contains(
+            "use pinocchio_groth16::bls12_381::{Bls12Verifyingkey, SizedBls12Verifyingkey};\n"
+        ));
+        assert!(
+            output.contains("pub const VERIFYINGKEY: Bls12Verifyingkey = Bls12Verifyingkey {\n")
+        );
+        // 96 bytes per G1 point, 192 per G2 point
+        let lines = |name: &str| {
+            output
+                .split(&format!("    {name}: [\n"))
+                .nth(1)
+                .unwrap()
+                .lines()
+                .take_while(|line| *line != "    ],")
+                .count()
+        };
+        assert_eq!(lines("vk_alpha_g1"), 6);
+        assert_eq!(lines("vk_beta_g2"), 12);
+
+        assert!(matches!(
+            parse_vk_json_to_rust_string(&json),
+            Err(VkParseError::InvalidData(_))
+        ));
+        assert!(matches!(
+            parse_vk_json_to_rust_string_with_mode(
+                &VK_JSON.replace("bn128", "bls12377"),
+                CodegenMode::Deterministic
+            ),
+            Err(VkParseError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn gnark_keys_are_converted_to_snarkjs_form() {
+        let p: BigUint = ark_bn254::Fq::MODULUS.into();
+        let neg = |v: u32| (&p - BigUint::from(v)).to_string();
+        let gnark_json = format!(
+            r#"{{
+            "vk_alpha_1": ["1", "2", "1"],
+            "vk_beta_2": [["4", "3"], ["{}", "{}"], ["0", "1"]],
+            "vk_gamma_2": [["8", "7"], ["{}", "{}"], ["0", "1"]],
+            "vk_delta_2": [["12", "11"], ["{}", "{}"], ["0", "1"]],
+            "IC": [["15", "16", "1"], ["17", "18", "1"]]
+        }}"#,
+            neg(6),
+            neg(5),
+            neg(10),
+            neg(9),
+            neg(14),
+            neg(13)
+        );
+        assert_eq!(
+            parse_vk_json_to_rust_string_with_convention(
+                &gnark_json,
+                CodegenMode::Deterministic,
+                ProverConvention::RawA
+            )
+            .unwrap(),
+            parse_vk_json_to_rust_string_with_mode(VK_JSON, CodegenMode::Deterministic).unwrap()
+        );
+        assert!(matches!(
+            parse_vk_json_to_rust_string_with_convention(
+                &gnark_json,
+                CodegenMode::Legacy,
+                ProverConvention::RawA
+            ),
+            Err(VkParseError::InvalidData(_))
+        ));
+    }
+
+    #[cfg(feature = "test-vectors")]
+    #[test]
+    fn gnark_exports_parse_to_the_same_key() {
+        use crate::mock::{g1_from_be, g2_from_be};
+
+        let vector = crate::test_vectors::generate(0);
+        let compress_g1 = |point: &[u8; 64]| {
+            let affine = g1_from_be(point).unwrap();
+            let mut x = point[..32].to_vec();
+            x[0] |= if affine.y > -affine.y {
+                0b11 << 6
+            } else {
+                0b10 << 6
+            };
+            x
+        };
+        let compress_g2 = |point: &[u8; 128]| {
+            let affine = g2_from_be(point).unwrap();
+            let mut x = point[..64].to_vec();
+            x[0] |= if affine.y > -affine.y {
+                0b11 << 6
+            } else {
+                0b10 << 6
+            };
+            x
+        };
+        // beta_g1 and delta_g1 are skipped, alpha stands in for them
+        let g1 = &vector.vk_alpha_g1;
+        let mut raw = [
+            &g1[..],
+            g1,
+            &vector.vk_beta_g2,
+            &vector.vk_gamma_g2,
+            g1,
+            &vector.vk_delta_g2,
+        ]
+        .concat();
+        let mut compressed = [
+            compress_g1(g1),
+            compress_g1(g1),
+            compress_g2(&vector.vk_beta_g2),
+            compress_g2(&vector.vk_gamma_g2),
+            compress_g1(g1),
+            compress_g2(&vector.vk_delta_g2),
+        ]
+        .concat();
+        for bytes in [&mut raw, &mut compressed] {
+            bytes.extend((vector.vk_ic.len() as u32).to_be_bytes());
+        }
+        for ic in &vector.vk_ic {
+            raw.extend_from_slice(ic);
+            compressed.extend(compress_g1(ic));
+        }
+        // No committed wires and no commitment keys
+        for bytes in [&mut raw, &mut compressed] {
+            bytes.extend([0u8; 8]);
+        }
+
+        let json_g1 = |point: &[u8]| {
+            let field = |bytes: &[u8]| BigUint::from_bytes_be(bytes).to_string();
+            serde_json::json!({ "X": field(&point[..32]), "Y": field(&point[32..64]) })
+        };
+        let json_g2 = |point: &[u8]| {
+            serde_json::json!({
+                "X": { "A0": json_g1(&point[..64])["Y"], "A1": json_g1(&point[..64])["X"] },
+                "Y": { "A0": json_g1(&point[64..])["Y"], "A1": json_g1(&point[64..])["X"] },
+            })
+        };
+        let json = serde_json::json!({
+            "G1": {
+                "Alpha": json_g1(&vector.vk_alpha_g1),
+                "Beta": json_g1(&vector.vk_alpha_g1),
+                "Delta": json_g1(&vector.vk_alpha_g1),
+                "K": vector.vk_ic.iter().map(|ic| json_g1(ic)).collect::<Vec<_>>(),
+            },
+            "G2": {
+                "Beta": json_g2(&vector.vk_beta_g2),
+                "Gamma": json_g2(&vector.vk_gamma_g2),
+                "Delta": json_g2(&vector.vk_delta_g2),
+            },
+            "CommitmentKeys": [],
+            "PublicAndCommitmentCommitted": [],
+        })
+        .to_string();
+
+        let expected = groth16_rust_string(
+            Curve::Bn254,
+            &vector.vk_alpha_g1,
+            &vector.vk_beta_g2,
+            &vector.vk_gamma_g2,
+            &vector.vk_delta_g2,
+            &vector
+                .vk_ic
+                .iter()
+                .map(|ic| ic.to_vec())
+                .collect::<Vec<_>>(),
+            false,
+        );
+        for export in [&raw, &compressed, json.as_bytes()] {
+            assert_eq!(parse_gnark_vk_to_rust_string(export).unwrap(), expected);
+        }
+
+        let mut with_commitments = raw.clone();
+        *with_commitments.last_mut().unwrap() = 1;
+        assert!(matches!(
+            parse_gnark_vk_to_rust_string(&with_commitments),
+            Err(VkParseError::InvalidData(_))
+        ));
+    }
+}
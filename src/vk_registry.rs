@@ -0,0 +1,353 @@
+//! Verifying key registry with merkle membership proofs
+//!
+//! Instead of one account per circuit, a registry account stores the merkle
+//! root over the hashes of all registered verifying keys. A proof is verified
+//! against a key passed in instruction data, in the packed encoding of
+//! [`Groth16Verifyingkey::from_packed_bytes`], together with a merkle proof
+//! that the key is a leaf of the registry. Registering a key only changes
+//! the root, so thousands of circuits share one account.
+//!
+//! The tree has a fixed depth, empty leaves are zero. Leaves and inner nodes
+//! are domain separated, `leaf = keccak256(0x00 | vk_hash(vk))` and
+//! `node = keccak256(0x01 | left | right)`, with
+//! [`vk_hash`](crate::recursion::vk_hash), so an inner node can't be passed
+//! off as a key. [`vk_merkle_root`] and [`vk_merkle_proof`] build the tree
+//! off chain.
+//!
+//! Account layout:
+//!
+//! | offset | size | field                                    |
+//! |--------|------|------------------------------------------|
+//! | 0      | 8    | discriminator `VK_REGISTRY_DISCRIMINATOR` |
+//! | 8      | 32   | authority                                |
+//! | 40     | 32   | merkle root                              |
+//! | 72     | 4    | number of registered keys, u32 LE        |
+//! | 76     | 4    | depth of the tree, u32 LE                |
+
+use crate::errors::Groth16Error;
+use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+use crate::hash::keccak256;
+use crate::recursion::vk_hash;
+use crate::Pubkey;
+use alloc::vec::Vec;
+#[cfg(feature = "pinocchio")]
+use pinocchio::account_info::AccountInfo;
+
+/// Discriminator stored in the first 8 bytes of a registry account
+pub const VK_REGISTRY_DISCRIMINATOR: [u8; 8] = *b"g16vkreg";
+
+/// Size of a registry account
+pub const VK_REGISTRY_ACCOUNT_SIZE: usize = 80;
+
+/// Largest supported tree depth
+pub const MAX_VK_REGISTRY_DEPTH: u32 = 32;
+
+/// Merkle leaf of a verifying key
+pub fn vk_leaf(vk: &Groth16Verifyingkey) -> [u8; 32] {
+    keccak256(&[&[0u8][..], &vk_hash(vk)])
+}
+
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    keccak256(&[&[1u8][..], left, right])
+}
+
+/// Membership proof of a leaf
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VkMerkleProof<'a> {
+    /// Position of the leaf
+    pub index: u32,
+    /// Sibling hashes from the leaf level up, one per level of the tree
+    pub siblings: &'a [[u8; 32]],
+}
+
+impl VkMerkleProof<'_> {
+    /// Root of the tree `leaf` is at [`Self::index`] of
+    ///
+    /// # Returns
+    /// * `Err(Groth16Error::InvalidMerkleProof)` - If the index does not fit
+    ///   the depth or the depth exceeds [`MAX_VK_REGISTRY_DEPTH`]
+    pub fn root(&self, leaf: &[u8; 32]) -> Result<[u8; 32], Groth16Error> {
+        let depth = self.siblings.len() as u32;
+        if depth > MAX_VK_REGISTRY_DEPTH || (depth < 32 && self.index >> depth != 0) {
+            return Err(Groth16Error::InvalidMerkleProof);
+        }
+        let mut node = *leaf;
+        for (level, sibling) in self.siblings.iter().enumerate() {
+            node = if (self.index >> level) & 1 == 0 {
+                hash_node(&node, sibling)
+            } else {
+                hash_node(sibling, &node)
+            };
+        }
+        Ok(node)
+    }
+}
+
+/// Checks that `vk` is a leaf of the tree with `root`
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidMerkleProof)` - If `merkle_proof` does not
+///   lead from the key to `root`
+pub fn check_vk_membership(
+    vk: &Groth16Verifyingkey,
+    merkle_proof: &VkMerkleProof,
+    root: &[u8; 32],
+) -> Result<(), Groth16Error> {
+    if merkle_proof.root(&vk_leaf(vk))? != *root {
+        return Err(Groth16Error::InvalidMerkleProof);
+    }
+    Ok(())
+}
+
+/// Verifies a proof with a registered verifying key
+///
+/// # Arguments
+/// * `vk_bytes` - The packed verifying key
+/// * `merkle_proof` - Membership proof of the key
+/// * `root` - Root of the registry
+#[allow(clippy::too_many_arguments)]
+pub fn verify_with_vk_membership<const NR_INPUTS: usize>(
+    vk_bytes: &[u8],
+    merkle_proof: &VkMerkleProof,
+    root: &[u8; 32],
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+) -> Result<(), Groth16Error> {
+    let vk = Groth16Verifyingkey::from_packed_bytes(vk_bytes)?;
+    check_vk_membership(&vk, merkle_proof, root)?;
+    Groth16Verifier::new(proof_a, proof_b, proof_c, public_inputs, &vk)?.verify()
+}
+
+/// Root of a tree of `depth` with `leaves` and zero leaves after them
+///
+/// # Returns
+/// * `Err(Groth16Error::InvalidMerkleProof)` - If the leaves don't fit
+pub fn vk_merkle_root(leaves: &[[u8; 32]], depth: u32) -> Result<[u8; 32], Groth16Error> {
+    Ok(merkle_layers(leaves, depth)?.0)
+}
+
+/// Siblings proving the membership of `leaves[index]` in a tree of `depth`
+pub fn vk_merkle_proof(
+    leaves: &[[u8; 32]],
+    index: u32,
+    depth: u32,
+) -> Result<Vec<[u8; 32]>, Groth16Error> {
+    if index as usize >= leaves.len() {
+        return Err(Groth16Error::InvalidMerkleProof);
+    }
+    let (_, layers) = merkle_layers(leaves, depth)?;
+    Ok(layers
+        .iter()
+        .enumerate()
+        .map(|(level, (layer, zero))| *layer.get(((index >> level) ^ 1) as usize).unwrap_or(zero))
+        .collect())
+}
+
+/// Returns the root and each non-empty layer below it with its empty node
+#[allow(clippy::type_complexity)]
+fn merkle_layers(
+    leaves: &[[u8; 32]],
+    depth: u32,
+) -> Result<([u8; 32], Vec<(Vec<[u8; 32]>, [u8; 32])>), Groth16Error> {
+    if depth > MAX_VK_REGISTRY_DEPTH || (leaves.len() as u64) > 1u64 << depth {
+        return Err(Groth16Error::InvalidMerkleProof);
+    }
+    let mut layers = Vec::with_capacity(depth as usize);
+    let mut layer = leaves.to_vec();
+    let mut zero = [0u8; 32];
+    for _ in 0..depth {
+        let next = layer
+            .chunks(2)
+            .map(|pair| hash_node(&pair[0], pair.get(1).unwrap_or(&zero)))
+            .collect();
+        let next_zero = hash_node(&zero, &zero);
+        layers.push((core::mem::replace(&mut layer, next), zero));
+        zero = next_zero;
+    }
+    Ok((layer.first().copied().unwrap_or(zero), layers))
+}
+
+fn check_header(data: &[u8]) -> Result<(), Groth16Error> {
+    if data.len() < VK_REGISTRY_ACCOUNT_SIZE || data[..8] != VK_REGISTRY_DISCRIMINATOR {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// Initializes a zeroed account buffer as a registry of `depth`
+pub fn initialize_vk_registry(
+    data: &mut [u8],
+    authority: &Pubkey,
+    root: &[u8; 32],
+    nr_keys: u32,
+    depth: u32,
+) -> Result<(), Groth16Error> {
+    if data.len() < VK_REGISTRY_ACCOUNT_SIZE || data[..8] != [0u8; 8] {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    if depth > MAX_VK_REGISTRY_DEPTH {
+        return Err(Groth16Error::InvalidMerkleProof);
+    }
+    data[..8].copy_from_slice(&VK_REGISTRY_DISCRIMINATOR);
+    data[8..40].copy_from_slice(authority);
+    data[40..72].copy_from_slice(root);
+    data[72..76].copy_from_slice(&nr_keys.to_le_bytes());
+    data[76..80].copy_from_slice(&depth.to_le_bytes());
+    Ok(())
+}
+
+/// Replaces the root after keys were registered or removed
+pub fn set_vk_registry_root(
+    data: &mut [u8],
+    authority: &Pubkey,
+    root: &[u8; 32],
+    nr_keys: u32,
+) -> Result<(), Groth16Error> {
+    check_header(data)?;
+    if data[8..40] != authority[..] || *authority == [0u8; 32] {
+        return Err(Groth16Error::InvalidAuthority);
+    }
+    data[40..72].copy_from_slice(root);
+    data[72..76].copy_from_slice(&nr_keys.to_le_bytes());
+    Ok(())
+}
+
+/// Root and depth of a registry account
+pub fn vk_registry_root(data: &[u8]) -> Result<(&[u8; 32], u32), Groth16Error> {
+    check_header(data)?;
+    let depth = u32::from_le_bytes(data[76..80].try_into().unwrap());
+    Ok((data[40..72].try_into().unwrap(), depth))
+}
+
+/// [`set_vk_registry_root`] with owner, writable and signer checks
+#[cfg(feature = "pinocchio")]
+pub fn set_vk_registry_root_checked(
+    account: &AccountInfo,
+    authority: &AccountInfo,
+    program_id: &Pubkey,
+    root: &[u8; 32],
+    nr_keys: u32,
+) -> Result<(), Groth16Error> {
+    if !account.is_owned_by(program_id) {
+        return Err(Groth16Error::InvalidAccountOwner);
+    }
+    if !account.is_writable() {
+        return Err(Groth16Error::AccountNotWritable);
+    }
+    if !authority.is_signer() {
+        return Err(Groth16Error::MissingRequiredSignature);
+    }
+    let mut data = account
+        .try_borrow_mut_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    set_vk_registry_root(&mut data, authority.key(), root, nr_keys)
+}
+
+/// [`verify_with_vk_membership`] against a registry account owned by `program_id`
+///
+/// The merkle proof must have one sibling per level of the registry's tree.
+#[allow(clippy::too_many_arguments)]
+#[cfg(feature = "pinocchio")]
+pub fn verify_with_vk_registry_account<const NR_INPUTS: usize>(
+    account: &AccountInfo,
+    program_id: &Pubkey,
+    vk_bytes: &[u8],
+    merkle_proof: &VkMerkleProof,
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+) -> Result<(), Groth16Error> {
+    if !account.is_owned_by(program_id) {
+        return Err(Groth16Error::InvalidAccountOwner);
+    }
+    let data = account
+        .try_borrow_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    let (root, depth) = vk_registry_root(&data)?;
+    if merkle_proof.siblings.len() != depth as usize {
+        return Err(Groth16Error::InvalidMerkleProof);
+    }
+    verify_with_vk_membership(
+        vk_bytes,
+        merkle_proof,
+        root,
+        proof_a,
+        proof_b,
+        proof_c,
+        public_inputs,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AUTHORITY: Pubkey = [1u8; 32];
+
+    #[test]
+    fn registered_keys_verify() {
+        let vk_ic = [[0u8; 64]; 2];
+        let vk = Groth16Verifyingkey {
+            nr_pubinputs: 1,
+            vk_alpha_g1: [0u8; 64],
+            vk_beta_g2: [0u8; 128],
+            vk_gamma_g2: [0u8; 128],
+            vk_delta_g2: [0u8; 128],
+            vk_ic: &vk_ic,
+        };
+        let mut vk_bytes = vec![0u8; Groth16Verifyingkey::packed_size(1)];
+        vk.write_packed_bytes(&mut vk_bytes).unwrap();
+
+        let leaves = [[5u8; 32], [6u8; 32], vk_leaf(&vk)];
+        let root = vk_merkle_root(&leaves, 3).unwrap();
+        let siblings = vk_merkle_proof(&leaves, 2, 3).unwrap();
+        assert_eq!(siblings.len(), 3);
+        let merkle_proof = VkMerkleProof {
+            index: 2,
+            siblings: &siblings,
+        };
+
+        let mut data = [0u8; VK_REGISTRY_ACCOUNT_SIZE];
+        initialize_vk_registry(&mut data, &AUTHORITY, &[0u8; 32], 0, 3).unwrap();
+        set_vk_registry_root(&mut data, &AUTHORITY, &root, 3).unwrap();
+        assert_eq!(vk_registry_root(&data).unwrap(), (&root, 3));
+
+        // The identity key accepts identity proofs
+        let inputs = [[1u8; 32]];
+        let verify = |merkle_proof: &VkMerkleProof| {
+            verify_with_vk_membership(
+                &vk_bytes,
+                merkle_proof,
+                &root,
+                &[0u8; 64],
+                &[0u8; 128],
+                &[0u8; 64],
+                &inputs,
+            )
+        };
+        verify(&merkle_proof).unwrap();
+        for wrong in [
+            VkMerkleProof {
+                index: 1,
+                ..merkle_proof
+            },
+            VkMerkleProof {
+                index: 8,
+                ..merkle_proof
+            },
+            VkMerkleProof {
+                siblings: &siblings[..2],
+                ..merkle_proof
+            },
+        ] {
+            assert_eq!(verify(&wrong), Err(Groth16Error::InvalidMerkleProof));
+        }
+        assert_eq!(
+            vk_merkle_root(&[[0u8; 32]; 3], 1),
+            Err(Groth16Error::InvalidMerkleProof)
+        );
+    }
+}
@@ -0,0 +1,467 @@
+//! Authority-gated verifying key rotation
+//!
+//! A verifying key account holds the active key and at most one pending key.
+//! The authority proposes a pending key together with an activation slot that
+//! must be at least `min_delay_slots` in the future. Until that slot the
+//! active key stays in use and the authority can cancel the proposal; from
+//! that slot on the pending key is used automatically and anyone may crank
+//! [`activate_pending_key`] to make the switch permanent. A key in effect is
+//! never rolled back without the delay: cancelling fails from the activation
+//! slot on, and a new proposal first promotes the pending key.
+//!
+//! Setting the authority to the zero pubkey freezes the key for good.
+//!
+//! Account layout:
+//!
+//! | offset   | size      | field                                   |
+//! |----------|-----------|-----------------------------------------|
+//! | 0        | 8         | discriminator `VK_UPGRADE_DISCRIMINATOR` |
+//! | 8        | 32        | authority                               |
+//! | 40       | 8         | activation slot of the pending key      |
+//! | 48       | 4         | max number of public inputs, u32 LE     |
+//! | 52       | 4         | length of the active key, u32 LE        |
+//! | 56       | 4         | length of the pending key, 0 if none    |
+//! | 60       | 4         | padding                                 |
+//! | 64       | S         | active key, packed                      |
+//! | 64 + S   | S         | pending key, packed                     |
+//!
+//! where `S = Groth16Verifyingkey::packed_size(max_nr_pubinputs)`.
+
+use crate::errors::Groth16Error;
+use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
+use crate::Pubkey;
+#[cfg(feature = "pinocchio")]
+use pinocchio::account_info::AccountInfo;
+
+/// Discriminator stored in the first 8 bytes of a verifying key account
+pub const VK_UPGRADE_DISCRIMINATOR: [u8; 8] = *b"g16vkupg";
+
+const HEADER_SIZE: usize = 64;
+
+/// Returns the account size for keys with up to `max_nr_pubinputs` public inputs
+pub const fn vk_upgrade_account_size(max_nr_pubinputs: usize) -> usize {
+    HEADER_SIZE + 2 * Groth16Verifyingkey::packed_size(max_nr_pubinputs)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> usize {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()) as usize
+}
+
+/// Returns the slot size if `data` holds an account of `max_nr_pubinputs`
+///
+/// Checked, the count is read from account data and the size overflows on
+/// 32-bit targets.
+fn checked_slot_size(data: &[u8], max_nr_pubinputs: usize) -> Result<usize, Groth16Error> {
+    let size = Groth16Verifyingkey::checked_packed_size(max_nr_pubinputs)
+        .ok_or(Groth16Error::InvalidAccountData)?;
+    match size
+        .checked_mul(2)
+        .and_then(|slots| slots.checked_add(HEADER_SIZE))
+    {
+        Some(account_size) if data.len() >= account_size => Ok(size),
+        _ => Err(Groth16Error::InvalidAccountData),
+    }
+}
+
+/// Checks the header and returns the slot size
+fn check_header(data: &[u8]) -> Result<usize, Groth16Error> {
+    if data.len() < HEADER_SIZE || data[..8] != VK_UPGRADE_DISCRIMINATOR {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    checked_slot_size(data, read_u32(data, 48))
+}
+
+/// Length of the pending key, 0 if none
+fn pending_len(data: &[u8], size: usize) -> Result<usize, Groth16Error> {
+    let pending_len = read_u32(data, 56);
+    if pending_len > size {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    Ok(pending_len)
+}
+
+/// Makes the pending key active if it is in effect at `current_slot`
+fn promote_pending_key(
+    data: &mut [u8],
+    size: usize,
+    current_slot: u64,
+) -> Result<(), Groth16Error> {
+    let pending_len = pending_len(data, size)?;
+    if pending_len == 0 || current_slot < u64::from_le_bytes(data[40..48].try_into().unwrap()) {
+        return Ok(());
+    }
+    data.copy_within(
+        HEADER_SIZE + size..HEADER_SIZE + size + pending_len,
+        HEADER_SIZE,
+    );
+    data[52..56].copy_from_slice(&(pending_len as u32).to_le_bytes());
+    data[40..48].copy_from_slice(&0u64.to_le_bytes());
+    data[56..60].copy_from_slice(&0u32.to_le_bytes());
+    Ok(())
+}
+
+/// Checks the header and authority and returns the slot size
+fn check_authority(data: &[u8], authority: &Pubkey) -> Result<usize, Groth16Error> {
+    let size = check_header(data)?;
+    if data[8..40] != authority[..] || *authority == [0u8; 32] {
+        return Err(Groth16Error::InvalidAuthority);
+    }
+    Ok(size)
+}
+
+/// Initializes a zeroed account buffer with an initial active key
+pub fn initialize_vk_account(
+    data: &mut [u8],
+    authority: &Pubkey,
+    max_nr_pubinputs: u32,
+    verifyingkey: &Groth16Verifyingkey,
+) -> Result<(), Groth16Error> {
+    let size = checked_slot_size(data, max_nr_pubinputs as usize)?;
+    if data[..8] != [0u8; 8] {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    data[8..40].copy_from_slice(authority);
+    data[48..52].copy_from_slice(&max_nr_pubinputs.to_le_bytes());
+    let written = verifyingkey.write_packed_bytes(&mut data[HEADER_SIZE..HEADER_SIZE + size])?;
+    data[52..56].copy_from_slice(&(written as u32).to_le_bytes());
+    data[..8].copy_from_slice(&VK_UPGRADE_DISCRIMINATOR);
+    Ok(())
+}
+
+/// Stores `verifyingkey` as pending key, replacing a proposal not yet in effect
+///
+/// A pending key already in effect at `current_slot` is made active first,
+/// so the new proposal starts from the key in use.
+///
+/// # Arguments
+/// * `authority` - Must match the stored authority, the caller checks the signature
+/// * `activation_slot` - First slot the new key is used in
+/// * `current_slot` - Usually `Clock::get()?.slot`
+/// * `min_delay_slots` - Minimum distance between `current_slot` and `activation_slot`
+pub fn propose_verifying_key(
+    data: &mut [u8],
+    authority: &Pubkey,
+    verifyingkey: &Groth16Verifyingkey,
+    activation_slot: u64,
+    current_slot: u64,
+    min_delay_slots: u64,
+) -> Result<(), Groth16Error> {
+    let size = check_authority(data, authority)?;
+    if activation_slot < current_slot.saturating_add(min_delay_slots) {
+        return Err(Groth16Error::InvalidActivationSlot);
+    }
+    promote_pending_key(data, size, current_slot)?;
+    let pending = HEADER_SIZE + size;
+    let written = verifyingkey
+        .write_packed_bytes(&mut data[pending..pending + size])
+        .map_err(|_| Groth16Error::InvalidPublicInputsLength)?;
+    data[40..48].copy_from_slice(&activation_slot.to_le_bytes());
+    data[56..60].copy_from_slice(&(written as u32).to_le_bytes());
+    Ok(())
+}
+
+/// Drops the pending key before its activation slot
+///
+/// # Returns
+/// * `Err(Groth16Error::NoPendingVerifyingKey)` - If there is no pending key
+/// * `Err(Groth16Error::InvalidActivationSlot)` - If the pending key is in
+///   effect at `current_slot`, cancelling would roll it back without delay
+pub fn cancel_pending_key(
+    data: &mut [u8],
+    authority: &Pubkey,
+    current_slot: u64,
+) -> Result<(), Groth16Error> {
+    let size = check_authority(data, authority)?;
+    if pending_len(data, size)? == 0 {
+        return Err(Groth16Error::NoPendingVerifyingKey);
+    }
+    if current_slot >= u64::from_le_bytes(data[40..48].try_into().unwrap()) {
+        return Err(Groth16Error::InvalidActivationSlot);
+    }
+    data[40..48].copy_from_slice(&0u64.to_le_bytes());
+    data[56..60].copy_from_slice(&0u32.to_le_bytes());
+    Ok(())
+}
+
+/// Promotes the pending key to active once its activation slot is reached
+///
+/// Permissionless, [`active_verifying_key`] already returns the pending key
+/// from its activation slot on, this only makes the switch permanent.
+pub fn activate_pending_key(data: &mut [u8], current_slot: u64) -> Result<(), Groth16Error> {
+    let size = check_header(data)?;
+    if pending_len(data, size)? == 0 {
+        return Err(Groth16Error::NoPendingVerifyingKey);
+    }
+    if current_slot < u64::from_le_bytes(data[40..48].try_into().unwrap()) {
+        return Err(Groth16Error::InvalidActivationSlot);
+    }
+    promote_pending_key(data, size, current_slot)
+}
+
+/// Hands the account over to `new_authority`, the zero pubkey freezes it
+pub fn set_vk_authority(
+    data: &mut [u8],
+    authority: &Pubkey,
+    new_authority: &Pubkey,
+) -> Result<(), Groth16Error> {
+    check_authority(data, authority)?;
+    data[8..40].copy_from_slice(new_authority);
+    Ok(())
+}
+
+/// Returns the key that is in effect at `current_slot`
+pub fn active_verifying_key(
+    data: &[u8],
+    current_slot: u64,
+) -> Result<Groth16Verifyingkey<'_>, Groth16Error> {
+    let size = check_header(data)?;
+    let pending_len = pending_len(data, size)?;
+    let activation_slot = u64::from_le_bytes(data[40..48].try_into().unwrap());
+    if pending_len != 0 && current_slot >= activation_slot {
+        let start = HEADER_SIZE + size;
+        return Groth16Verifyingkey::from_packed_bytes(&data[start..start + pending_len]);
+    }
+    let active_len = read_u32(data, 52);
+    if active_len > size {
+        return Err(Groth16Error::InvalidAccountData);
+    }
+    Groth16Verifyingkey::from_packed_bytes(&data[HEADER_SIZE..HEADER_SIZE + active_len])
+}
+
+/// Verifies a proof with the key that is in effect at `current_slot`
+pub fn verify_with_active_key<const NR_INPUTS: usize>(
+    data: &[u8],
+    current_slot: u64,
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+) -> Result<(), Groth16Error> {
+    let verifyingkey = active_verifying_key(data, current_slot)?;
+    Groth16Verifier::new(proof_a, proof_b, proof_c, public_inputs, &verifyingkey)?.verify()
+}
+
+#[cfg(feature = "pinocchio")]
+fn check_vk_account(
+    account: &AccountInfo,
+    authority: &AccountInfo,
+    program_id: &Pubkey,
+) -> Result<(), Groth16Error> {
+    if !account.is_owned_by(program_id) {
+        return Err(Groth16Error::InvalidAccountOwner);
+    }
+    if !account.is_writable() {
+        return Err(Groth16Error::AccountNotWritable);
+    }
+    if !authority.is_signer() {
+        return Err(Groth16Error::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+/// [`propose_verifying_key`] with owner, writable and signer checks
+#[cfg(feature = "pinocchio")]
+pub fn propose_verifying_key_checked(
+    account: &AccountInfo,
+    authority: &AccountInfo,
+    program_id: &Pubkey,
+    verifyingkey: &Groth16Verifyingkey,
+    activation_slot: u64,
+    current_slot: u64,
+    min_delay_slots: u64,
+) -> Result<(), Groth16Error> {
+    check_vk_account(account, authority, program_id)?;
+    let mut data = account
+        .try_borrow_mut_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    propose_verifying_key(
+        &mut data,
+        authority.key(),
+        verifyingkey,
+        activation_slot,
+        current_slot,
+        min_delay_slots,
+    )
+}
+
+/// [`cancel_pending_key`] with owner, writable and signer checks
+#[cfg(feature = "pinocchio")]
+pub fn cancel_pending_key_checked(
+    account: &AccountInfo,
+    authority: &AccountInfo,
+    program_id: &Pubkey,
+    current_slot: u64,
+) -> Result<(), Groth16Error> {
+    check_vk_account(account, authority, program_id)?;
+    let mut data = account
+        .try_borrow_mut_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    cancel_pending_key(&mut data, authority.key(), current_slot)
+}
+
+/// [`set_vk_authority`] with owner, writable and signer checks
+#[cfg(feature = "pinocchio")]
+pub fn set_vk_authority_checked(
+    account: &AccountInfo,
+    authority: &AccountInfo,
+    program_id: &Pubkey,
+    new_authority: &Pubkey,
+) -> Result<(), Groth16Error> {
+    check_vk_account(account, authority, program_id)?;
+    let mut data = account
+        .try_borrow_mut_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    set_vk_authority(&mut data, authority.key(), new_authority)
+}
+
+/// [`verify_with_active_key`] reading a verifying key account owned by `program_id`
+#[cfg(feature = "pinocchio")]
+pub fn verify_with_vk_account<const NR_INPUTS: usize>(
+    account: &AccountInfo,
+    program_id: &Pubkey,
+    current_slot: u64,
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    public_inputs: &[[u8; 32]; NR_INPUTS],
+) -> Result<(), Groth16Error> {
+    if !account.is_owned_by(program_id) {
+        return Err(Groth16Error::InvalidAccountOwner);
+    }
+    let data = account
+        .try_borrow_data()
+        .map_err(|_| Groth16Error::InvalidAccountData)?;
+    verify_with_active_key(&data, current_slot, proof_a, proof_b, proof_c, public_inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AUTHORITY: Pubkey = [1u8; 32];
+    const IC_A: [[u8; 64]; 2] = [[1u8; 64], [2u8; 64]];
+    const IC_B: [[u8; 64]; 3] = [[3u8; 64], [4u8; 64], [5u8; 64]];
+
+    fn vk(fill: u8, vk_ic: &[[u8; 64]]) -> Groth16Verifyingkey<'_> {
+        Groth16Verifyingkey {
+            nr_pubinputs: vk_ic.len() - 1,
+            vk_alpha_g1: [fill; 64],
+            vk_beta_g2: [fill; 128],
+            vk_gamma_g2: [fill; 128],
+            vk_delta_g2: [fill; 128],
+            vk_ic,
+        }
+    }
+
+    fn account() -> Vec<u8> {
+        let mut data = vec![0u8; vk_upgrade_account_size(2)];
+        initialize_vk_account(&mut data, &AUTHORITY, 2, &vk(1, &IC_A)).unwrap();
+        data
+    }
+
+    #[test]
+    fn pending_key_takes_effect_at_activation_slot() {
+        let mut data = account();
+        assert_eq!(active_verifying_key(&data, 0).unwrap(), vk(1, &IC_A));
+
+        assert_eq!(
+            propose_verifying_key(&mut data, &AUTHORITY, &vk(2, &IC_B), 109, 10, 100),
+            Err(Groth16Error::InvalidActivationSlot)
+        );
+        propose_verifying_key(&mut data, &AUTHORITY, &vk(2, &IC_B), 110, 10, 100).unwrap();
+        assert_eq!(active_verifying_key(&data, 109).unwrap(), vk(1, &IC_A));
+        assert_eq!(active_verifying_key(&data, 110).unwrap(), vk(2, &IC_B));
+
+        assert_eq!(
+            activate_pending_key(&mut data, 109),
+            Err(Groth16Error::InvalidActivationSlot)
+        );
+        activate_pending_key(&mut data, 110).unwrap();
+        assert_eq!(active_verifying_key(&data, 0).unwrap(), vk(2, &IC_B));
+        assert_eq!(
+            activate_pending_key(&mut data, 110),
+            Err(Groth16Error::NoPendingVerifyingKey)
+        );
+    }
+
+    #[test]
+    fn key_in_effect_is_not_rolled_back() {
+        let mut data = account();
+        propose_verifying_key(&mut data, &AUTHORITY, &vk(2, &IC_B), 110, 10, 100).unwrap();
+
+        // Nobody cranked activate_pending_key, the key is in effect anyway
+        assert_eq!(
+            cancel_pending_key(&mut data, &AUTHORITY, 110),
+            Err(Groth16Error::InvalidActivationSlot)
+        );
+        assert_eq!(active_verifying_key(&data, 110).unwrap(), vk(2, &IC_B));
+
+        // A new proposal keeps the key in effect until its own activation slot
+        propose_verifying_key(&mut data, &AUTHORITY, &vk(1, &IC_A), 210, 110, 100).unwrap();
+        assert_eq!(active_verifying_key(&data, 110).unwrap(), vk(2, &IC_B));
+        assert_eq!(active_verifying_key(&data, 209).unwrap(), vk(2, &IC_B));
+        cancel_pending_key(&mut data, &AUTHORITY, 209).unwrap();
+        assert_eq!(active_verifying_key(&data, 210).unwrap(), vk(2, &IC_B));
+    }
+
+    #[test]
+    fn only_authority_can_rotate() {
+        let mut data = account();
+        let other = [2u8; 32];
+        assert_eq!(
+            propose_verifying_key(&mut data, &other, &vk(2, &IC_B), 110, 10, 100),
+            Err(Groth16Error::InvalidAuthority)
+        );
+        propose_verifying_key(&mut data, &AUTHORITY, &vk(2, &IC_B), 110, 10, 100).unwrap();
+        assert_eq!(
+            cancel_pending_key(&mut data, &other, 10),
+            Err(Groth16Error::InvalidAuthority)
+        );
+        cancel_pending_key(&mut data, &AUTHORITY, 109).unwrap();
+        assert_eq!(active_verifying_key(&data, 110).unwrap(), vk(1, &IC_A));
+
+        // Freezing the key disables every authority action.
+        set_vk_authority(&mut data, &AUTHORITY, &[0u8; 32]).unwrap();
+        assert_eq!(
+            propose_verifying_key(&mut data, &[0u8; 32], &vk(2, &IC_B), 110, 10, 100),
+            Err(Groth16Error::InvalidAuthority)
+        );
+    }
+
+    #[test]
+    fn rejects_corrupted_sizes() {
+        let mut data = account();
+        data[48..52].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            active_verifying_key(&data, 0),
+            Err(Groth16Error::InvalidAccountData)
+        );
+
+        // A pending key longer than its slot
+        let mut data = account();
+        data[56..60].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            active_verifying_key(&data, 0),
+            Err(Groth16Error::InvalidAccountData)
+        );
+        assert_eq!(
+            activate_pending_key(&mut data, 0),
+            Err(Groth16Error::InvalidAccountData)
+        );
+        let mut data = vec![0u8; vk_upgrade_account_size(2)];
+        assert_eq!(
+            initialize_vk_account(&mut data, &AUTHORITY, u32::MAX, &vk(1, &IC_A)),
+            Err(Groth16Error::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn proposed_key_must_fit() {
+        let mut data = account();
+        let ic = [[6u8; 64]; 4];
+        assert_eq!(
+            propose_verifying_key(&mut data, &AUTHORITY, &vk(3, &ic), 110, 10, 100),
+            Err(Groth16Error::InvalidPublicInputsLength)
+        );
+    }
+}
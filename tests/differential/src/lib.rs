@@ -0,0 +1,56 @@
+//! # differential-verifier
+//!
+//! Minimal program verifying a proof of any circuit against the alt_bn128
+//! syscalls of the runtime. `tests/svm.rs` runs the
+//! `pinocchio_groth16::differential` corpus through it in LiteSVM and
+//! compares the results with the arkworks reference. It is also the program
+//! of the `pinocchio_groth16::svm` test harness, which measures the compute
+//! units of verifying a fixture.
+//!
+//! Instruction data: `verifying key binary encoding | ix_data`, see
+//! `pinocchio_groth16::ix_data` and `pinocchio_groth16::svm::harness_instruction_data`
+
+#![cfg_attr(target_os = "solana", no_std)]
+
+use pinocchio::account_info::AccountInfo;
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey::Pubkey;
+use pinocchio::ProgramResult;
+use pinocchio_groth16::groth16::{
+    verify_with_prepared_inputs, Groth16Verifyingkey, PreparedInputs,
+};
+use pinocchio_groth16::ix_data::decode_verify_ix_data;
+
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let nr_pubinputs = data
+        .get(8..12)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let nr_pubinputs = u32::from_le_bytes(nr_pubinputs.try_into().unwrap()) as usize;
+    let vk_size = Groth16Verifyingkey::binary_size(nr_pubinputs);
+    if data.len() < vk_size {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (vk, ix_data) = data.split_at(vk_size);
+    let verifyingkey = Groth16Verifyingkey::from_bytes(vk)?;
+    let ix_data = decode_verify_ix_data(ix_data)?;
+    let proof = ix_data.proof_points()?;
+
+    PreparedInputs::new(ix_data.public_inputs, &verifyingkey)
+        .and_then(|prepared_inputs| {
+            verify_with_prepared_inputs(
+                &proof.proof_a,
+                &proof.proof_b,
+                &proof.proof_c,
+                &prepared_inputs,
+                &verifyingkey,
+            )
+        })
+        .map_err(ProgramError::from)
+}
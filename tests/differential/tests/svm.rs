@@ -0,0 +1,36 @@
+//! Differential test of the runtime syscalls and the host mock against arkworks
+//!
+//! The runtime test requires the program binary (`cargo build-sbf`).
+
+use pinocchio_groth16::differential::{compare, corpus, Case};
+use pinocchio_groth16::proof::Proof;
+use pinocchio_groth16::svm::{harness_instruction_data, VerifierSvm};
+
+const PROGRAM_PATH: &str = "../../target/deploy/differential_verifier.so";
+
+fn instruction_data(case: &Case) -> Vec<u8> {
+    let vector = &case.vector;
+    let proof = Proof::try_from(&vector.proof_be()[..]).unwrap();
+    harness_instruction_data(&vector.verifying_key(), &proof, &vector.public_inputs).unwrap()
+}
+
+#[test]
+fn runtime_syscalls_agree_with_arkworks() {
+    let mut svm = VerifierSvm::new();
+    let program_id = svm.load_program(PROGRAM_PATH).unwrap();
+
+    let cases = corpus(0, 4);
+    let mismatches = compare(&cases, |case| {
+        svm.invoke(program_id, &instruction_data(case), vec![])
+            .is_ok()
+    });
+    assert!(mismatches.is_empty(), "{mismatches:#?}");
+}
+
+#[test]
+fn host_mock_agrees_with_arkworks() {
+    let mismatches = compare(&corpus(0, 1), |case| {
+        differential_verifier::process_instruction(&[0u8; 32], &[], &instruction_data(case)).is_ok()
+    });
+    assert!(mismatches.is_empty(), "{mismatches:#?}");
+}
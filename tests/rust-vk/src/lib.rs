@@ -2,6 +2,7 @@
 use circom_prover::{prover::ProofLib, witness::WitnessFn, CircomProver};
 use pinocchio_groth16::groth16::Groth16Verifier;
 use pinocchio_groth16::proof_parser::circom_prover::{convert_proof, convert_public_inputs};
+use pinocchio_groth16::prover::prove;
 use light_compressed_account::compressed_account::{CompressedAccount, CompressedAccountData};
 use light_compressed_account::Pubkey;
 use light_hasher::{hash_to_field_size::hash_to_bn254_field_size_be, Poseidon};
@@ -89,10 +90,10 @@ fn add_merkle_proof_to_circuit_inputs(
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_compressed_account_proof_with_groth16_solana() {
-        let zkey_path = "./build/compressed_account_merkle_proof_final.zkey".to_string();
+    const ZKEY_PATH: &str = "./build/compressed_account_merkle_proof_final.zkey";
 
+    /// Circuit inputs proving a compressed account at leaf 0 of a fresh tree
+    fn circuit_inputs() -> String {
         // Create compressed account
         let owner = Pubkey::new_from_array([1u8; 32]);
         let merkle_tree_pubkey = Pubkey::new_from_array([2u8; 32]);
@@ -131,9 +132,15 @@ mod tests {
             leaf_index,
         );
         add_merkle_proof_to_circuit_inputs(&mut proof_inputs, &merkle_proof_hashes, &merkle_root);
+        serde_json::to_string(&proof_inputs).unwrap()
+    }
+
+    #[test]
+    fn test_compressed_account_proof_with_groth16_solana() {
+        let zkey_path = ZKEY_PATH.to_string();
+        let circuit_inputs = circuit_inputs();
 
         // Generate proof using circom-prover
-        let circuit_inputs = serde_json::to_string(&proof_inputs).unwrap();
         let proof = CircomProver::prove(
             ProofLib::Arkworks,
             WitnessFn::RustWitness(compressedaccountmerkleproof_witness),
@@ -171,4 +178,28 @@ mod tests {
 
         println!("Proof verified successfully with groth16-solana!");
     }
+
+    #[test]
+    fn test_prove_from_zkey_bytes() {
+        let zkey = std::fs::read(ZKEY_PATH).unwrap();
+        let witness = circom_prover::witness::generate_witness(
+            WitnessFn::RustWitness(compressedaccountmerkleproof_witness),
+            circuit_inputs(),
+        )
+        .join()
+        .unwrap();
+
+        let proof = prove(&zkey, &witness).expect("Proof generation failed");
+        let public_inputs: [[u8; 32]; 5] = proof.public_inputs.clone().try_into().unwrap();
+        Groth16Verifier::new(
+            &proof.proof_a,
+            &proof.proof_b,
+            &proof.proof_c,
+            &public_inputs,
+            &VERIFYINGKEY,
+        )
+        .expect("Failed to create verifier")
+        .verify()
+        .expect("Proof verification failed");
+    }
 }
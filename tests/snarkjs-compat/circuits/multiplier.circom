@@ -0,0 +1,14 @@
+pragma circom 2.0.0;
+
+// c = a * b with a public and b private, plus a public sum to get two inputs
+template Multiplier() {
+    signal input a;
+    signal input b;
+    signal output c;
+    signal output d;
+
+    c <== a * b;
+    d <== c + a;
+}
+
+component main {public [a]} = Multiplier();
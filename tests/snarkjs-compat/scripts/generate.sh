@@ -0,0 +1,32 @@
+#!/usr/bin/env bash
+# Generates fixtures of the multiplier circuit with a pinned snarkjs version
+# and the circom on the PATH, once per circom optimization level.
+#
+# Usage: scripts/generate.sh <snarkjs-version>, e.g. scripts/generate.sh 0.7.5
+set -euo pipefail
+
+SNARKJS_VERSION="$1"
+SNARKJS="npx --yes snarkjs@${SNARKJS_VERSION}"
+ROOT="$(cd "$(dirname "$0")/.." && pwd)"
+CIRCOM_VERSION="$(circom --version | awk '{print $NF}')"
+WORK="$(mktemp -d)"
+trap 'rm -rf "$WORK"' EXIT
+
+$SNARKJS powersoftau new bn128 8 "$WORK/pot_0000.ptau"
+$SNARKJS powersoftau contribute "$WORK/pot_0000.ptau" "$WORK/pot_0001.ptau" --name=fixture -e=fixture
+$SNARKJS powersoftau prepare phase2 "$WORK/pot_0001.ptau" "$WORK/pot_final.ptau"
+
+for OPT in O0 O1 O2; do
+    OUT="$ROOT/fixtures/snarkjs-${SNARKJS_VERSION}/circom-${CIRCOM_VERSION}-${OPT}"
+    BUILD="$WORK/$OPT"
+    mkdir -p "$OUT" "$BUILD"
+
+    circom "$ROOT/circuits/multiplier.circom" --"$OPT" --r1cs --wasm -o "$BUILD"
+    $SNARKJS groth16 setup "$BUILD/multiplier.r1cs" "$WORK/pot_final.ptau" "$BUILD/multiplier_0000.zkey"
+    $SNARKJS zkey contribute "$BUILD/multiplier_0000.zkey" "$BUILD/multiplier.zkey" --name=fixture -e=fixture
+    $SNARKJS zkey export verificationkey "$BUILD/multiplier.zkey" "$OUT/verification_key.json"
+    $SNARKJS groth16 fullprove "$ROOT/circuits/input.json" \
+        "$BUILD/multiplier_js/multiplier.wasm" "$BUILD/multiplier.zkey" \
+        "$OUT/proof.json" "$OUT/public.json"
+    echo "Wrote $OUT"
+done
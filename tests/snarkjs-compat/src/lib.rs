@@ -0,0 +1,81 @@
+//! Compatibility kit for proofs and verifying keys of different snarkjs and circom versions
+//!
+//! A fixture is a directory holding the `verification_key.json`, `proof.json`
+//! and `public.json` written by one snarkjs version for one circom build of
+//! `circuits/multiplier.circom`. `scripts/generate.sh <snarkjs-version>`
+//! writes them to `fixtures/snarkjs-<version>/circom-<version>-<optimization>`.
+//!
+//! [`check_fixture`] converts the JSON files into the verifier's encoding and
+//! asserts that the proof verifies with the crate's verifier and arkworks and
+//! that a modified public input is rejected by both.
+
+use pinocchio_groth16::differential::{arkworks_verify, syscall_verify};
+use pinocchio_groth16::test_vectors::TestVector;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// JSON files of one fixture
+#[derive(Clone, Debug)]
+pub struct Fixture {
+    pub name: String,
+    pub verification_key: String,
+    pub proof: String,
+    pub public: String,
+}
+
+/// Loads every fixture below `dir`, sorted by name
+///
+/// A fixture is any directory containing a `verification_key.json`.
+pub fn load_fixtures(dir: &Path) -> Vec<Fixture> {
+    let mut fixtures = Vec::new();
+    collect_fixtures(dir, dir, &mut fixtures);
+    fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+    fixtures
+}
+
+fn collect_fixtures(root: &Path, dir: &Path, fixtures: &mut Vec<Fixture>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut subdirs: Vec<PathBuf> = Vec::new();
+    for entry in entries.flatten() {
+        if entry.path().is_dir() {
+            subdirs.push(entry.path());
+        }
+    }
+    if dir.join("verification_key.json").exists() {
+        let read = |file: &str| fs::read_to_string(dir.join(file)).unwrap();
+        fixtures.push(Fixture {
+            name: dir.strip_prefix(root).unwrap().display().to_string(),
+            verification_key: read("verification_key.json"),
+            proof: read("proof.json"),
+            public: read("public.json"),
+        });
+    }
+    for subdir in subdirs {
+        collect_fixtures(root, &subdir, fixtures);
+    }
+}
+
+/// Converts the JSON files of `fixture` into a [`TestVector`]
+pub fn parse_fixture(fixture: &Fixture) -> Result<TestVector, String> {
+    TestVector::from_snarkjs_json(&fixture.verification_key, &fixture.proof, &fixture.public)
+}
+
+/// Asserts the verifier accepts the proof of `fixture` and rejects a modified input
+pub fn check_fixture(fixture: &Fixture) -> Result<(), String> {
+    let vector = parse_fixture(fixture)?;
+    if !arkworks_verify(&vector) {
+        return Err("arkworks rejects the proof".into());
+    }
+    if !syscall_verify(&vector) {
+        return Err(format!("verifier rejects the proof: {:?}", vector.verify()));
+    }
+
+    let mut modified = vector;
+    modified.public_inputs[0][31] ^= 1;
+    if arkworks_verify(&modified) || syscall_verify(&modified) {
+        return Err("a modified public input is accepted".into());
+    }
+    Ok(())
+}
@@ -0,0 +1,50 @@
+//! Runs the compatibility kit over the fixtures in `fixtures`
+//!
+//! Fixtures are generated with `scripts/generate.sh`. Set
+//! `SNARKJS_COMPAT_REQUIRE_FIXTURES=1` to fail if there are none, as CI does.
+
+use pinocchio_groth16::test_vectors::{generate, generate_merkle_proof};
+use snarkjs_compat::{check_fixture, load_fixtures, parse_fixture, Fixture};
+use std::path::Path;
+
+#[test]
+fn snarkjs_fixtures_verify() {
+    let fixtures = load_fixtures(&Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures"));
+    if fixtures.is_empty() {
+        assert!(
+            std::env::var_os("SNARKJS_COMPAT_REQUIRE_FIXTURES").is_none(),
+            "no fixtures, run scripts/generate.sh"
+        );
+        eprintln!("no snarkjs fixtures, run scripts/generate.sh <snarkjs-version>");
+        return;
+    }
+    let failures: Vec<String> = fixtures
+        .iter()
+        .filter_map(|fixture| {
+            check_fixture(fixture)
+                .err()
+                .map(|error| format!("{}: {error}", fixture.name))
+        })
+        .collect();
+    assert!(failures.is_empty(), "{failures:#?}");
+}
+
+#[test]
+fn kit_accepts_snarkjs_encoding_of_arkworks_proofs() {
+    for vector in [generate(0), generate_merkle_proof(0)] {
+        let fixture = Fixture {
+            name: "arkworks".into(),
+            verification_key: vector.snarkjs_verification_key_json(),
+            proof: vector.snarkjs_proof_json(),
+            public: vector.snarkjs_public_json(),
+        };
+        assert_eq!(parse_fixture(&fixture), Ok(vector));
+        assert_eq!(check_fixture(&fixture), Ok(()));
+
+        let wrong_curve = Fixture {
+            proof: fixture.proof.replace("bn128", "bls12381"),
+            ..fixture
+        };
+        assert!(parse_fixture(&wrong_curve).is_err());
+    }
+}
@@ -0,0 +1,258 @@
+//! Smoke test of a deployed verifier program
+//!
+//! Submits a proof of one of the [`test_fixtures`](pinocchio_groth16::test_fixtures)
+//! to the verifier program on a cluster and reports the outcome, the decoded
+//! [`Groth16Error`] of a failure and the consumed compute units. With
+//! `--tamper` the proof is modified first and the program has to reject it
+//! with the error the verifier returns for the tampering. Exits with status 1
+//! if the outcome is not the expected one.
+//!
+//! The program must verify `verify_prefix | proof_a | proof_b | proof_c | inputs`
+//! against the verifying key of the fixture, see
+//! [`TransactionBuilder`](pinocchio_groth16::client::TransactionBuilder).
+//!
+//! ```bash
+//! cargo run -p devnet-smoke -- --url devnet --program-id <PROGRAM_ID> --fixture merkle-proof
+//! ```
+
+use pinocchio_groth16::client::TransactionBuilder;
+use pinocchio_groth16::corruption::Tampering;
+use pinocchio_groth16::errors::Groth16Error;
+use pinocchio_groth16::test_fixtures::{HASH_PREIMAGE, MERKLE_PROOF};
+use solana_commitment_config::CommitmentConfig;
+use solana_instruction::error::InstructionError;
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_pubkey::Pubkey;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+const USAGE: &str = "\
+usage: devnet-smoke --program-id <PUBKEY> [options]
+
+options:
+  --url <URL>              RPC url, or devnet, testnet, mainnet, localnet [default: localnet]
+  --keypair <PATH>         fee payer [default: ~/.config/solana/id.json]
+  --fixture <NAME>         hash-preimage or merkle-proof [default: hash-preimage]
+  --prefix <HEX>           instruction data preceding the proof [default: none]
+  --tamper <NAME>          submit a tampered proof and expect its error, e.g. NegateA
+  --simulate               only simulate the transaction";
+
+struct Args {
+    url: String,
+    keypair: String,
+    program_id: Pubkey,
+    fixture: String,
+    prefix: Vec<u8>,
+    tamper: Option<Tampering>,
+    simulate: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut url = "localnet".to_string();
+    let mut keypair = std::env::var("HOME")
+        .map(|home| format!("{home}/.config/solana/id.json"))
+        .unwrap_or_default();
+    let mut program_id = None;
+    let mut fixture = "hash-preimage".to_string();
+    let mut prefix = Vec::new();
+    let mut tamper = None;
+    let mut simulate = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--url" => url = value()?,
+            "--keypair" => keypair = value()?,
+            "--program-id" => {
+                let value = value()?;
+                program_id = Some(Pubkey::from_str(&value).map_err(|e| format!("{value}: {e}"))?);
+            }
+            "--fixture" => fixture = value()?,
+            "--prefix" => prefix = parse_hex(&value()?)?,
+            "--tamper" => {
+                let name = value()?;
+                tamper = Some(
+                    Tampering::ALL
+                        .into_iter()
+                        .find(|tampering| format!("{tampering:?}") == name)
+                        .ok_or(format!(
+                            "unknown tampering {name}, expected one of {:?}",
+                            Tampering::ALL
+                        ))?,
+                );
+            }
+            "--simulate" => simulate = true,
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+    Ok(Args {
+        url: match url.as_str() {
+            "devnet" => "https://api.devnet.solana.com".into(),
+            "testnet" => "https://api.testnet.solana.com".into(),
+            "mainnet" => "https://api.mainnet-beta.solana.com".into(),
+            "localnet" => "http://127.0.0.1:8899".into(),
+            _ => url,
+        },
+        keypair,
+        program_id: program_id.ok_or("--program-id is required")?,
+        fixture,
+        prefix,
+        tamper,
+        simulate,
+    })
+}
+
+fn parse_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let hex = hex.trim_start_matches("0x");
+    if !hex.len().is_multiple_of(2) {
+        return Err(format!("odd number of hex digits in {hex}"));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| format!("{hex}: {e}")))
+        .collect()
+}
+
+/// `proof_a`, `proof_b`, `proof_c` and the public inputs
+type Proof = ([u8; 64], [u8; 128], [u8; 64], Vec<[u8; 32]>);
+
+/// Returns the proof and public inputs of the fixture, tampered if requested
+fn proof(args: &Args) -> Result<Proof, String> {
+    fn tampered<const N: usize>(
+        fixture: &pinocchio_groth16::test_fixtures::Fixture<N>,
+        tamper: Option<Tampering>,
+    ) -> Proof {
+        let (a, b, c, inputs) = match tamper {
+            Some(tampering) => tampering.apply(
+                &fixture.proof_a,
+                &fixture.proof_b,
+                &fixture.proof_c,
+                &fixture.public_inputs,
+            ),
+            None => (
+                fixture.proof_a,
+                fixture.proof_b,
+                fixture.proof_c,
+                fixture.public_inputs,
+            ),
+        };
+        (a, b, c, inputs.to_vec())
+    }
+    match args.fixture.as_str() {
+        "hash-preimage" => Ok(tampered(&HASH_PREIMAGE, args.tamper)),
+        "merkle-proof" => Ok(tampered(&MERKLE_PROOF, args.tamper)),
+        fixture => Err(format!("unknown fixture {fixture}")),
+    }
+}
+
+/// Describes a transaction error, decoding custom program errors as [`Groth16Error`]
+fn describe(error: &TransactionError) -> String {
+    match error {
+        TransactionError::InstructionError(index, InstructionError::Custom(code)) => {
+            match Groth16Error::try_from(*code) {
+                Ok(decoded) => format!("instruction {index} failed with {decoded:?} ({code})"),
+                Err(code) => format!("instruction {index} failed with custom error {code}"),
+            }
+        }
+        error => error.to_string(),
+    }
+}
+
+fn run(args: &Args) -> Result<bool, String> {
+    let payer: Keypair = read_keypair_file(&args.keypair)
+        .map_err(|e| format!("reading keypair {}: {e}", args.keypair))?;
+    let (a, b, c, inputs) = proof(args)?;
+    let transactions =
+        TransactionBuilder::new(args.program_id, payer.pubkey(), args.prefix.clone(), vec![])
+            .with_estimated_compute_unit_limit(20_000)
+            .build(&a, &b, &c, &inputs)
+            .map_err(|e| format!("building the transaction: {e}"))?;
+    let [instructions] = transactions.as_slice() else {
+        return Err("the proof does not fit into one transaction".into());
+    };
+
+    let rpc = RpcClient::new_with_commitment(args.url.clone(), CommitmentConfig::confirmed());
+    let blockhash = rpc
+        .get_latest_blockhash()
+        .map_err(|e| format!("{}: {e}", args.url))?;
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[&payer],
+        blockhash,
+    );
+
+    println!("cluster:    {}", args.url);
+    println!("program:    {}", args.program_id);
+    println!("fixture:    {}", args.fixture);
+    if let Some(tampering) = args.tamper {
+        println!("tampering:  {tampering:?}");
+    }
+    let simulation = rpc
+        .simulate_transaction(&transaction)
+        .map_err(|e| format!("simulating: {e}"))?
+        .value;
+    if let Some(units) = simulation.units_consumed {
+        println!("compute:    {units} units");
+    }
+    let error = simulation.err.map(TransactionError::from);
+    match &error {
+        None => println!("result:     accepted"),
+        Some(error) => {
+            println!("result:     rejected, {}", describe(error));
+            for log in simulation.logs.unwrap_or_default() {
+                println!("  {log}");
+            }
+        }
+    }
+
+    let expected = args
+        .tamper
+        .map(|tampering| u32::from(tampering.expected_error()));
+    let as_expected = match (&error, expected) {
+        (None, None) => true,
+        (
+            Some(TransactionError::InstructionError(_, InstructionError::Custom(code))),
+            Some(expected),
+        ) => *code == expected,
+        _ => false,
+    };
+    if !as_expected {
+        match args.tamper {
+            Some(tampering) => println!("expected:   {:?}", tampering.expected_error()),
+            None => println!("expected:   accepted"),
+        }
+        return Ok(false);
+    }
+
+    if !args.simulate && error.is_none() {
+        let signature = rpc
+            .send_and_confirm_transaction(&transaction)
+            .map_err(|e| format!("sending: {e}"))?;
+        println!("signature:  {signature}");
+    }
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}